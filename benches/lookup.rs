@@ -0,0 +1,55 @@
+//! Looking up UT offsets for a run of timestamps, as when bucketing log records by local time.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use tzif::ZoneDatabase;
+
+/// A day of timestamps in 2024, a little over a second apart, and a spread over 1900 to 2100.
+fn timestamps() -> [(&'static str, Vec<i64>); 2] {
+    [
+        (
+            "recent",
+            (1_710_000_000..1_710_086_400).step_by(1_001).collect(),
+        ),
+        (
+            "spread",
+            (-2_208_988_800..4_102_444_800).step_by(7_300_003).collect(),
+        ),
+    ]
+}
+
+fn lookup(c: &mut Criterion) {
+    let db = ZoneDatabase::bundled();
+    for name in ["America/New_York", "Australia/Lord_Howe"] {
+        let tz = db.get(name).unwrap();
+        let table = tz.offset_table(0..4_102_444_800);
+        for (label, times) in timestamps() {
+            let mut group = c.benchmark_group(format!("{name} {label}"));
+            group.throughput(Throughput::Elements(times.len() as u64));
+            group.bench_function("local_time_type_at", |b| {
+                b.iter(|| {
+                    for &ut in &times {
+                        black_box(tz.local_time_type_at(black_box(ut)));
+                    }
+                })
+            });
+            group.bench_function("offset_at", |b| {
+                b.iter(|| {
+                    for &ut in &times {
+                        black_box(tz.offset_at(black_box(ut)));
+                    }
+                })
+            });
+            group.bench_function("OffsetTable::offset_at", |b| {
+                b.iter(|| {
+                    for &ut in &times {
+                        black_box(table.offset_at(black_box(ut)));
+                    }
+                })
+            });
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, lookup);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+//! Parsing TZif data, from a zone with a long history and from a slim one.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tzif::{ParseOptions, PosixTz, TimeZoneInfo, ZoneDatabase};
+
+fn parse(c: &mut Criterion) {
+    let db = ZoneDatabase::bundled();
+    let opts = ParseOptions::default();
+    for name in ["America/New_York", "Europe/London", "Asia/Kolkata"] {
+        let data = db.get(name).unwrap().to_bytes();
+        c.bench_function(&format!("parse_slice {name}"), |b| {
+            b.iter(|| TimeZoneInfo::parse_slice(black_box(&data), &opts).unwrap())
+        });
+    }
+    c.bench_function("parse PosixTz", |b| {
+        b.iter(|| black_box("<+1030>-10:30<+11>-11,M10.1.0,M4.1.0").parse::<PosixTz>())
+    });
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+//! Generates the C header for the `ffi` module, and the compressed snapshot for the
+//! `bundled-compressed` feature, when those features are enabled.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "ffi")]
+    generate_header();
+    #[cfg(feature = "bundled-compressed")]
+    compress_bundle();
+}
+
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    cbindgen::Builder::new()
+        .with_src(format!("{crate_dir}/src/ffi.rs"))
+        .with_language(cbindgen::Language::C)
+        .with_include_guard("TZIF_H")
+        .with_header("/* Generated from src/ffi.rs by cbindgen. Do not edit. */")
+        .generate()
+        .expect("couldn't generate the C header")
+        .write_to_file(format!("{out_dir}/tzif.h"));
+}
+
+/// Write the zones of `data/zoneinfo.bin` to `zoneinfo.z`, each deflated separately.
+///
+/// The layout is that of `data/zoneinfo.bin`, but with the magic `b"TZDZ"`, and data offsets
+/// and lengths which refer to the compressed data.
+#[cfg(feature = "bundled-compressed")]
+fn compress_bundle() {
+    use std::collections::BTreeMap;
+
+    println!("cargo:rerun-if-changed=data/zoneinfo.bin");
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let archive = std::fs::read(format!("{crate_dir}/data/zoneinfo.bin")).unwrap();
+    let field = |at: usize| u32::from_be_bytes(archive[at..at + 4].try_into().unwrap()) as usize;
+    assert_eq!(b"TZDB", &archive[..4]);
+
+    let mut entries = Vec::new();
+    let mut pos = 8;
+    for _ in 0..field(4) {
+        let name_len = usize::from(archive[pos]);
+        let name = &archive[pos + 1..pos + 1 + name_len];
+        pos += 1 + name_len;
+        entries.push((name, field(pos), field(pos + 4)));
+        pos += 8;
+    }
+    let data = &archive[pos..];
+
+    let mut index = b"TZDZ".to_vec();
+    let mut compressed = Vec::new();
+    let mut offsets = BTreeMap::<usize, (u32, u32)>::new();
+    let mut count = 0u32;
+    for (name, offset, len) in entries {
+        let contents = &data[offset..offset + len];
+        if !contents.starts_with(b"TZif") || name == b"posixrules" {
+            continue;
+        }
+        let (offset, len) = *offsets.entry(offset).or_insert_with(|| {
+            let deflated = miniz_oxide::deflate::compress_to_vec(contents, 10);
+            let offset = compressed.len() as u32;
+            compressed.extend_from_slice(&deflated);
+            (offset, deflated.len() as u32)
+        });
+        index.push(name.len() as u8);
+        index.extend_from_slice(name);
+        index.extend_from_slice(&offset.to_be_bytes());
+        index.extend_from_slice(&len.to_be_bytes());
+        count += 1;
+    }
+    index.splice(4..4, count.to_be_bytes());
+    index.extend_from_slice(&compressed);
+    std::fs::write(format!("{out_dir}/zoneinfo.z"), index).unwrap();
+}
@@ -0,0 +1,74 @@
+//! Regenerate `data/zoneinfo.bin`, the zoneinfo snapshot embedded by the `bundled` feature, from
+//! a compiled zoneinfo directory.
+//!
+//! Usage: `cargo run --example pack_zoneinfo -- /usr/share/zoneinfo data/zoneinfo.bin`
+//!
+//! The archive holds every file in the directory other than the `posix/` and `right/` variant
+//! trees and `localtime`. Its layout is:
+//!
+//! ```text
+//! b"TZDB", entry count: u32
+//! per entry: name length: u8, name, data offset: u32, data length: u32
+//! data
+//! ```
+//!
+//! All integers are big-endian, entries are sorted by name, and data offsets are relative to
+//! the start of the data section. Entries with identical contents, such as links, share data.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+fn collect(root: &Path, dir: &Path, files: &mut BTreeMap<String, Vec<u8>>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path
+            .strip_prefix(root)
+            .unwrap()
+            .to_str()
+            .expect("non-UTF-8 file name")
+            .to_owned();
+        if matches!(name.as_str(), "posix" | "right" | "localtime") {
+            continue;
+        }
+        if path.is_dir() {
+            collect(root, &path, files)?;
+        } else {
+            files.insert(name, fs::read(&path)?);
+        }
+    }
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    let mut args = env::args_os().skip(1);
+    let (Some(src), Some(dest)) = (args.next(), args.next()) else {
+        eprintln!("usage: pack_zoneinfo <zoneinfo dir> <output file>");
+        std::process::exit(1);
+    };
+
+    let mut files = BTreeMap::new();
+    collect(Path::new(&src), Path::new(&src), &mut files)?;
+
+    let mut index = b"TZDB".to_vec();
+    index.extend_from_slice(&(files.len() as u32).to_be_bytes());
+    let mut data = Vec::new();
+    let mut offsets = BTreeMap::<&[u8], u32>::new();
+    for (name, contents) in &files {
+        let offset = *offsets.entry(contents).or_insert_with(|| {
+            let offset = data.len() as u32;
+            data.extend_from_slice(contents);
+            offset
+        });
+        index.push(u8::try_from(name.len()).expect("name too long"));
+        index.extend_from_slice(name.as_bytes());
+        index.extend_from_slice(&offset.to_be_bytes());
+        index.extend_from_slice(&(contents.len() as u32).to_be_bytes());
+    }
+    index.extend_from_slice(&data);
+    fs::write(dest, index)?;
+    println!("packed {} files", files.len());
+    Ok(())
+}
@@ -0,0 +1,88 @@
+/* Generated from src/ffi.rs by cbindgen. Do not edit. */
+
+#ifndef TZIF_H
+#define TZIF_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * A parsed zone, from `tzif_parse`.
+ */
+typedef struct TzifZone TzifZone;
+
+/**
+ * A transition, from `tzif_transition`.
+ */
+typedef struct TzifTransition {
+  /**
+   * The UT time of the transition, in seconds since the epoch.
+   */
+  int64_t at;
+  /**
+   * The UT offset from then on, in seconds.
+   */
+  int32_t ut_offset;
+  /**
+   * Whether DST is in effect from then on.
+   */
+  bool is_dst;
+  /**
+   * The designation from then on, a NUL-terminated string which lives as long as the zone.
+   */
+  const char *designation;
+} TzifTransition;
+
+/**
+ * Parse `len` bytes of TZif data at `data`, returning the zone, or null if the data isn't
+ * valid. The zone must be freed with `tzif_free`.
+ *
+ * # Safety
+ *
+ * `data` must point to `len` readable bytes, or may be null if `len` is 0.
+ */
+struct TzifZone *tzif_parse(const uint8_t *data, uintptr_t len);
+
+/**
+ * Free a zone from `tzif_parse`. Null is ignored.
+ *
+ * # Safety
+ *
+ * `zone` must be null or a zone from `tzif_parse` which hasn't been freed.
+ */
+void tzif_free(struct TzifZone *zone);
+
+/**
+ * Look up the UT offset in effect at the UT time `ut`, in seconds since the epoch, storing it
+ * in `*offset` and whether DST is in effect in `*is_dst`, either of which may be null.
+ * Returns whether the zone has an offset to give.
+ *
+ * # Safety
+ *
+ * `zone` must be a live zone from `tzif_parse`, and `offset` and `is_dst` null or writable.
+ */
+bool tzif_offset_at(const struct TzifZone *zone, int64_t ut, int32_t *offset, bool *is_dst);
+
+/**
+ * The number of transitions recorded in the zone, not counting those its footer gives.
+ *
+ * # Safety
+ *
+ * `zone` must be a live zone from `tzif_parse`.
+ */
+uintptr_t tzif_transition_count(const struct TzifZone *zone);
+
+/**
+ * Store the transition with index `idx` in `*out`, returning false if there's no such
+ * transition. Transitions are in order of time, so calling this with each index from 0 up to
+ * `tzif_transition_count` iterates over them.
+ *
+ * # Safety
+ *
+ * `zone` must be a live zone from `tzif_parse`, and `out` writable.
+ */
+bool tzif_transition(const struct TzifZone *zone, uintptr_t idx, struct TzifTransition *out);
+
+#endif  /* TZIF_H */
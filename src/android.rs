@@ -0,0 +1,141 @@
+//! The single-file `tzdata` container Android ships in place of a zoneinfo directory.
+//!
+//! The file begins with a header:
+//!
+//! ```text
+//! version: [u8; 12]    e.g. b"tzdata2024a\0"
+//! index_offset: i32
+//! data_offset: i32
+//! final_offset: i32
+//! ```
+//!
+//! followed, at `index_offset`, by 52-byte index entries running up to `data_offset`:
+//!
+//! ```text
+//! name: [u8; 40]       NUL-padded zone name
+//! start: i32           offset of the zone's TZif data, relative to data_offset
+//! length: i32
+//! unused: i32
+//! ```
+//!
+//! All integers are big-endian.
+
+use std::fmt;
+use std::io;
+
+use crate::ZoneError;
+
+const HEADER_LEN: usize = 24;
+const ENTRY_LEN: usize = 52;
+const NAME_LEN: usize = 40;
+
+/// A parsed Android `tzdata` file.
+pub(crate) struct AndroidTzdata {
+    data: Vec<u8>,
+    /// Zone name and the range of its TZif data in `data`, sorted by name.
+    entries: Vec<(String, usize, usize)>,
+}
+
+impl AndroidTzdata {
+    pub fn parse(data: Vec<u8>) -> Result<Self, ZoneError> {
+        let invalid = || {
+            ZoneError::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid tzdata file",
+            ))
+        };
+        if data.len() < HEADER_LEN || !data.starts_with(b"tzdata") {
+            return Err(invalid());
+        }
+        let int = |at: usize| -> Option<usize> {
+            let bytes = data.get(at..at + 4)?;
+            usize::try_from(i32::from_be_bytes(bytes.try_into().ok()?)).ok()
+        };
+        let (index_offset, data_offset) =
+            (int(12).ok_or_else(invalid)?, int(16).ok_or_else(invalid)?);
+        if index_offset > data_offset || (data_offset - index_offset) % ENTRY_LEN != 0 {
+            return Err(invalid());
+        }
+
+        let mut entries = Vec::new();
+        for entry in (index_offset..data_offset).step_by(ENTRY_LEN) {
+            let name = data.get(entry..entry + NAME_LEN).ok_or_else(invalid)?;
+            let name_len = name.iter().position(|&b| b == 0).unwrap_or(NAME_LEN);
+            let name = std::str::from_utf8(&name[..name_len]).map_err(|_| invalid())?;
+            let start = int(entry + NAME_LEN).ok_or_else(invalid)? + data_offset;
+            let len = int(entry + NAME_LEN + 4).ok_or_else(invalid)?;
+            if data.len() < start.saturating_add(len) {
+                return Err(invalid());
+            }
+            entries.push((name.to_owned(), start, len));
+        }
+        entries.sort();
+        Ok(Self { data, entries })
+    }
+
+    /// The TZif data of the zone with the given name.
+    pub fn get(&self, name: &str) -> Option<&[u8]> {
+        let idx = self
+            .entries
+            .binary_search_by(|(entry, _, _)| entry.as_str().cmp(name))
+            .ok()?;
+        let (_, start, len) = self.entries[idx];
+        Some(&self.data[start..start + len])
+    }
+
+    /// The names of the zones in the file, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(name, _, _)| name.as_str())
+    }
+}
+
+impl fmt::Debug for AndroidTzdata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AndroidTzdata")
+            .field("zones", &self.entries.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Build a tzdata file holding the given zones.
+    pub(crate) fn build(zones: &[(&str, &[u8])]) -> Vec<u8> {
+        let index_offset = HEADER_LEN;
+        let data_offset = index_offset + zones.len() * ENTRY_LEN;
+        let mut out = b"tzdata2024a\0".to_vec();
+        for offset in [index_offset, data_offset, 0] {
+            out.extend_from_slice(&(offset as i32).to_be_bytes());
+        }
+        let mut start = 0;
+        for (name, tzif) in zones {
+            let mut padded = [0; NAME_LEN];
+            padded[..name.len()].copy_from_slice(name.as_bytes());
+            out.extend_from_slice(&padded);
+            for value in [start, tzif.len(), 0] {
+                out.extend_from_slice(&(value as i32).to_be_bytes());
+            }
+            start += tzif.len();
+        }
+        for (_, tzif) in zones {
+            out.extend_from_slice(tzif);
+        }
+        out
+    }
+
+    #[test]
+    fn test_android_tzdata() {
+        let tzdata = AndroidTzdata::parse(build(&[("B/Two", b"two"), ("A/One", b"one")])).unwrap();
+        assert_eq!(Some(&b"one"[..]), tzdata.get("A/One"));
+        assert_eq!(Some(&b"two"[..]), tzdata.get("B/Two"));
+        assert_eq!(None, tzdata.get("C/Three"));
+        assert_eq!(vec!["A/One", "B/Two"], Vec::from_iter(tzdata.names()));
+
+        let mut truncated = build(&[("A/One", b"one")]);
+        truncated.pop();
+        assert!(AndroidTzdata::parse(truncated).is_err());
+        assert!(AndroidTzdata::parse(b"TZif".to_vec()).is_err());
+    }
+}
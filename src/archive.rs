@@ -0,0 +1,510 @@
+//! A single-file archive of zones which can be memory-mapped and queried in place, without
+//! parsing each zone up front.
+//!
+//! The file begins with a header:
+//!
+//! ```text
+//! magic: b"TZARCHV1"
+//! count: u32           number of zones
+//! names_len: u32       length of the names section
+//! ```
+//!
+//! followed by `count` 16-byte index entries, sorted by name:
+//!
+//! ```text
+//! name_offset: u32     relative to the start of the names section
+//! name_len: u32
+//! record_offset: u32   relative to the start of the records section
+//! record_len: u32
+//! ```
+//!
+//! then the names section, the zone names back to back, and the records section. Each zone
+//! record is:
+//!
+//! ```text
+//! version: u8
+//! indicators: u8       bit 0 set if the zone has standard/wall indicators, bit 1 UT/local
+//! unused: u16
+//! transition_count: u32
+//! type_count: u32
+//! leap_count: u32
+//! designations_len: u32
+//! footer_len: u32
+//! transition times: i64 each
+//! transition types: u8 each
+//! local time types: UT offset: i32, is_dst: u8, designation index: u8, is_std: u8, is_ut: u8
+//! leap seconds: occurrence: i64, correction: i32
+//! designations: the designation table
+//! footer: the TZ string, or nothing
+//! ```
+//!
+//! All integers are little-endian. Zones with identical records, such as links, share one.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+
+use crate::{
+    IsStd, IsUT, LeapSecondRecord, LocalTimeType, LocalTimeTypeRecord, PosixTz, TimeZoneInfo,
+    ZoneDatabase, ZoneError,
+};
+
+const MAGIC: &[u8; 8] = b"TZARCHV1";
+const HEADER_LEN: usize = 16;
+const ENTRY_LEN: usize = 16;
+const RECORD_HEADER_LEN: usize = 24;
+const TYPE_LEN: usize = 8;
+const LEAP_LEN: usize = 12;
+
+/// An archive of zones, as written by [`ZoneArchive::build`] or
+/// [`ZoneDatabase::to_archive`], read in place from a byte slice such as a memory-mapped file.
+///
+/// Opening one only checks its header. Each zone's data is found by binary search of the
+/// index when it's asked for, and its lookups read the archive directly.
+#[derive(Clone, Copy)]
+pub struct ZoneArchive<'a> {
+    data: &'a [u8],
+    count: usize,
+    names: &'a [u8],
+    records: &'a [u8],
+}
+
+/// A zone in a [`ZoneArchive`], with lookups which read its data in place.
+#[derive(Debug, Clone)]
+pub struct ArchivedZone<'a> {
+    version: u8,
+    indicators: u8,
+    times: &'a [u8],
+    types: &'a [u8],
+    local_time_types: &'a [u8],
+    leap_seconds: &'a [u8],
+    designations: &'a [u8],
+    footer: Option<PosixTz>,
+}
+
+fn invalid() -> ZoneError {
+    ZoneError::Io(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "invalid zone archive",
+    ))
+}
+
+fn u32_at(data: &[u8], at: usize) -> Option<usize> {
+    let bytes = data.get(at..at.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?) as usize)
+}
+
+fn i64_at(data: &[u8], at: usize) -> i64 {
+    i64::from_le_bytes(data[at..at + 8].try_into().unwrap())
+}
+
+impl<'a> ZoneArchive<'a> {
+    /// Check the archive's header and index bounds. Nothing else is read until it's needed.
+    pub fn new(data: &'a [u8]) -> Result<Self, ZoneError> {
+        if !data.starts_with(MAGIC) {
+            return Err(invalid());
+        }
+        let count = u32_at(data, 8).ok_or_else(invalid)?;
+        let names_len = u32_at(data, 12).ok_or_else(invalid)?;
+        let names_start = count
+            .checked_mul(ENTRY_LEN)
+            .and_then(|index_len| index_len.checked_add(HEADER_LEN))
+            .ok_or_else(invalid)?;
+        let records_start = names_start.checked_add(names_len).ok_or_else(invalid)?;
+        if data.len() < records_start {
+            return Err(invalid());
+        }
+        Ok(Self {
+            data,
+            count,
+            names: &data[names_start..records_start],
+            records: &data[records_start..],
+        })
+    }
+
+    /// The number of zones in the archive.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// The names of the zones in the archive, in order. Any the archive can't give are skipped.
+    pub fn names(&self) -> impl Iterator<Item = &'a str> + '_ {
+        (0..self.count).filter_map(|idx| self.name(idx))
+    }
+
+    /// The zone with the given name.
+    pub fn get(&self, name: &str) -> Result<ArchivedZone<'a>, ZoneError> {
+        let (mut lo, mut hi) = (0, self.count);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match self.name(mid).ok_or_else(invalid)?.cmp(name) {
+                core::cmp::Ordering::Less => lo = mid + 1,
+                core::cmp::Ordering::Greater => hi = mid,
+                core::cmp::Ordering::Equal => return self.zone(mid).ok_or_else(invalid),
+            }
+        }
+        Err(ZoneError::NotFound(name.to_owned()))
+    }
+
+    fn entry(&self, idx: usize, field: usize) -> Option<usize> {
+        u32_at(self.data, HEADER_LEN + idx * ENTRY_LEN + field * 4)
+    }
+
+    fn name(&self, idx: usize) -> Option<&'a str> {
+        let (offset, len) = (self.entry(idx, 0)?, self.entry(idx, 1)?);
+        let name = self.names.get(offset..offset.checked_add(len)?)?;
+        std::str::from_utf8(name).ok()
+    }
+
+    fn zone(&self, idx: usize) -> Option<ArchivedZone<'a>> {
+        let (offset, len) = (self.entry(idx, 2)?, self.entry(idx, 3)?);
+        let record = self.records.get(offset..offset.checked_add(len)?)?;
+        let header = record.get(..RECORD_HEADER_LEN)?;
+        let field = |n: usize| u32_at(header, 4 + n * 4);
+        let transition_count = field(0)?;
+        let lens = [
+            transition_count.checked_mul(8)?,
+            transition_count,
+            field(1)?.checked_mul(TYPE_LEN)?,
+            field(2)?.checked_mul(LEAP_LEN)?,
+            field(3)?,
+            field(4)?,
+        ];
+        let mut rest = &record[RECORD_HEADER_LEN..];
+        let mut sections = [&[][..]; 6];
+        for (section, len) in sections.iter_mut().zip(lens) {
+            if rest.len() < len {
+                return None;
+            }
+            (*section, rest) = rest.split_at(len);
+        }
+        let [times, types, local_time_types, leap_seconds, designations, footer] = sections;
+        let footer = match footer {
+            [] => None,
+            footer => Some(std::str::from_utf8(footer).ok()?.parse().ok()?),
+        };
+        Some(ArchivedZone {
+            version: header[0],
+            indicators: header[1],
+            times,
+            types,
+            local_time_types,
+            leap_seconds,
+            designations,
+            footer,
+        })
+    }
+
+    /// Write an archive of the given zones, which must have distinct names. Zones which are
+    /// identical share their data.
+    pub fn build<'z>(zones: impl IntoIterator<Item = (&'z str, &'z TimeZoneInfo)>) -> Vec<u8> {
+        let zones: BTreeMap<&str, &TimeZoneInfo> = zones.into_iter().collect();
+        let mut index = Vec::with_capacity(zones.len() * ENTRY_LEN);
+        let mut names = Vec::new();
+        let mut records = Vec::new();
+        let mut offsets = BTreeMap::<Vec<u8>, usize>::new();
+        for (name, tz) in zones.iter() {
+            let record = record(tz);
+            let record_len = record.len();
+            let offset = *offsets.entry(record).or_insert_with_key(|record| {
+                records.extend_from_slice(record);
+                records.len() - record.len()
+            });
+            for value in [names.len(), name.len(), offset, record_len] {
+                index.extend_from_slice(&(value as u32).to_le_bytes());
+            }
+            names.extend_from_slice(name.as_bytes());
+        }
+
+        let mut out = MAGIC.to_vec();
+        out.extend_from_slice(&(zones.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(names.len() as u32).to_le_bytes());
+        out.extend_from_slice(&index);
+        out.extend_from_slice(&names);
+        out.extend_from_slice(&records);
+        out
+    }
+}
+
+/// A zone's record in an archive.
+fn record(tz: &TimeZoneInfo) -> Vec<u8> {
+    let footer = tz
+        .footer
+        .as_ref()
+        .map(PosixTz::to_string)
+        .unwrap_or_default();
+    let indicators = u8::from(!tz.is_std.is_empty()) | u8::from(!tz.is_ut.is_empty()) << 1;
+    let mut out = vec![tz.version, indicators, 0, 0];
+    for count in [
+        tz.transition_times.len(),
+        tz.local_time_types.len(),
+        tz.leap_second_records.len(),
+        tz.time_zone_designations.len(),
+        footer.len(),
+    ] {
+        out.extend_from_slice(&(count as u32).to_le_bytes());
+    }
+    for &at in &tz.transition_times {
+        out.extend_from_slice(&at.to_le_bytes());
+    }
+    out.extend_from_slice(&tz.transition_types);
+    for (idx, typ) in tz.local_time_types.iter().enumerate() {
+        out.extend_from_slice(&typ.ut_off_secs.to_le_bytes());
+        out.extend([
+            u8::from(typ.is_dst),
+            typ.desig_idx,
+            u8::from(tz.is_std.get(idx) == Some(&IsStd::Standard)),
+            u8::from(tz.is_ut.get(idx) == Some(&IsUT::UT)),
+        ]);
+    }
+    for leap in &tz.leap_second_records {
+        out.extend_from_slice(&leap.occurrence.to_le_bytes());
+        out.extend_from_slice(&leap.correction.to_le_bytes());
+    }
+    out.extend_from_slice(&tz.time_zone_designations);
+    out.extend_from_slice(footer.as_bytes());
+    out
+}
+
+impl fmt::Debug for ZoneArchive<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZoneArchive")
+            .field("zones", &self.count)
+            .finish()
+    }
+}
+
+impl ArchivedZone<'_> {
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn transition_count(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn footer(&self) -> Option<&PosixTz> {
+        self.footer.as_ref()
+    }
+
+    fn time(&self, idx: usize) -> i64 {
+        i64_at(self.times, idx * 8)
+    }
+
+    /// The local time type with the given index, if it's in range.
+    fn local_time_type(&self, idx: usize) -> Option<LocalTimeType<'_>> {
+        let record = self
+            .local_time_types
+            .get(idx * TYPE_LEN..(idx + 1) * TYPE_LEN)?;
+        let desig = self
+            .designations
+            .get(usize::from(record[5])..)
+            .unwrap_or(&[]);
+        let desig = desig.split(|&b| b == 0).next().unwrap_or(&[]);
+        Some(LocalTimeType {
+            desig: std::str::from_utf8(desig).unwrap_or(""),
+            ut_offset_secs: i32::from_le_bytes(record[..4].try_into().unwrap()),
+            is_dst: record[4] != 0,
+        })
+    }
+
+    /// The local time type in effect at the given UT timestamp, as
+    /// [`TimeZoneInfo::local_time_type_at`] gives it.
+    pub fn local_time_type_at(&self, ut: i64) -> Option<LocalTimeType<'_>> {
+        let count = self.transition_count();
+        if let Some(footer) = &self.footer {
+            if count == 0 || ut >= self.time(count - 1) {
+                return Some(footer.local_time_type_at(ut));
+            }
+        }
+        let (mut lo, mut hi) = (0, count);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.time(mid) <= ut {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        let typ = match lo {
+            0 => 0,
+            idx => usize::from(self.types[idx - 1]),
+        };
+        self.local_time_type(typ)
+    }
+
+    /// The UT offset in effect at the given UT timestamp, in seconds.
+    pub fn offset_at(&self, ut: i64) -> Option<i32> {
+        self.local_time_type_at(ut)
+            .map(|local| local.ut_offset_secs)
+    }
+
+    /// Whether DST is in effect at the given UT timestamp.
+    pub fn is_dst_at(&self, ut: i64) -> bool {
+        self.local_time_type_at(ut)
+            .is_some_and(|local| local.is_dst)
+    }
+
+    /// The time zone designation (abbreviation) in effect at the given UT timestamp.
+    pub fn designation_at(&self, ut: i64) -> Option<&str> {
+        self.local_time_type_at(ut).map(|local| local.desig)
+    }
+
+    /// Copy the zone out of the archive in full.
+    pub fn to_tzif(&self) -> TimeZoneInfo {
+        let types = self.local_time_types.chunks_exact(TYPE_LEN);
+        let (has_std, has_ut) = (self.indicators & 1 != 0, self.indicators & 2 != 0);
+        TimeZoneInfo {
+            version: self.version,
+            transition_times: (0..self.transition_count()).map(|i| self.time(i)).collect(),
+            transition_types: self.types.to_vec(),
+            local_time_types: types
+                .clone()
+                .map(|record| LocalTimeTypeRecord {
+                    ut_off_secs: i32::from_le_bytes(record[..4].try_into().unwrap()),
+                    is_dst: record[4] != 0,
+                    desig_idx: record[5],
+                })
+                .collect(),
+            time_zone_designations: self.designations.to_vec(),
+            leap_second_records: self
+                .leap_seconds
+                .chunks_exact(LEAP_LEN)
+                .map(|record| {
+                    LeapSecondRecord::new(
+                        i64_at(record, 0),
+                        i32::from_le_bytes(record[8..].try_into().unwrap()),
+                    )
+                })
+                .collect(),
+            is_std: match has_std {
+                true => types
+                    .clone()
+                    .map(|record| match record[6] {
+                        0 => IsStd::Wall,
+                        _ => IsStd::Standard,
+                    })
+                    .collect(),
+                false => Vec::new(),
+            },
+            is_ut: match has_ut {
+                true => types
+                    .map(|record| match record[7] {
+                        0 => IsUT::Local,
+                        _ => IsUT::UT,
+                    })
+                    .collect(),
+                false => Vec::new(),
+            },
+            footer: self.footer.clone(),
+            raw_footer: None,
+        }
+    }
+}
+
+impl ZoneDatabase {
+    /// Parse every zone in the database, as listed by [`zone_names`](Self::zone_names), and
+    /// write them to an archive, which [`ZoneArchive`] can query without parsing them again.
+    pub fn to_archive(&self) -> Result<Vec<u8>, ZoneError> {
+        let names = self.zone_names()?;
+        let zones = names
+            .iter()
+            .map(|name| self.get(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ZoneArchive::build(
+            names
+                .iter()
+                .map(String::as_str)
+                .zip(zones.iter().map(|tz| &**tz)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{leap_records, sample};
+
+    #[test]
+    fn test_archive() {
+        let sample = sample();
+        let mut leaps = TimeZoneInfo {
+            version: 4,
+            footer: Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap()),
+            is_std: vec![],
+            leap_second_records: leap_records(&[(78796800, 1), (94694401, 2)]),
+            ..sample.clone()
+        };
+        leaps.is_ut.clear();
+        let posix = TimeZoneInfo::from_posix("<+0530>-5:30".parse().unwrap());
+        let data = ZoneArchive::build([
+            ("Test/Sample", &sample),
+            ("Test/Leaps", &leaps),
+            ("Test/Link", &sample),
+            ("Posix", &posix),
+        ]);
+
+        let archive = ZoneArchive::new(&data).unwrap();
+        assert_eq!(4, archive.len());
+        assert_eq!(
+            vec!["Posix", "Test/Leaps", "Test/Link", "Test/Sample"],
+            Vec::from_iter(archive.names())
+        );
+        for (name, tz) in [
+            ("Test/Sample", &sample),
+            ("Test/Leaps", &leaps),
+            ("Test/Link", &sample),
+            ("Posix", &posix),
+        ] {
+            let zone = archive.get(name).unwrap();
+            assert_eq!(*tz, zone.to_tzif());
+            for ut in [i64::MIN, -1, 0, 100, 150, 200, 299, 300, 1 << 40, i64::MAX] {
+                assert_eq!(tz.local_time_type_at(ut), zone.local_time_type_at(ut));
+            }
+        }
+        assert_eq!(
+            Some("+0530"),
+            archive.get("Posix").unwrap().designation_at(0)
+        );
+        assert!(archive.get("Test/Leaps").unwrap().is_dst_at(1_720_000_000));
+        assert!(matches!(
+            archive.get("Test/Missing"),
+            Err(ZoneError::NotFound(_))
+        ));
+
+        // The link shares the sample's record.
+        let without_link = ZoneArchive::build([("Test/Sample", &sample)]);
+        let with_link = ZoneArchive::build([("Test/Sample", &sample), ("Test/Link", &sample)]);
+        assert_eq!(
+            without_link.len() + ENTRY_LEN + "Test/Link".len(),
+            with_link.len()
+        );
+
+        assert!(ZoneArchive::new(b"TZDB").is_err());
+        let truncated = &data[..data.len() - 1];
+        assert!(ZoneArchive::new(truncated)
+            .unwrap()
+            .get("Test/Sample")
+            .is_err());
+    }
+
+    #[test]
+    fn test_to_archive() {
+        let tzdata = crate::android::tests::build(&[
+            ("Test/Sample", &sample().to_bytes()),
+            ("UTC", &TimeZoneInfo::utc().to_bytes()),
+        ]);
+        let db = ZoneDatabase::from_android_bytes(tzdata).unwrap();
+        let data = db.to_archive().unwrap();
+        let archive = ZoneArchive::new(&data).unwrap();
+        assert_eq!(vec!["Test/Sample", "UTC"], Vec::from_iter(archive.names()));
+        assert_eq!(
+            *db.get("UTC").unwrap(),
+            archive.get("UTC").unwrap().to_tzif()
+        );
+    }
+}
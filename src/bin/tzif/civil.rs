@@ -0,0 +1,70 @@
+//! Calendar arithmetic for the commands, in UT.
+
+use crate::CommandError;
+
+pub const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Number of days from 1970-01-01 to the given date. `month` and `day` are 1-based.
+pub fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let m = i64::from(month);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// The date (year, month, day) which is the given number of days after 1970-01-01.
+pub fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Format seconds since the epoch as "YYYY-MM-DD HH:MM:SS".
+pub fn format_ut(t: i64) -> String {
+    let (year, month, day) = civil_from_days(t.div_euclid(SECS_PER_DAY));
+    let secs = t.rem_euclid(SECS_PER_DAY);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Parse a time given on the command line: seconds since the epoch, or a UT date as
+/// `YYYY-MM-DD`, optionally followed by `THH:MM` or `THH:MM:SS`.
+pub fn parse_time(arg: &str) -> Result<i64, CommandError> {
+    if let Ok(t) = arg.parse() {
+        return Ok(t);
+    }
+    let invalid = || format!("invalid time {arg:?}: expected seconds or YYYY-MM-DD[THH:MM[:SS]]");
+    let (date, time) = arg.split_once(['T', ' ']).unwrap_or((arg, "00:00"));
+    let date: Vec<&str> = date.splitn(3, '-').collect();
+    let time: Vec<&str> = time.split(':').collect();
+    let &[year, month, day] = &date[..] else {
+        return Err(invalid().into());
+    };
+    let (Ok(year), Ok(month @ 1..=12), Ok(day @ 1..=31)) =
+        (year.parse::<i64>(), month.parse::<u8>(), day.parse::<u8>())
+    else {
+        return Err(invalid().into());
+    };
+    let secs = match &time[..] {
+        [hh, mm] => [hh, mm, &"0"],
+        [hh, mm, ss] => [hh, mm, ss],
+        _ => return Err(invalid().into()),
+    };
+    let [Ok(hh @ 0..=23), Ok(mm @ 0..=59), Ok(ss @ 0..=60)] = secs.map(|s| s.parse::<i64>()) else {
+        return Err(invalid().into());
+    };
+    Ok(days_from_civil(year, month, day) * SECS_PER_DAY + hh * 3600 + mm * 60 + ss)
+}
@@ -0,0 +1,54 @@
+//! `tzif diff`: compare two zones by the local time they describe, or every zone in two
+//! zoneinfo directories.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::process;
+
+use tzif::ZoneDatabase;
+
+use crate::{zdump, CommandError};
+
+pub fn run(args: &[OsString]) -> Result<(), CommandError> {
+    let [old, new] = args else {
+        return Err("usage: tzif diff OLD NEW".into());
+    };
+    if Path::new(old).is_dir() && Path::new(new).is_dir() {
+        return diff_trees(Path::new(old), Path::new(new));
+    }
+    let db = ZoneDatabase::system();
+    let old = zdump::load(old, db.as_ref())?;
+    let new = zdump::load(new, db.as_ref())?;
+
+    let diffs = old.diff(&new);
+    if diffs.is_empty() {
+        println!("no differences");
+        return Ok(());
+    }
+    for diff in &diffs {
+        println!("{diff}");
+    }
+    process::exit(1);
+}
+
+/// List each name which changed between two trees, with how its data differs, and a summary.
+fn diff_trees(old: &Path, new: &Path) -> Result<(), CommandError> {
+    let changes = tzif::diff_trees(old, new)?;
+    let (mut added, mut removed, mut changed) = (0, 0, 0);
+    for change in &changes {
+        match (&change.old, &change.new) {
+            (None, _) => added += 1,
+            (_, None) => removed += 1,
+            _ => changed += 1,
+        }
+        println!("{change}");
+        for diff in &change.differences {
+            println!("    {diff}");
+        }
+    }
+    println!("{added} added, {removed} removed, {changed} changed");
+    if !changes.is_empty() {
+        process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,155 @@
+//! The default command: inspect a single file.
+//!
+//! Without options, prints a summary of the file, the local time type in effect now, and a
+//! table of every transition. `--at` prints just the local time type in effect at a time,
+//! `--transitions-after` leaves out transitions up to a time, and `--header-only` prints the
+//! header of the newest data block without reading the rest. `--json` prints any of these as
+//! JSON instead, for scripts.
+
+use std::ffi::OsString;
+use std::fs::File;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tzif::{peek_header, Header, LocalTimeType, TimeZoneInfo};
+
+use crate::civil::{format_ut, parse_time};
+use crate::CommandError;
+
+const USAGE: &str =
+    "usage: tzif [--json] [--header-only | --at TIME | --transitions-after TIME] FILE";
+
+#[derive(Default)]
+pub struct Options {
+    json: bool,
+    pub header_only: bool,
+    pub at: Option<i64>,
+    pub after: Option<i64>,
+}
+
+pub fn run(args: &[OsString]) -> Result<(), CommandError> {
+    let mut opts = Options::default();
+    let mut path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut time = || -> Result<i64, CommandError> {
+            let value = args.next().and_then(|arg| arg.to_str()).ok_or(USAGE)?;
+            parse_time(value)
+        };
+        match arg.to_str() {
+            Some("--json") => opts.json = true,
+            Some("--header-only") => opts.header_only = true,
+            Some("--at") => opts.at = Some(time()?),
+            Some("--transitions-after") => opts.after = Some(time()?),
+            Some(flag) if flag.starts_with("--") => return Err(USAGE.into()),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let path = path.ok_or(USAGE)?;
+    #[cfg(feature = "json")]
+    if opts.json {
+        return crate::json::run(path, &opts);
+    }
+    #[cfg(not(feature = "json"))]
+    if opts.json {
+        return Err("--json needs tzif to be built with the json feature".into());
+    }
+
+    if opts.header_only {
+        print_header(&peek_header(File::open(path)?)?);
+        return Ok(());
+    }
+    let info = TimeZoneInfo::parse(File::open(path)?)?;
+    if let Some(at) = opts.at {
+        print_at(&info, at);
+        return Ok(());
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    println!(
+        "version {}, {} transitions, {} local time types, {} leap seconds",
+        info.version,
+        info.transition_times.len(),
+        info.local_time_types.len(),
+        info.iter_leap_seconds().len(),
+    );
+    if let Some(footer) = &info.footer {
+        println!("footer: {footer}");
+    }
+    match info.local_time_type_at(now) {
+        Some(local) => println!("now: {local}"),
+        None => println!("now: no local time type"),
+    }
+    println!();
+
+    println!("{:19}  {:9}  {:3}  designation", "at (UT)", "offset", "dst");
+    let mut found = false;
+    for (at, local) in transitions_after(&info, opts.after) {
+        if at > now && !found {
+            println!("--- now ---");
+            found = true;
+        }
+        println!(
+            "{}  {:9}  {:3}  {}",
+            format_ut(at),
+            format_offset(local.ut_offset_secs),
+            if local.is_dst { "yes" } else { "" },
+            local.desig,
+        );
+    }
+    Ok(())
+}
+
+/// The transitions after the given time, or all of them, with their UT times.
+pub fn transitions_after(info: &TimeZoneInfo, after: Option<i64>) -> Vec<(i64, LocalTimeType<'_>)> {
+    info.transition_times
+        .iter()
+        .zip(info.iter_transitions())
+        .map(|(&at, t)| (at, t.local))
+        .filter(|&(at, _)| after.is_none_or(|after| at > after))
+        .collect()
+}
+
+fn print_at(info: &TimeZoneInfo, at: i64) {
+    match info.local_time_type_at(at) {
+        Some(local) => println!(
+            "{} UT = {} {local}",
+            format_ut(at),
+            format_ut(at + i64::from(local.ut_offset_secs)),
+        ),
+        None => println!("{} UT: no local time type", format_ut(at)),
+    }
+}
+
+fn print_header(header: &Header) {
+    // The header has been checked, so the version is known.
+    println!("version: {}", header.version().unwrap_or_default());
+    for (name, count) in header_fields(header) {
+        println!("{name}: {count}");
+    }
+}
+
+/// The counts in a header, by name.
+pub fn header_fields(header: &Header) -> [(&'static str, u32); 6] {
+    [
+        ("isutcnt", header.isutcnt),
+        ("isstdcnt", header.isstdcnt),
+        ("leapcnt", header.leapcnt),
+        ("timecnt", header.timecnt),
+        ("typecnt", header.typecnt),
+        ("charcnt", header.charcnt),
+    ]
+}
+
+/// A UT offset as in "-04:00", or "-04:56:02" if it isn't a whole number of minutes.
+fn format_offset(secs: i32) -> String {
+    let sign = if secs < 0 { '-' } else { '+' };
+    let off = secs.unsigned_abs();
+    let mut s = format!("{sign}{:02}:{:02}", off / 3600, off / 60 % 60);
+    if !off.is_multiple_of(60) {
+        s += &format!(":{:02}", off % 60);
+    }
+    s
+}
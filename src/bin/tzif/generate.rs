@@ -0,0 +1,36 @@
+//! `tzif generate`: write zones crafted to exercise TZif's edge cases into a directory.
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::PathBuf;
+
+use tzif::EdgeCase;
+
+use crate::CommandError;
+
+pub fn run(args: &[OsString]) -> Result<(), CommandError> {
+    let [dir, names @ ..] = args else {
+        return Err("usage: tzif generate DIR [CASE...]".into());
+    };
+    let cases = if names.is_empty() {
+        EdgeCase::ALL.to_vec()
+    } else {
+        names
+            .iter()
+            .map(|name| {
+                name.to_str()
+                    .and_then(EdgeCase::from_name)
+                    .ok_or_else(|| format!("unknown edge case {}", name.to_string_lossy()))
+            })
+            .collect::<Result<_, _>>()?
+    };
+
+    let dir = PathBuf::from(dir);
+    fs::create_dir_all(&dir)?;
+    for case in cases {
+        let path = dir.join(case.name());
+        fs::write(&path, tzif::generate(case).to_bytes())?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
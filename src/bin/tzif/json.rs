@@ -0,0 +1,53 @@
+//! `--json` output for the default command.
+
+use std::ffi::OsString;
+use std::fs::File;
+
+use serde_json::{json, Map, Value};
+use tzif::{peek_header, TimeZoneInfo};
+
+use crate::dump::{header_fields, transitions_after, Options};
+use crate::CommandError;
+
+/// Print what the options ask for as JSON. Without any, this is the whole file in the schema
+/// of [`TimeZoneInfo::to_json`].
+pub fn run(path: &OsString, opts: &Options) -> Result<(), CommandError> {
+    if opts.header_only {
+        let header = peek_header(File::open(path)?)?;
+        let mut fields = Map::new();
+        fields.insert("version".into(), header.version().into());
+        for (name, count) in header_fields(&header) {
+            fields.insert(name.into(), count.into());
+        }
+        println!("{:#}", Value::from(fields));
+        return Ok(());
+    }
+
+    let info = TimeZoneInfo::parse(File::open(path)?)?;
+    let value = if let Some(at) = opts.at {
+        match info.local_time_type_at(at) {
+            Some(local) => json!({
+                "at": at,
+                "ut_offset": local.ut_offset_secs,
+                "is_dst": local.is_dst,
+                "designation": local.desig,
+            }),
+            None => json!({ "at": at }),
+        }
+    } else if opts.after.is_some() {
+        let transitions = transitions_after(&info, opts.after);
+        Value::from_iter(transitions.into_iter().map(|(at, local)| {
+            json!({
+                "at": at,
+                "ut_offset": local.ut_offset_secs,
+                "is_dst": local.is_dst,
+                "designation": local.desig,
+            })
+        }))
+    } else {
+        println!("{}", info.to_json());
+        return Ok(());
+    };
+    println!("{value:#}");
+    Ok(())
+}
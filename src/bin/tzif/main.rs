@@ -0,0 +1,54 @@
+//! Inspect TZif files.
+//!
+//! ```text
+//! tzif [--json] [--header-only | --at TIME | --transitions-after TIME] FILE
+//! tzif diff OLD NEW
+//! tzif generate DIR [CASE...]
+//! tzif zdump [-v | -V] [-c [LOYEAR,]HIYEAR] ZONE...
+//! tzif verify [DIR]
+//! ```
+//!
+//! With just a file, prints a summary of it, the local time type in effect now, and a table of
+//! every transition; the options narrow that down, or print it as JSON.
+//! `diff` lists the ways two zones differ in the local time they give, or given two directories,
+//! which zones and links were added, removed or changed between them, and exits with an error
+//! if there are any differences. `zdump` prints the same output as the system's `zdump`, so the
+//! two can be diffed. `generate` writes zones crafted to exercise TZif's edge cases into a
+//! directory, every one or those named, such as `negative-dst`, for testing readers. `verify`
+//! checks every file in a zoneinfo directory, the system's by default, and exits with an error
+//! if any TZif file is invalid.
+
+use std::env;
+use std::error::Error;
+use std::ffi::OsString;
+use std::process::ExitCode;
+
+mod civil;
+mod diff;
+mod dump;
+mod generate;
+#[cfg(feature = "json")]
+mod json;
+mod verify;
+mod zdump;
+
+fn main() -> ExitCode {
+    let args: Vec<OsString> = env::args_os().skip(1).collect();
+    let result = match args.first().and_then(|arg| arg.to_str()) {
+        Some("diff") => diff::run(&args[1..]),
+        Some("generate") => generate::run(&args[1..]),
+        Some("verify") => verify::run(&args[1..]),
+        Some("zdump") => zdump::run(&args[1..]),
+        _ => dump::run(&args),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("tzif: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The error type of each command.
+pub type CommandError = Box<dyn Error>;
@@ -0,0 +1,51 @@
+//! `tzif verify`: check every file in a zoneinfo directory.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process;
+
+use tzif::{FileStatus, ZoneDatabase};
+
+use crate::CommandError;
+
+pub fn run(args: &[OsString]) -> Result<(), CommandError> {
+    let root = match args {
+        [] => ZoneDatabase::system()
+            .and_then(|db| db.root().map(PathBuf::from))
+            .ok_or("no system zoneinfo directory")?,
+        [root] => PathBuf::from(root),
+        _ => return Err("usage: tzif verify [DIR]".into()),
+    };
+
+    let reports = tzif::verify_tree(&root)?;
+    let (mut valid, mut skipped, mut failed) = (0, 0, 0);
+    for report in &reports {
+        match &report.status {
+            FileStatus::Valid { version } => {
+                valid += 1;
+                println!("ok    {} (version {version})", report.name);
+            }
+            FileStatus::NotTzif => {
+                skipped += 1;
+                println!("skip  {} (not TZif)", report.name);
+            }
+            FileStatus::Invalid(e) => {
+                failed += 1;
+                println!("FAIL  {}: {e}", report.name);
+            }
+            FileStatus::Unreadable(e) => {
+                failed += 1;
+                println!("FAIL  {}: {e}", report.name);
+            }
+            _ => {}
+        }
+    }
+    println!(
+        "{}: {valid} valid, {failed} failed, {skipped} not TZif",
+        root.display()
+    );
+    if failed > 0 {
+        process::exit(1);
+    }
+    Ok(())
+}
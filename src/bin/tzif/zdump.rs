@@ -0,0 +1,192 @@
+//! `tzif zdump`: output in the format of `zdump`, for diffing against the system's.
+//!
+//! `-v` lists the instants either side of each change in UT offset, DST or abbreviation, from
+//! the year -500 to 2500 or the range given with `-c`, bracketed by the lines `zdump` prints for
+//! the extremes of `time_t`. `-V` leaves out those lines. Like `zdump`, changes are looked for
+//! twelve hours apart once the TZ string footer takes over.
+
+use std::ffi::{OsStr, OsString};
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tzif::{TimeZoneInfo, ZoneDatabase};
+
+use crate::civil::{civil_from_days, days_from_civil, SECS_PER_DAY};
+use crate::CommandError;
+
+/// How far apart `zdump` samples local time when looking for changes.
+const STEP: i64 = SECS_PER_DAY / 2;
+
+/// The years `zdump -v` covers without `-c`.
+const DEFAULT_YEARS: (i64, i64) = (-500, 2500);
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Now,
+    Verbose,
+    VerboseWithoutExtremes,
+}
+
+pub fn run(args: &[OsString]) -> Result<(), CommandError> {
+    let mut mode = Mode::Now;
+    let mut years = DEFAULT_YEARS;
+    let mut zones = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("-v") => mode = Mode::Verbose,
+            Some("-V") => mode = Mode::VerboseWithoutExtremes,
+            Some("-c") => {
+                let range = args.next().and_then(|arg| arg.to_str());
+                years = parse_years(range.ok_or("-c needs a year range")?)?;
+            }
+            Some(arg) if arg.starts_with("-c") => years = parse_years(&arg[2..])?,
+            _ => zones.push(arg),
+        }
+    }
+    if zones.is_empty() {
+        return Err("usage: tzif zdump [-v | -V] [-c [LOYEAR,]HIYEAR] ZONE...".into());
+    }
+
+    let db = ZoneDatabase::system();
+    let width = zones.iter().map(|zone| zone.len()).max().unwrap_or(0);
+    for zone in zones {
+        let tz = load(zone, db.as_ref())?;
+        let name = format!("{:width$}", zone.to_string_lossy());
+        if mode == Mode::Now {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs() as i64);
+            match tz.local_time_type_at(now) {
+                Some(local) => println!(
+                    "{name}  {} {}",
+                    format_time(now + i64::from(local.ut_offset_secs)),
+                    local.desig
+                ),
+                None => println!("{name}  {now} = NULL"),
+            }
+            continue;
+        }
+
+        if mode == Mode::Verbose {
+            println!("{name}  {} = NULL", i64::MIN);
+            println!("{name}  {} = NULL", i64::MIN + SECS_PER_DAY);
+        }
+        let (lo, hi) = (year_start(years.0), year_start(years.1));
+        for t in changes(&tz, lo, hi) {
+            show(&name, &tz, t - 1);
+            show(&name, &tz, t);
+        }
+        if mode == Mode::Verbose {
+            println!("{name}  {} = NULL", i64::MAX - SECS_PER_DAY);
+            println!("{name}  {} = NULL", i64::MAX);
+        }
+    }
+    Ok(())
+}
+
+/// Parse a `-c` argument: `HIYEAR` or `LOYEAR,HIYEAR`.
+fn parse_years(range: &str) -> Result<(i64, i64), CommandError> {
+    let invalid = || format!("invalid year range {range:?}");
+    let (lo, hi) = match range.split_once(',') {
+        Some((lo, hi)) => (lo.parse().map_err(|_| invalid())?, hi),
+        None => (DEFAULT_YEARS.0, range),
+    };
+    Ok((lo, hi.parse().map_err(|_| invalid())?))
+}
+
+/// Load a zone given as an absolute path or a name in the system database.
+pub fn load(zone: &OsStr, db: Option<&ZoneDatabase>) -> Result<TimeZoneInfo, CommandError> {
+    let path = Path::new(zone);
+    if path.is_absolute() {
+        return Ok(TimeZoneInfo::parse(File::open(path)?)?);
+    }
+    let name = zone.to_str().ok_or("invalid zone name")?;
+    let tz = db.ok_or("no system zoneinfo database")?.get(name)?;
+    Ok(Arc::unwrap_or_clone(tz))
+}
+
+/// The instants after `lo` and in time for `zdump`'s last sample before `hi` at which local
+/// time changes UT offset, DST, or abbreviation.
+fn changes(tz: &TimeZoneInfo, lo: i64, hi: i64) -> Vec<i64> {
+    let key = |t: i64| {
+        tz.local_time_type_at(t)
+            .map(|local| (local.ut_offset_secs, local.is_dst, local.desig))
+    };
+    let mut changes: Vec<i64> = tz
+        .transition_times
+        .iter()
+        .copied()
+        .filter(|&t| lo < t && t <= hi - STEP && key(t - 1) != key(t))
+        .collect();
+    if tz.footer.is_none() {
+        return changes;
+    }
+
+    // Sample from the last transition on, as zdump would, and search for the exact instant
+    // of any change.
+    let start = tz.transition_times.last().map_or(lo, |&last| last.max(lo));
+    let mut t = start.div_euclid(STEP) * STEP + STEP;
+    let mut prev = key(t);
+    while t + STEP < hi {
+        let next = t + STEP;
+        let after = key(next);
+        if after != prev {
+            let (mut before, mut changed) = (t, next);
+            while changed - before > 1 {
+                let mid = before + (changed - before) / 2;
+                if key(mid) == prev {
+                    before = mid;
+                } else {
+                    changed = mid;
+                }
+            }
+            changes.push(changed);
+        }
+        t = next;
+        prev = after;
+    }
+    changes
+}
+
+/// Print a `zdump -v` line for the given instant.
+fn show(name: &str, tz: &TimeZoneInfo, t: i64) {
+    match tz.local_time_type_at(t) {
+        Some(local) => println!(
+            "{name}  {} UT = {} {} isdst={} gmtoff={}",
+            format_time(t),
+            format_time(t + i64::from(local.ut_offset_secs)),
+            local.desig,
+            u8::from(local.is_dst),
+            local.ut_offset_secs,
+        ),
+        None => println!("{name}  {t} = NULL"),
+    }
+}
+
+/// Format seconds since the epoch the way `asctime` does, as in "Sun Nov  1 06:00:00 2499".
+fn format_time(t: i64) -> String {
+    let days = t.div_euclid(SECS_PER_DAY);
+    let secs = t.rem_euclid(SECS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{} {} {day:2} {:02}:{:02}:{:02} {year}",
+        WEEKDAYS[(days + 4).rem_euclid(7) as usize],
+        MONTHS[month as usize - 1],
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+    )
+}
+
+/// Seconds since the epoch at the start of the given year, UT.
+fn year_start(year: i64) -> i64 {
+    days_from_civil(year, 1, 1) * SECS_PER_DAY
+}
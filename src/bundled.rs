@@ -0,0 +1,147 @@
+//! The zoneinfo snapshots embedded by the `bundled` and `bundled-compressed` features.
+//!
+//! `data/zoneinfo.bin` is generated by the `pack_zoneinfo` example, which documents its layout.
+//! The compressed snapshot is made from it by the build script, keeping only the zones and
+//! deflating each separately, so that looking up a zone only decompresses that zone.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "bundled")]
+use crate::embedded::{be_u32, bytes_eq, subslice};
+#[cfg(feature = "bundled-compressed")]
+use crate::{ParseOptions, TimeZoneInfo};
+
+#[cfg(feature = "bundled")]
+static ARCHIVE: &[u8] = include_bytes!("../data/zoneinfo.bin");
+
+#[cfg(feature = "bundled-compressed")]
+static COMPRESSED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/zoneinfo.z"));
+
+/// Look up a file in the snapshot by its path relative to the zoneinfo root.
+#[cfg(feature = "bundled")]
+pub(crate) fn get(name: &str) -> Option<&'static [u8]> {
+    find(ARCHIVE, b"TZDB", name)
+}
+
+/// Look up a file in the snapshot like [`get`], but at compile time, for the
+/// [`tzif!`](crate::tzif!) macro. Panics if the file isn't there.
+#[cfg(feature = "bundled")]
+pub(crate) const fn get_const(name: &str) -> &'static [u8] {
+    let archive: &'static [u8] = ARCHIVE;
+    let count = be_u32(archive, 4) as usize;
+    let mut pos = 8;
+    let mut i = 0;
+    let mut found = None;
+    while i < count {
+        let name_len = archive[pos] as usize;
+        let entry_name = subslice(archive, pos + 1, name_len);
+        if bytes_eq(entry_name, name.as_bytes()) {
+            found = Some((
+                be_u32(archive, pos + 1 + name_len) as usize,
+                be_u32(archive, pos + 5 + name_len) as usize,
+            ));
+        }
+        pos += 9 + name_len;
+        i += 1;
+    }
+    let Some((offset, len)) = found else {
+        panic!("no such zone in the bundled snapshot");
+    };
+    subslice(archive, pos + offset, len)
+}
+
+/// The paths of the files in the snapshot, in order.
+#[cfg(feature = "bundled")]
+pub(crate) fn names() -> Vec<&'static str> {
+    names_in(ARCHIVE, b"TZDB")
+}
+
+/// Decompress and parse the zone with the given IANA name, like "America/New_York", from the
+/// compressed snapshot embedded by the `bundled-compressed` feature.
+///
+/// Only the zone asked for is decompressed, and nothing is kept, so keep the result rather than
+/// calling this again. This needs no filesystem, so it suits `wasm32-unknown-unknown` and other
+/// targets without one.
+#[cfg(feature = "bundled-compressed")]
+pub fn bundled_zone(name: &str) -> Option<TimeZoneInfo> {
+    let deflated = find(COMPRESSED, b"TZDZ", name)?;
+    let data = miniz_oxide::inflate::decompress_to_vec(deflated).ok()?;
+    TimeZoneInfo::parse_slice(&data, &ParseOptions::default()).ok()
+}
+
+/// The names of the zones in the compressed snapshot, in order.
+#[cfg(feature = "bundled-compressed")]
+pub fn bundled_zone_names() -> Vec<&'static str> {
+    names_in(COMPRESSED, b"TZDZ")
+}
+
+fn find(archive: &'static [u8], magic: &[u8; 4], name: &str) -> Option<&'static [u8]> {
+    let mut found = None;
+    let data_start = for_each_entry(archive, magic, |entry_name, offset, len| {
+        if entry_name == name.as_bytes() {
+            found = Some((offset, len));
+        }
+    })?;
+    let (offset, len) = found?;
+    archive.get(data_start + offset..data_start + offset + len)
+}
+
+fn names_in(archive: &'static [u8], magic: &[u8; 4]) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    for_each_entry(archive, magic, |name, _, _| {
+        names.extend(core::str::from_utf8(name).ok())
+    });
+    names
+}
+
+/// Call `f` with the name, data offset and data length of each index entry, returning where
+/// the data section starts, or `None` if the index is malformed.
+fn for_each_entry(
+    archive: &'static [u8],
+    magic: &[u8; 4],
+    mut f: impl FnMut(&'static [u8], usize, usize),
+) -> Option<usize> {
+    if archive.get(..4)? != magic {
+        return None;
+    }
+    let count = u32::from_be_bytes(archive.get(4..8)?.try_into().ok()?);
+    let mut pos = 8;
+    for _ in 0..count {
+        let name_len = usize::from(*archive.get(pos)?);
+        let entry_name = archive.get(pos + 1..pos + 1 + name_len)?;
+        pos += 1 + name_len;
+        let field = |at: usize| -> Option<usize> {
+            let bytes = archive.get(at..at + 4)?;
+            Some(u32::from_be_bytes(bytes.try_into().ok()?) as usize)
+        };
+        f(entry_name, field(pos)?, field(pos + 4)?);
+        pos += 8;
+    }
+    // The data section starts right after the index.
+    Some(pos)
+}
+
+#[cfg(all(test, feature = "bundled-compressed"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_zone() {
+        let names = bundled_zone_names();
+        assert!(names.len() > 500);
+        assert!(names.contains(&"Europe/London"));
+        assert!(!names.contains(&"zone.tab"));
+        assert!(!names.contains(&"posixrules"));
+
+        let tz = bundled_zone("America/New_York").unwrap();
+        assert_eq!(Some(-4 * 3600), tz.offset_at(1_720_000_000));
+        assert_eq!(Some(-5 * 3600), tz.offset_at(1_700_000_000));
+        assert!(bundled_zone("Nowhere/Special").is_none());
+
+        #[cfg(feature = "bundled")]
+        for name in names {
+            let plain = TimeZoneInfo::parse_slice(get(name).unwrap(), &ParseOptions::default());
+            assert_eq!(plain.unwrap(), bundled_zone(name).unwrap(), "{name}");
+        }
+    }
+}
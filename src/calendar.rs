@@ -0,0 +1,230 @@
+//! Adding calendar durations, like "1 day" or "2 months", to instants on a zone's wall clock.
+
+use crate::civil::{civil_from_days, days_from_civil, days_in_month, SECS_PER_DAY};
+use crate::local::Resolution;
+use crate::{CivilDateTime, LocalTimeError, TimeZoneInfo};
+
+/// The most days either side of 1970 a timestamp can be, which keeps the calendar arithmetic
+/// from overflowing.
+const MAX_DAYS: i64 = i64::MAX / SECS_PER_DAY;
+
+/// An amount of calendar time: months and days, which vary in length and are added on the
+/// wall clock, and seconds, which are added to the instant that gives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalendarDuration {
+    pub months: i64,
+    pub days: i64,
+    pub seconds: i64,
+}
+
+/// What [`TimeZoneInfo::add_calendar`] does when adding to the wall clock lands on a local time
+/// which was skipped or repeated by a transition.
+///
+/// For a repeated time, `Shift` and `Clamp` keep the UT offset the starting instant had if
+/// they can, and otherwise take the earlier of the two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WallClockPolicy {
+    /// Move a skipped time forward by the length of the gap, so that 02:30 in a gap from 02:00
+    /// to 03:00 becomes 03:30.
+    #[default]
+    Shift,
+
+    /// Move a skipped time forward to the end of the gap, 03:00 in that example.
+    Clamp,
+
+    /// Fail with a [`LocalTimeError`] for skipped and repeated times alike.
+    Reject,
+}
+
+impl CalendarDuration {
+    pub fn years(n: i64) -> Self {
+        Self::months(n.saturating_mul(12))
+    }
+
+    pub fn months(n: i64) -> Self {
+        Self {
+            months: n,
+            ..Self::default()
+        }
+    }
+
+    pub fn weeks(n: i64) -> Self {
+        Self::days(n.saturating_mul(7))
+    }
+
+    pub fn days(n: i64) -> Self {
+        Self {
+            days: n,
+            ..Self::default()
+        }
+    }
+
+    pub fn seconds(n: i64) -> Self {
+        Self {
+            seconds: n,
+            ..Self::default()
+        }
+    }
+}
+
+impl core::ops::Add for CalendarDuration {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            months: self.months + other.months,
+            days: self.days + other.days,
+            seconds: self.seconds + other.seconds,
+        }
+    }
+}
+
+impl core::ops::Neg for CalendarDuration {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            months: -self.months,
+            days: -self.days,
+            seconds: -self.seconds,
+        }
+    }
+}
+
+impl TimeZoneInfo {
+    /// Add a calendar duration to the UT timestamp `ut` as a person would with this zone's wall
+    /// clock: months first, keeping the day of the month unless the month is too short for it,
+    /// then days, at the same time of day, and finally seconds of elapsed time.
+    ///
+    /// "1 day" after 12:00 the day before DST starts is 12:00 the next day, 23 hours later.
+    /// `policy` says what happens when the wall clock time wasn't or was doubly observed.
+    /// Fails with [`LocalTimeError::InvalidDate`] if the result is out of range.
+    pub fn add_calendar(
+        &self,
+        ut: i64,
+        duration: CalendarDuration,
+        policy: WallClockPolicy,
+    ) -> Result<i64, LocalTimeError> {
+        let offset = self
+            .local_time_type_at(ut)
+            .map_or(0, |local| i64::from(local.ut_offset_secs));
+        let start = self.to_civil(ut);
+
+        let month_index = start
+            .year
+            .checked_mul(12)
+            .and_then(|m| m.checked_add(i64::from(start.month) - 1))
+            .and_then(|m| m.checked_add(duration.months))
+            .ok_or(LocalTimeError::InvalidDate)?;
+        let (year, month) = (
+            month_index.div_euclid(12),
+            month_index.rem_euclid(12) as u8 + 1,
+        );
+        if year.unsigned_abs() > (MAX_DAYS / 366) as u64 {
+            return Err(LocalTimeError::InvalidDate);
+        }
+        let day = start.day.min(days_in_month(year, month));
+        let days = days_from_civil(year, month, day)
+            .checked_add(duration.days)
+            .filter(|days| days.unsigned_abs() <= MAX_DAYS as u64)
+            .ok_or(LocalTimeError::InvalidDate)?;
+        let (year, month, day) = civil_from_days(days);
+        let civil = CivilDateTime {
+            year,
+            month,
+            day,
+            ..start
+        };
+
+        let local = civil.timestamp().ok_or(LocalTimeError::InvalidDate)?;
+        let instant = match (self.resolve(local), policy) {
+            (Resolution::Single(ut), _) => ut,
+            (Resolution::Ambiguous { earlier, later }, WallClockPolicy::Reject) => {
+                return Err(LocalTimeError::Ambiguous { earlier, later });
+            }
+            (Resolution::Ambiguous { earlier, later }, _) => {
+                if local - later == offset {
+                    later
+                } else {
+                    earlier
+                }
+            }
+            (Resolution::Skipped { later, .. }, WallClockPolicy::Shift) => later,
+            (Resolution::Skipped { transition, .. }, WallClockPolicy::Clamp) => transition,
+            (Resolution::Skipped { earlier, later, .. }, WallClockPolicy::Reject) => {
+                return Err(LocalTimeError::Skipped { earlier, later });
+            }
+        };
+        instant
+            .checked_add(duration.seconds)
+            .ok_or(LocalTimeError::InvalidDate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_add_calendar() {
+        let mut tz = sample();
+        tz.transition_times.clear();
+        tz.transition_types.clear();
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        let civil = |m, d, h, min| {
+            let c = CivilDateTime::new(2024, m, d, h, min, 0);
+            tz.from_civil(c, Default::default()).unwrap()
+        };
+        let add = |ut, duration, policy| tz.add_calendar(ut, duration, policy);
+        use WallClockPolicy::*;
+
+        // A day over the start of DST is 23 hours.
+        let noon = civil(3, 9, 12, 0);
+        let next = add(noon, CalendarDuration::days(1), Shift).unwrap();
+        assert_eq!(23 * 3600, next - noon);
+        assert_eq!(CivilDateTime::new(2024, 3, 10, 12, 0, 0), tz.to_civil(next));
+
+        // Landing in the gap.
+        let early = civil(3, 9, 2, 30);
+        let spring = 1710064800;
+        assert_eq!(
+            Ok(spring + 1800),
+            add(early, CalendarDuration::days(1), Shift)
+        );
+        assert_eq!(Ok(spring), add(early, CalendarDuration::days(1), Clamp));
+        assert!(matches!(
+            add(early, CalendarDuration::days(1), Reject),
+            Err(LocalTimeError::Skipped { .. })
+        ));
+
+        // Landing in the overlap keeps the starting offset.
+        let fall = 1730624400;
+        let pdt = civil(10, 27, 1, 30);
+        assert_eq!(Ok(fall - 1800), add(pdt, CalendarDuration::weeks(1), Shift));
+        let pst = civil(11, 10, 1, 30);
+        assert_eq!(
+            Ok(fall + 1800),
+            add(pst, -CalendarDuration::weeks(1), Clamp)
+        );
+        assert!(matches!(
+            add(pst, -CalendarDuration::weeks(1), Reject),
+            Err(LocalTimeError::Ambiguous { .. })
+        ));
+
+        // Months clamp the day, and seconds are elapsed time.
+        let jan31 = civil(1, 31, 0, 0);
+        let feb = add(jan31, CalendarDuration::months(1), Shift).unwrap();
+        assert_eq!(CivilDateTime::new(2024, 2, 29, 0, 0, 0), tz.to_civil(feb));
+        let leap_day = civil(2, 29, 0, 0);
+        let year = add(leap_day, CalendarDuration::years(1), Shift).unwrap();
+        assert_eq!(CivilDateTime::new(2025, 2, 28, 0, 0, 0), tz.to_civil(year));
+        let over = add(noon, CalendarDuration::seconds(86400), Shift).unwrap();
+        assert_eq!(86400, over - noon);
+        assert_eq!(
+            Err(LocalTimeError::InvalidDate),
+            add(noon, CalendarDuration::years(i64::MAX), Shift)
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Integration with the `chrono` crate.
+
+use alloc::sync::Arc;
+use core::fmt;
+
+use ::chrono::{FixedOffset, MappedLocalTime, NaiveDate, NaiveDateTime, Offset, TimeZone};
+
+use crate::TimeZoneInfo;
+
+/// Two days is more than the largest possible UT offset, so every interval which could contain
+/// a given local time starts no earlier than this before it.
+const SEARCH_WINDOW: i64 = 2 * 24 * 60 * 60;
+
+/// A [`chrono::TimeZone`] backed by parsed TZif data.
+///
+/// This is cheap to clone: the data is shared.
+#[derive(Debug, Clone)]
+pub struct TzifZone(Arc<TimeZoneInfo>);
+
+impl TzifZone {
+    pub fn new(info: TimeZoneInfo) -> Self {
+        Self(Arc::new(info))
+    }
+
+    pub fn from_arc(info: Arc<TimeZoneInfo>) -> Self {
+        Self(info)
+    }
+
+    pub fn info(&self) -> &TimeZoneInfo {
+        &self.0
+    }
+
+    fn offset(&self, type_idx: Option<usize>) -> TzifOffset {
+        let secs = type_idx.map_or(0, |idx| self.0.local_time_types[idx].ut_off_secs);
+        TzifOffset {
+            zone: self.clone(),
+            type_idx,
+            // chrono can't represent offsets of a day or more; v3 files aren't limited that way.
+            fixed: FixedOffset::east_opt(secs).unwrap_or(FixedOffset::east_opt(0).unwrap()),
+        }
+    }
+
+    /// Find the local time types which could be in effect at the given local time, each paired
+    /// with the UT timestamp it maps to, in order of UT.
+    fn candidates(&self, local: i64) -> impl Iterator<Item = (usize, i64)> + '_ {
+        let tz = &*self.0;
+        let first = tz.partition_transitions(|t| t <= local - SEARCH_WINDOW);
+        let last = tz.partition_transitions(|t| t <= local + SEARCH_WINDOW);
+        // Interval `i` is the span before transition `i`, governed by the preceding transition
+        // (or the first local time type, for the initial interval).
+        (first..=last).filter_map(move |i| {
+            let start = if i == 0 {
+                i64::MIN
+            } else {
+                tz.transition_times[i - 1]
+            };
+            let end = if i < tz.transition_times.len() {
+                tz.transition_times[i]
+            } else {
+                i64::MAX
+            };
+            let type_idx = if i == 0 {
+                if tz.local_time_types.is_empty() {
+                    return None;
+                }
+                0
+            } else {
+                tz.transition_types[i - 1] as usize
+            };
+            let ut = local - i64::from(tz.local_time_types[type_idx].ut_off_secs);
+            (start <= ut && ut < end).then_some((type_idx, ut))
+        })
+    }
+}
+
+impl From<TimeZoneInfo> for TzifZone {
+    fn from(info: TimeZoneInfo) -> Self {
+        Self::new(info)
+    }
+}
+
+/// The offset from UTC of a [`TzifZone`] at some instant.
+#[derive(Debug, Clone)]
+pub struct TzifOffset {
+    zone: TzifZone,
+    type_idx: Option<usize>,
+    fixed: FixedOffset,
+}
+
+impl TzifOffset {
+    /// The time zone designation (abbreviation) in effect, if the zone has one.
+    pub fn designation(&self) -> Option<&str> {
+        self.type_idx
+            .map(|idx| self.zone.0.local_time_type(idx).desig)
+    }
+
+    pub fn is_dst(&self) -> bool {
+        self.type_idx
+            .is_some_and(|idx| self.zone.0.local_time_types[idx].is_dst)
+    }
+}
+
+impl Offset for TzifOffset {
+    fn fix(&self) -> FixedOffset {
+        self.fixed
+    }
+}
+
+impl fmt::Display for TzifOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.designation() {
+            Some(desig) if !desig.is_empty() => f.write_str(desig),
+            _ => write!(f, "{}", self.fixed),
+        }
+    }
+}
+
+impl TimeZone for TzifZone {
+    type Offset = TzifOffset;
+
+    fn from_offset(offset: &TzifOffset) -> Self {
+        offset.zone.clone()
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<TzifOffset> {
+        self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> MappedLocalTime<TzifOffset> {
+        let local = local.and_utc().timestamp();
+        if self.0.local_time_types.is_empty() {
+            return MappedLocalTime::Single(self.offset(None));
+        }
+        let mut candidates = self.candidates(local);
+        match (candidates.next(), candidates.next()) {
+            (None, _) => MappedLocalTime::None,
+            (Some((a, _)), None) => MappedLocalTime::Single(self.offset(Some(a))),
+            (Some((a, _)), Some((b, _))) => {
+                MappedLocalTime::Ambiguous(self.offset(Some(a)), self.offset(Some(b)))
+            }
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> TzifOffset {
+        self.offset_from_utc_datetime(&utc.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> TzifOffset {
+        self.offset(self.0.type_idx_at(utc.and_utc().timestamp()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use ::chrono::{DateTime, Utc};
+
+    #[test]
+    fn test_chrono() {
+        let mut tz = sample();
+        // Spring forward at 1_000_000, fall back at 2_000_000.
+        tz.transition_times = vec![1_000_000, 2_000_000];
+        tz.transition_types = vec![1, 0];
+        let zone = TzifZone::new(tz);
+
+        let utc = DateTime::<Utc>::from_timestamp(1_500_000, 0).unwrap();
+        let local = utc.with_timezone(&zone);
+        assert_eq!(-7 * 3600, local.offset().fix().local_minus_utc());
+        assert_eq!("PDT", local.offset().to_string());
+        assert!(local.offset().is_dst());
+
+        let naive = |t: i64| DateTime::from_timestamp(t, 0).unwrap().naive_utc();
+
+        // Before the first transition, the first type is used.
+        let early = zone.from_local_datetime(&naive(0)).single().unwrap();
+        assert_eq!(8 * 3600, early.timestamp());
+
+        // Local times skipped by the spring-forward transition don't exist.
+        let gap = 1_000_000 - 8 * 3600 + 1800;
+        assert_eq!(MappedLocalTime::None, zone.from_local_datetime(&naive(gap)));
+
+        // Local times repeated by the fall-back transition are ambiguous; the earlier is DST.
+        let overlap = 2_000_000 - 7 * 3600 - 1800;
+        let MappedLocalTime::Ambiguous(a, b) = zone.from_local_datetime(&naive(overlap)) else {
+            panic!("expected ambiguity");
+        };
+        assert_eq!("PDT", a.offset().to_string());
+        assert_eq!("PST", b.offset().to_string());
+        assert_eq!(3600, b.timestamp() - a.timestamp());
+    }
+}
@@ -0,0 +1,57 @@
+//! Proleptic Gregorian calendar arithmetic.
+//!
+//! These are Howard Hinnant's `days_from_civil` / `civil_from_days` algorithms.
+
+pub(crate) const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+pub(crate) fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub(crate) fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Number of days from 1970-01-01 to the given date. `month` and `day` are 1-based.
+pub(crate) fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let m = i64::from(month);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// As [`days_from_civil`], but `None` rather than overflowing for years too far from 1970.
+pub(crate) fn checked_days_from_civil(year: i64, month: u8, day: u8) -> Option<i64> {
+    // Every 400 years of the calendar are 146097 days.
+    year.div_euclid(400)
+        .checked_mul(146097)?
+        .checked_add(days_from_civil(year.rem_euclid(400), month, day))
+}
+
+/// The date (year, month, day) which is the given number of days after 1970-01-01.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Day of the week of the given number of days after 1970-01-01, with Sunday as 0.
+pub(crate) fn weekday(days: i64) -> u8 {
+    // 1970-01-01 was a Thursday.
+    (days + 4).rem_euclid(7) as u8
+}
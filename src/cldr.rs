@@ -0,0 +1,445 @@
+//! Human-facing names of zones, such as "New York" and "Eastern Standard Time", keyed by
+//! locale, from the Unicode CLDR's JSON data.
+//!
+//! The names come from two kinds of file in the `cldr-json` distribution:
+//!
+//! - `cldr-dates-full/main/<locale>/timeZoneNames.json`, one per locale, with each zone's
+//!   exemplar city and the long names of the "metazones" zones share, such as
+//!   `America_Eastern`, along with any names particular to a zone.
+//! - `cldr-core/supplemental/metaZones.json`, which says which metazone each zone has belonged
+//!   to and when.
+//!
+//! CLDR keys zones by its own canonical IDs, which are sometimes older names than the IANA
+//! ones, such as `Asia/Calcutta` for `Asia/Kolkata`; a zone which isn't found under its own
+//! name may be under one of the names [`ZoneDatabase::aliases`](crate::ZoneDatabase::aliases)
+//! gives for it.
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::de::Error as _;
+use serde_json::{Map, Value};
+
+use crate::civil::{checked_days_from_civil, SECS_PER_DAY};
+
+/// The names of a zone in one locale, from [`DisplayNames::names`].
+///
+/// Fields are `None` where CLDR has no name for the locale. It has long names for most zones in
+/// widely used locales, but not for zones such as `Etc/GMT+5`, for which a display would fall
+/// back to giving the offset.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ZoneDisplayNames<'a> {
+    /// The city the zone is named after, such as "New York". Where CLDR doesn't give one, it's
+    /// made from the last part of the zone's ID, as CLDR specifies.
+    pub exemplar_city: Cow<'a, str>,
+
+    /// The name of the zone's time whether or not DST is in effect, such as "Eastern Time".
+    pub generic: Option<&'a str>,
+
+    /// The name of the zone's standard time, such as "Eastern Standard Time".
+    pub standard: Option<&'a str>,
+
+    /// The name of the zone's daylight saving time, such as "Eastern Daylight Time".
+    pub daylight: Option<&'a str>,
+}
+
+/// Long names, from a metazone or a zone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct LongNames {
+    generic: Option<String>,
+    standard: Option<String>,
+    daylight: Option<String>,
+}
+
+/// The names of one locale.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct LocaleNames {
+    cities: BTreeMap<String, String>,
+    zones: BTreeMap<String, LongNames>,
+    meta_zones: BTreeMap<String, LongNames>,
+}
+
+/// A period in which a zone belonged to a metazone, from `from` to before `to`, in UT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MetaZoneUse {
+    meta_zone: String,
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+/// Display names of zones in any number of locales, loaded from CLDR's JSON data as described
+/// in the module docs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisplayNames {
+    locales: BTreeMap<String, LocaleNames>,
+    meta_zones: BTreeMap<String, Vec<MetaZoneUse>>,
+}
+
+impl DisplayNames {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a locale's names from the text of its `timeZoneNames.json`. Loading a locale
+    /// already loaded adds to its names, replacing any given again.
+    pub fn add_locale(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let doc: Value = serde_json::from_str(json)?;
+        let main = doc
+            .get("main")
+            .and_then(Value::as_object)
+            .ok_or_else(|| serde_json::Error::custom("no \"main\" object"))?;
+        for (locale, data) in main {
+            let names = data
+                .pointer("/dates/timeZoneNames")
+                .and_then(Value::as_object)
+                .ok_or_else(|| serde_json::Error::custom("no \"timeZoneNames\" object"))?;
+            let entry = self.locales.entry(locale.clone()).or_default();
+            if let Some(zones) = names.get("zone").and_then(Value::as_object) {
+                walk_zones(zones, &mut String::new(), &mut |zone, value| {
+                    if let Some(city) = value.get("exemplarCity").and_then(Value::as_str) {
+                        entry.cities.insert(zone.to_string(), city.to_string());
+                    }
+                    if let Some(long) = long_names(value) {
+                        entry.zones.insert(zone.to_string(), long);
+                    }
+                });
+            }
+            if let Some(meta_zones) = names.get("metazone").and_then(Value::as_object) {
+                for (meta_zone, value) in meta_zones {
+                    if let Some(long) = long_names(value) {
+                        entry.meta_zones.insert(meta_zone.clone(), long);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Load which metazones zones belong to from the text of `metaZones.json`, replacing what
+    /// was loaded before for each zone it lists.
+    pub fn add_meta_zones(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let doc: Value = serde_json::from_str(json)?;
+        let zones = doc
+            .pointer("/supplemental/metaZones/metazoneInfo/timezone")
+            .and_then(Value::as_object)
+            .ok_or_else(|| serde_json::Error::custom("no \"metazoneInfo\" object"))?;
+        let mut result = Ok(());
+        walk_zones(zones, &mut String::new(), &mut |zone, value| {
+            let uses = value.as_array().into_iter().flatten().map(|entry| {
+                let entry = entry.get("usesMetazone");
+                let field = |name| entry.and_then(|e| e.get(name)).and_then(Value::as_str);
+                let time = |name| field(name).map(|t| parse_time(t).ok_or(t)).transpose();
+                Ok(MetaZoneUse {
+                    meta_zone: field("_mzone").ok_or("no _mzone")?.to_string(),
+                    from: time("_from")?,
+                    to: time("_to")?,
+                })
+            });
+            match uses.collect::<Result<_, &str>>() {
+                Ok(uses) => {
+                    self.meta_zones.insert(zone.to_string(), uses);
+                }
+                Err(bad) => {
+                    result = Err(serde_json::Error::custom(format!(
+                        "bad metazone use for {zone}: {bad:?}"
+                    )));
+                }
+            }
+        });
+        result
+    }
+
+    /// The locales loaded, as CLDR names them, such as `en` and `en-GB`.
+    pub fn locales(&self) -> impl Iterator<Item = &str> + '_ {
+        self.locales.keys().map(String::as_str)
+    }
+
+    /// The names of a zone in a locale, as they are now.
+    ///
+    /// A locale with no names of its own, or none for the zone, falls back to the locale it's
+    /// a variant of, `en` for `en-GB`; names particular to the zone take precedence over those
+    /// of its metazone. Returns `None` if the zone is in neither the locale's names nor the
+    /// metazone data, or the locale isn't loaded.
+    pub fn names(&self, locale: &str, zone: &str) -> Option<ZoneDisplayNames<'_>> {
+        let meta_zone = self
+            .meta_zones
+            .get(zone)
+            .and_then(|uses| uses.iter().find(|u| u.to.is_none()));
+        self.resolve(locale, zone, meta_zone)
+    }
+
+    /// The names of a zone in a locale at the given UT time, as [`names`](Self::names) but with
+    /// the metazone the zone belonged to then: "Eastern Time" for `America/Indiana/Knox` in
+    /// 1995, say, rather than the "Central Time" it has now.
+    pub fn names_at(&self, locale: &str, zone: &str, ut: i64) -> Option<ZoneDisplayNames<'_>> {
+        let meta_zone = self.meta_zones.get(zone).and_then(|uses| {
+            uses.iter()
+                .find(|u| u.from.is_none_or(|from| from <= ut) && u.to.is_none_or(|to| ut < to))
+        });
+        self.resolve(locale, zone, meta_zone)
+    }
+
+    fn resolve(
+        &self,
+        locale: &str,
+        zone: &str,
+        meta_zone: Option<&MetaZoneUse>,
+    ) -> Option<ZoneDisplayNames<'_>> {
+        let chain: Vec<&LocaleNames> = fallbacks(locale)
+            .filter_map(|locale| self.locales.get(locale))
+            .collect();
+        let known = self.meta_zones.contains_key(zone)
+            || chain
+                .iter()
+                .any(|names| names.cities.contains_key(zone) || names.zones.contains_key(zone));
+        if chain.is_empty() || !known {
+            return None;
+        }
+
+        let exemplar_city = chain
+            .iter()
+            .find_map(|names| names.cities.get(zone))
+            .map_or_else(
+                || {
+                    let last = zone.rsplit('/').next().unwrap_or(zone);
+                    Cow::Owned(last.replace('_', " "))
+                },
+                |city| Cow::Borrowed(city.as_str()),
+            );
+        let long = |field: fn(&LongNames) -> &Option<String>| {
+            chain.iter().find_map(|names| {
+                let own = names
+                    .zones
+                    .get(zone)
+                    .and_then(|long| field(long).as_deref());
+                own.or_else(|| {
+                    let meta = names.meta_zones.get(&meta_zone?.meta_zone)?;
+                    field(meta).as_deref()
+                })
+            })
+        };
+        Some(ZoneDisplayNames {
+            exemplar_city,
+            generic: long(|long| &long.generic),
+            standard: long(|long| &long.standard),
+            daylight: long(|long| &long.daylight),
+        })
+    }
+}
+
+/// Call `f` with each zone ID and its value in CLDR's nesting of zones by the parts of their
+/// IDs, in which `America/Argentina/Buenos_Aires` is under `America` and `Argentina`.
+///
+/// A value is taken to be a zone's if it isn't an object, or has a key starting with
+/// `exemplarCity`, `long`, `short` or `_`, which no part of an ID does.
+fn walk_zones(map: &Map<String, Value>, prefix: &mut String, f: &mut impl FnMut(&str, &Value)) {
+    for (part, value) in map {
+        let len = prefix.len();
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+        prefix.push_str(part);
+        match value.as_object() {
+            Some(inner) if !inner.keys().any(|key| is_name_key(key)) => {
+                walk_zones(inner, prefix, f);
+            }
+            _ => f(prefix, value),
+        }
+        prefix.truncate(len);
+    }
+}
+
+fn is_name_key(key: &str) -> bool {
+    ["exemplarCity", "long", "short", "_"]
+        .iter()
+        .any(|name| key.starts_with(name))
+}
+
+/// The long names in a zone's or metazone's value, if it has any.
+fn long_names(value: &Value) -> Option<LongNames> {
+    let long = value.get("long")?;
+    let name = |kind| {
+        long.get(kind)
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+    };
+    Some(LongNames {
+        generic: name("generic"),
+        standard: name("standard"),
+        daylight: name("daylight"),
+    })
+}
+
+/// Parse a time in the `YYYY-MM-DD HH:MM` form of `metaZones.json`, which is UT.
+fn parse_time(time: &str) -> Option<i64> {
+    let (date, clock) = time.split_once(' ')?;
+    let mut date = date.splitn(3, '-');
+    let year: i64 = date.next()?.parse().ok()?;
+    let month: u8 = date.next()?.parse().ok()?;
+    let day: u8 = date.next()?.parse().ok()?;
+    let (hours, minutes) = clock.split_once(':')?;
+    let (hours, minutes): (i64, i64) = (hours.parse().ok()?, minutes.parse().ok()?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hours > 24 || minutes > 59 {
+        return None;
+    }
+    checked_days_from_civil(year, month, day)?
+        .checked_mul(SECS_PER_DAY)?
+        .checked_add(hours * 3600 + minutes * 60)
+}
+
+/// The locale, then each it falls back to by dropping its last subtag.
+fn fallbacks(locale: &str) -> impl Iterator<Item = &str> {
+    core::iter::successors(Some(locale), |locale| {
+        locale.rfind(['-', '_']).map(|idx| &locale[..idx])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EN: &str = r#"{
+  "main": {
+    "en": {
+      "identity": { "language": "en" },
+      "dates": {
+        "timeZoneNames": {
+          "zone": {
+            "America": {
+              "New_York": { "exemplarCity": "New York" },
+              "Argentina": { "Buenos_Aires": { "exemplarCity": "Buenos Aires" } }
+            },
+            "Europe": {
+              "London": { "long": { "daylight": "British Summer Time" } }
+            },
+            "Etc": { "UTC": { "long": { "standard": "Coordinated Universal Time" } } }
+          },
+          "metazone": {
+            "America_Central": {
+              "long": {
+                "generic": "Central Time",
+                "standard": "Central Standard Time",
+                "daylight": "Central Daylight Time"
+              }
+            },
+            "America_Eastern": {
+              "long": {
+                "generic": "Eastern Time",
+                "standard": "Eastern Standard Time",
+                "daylight": "Eastern Daylight Time"
+              },
+              "short": { "generic": "ET" }
+            },
+            "GMT": { "long": { "standard": "Greenwich Mean Time" } }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+    const EN_GB: &str = r#"{
+  "main": {
+    "en-GB": {
+      "dates": {
+        "timeZoneNames": {
+          "metazone": {
+            "Europe_Central": { "long": { "standard": "Central European Standard Time" } }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+    const META_ZONES: &str = r#"{
+  "supplemental": {
+    "metaZones": {
+      "metazoneInfo": {
+        "timezone": {
+          "America": {
+            "New_York": [ { "usesMetazone": { "_mzone": "America_Eastern" } } ],
+            "Indiana": {
+              "Knox": [
+                { "usesMetazone": { "_to": "1991-10-27 07:00", "_mzone": "America_Central" } },
+                {
+                  "usesMetazone": {
+                    "_to": "2006-04-02 07:00",
+                    "_from": "1991-10-27 07:00",
+                    "_mzone": "America_Eastern"
+                  }
+                },
+                { "usesMetazone": { "_from": "2006-04-02 07:00", "_mzone": "America_Central" } }
+              ]
+            }
+          },
+          "Europe": {
+            "London": [ { "usesMetazone": { "_mzone": "GMT" } } ]
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+    #[test]
+    fn test_display_names() {
+        let mut names = DisplayNames::new();
+        names.add_locale(EN).unwrap();
+        names.add_locale(EN_GB).unwrap();
+        names.add_meta_zones(META_ZONES).unwrap();
+        assert_eq!(vec!["en", "en-GB"], names.locales().collect::<Vec<_>>());
+
+        let ny = names.names("en", "America/New_York").unwrap();
+        assert_eq!(
+            ZoneDisplayNames {
+                exemplar_city: Cow::Borrowed("New York"),
+                generic: Some("Eastern Time"),
+                standard: Some("Eastern Standard Time"),
+                daylight: Some("Eastern Daylight Time"),
+            },
+            ny
+        );
+        // Falling back from en-GB to en.
+        assert_eq!(Some(ny), names.names("en-GB", "America/New_York"));
+
+        // The zone's own name, the metazone's, and a city made from the ID.
+        let london = names.names("en", "Europe/London").unwrap();
+        assert_eq!("London", london.exemplar_city);
+        assert_eq!(Some("Greenwich Mean Time"), london.standard);
+        assert_eq!(Some("British Summer Time"), london.daylight);
+        assert_eq!(None, london.generic);
+
+        let knox = names.names("en", "America/Indiana/Knox").unwrap();
+        assert_eq!("Knox", knox.exemplar_city);
+        assert_eq!(Some("Central Time"), knox.generic);
+        // 1995-01-01.
+        let knox = names
+            .names_at("en", "America/Indiana/Knox", 788_918_400)
+            .unwrap();
+        assert_eq!(Some("Eastern Time"), knox.generic);
+
+        let ba = names.names("en", "America/Argentina/Buenos_Aires").unwrap();
+        assert_eq!("Buenos Aires", ba.exemplar_city);
+        assert_eq!(None, ba.standard);
+
+        assert_eq!(None, names.names("en", "Mars/Olympus_Mons"));
+        assert_eq!(None, names.names("fr", "America/New_York"));
+
+        assert!(names.add_locale("{}").is_err());
+        assert!(names.add_meta_zones("[").is_err());
+        let bad = META_ZONES.replace("2006-04-02 07:00", "April 2006");
+        assert!(names.add_meta_zones(&bad).is_err());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(Some(0), parse_time("1970-01-01 00:00"));
+        assert_eq!(Some(1_143_961_200), parse_time("2006-04-02 07:00"));
+        assert_eq!(None, parse_time("2006-13-02 07:00"));
+        assert_eq!(None, parse_time("2006-04-02"));
+    }
+}
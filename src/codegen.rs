@@ -0,0 +1,495 @@
+//! Rendering zones as Rust source for static tables, for vendoring a fixed tzdata snapshot.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::posix::is_dst_at;
+use crate::{
+    ConsistencyError, LocalTimeType, LocalTimeTypeRecord, PosixDst, PosixRule, PosixTz,
+    TimeZoneInfo,
+};
+
+/// A zone held in static data, as written by [`to_rust_source`].
+///
+/// Like the tables of `chrono-tz`, this is the local time type before the first change and the
+/// UT time of each change with the type from then on, in order. After the last change, the
+/// footer governs, if there is one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StaticZone {
+    /// The zone's IANA name, like "America/New_York".
+    pub name: &'static str,
+    pub first: StaticSpan,
+    pub rest: &'static [(i64, StaticSpan)],
+    pub footer: Option<StaticFooter>,
+}
+
+/// The footer's TZ string of a [`StaticZone`], parsed, so that looking up a time after the
+/// last change neither parses nor allocates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StaticFooter {
+    pub std: StaticSpan,
+
+    /// DST, and the rules for when it starts and ends.
+    pub dst: Option<(StaticSpan, PosixRule, PosixRule)>,
+}
+
+/// A local time type in a [`StaticZone`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StaticSpan {
+    pub ut_offset: i32,
+    pub is_dst: bool,
+    pub designation: &'static str,
+}
+
+impl StaticZone {
+    /// The local time type in effect at the given UT timestamp.
+    pub fn local_time_type_at(&self, ut: i64) -> LocalTimeType<'static> {
+        match (self.span_at(ut), &self.footer) {
+            ((_, true), Some(footer)) => footer.local_time_type_at(ut),
+            ((span, _), _) => span.local_time_type(),
+        }
+    }
+
+    /// The span in effect at the given UT timestamp, and whether that's after the last change,
+    /// where the footer governs if there is one.
+    pub(crate) fn span_at(&self, ut: i64) -> (StaticSpan, bool) {
+        let idx = self.rest.partition_point(|&(at, _)| at <= ut);
+        let span = match idx {
+            0 => self.first,
+            idx => self.rest[idx - 1].1,
+        };
+        (span, idx == self.rest.len())
+    }
+
+    /// The UT offset in effect at the given UT timestamp, in seconds.
+    pub fn offset_at(&self, ut: i64) -> i32 {
+        self.local_time_type_at(ut).ut_offset_secs
+    }
+}
+
+impl StaticFooter {
+    /// The local time type in effect at the given UT timestamp.
+    pub fn local_time_type_at(&self, ut: i64) -> LocalTimeType<'static> {
+        match self.dst {
+            Some((dst, start, end))
+                if is_dst_at(self.std.ut_offset, dst.ut_offset, &start, &end, ut) =>
+            {
+                dst.local_time_type()
+            }
+            _ => self.std.local_time_type(),
+        }
+    }
+}
+
+impl From<&StaticFooter> for PosixTz {
+    fn from(footer: &StaticFooter) -> Self {
+        PosixTz {
+            std_abbr: footer.std.designation.to_string(),
+            std_offset: footer.std.ut_offset,
+            dst: footer.dst.map(|(dst, start, end)| PosixDst {
+                abbr: dst.designation.to_string(),
+                offset: dst.ut_offset,
+                start,
+                end,
+            }),
+        }
+    }
+}
+
+impl StaticSpan {
+    pub(crate) fn local_time_type(self) -> LocalTimeType<'static> {
+        LocalTimeType {
+            desig: self.designation,
+            ut_offset_secs: self.ut_offset,
+            is_dst: self.is_dst,
+        }
+    }
+}
+
+impl TryFrom<&StaticZone> for TimeZoneInfo {
+    type Error = ConsistencyError;
+
+    /// A zone with the static zone's changes as its transitions and its footer. Fails with
+    /// [`ConsistencyError::TooManyLocalTimeTypes`] if there are more than 256 distinct spans.
+    fn try_from(zone: &StaticZone) -> Result<Self, ConsistencyError> {
+        let mut tz = TimeZoneInfo {
+            version: 2,
+            ..TimeZoneInfo::default()
+        };
+        let mut types = Vec::<StaticSpan>::new();
+        let spans = core::iter::once(&zone.first).chain(zone.rest.iter().map(|(_, span)| span));
+        for (idx, span) in spans.enumerate() {
+            let typ = match types.iter().position(|t| t == span) {
+                Some(typ) => typ,
+                None => {
+                    // Designations too long to be indexed fall back to the first.
+                    let desig_idx = tz.find_or_add_designation(span.designation).unwrap_or(0);
+                    tz.local_time_types.push(LocalTimeTypeRecord {
+                        ut_off_secs: span.ut_offset,
+                        is_dst: span.is_dst,
+                        desig_idx,
+                    });
+                    types.push(*span);
+                    types.len() - 1
+                }
+            };
+            if idx > 0 {
+                tz.transition_times.push(zone.rest[idx - 1].0);
+                let typ = u8::try_from(typ).map_err(|_| ConsistencyError::TooManyLocalTimeTypes)?;
+                tz.transition_types.push(typ);
+            }
+        }
+        tz.footer = zone.footer.as_ref().map(PosixTz::from);
+        if tz.footer.as_ref().is_some_and(PosixTz::uses_v3_extensions) {
+            tz.version = 3;
+        }
+        Ok(tz)
+    }
+}
+
+/// Render the given zones, which must have distinct names, as Rust source defining a
+/// [`StaticZone`] constant for each, `ZONES`, a static slice of them all sorted by name, and a
+/// function `zone` which looks one up by name in it.
+///
+/// The changes the footer of each zone gives after its last transition are written out up to
+/// the UT time `until`, and the footer kept for times after. Constants are named after the zones
+/// in upper case, with "/" as "__", a "+" or "-" before a digit as "PLUS" or "MINUS", and other
+/// punctuation as "_", so "Etc/GMT-1" is `ETC__GMTMINUS1`. The source refers to the types by
+/// their paths under `::tzif`, like `::tzif::StaticZone`.
+pub fn to_rust_source<'z>(
+    zones: impl IntoIterator<Item = (&'z str, &'z TimeZoneInfo)>,
+    until: i64,
+) -> String {
+    let zones: BTreeMap<&str, &TimeZoneInfo> = zones.into_iter().collect();
+    let mut out = String::from("// Generated by tzif from TZif data. Do not edit.\n");
+    for (&name, tz) in &zones {
+        let first = tz.local_time_types.first().map_or(
+            LocalTimeType {
+                desig: "-00",
+                ut_offset_secs: 0,
+                is_dst: false,
+            },
+            |_| tz.local_time_type(0),
+        );
+        let mut rest: Vec<(i64, LocalTimeType<'_>)> = (0..tz.transition_times.len())
+            .map(|idx| (tz.transition_times[idx], tz.transition(idx).local))
+            .collect();
+        if let Some(footer) = &tz.footer {
+            // The footer applies from the last transition on, even where it disagrees with it.
+            // Without transitions, it applies throughout, so there's nothing to write out.
+            if let Some(&(from, _)) = rest.last() {
+                let last = rest.len() - 1;
+                rest[last].1 = footer.local_time_type_at(from);
+                for (at, _, after) in tz.changes_between(from, until) {
+                    rest.push((at, after));
+                }
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "\npub const {}: ::tzif::StaticZone = ::tzif::StaticZone {{",
+            const_name(name)
+        );
+        let _ = writeln!(out, "    name: {name:?},");
+        let _ = writeln!(out, "    first: {},", span(&first));
+        let _ = writeln!(out, "    rest: &[");
+        for (at, local) in &rest {
+            let _ = writeln!(out, "        ({at}, {}),", span(local));
+        }
+        let _ = writeln!(out, "    ],");
+        match &tz.footer {
+            Some(footer) => {
+                let _ = writeln!(out, "    footer: Some(::tzif::StaticFooter {{");
+                let std = LocalTimeType {
+                    desig: &footer.std_abbr,
+                    ut_offset_secs: footer.std_offset,
+                    is_dst: false,
+                };
+                let _ = writeln!(out, "        std: {},", span(&std));
+                match &footer.dst {
+                    Some(dst) => {
+                        let local = LocalTimeType {
+                            desig: &dst.abbr,
+                            ut_offset_secs: dst.offset,
+                            is_dst: true,
+                        };
+                        let _ = writeln!(out, "        dst: Some((");
+                        let _ = writeln!(out, "            {},", span(&local));
+                        let _ = writeln!(out, "            {},", rule(&dst.start));
+                        let _ = writeln!(out, "            {},", rule(&dst.end));
+                        let _ = writeln!(out, "        )),");
+                    }
+                    None => {
+                        let _ = writeln!(out, "        dst: None,");
+                    }
+                }
+                let _ = writeln!(out, "    }}),");
+            }
+            None => {
+                let _ = writeln!(out, "    footer: None,");
+            }
+        }
+        let _ = writeln!(out, "}};");
+    }
+
+    let _ = writeln!(out, "\npub static ZONES: &[&::tzif::StaticZone] = &[");
+    for name in zones.keys() {
+        let _ = writeln!(out, "    &{},", const_name(name));
+    }
+    let _ = writeln!(out, "];");
+    let _ = writeln!(
+        out,
+        "\n/// The zone with the given name, if it's one of `ZONES`."
+    );
+    let _ = writeln!(
+        out,
+        "pub fn zone(name: &str) -> Option<&'static ::tzif::StaticZone> {{"
+    );
+    let _ = writeln!(
+        out,
+        "    let idx = ZONES.binary_search_by(|zone| zone.name.cmp(name)).ok()?;"
+    );
+    let _ = writeln!(out, "    Some(ZONES[idx])");
+    let _ = writeln!(out, "}}");
+    out
+}
+
+/// Write the zones with the given names from the zoneinfo directory at `zoneinfo` to the file
+/// `out` as Rust source, for a build script to embed them in a program which then needs no
+/// files at run time.
+///
+/// The source is that of [`to_rust_source`], with the footers written out up to `until`, and a
+/// function `zone(name: &str) -> Option<&'static StaticZone>` which looks them up. It's meant
+/// to be included with `include!(concat!(env!("OUT_DIR"), "/zones.rs"))` or similar. Cargo is
+/// told to run the build script again when any of the zone files change.
+#[cfg(feature = "std")]
+pub fn embed(
+    zoneinfo: impl AsRef<std::path::Path>,
+    names: &[&str],
+    until: i64,
+    out: impl AsRef<std::path::Path>,
+) -> Result<(), crate::ZoneError> {
+    let db = crate::ZoneDatabase::open(zoneinfo.as_ref());
+    let zones = names
+        .iter()
+        .map(|name| db.get(name))
+        .collect::<Result<Vec<_>, _>>()?;
+    for name in names {
+        std::println!(
+            "cargo:rerun-if-changed={}",
+            zoneinfo.as_ref().join(name).display()
+        );
+    }
+    let source = to_rust_source(
+        names.iter().copied().zip(zones.iter().map(|tz| &**tz)),
+        until,
+    );
+    std::fs::write(out, source).map_err(crate::ZoneError::Io)
+}
+
+#[cfg(feature = "std")]
+impl crate::ZoneDatabase {
+    /// Parse every zone in the database, as listed by [`zone_names`](Self::zone_names), and
+    /// render them as Rust source with [`to_rust_source`].
+    pub fn to_rust_source(&self, until: i64) -> Result<String, crate::ZoneError> {
+        let names = self.zone_names()?;
+        let zones = names
+            .iter()
+            .map(|name| self.get(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(to_rust_source(
+            names
+                .iter()
+                .map(String::as_str)
+                .zip(zones.iter().map(|tz| &**tz)),
+            until,
+        ))
+    }
+}
+
+fn span(local: &LocalTimeType<'_>) -> String {
+    alloc::format!(
+        "::tzif::StaticSpan {{ ut_offset: {}, is_dst: {}, designation: {:?} }}",
+        local.ut_offset_secs,
+        local.is_dst,
+        local.desig
+    )
+}
+
+fn rule(rule: &PosixRule) -> String {
+    alloc::format!(
+        "::tzif::PosixRule {{ date: ::tzif::RuleDate::{:?}, time: {} }}",
+        rule.date,
+        rule.time
+    )
+}
+
+/// The name of the constant for the zone with the given name.
+fn const_name(name: &str) -> String {
+    let mut out = String::new();
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        let before_digit = chars.peek().is_some_and(char::is_ascii_digit);
+        match c {
+            '/' => out.push_str("__"),
+            '+' if before_digit => out.push_str("PLUS"),
+            '-' if before_digit => out.push_str("MINUS"),
+            c if c.is_ascii_alphanumeric() => out.push(c.to_ascii_uppercase()),
+            _ => out.push('_'),
+        }
+    }
+    if out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use crate::RuleDate;
+
+    #[test]
+    fn test_const_name() {
+        assert_eq!("AMERICA__NEW_YORK", const_name("America/New_York"));
+        assert_eq!("ETC__GMTMINUS1", const_name("Etc/GMT-1"));
+        assert_eq!("ETC__GMTPLUS10", const_name("Etc/GMT+10"));
+        assert_eq!(
+            "AMERICA__PORT_AU_PRINCE",
+            const_name("America/Port-au-Prince")
+        );
+    }
+
+    #[test]
+    fn test_static_zone() {
+        let mut tz = sample();
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        let source = to_rust_source([("Test/Zone", &tz)], 31_536_000);
+        assert!(source.contains("pub const TEST__ZONE: ::tzif::StaticZone"));
+        assert!(source.contains("        (200, ::tzif::StaticSpan { ut_offset: -28800, is_dst: false, designation: \"PST\" }),"));
+        assert!(source.contains("        std: ::tzif::StaticSpan { ut_offset: -28800, is_dst: false, designation: \"PST\" },"));
+        assert!(source.contains("            ::tzif::PosixRule { date: ::tzif::RuleDate::MonthWeekDay { month: 3, week: 2, weekday: 0 }, time: 7200 },"));
+        assert!(source.contains("    &TEST__ZONE,\n];"));
+        assert!(source.contains("pub fn zone(name: &str) -> Option<&'static ::tzif::StaticZone> {"));
+
+        // The same zone, written out by hand as the source above would give it.
+        static REST: &[(i64, StaticSpan)] = &[
+            (100, PDT),
+            (200, PST),
+            (300, PST),
+            (5_738_400, PDT),
+            (26_298_000, PST),
+        ];
+        const PST: StaticSpan = StaticSpan {
+            ut_offset: -8 * 3600,
+            is_dst: false,
+            designation: "PST",
+        };
+        const PDT: StaticSpan = StaticSpan {
+            ut_offset: -7 * 3600,
+            is_dst: true,
+            designation: "PDT",
+        };
+        let zone = StaticZone {
+            name: "Test/Zone",
+            first: PST,
+            rest: REST,
+            footer: Some(StaticFooter {
+                std: PST,
+                dst: Some((
+                    PDT,
+                    PosixRule {
+                        date: RuleDate::MonthWeekDay {
+                            month: 3,
+                            week: 2,
+                            weekday: 0,
+                        },
+                        time: 7200,
+                    },
+                    PosixRule {
+                        date: RuleDate::MonthWeekDay {
+                            month: 11,
+                            week: 1,
+                            weekday: 0,
+                        },
+                        time: 7200,
+                    },
+                )),
+            }),
+        };
+        for ut in [
+            i64::MIN,
+            99,
+            100,
+            250,
+            300,
+            5_738_399,
+            5_738_400,
+            1_720_000_000,
+            i64::MAX,
+        ] {
+            assert_eq!(
+                tz.local_time_type_at(ut),
+                Some(zone.local_time_type_at(ut)),
+                "{ut}"
+            );
+        }
+        assert_eq!(-7 * 3600, zone.offset_at(1_720_000_000));
+
+        let back = TimeZoneInfo::try_from(&zone).unwrap();
+        assert_eq!(tz.footer, back.footer);
+        for ut in [i64::MIN, 150, 250, 1_720_000_000] {
+            assert_eq!(
+                tz.local_time_type_at(ut),
+                back.local_time_type_at(ut),
+                "{ut}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_static_zone_too_many_spans() {
+        let span = |ut_offset| StaticSpan {
+            ut_offset,
+            is_dst: false,
+            designation: "XXX",
+        };
+        let rest: Vec<(i64, StaticSpan)> = (1..=256).map(|i| (i64::from(i), span(i))).collect();
+        let mut zone = StaticZone {
+            name: "Test/Zone",
+            first: span(0),
+            rest: Vec::leak(rest),
+            footer: None,
+        };
+        assert_eq!(
+            Err(ConsistencyError::TooManyLocalTimeTypes),
+            TimeZoneInfo::try_from(&zone)
+        );
+        zone.rest = &zone.rest[..255];
+        let tz = TimeZoneInfo::try_from(&zone).unwrap();
+        assert_eq!(256, tz.local_time_types.len());
+        assert_eq!(Some(255), tz.transition_types.last().copied());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_embed() {
+        let root =
+            std::env::temp_dir().join(alloc::format!("tzif-embed-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("Test")).unwrap();
+        std::fs::write(root.join("Test/Zone"), sample().to_bytes()).unwrap();
+        let out = root.join("zones.rs");
+        embed(&root, &["Test/Zone"], 0, &out).unwrap();
+        let source = std::fs::read_to_string(&out).unwrap();
+        assert_eq!(to_rust_source([("Test/Zone", &sample())], 0), source);
+        assert!(matches!(
+            embed(&root, &["Test/Missing"], 0, &out),
+            Err(crate::ZoneError::NotFound(_))
+        ));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
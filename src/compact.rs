@@ -0,0 +1,317 @@
+//! A compressed in-memory form of a zone, for holding many zones in little memory.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{LeapSecondRecord, LocalTimeType, LocalTimeTypeRecord, PosixTz, TimeZoneInfo, Zone};
+
+/// How many transitions are decoded from each checkpoint at most.
+const CHECKPOINT_INTERVAL: usize = 16;
+
+/// A zone's local time types, held in a fraction of the memory [`Zone`] needs, with the same
+/// lookups. Made with [`CompactZone::new`].
+///
+/// Each transition is stored as one LEB128 varint holding the time since the transition
+/// before, in hours or minutes where it's a whole number of them, and the index of its local
+/// time type in the low bits: typically three bytes, against nine. One transition in every
+/// sixteen has its absolute time kept aside instead, so lookups needn't decode from the start.
+/// Local time types which are the same but for their indicators are merged, and each
+/// designation is stored once. Leap seconds, indicators and the version aren't kept, as lookups
+/// don't use them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompactZone {
+    transitions: Box<[u8]>,
+    /// The time of every sixteenth transition, starting with the first.
+    checkpoints: Box<[i64]>,
+    /// Where in `transitions` each checkpointed transition starts.
+    checkpoint_pos: Box<[u32]>,
+    types: Box<[CompactType]>,
+    /// How many low bits of each transition's varint hold its type index.
+    type_bits: u32,
+    designations: Box<[Box<str>]>,
+    footer: Option<PosixTz>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct CompactType {
+    ut_off_secs: i32,
+    is_dst: bool,
+    desig: u8,
+}
+
+impl CompactZone {
+    pub fn new(zone: &Zone) -> Self {
+        let mut designations: Vec<Box<str>> = Vec::new();
+        let mut types: Vec<CompactType> = Vec::new();
+        // Each of the zone's local time types' index among the merged ones.
+        let mut type_map = Vec::with_capacity(zone.local_time_types().len());
+        for (idx, typ) in zone.local_time_types().iter().enumerate() {
+            let desig = zone.designation(idx).unwrap_or("");
+            let desig = match designations.iter().position(|d| **d == *desig) {
+                Some(pos) => pos,
+                None => {
+                    designations.push(desig.into());
+                    designations.len() - 1
+                }
+            };
+            let typ = CompactType {
+                ut_off_secs: typ.ut_off_secs,
+                is_dst: typ.is_dst,
+                // There are no more designations than local time types, which number 256 at
+                // most.
+                desig: desig as u8,
+            };
+            type_map.push(match types.iter().position(|&t| t == typ) {
+                Some(pos) => pos as u8,
+                None => {
+                    types.push(typ);
+                    (types.len() - 1) as u8
+                }
+            });
+        }
+
+        let type_bits = usize::BITS - types.len().saturating_sub(1).leading_zeros();
+        let mut transitions = Vec::new();
+        let mut checkpoints = Vec::new();
+        let mut checkpoint_pos = Vec::new();
+        let mut prev = 0i64;
+        for (idx, (&at, &typ)) in zone
+            .transition_times()
+            .iter()
+            .zip(zone.transition_types())
+            .enumerate()
+        {
+            let delta = if idx % CHECKPOINT_INTERVAL == 0 {
+                checkpoints.push(at);
+                checkpoint_pos.push(transitions.len() as u32);
+                0
+            } else {
+                // Times ascend, so the difference is never negative, though it may not fit in
+                // an i64.
+                at.wrapping_sub(prev) as u64
+            };
+            // The low two bits give the unit: hours, minutes or seconds.
+            let delta = match (delta % 3600, delta % 60) {
+                (0, _) => u128::from(delta / 3600) << 2,
+                (_, 0) => u128::from(delta / 60) << 2 | 1,
+                _ => u128::from(delta) << 2 | 2,
+            };
+            let typ = u128::from(type_map[usize::from(typ)]);
+            write_varint(&mut transitions, delta << type_bits | typ);
+            prev = at;
+        }
+
+        Self {
+            transitions: transitions.into(),
+            checkpoints: checkpoints.into(),
+            checkpoint_pos: checkpoint_pos.into(),
+            types: types.into(),
+            type_bits,
+            designations: designations.into(),
+            footer: zone.footer().cloned(),
+        }
+    }
+
+    /// The local time type in effect at the given UT timestamp, as
+    /// [`TimeZoneInfo::local_time_type_at`](crate::TimeZoneInfo::local_time_type_at) gives it.
+    pub fn local_time_type_at(&self, ut: i64) -> Option<LocalTimeType<'_>> {
+        let (idx, last) = self.type_idx_at(ut);
+        if let Some(footer) = &self.footer {
+            if last {
+                return Some(footer.local_time_type_at(ut));
+            }
+        }
+        let typ = self.types.get(idx)?;
+        Some(LocalTimeType {
+            desig: &self.designations[usize::from(typ.desig)],
+            ut_offset_secs: typ.ut_off_secs,
+            is_dst: typ.is_dst,
+        })
+    }
+
+    /// The UT offset in effect at the given UT timestamp, in seconds.
+    pub fn offset_at(&self, ut: i64) -> Option<i32> {
+        self.local_time_type_at(ut)
+            .map(|local| local.ut_offset_secs)
+    }
+
+    /// Whether DST is in effect at the given UT timestamp.
+    pub fn is_dst_at(&self, ut: i64) -> bool {
+        self.local_time_type_at(ut)
+            .is_some_and(|local| local.is_dst)
+    }
+
+    /// The time zone designation (abbreviation) in effect at the given UT timestamp.
+    pub fn designation_at(&self, ut: i64) -> Option<&str> {
+        self.local_time_type_at(ut).map(|local| local.desig)
+    }
+
+    /// The index of the merged local time type the transitions give at the given time, and
+    /// whether the time is on or after the last transition, or there are none.
+    fn type_idx_at(&self, ut: i64) -> (usize, bool) {
+        let Some(checkpoint) = self
+            .checkpoints
+            .partition_point(|&t| t <= ut)
+            .checked_sub(1)
+        else {
+            return (0, self.checkpoints.is_empty());
+        };
+        let mut at = self.checkpoints[checkpoint];
+        let mut pos = self.checkpoint_pos[checkpoint] as usize;
+        let (_, mut typ) = self.decode(&mut pos);
+        for _ in 1..CHECKPOINT_INTERVAL {
+            if pos == self.transitions.len() {
+                return (typ, true);
+            }
+            let (delta, next_typ) = self.decode(&mut pos);
+            let next = at.wrapping_add(delta as i64);
+            if next > ut {
+                return (typ, false);
+            }
+            at = next;
+            typ = next_typ;
+        }
+        (typ, pos == self.transitions.len())
+    }
+
+    /// Read the transition at `pos`, moving past it, giving its time since the one before and
+    /// its type index.
+    fn decode(&self, pos: &mut usize) -> (u64, usize) {
+        let value = read_varint(&self.transitions, pos);
+        let typ = (value & ((1 << self.type_bits) - 1)) as usize;
+        let delta = value >> self.type_bits;
+        let secs = match delta & 3 {
+            0 => (delta >> 2) as u64 * 3600,
+            1 => (delta >> 2) as u64 * 60,
+            _ => (delta >> 2) as u64,
+        };
+        (secs, typ)
+    }
+
+    /// Roughly how many bytes the zone takes up on the heap.
+    pub fn heap_size(&self) -> usize {
+        let designations: usize = self.designations.iter().map(|d| d.len()).sum();
+        let footer = self.footer.as_ref().map_or(0, |footer| {
+            footer.std_abbr.len() + footer.dst.as_ref().map_or(0, |dst| dst.abbr.len())
+        });
+        self.transitions.len()
+            + self.checkpoints.len() * (8 + 4)
+            + self.types.len() * core::mem::size_of::<CompactType>()
+            + self.designations.len() * core::mem::size_of::<Box<str>>()
+            + designations
+            + footer
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u128) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> u128 {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = data[*pos];
+        *pos += 1;
+        value |= u128::from(byte & 0x7f) << shift;
+        if byte < 0x80 {
+            return value;
+        }
+        shift += 7;
+    }
+}
+
+impl TimeZoneInfo {
+    /// Roughly how many bytes the zone takes up on the heap, to compare with
+    /// [`CompactZone::heap_size`].
+    pub fn heap_size(&self) -> usize {
+        let footer = |footer: &PosixTz| {
+            footer.std_abbr.len() + footer.dst.as_ref().map_or(0, |dst| dst.abbr.len())
+        };
+        self.transition_times.len() * 8
+            + self.transition_types.len()
+            + self.local_time_types.len() * core::mem::size_of::<LocalTimeTypeRecord>()
+            + self.time_zone_designations.len()
+            + self.leap_second_records.len() * core::mem::size_of::<LeapSecondRecord>()
+            + self.is_std.len()
+            + self.is_ut.len()
+            + self.footer.as_ref().map_or(0, footer)
+            + self.raw_footer.as_ref().map_or(0, String::len)
+    }
+}
+
+impl From<&Zone> for CompactZone {
+    fn from(zone: &Zone) -> Self {
+        Self::new(zone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use crate::ZoneSource;
+
+    fn check(zone: &Zone, compact: &CompactZone, times: impl Iterator<Item = i64>) {
+        for ut in times {
+            assert_eq!(
+                zone.local_time_type_at(ut),
+                compact.local_time_type_at(ut),
+                "{ut}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_compact_zone() {
+        let zone = Zone::new(sample()).unwrap();
+        let compact = CompactZone::new(&zone);
+        check(
+            &zone,
+            &compact,
+            [i64::MIN, -1, 0, 99, 100, 199, 200, 299, 300, i64::MAX].into_iter(),
+        );
+        assert_eq!(Some(-7 * 3600), compact.offset_at(150));
+        assert!(!compact.is_dst_at(250));
+        assert_eq!(Some("PDT"), compact.designation_at(350));
+
+        let zone = Zone::from_posix("UTC0".parse().unwrap());
+        check(
+            &zone,
+            &CompactZone::new(&zone),
+            [i64::MIN, 0, i64::MAX].into_iter(),
+        );
+
+        // Many transitions, with more than one checkpoint and a footer after.
+        let mut source = ZoneSource::new();
+        source
+            .parse(
+                "\
+Rule  US  1918  2006  -  Apr  lastSun  2:00  1:00  D
+Rule  US  1918  2006  -  Oct  lastSun  2:00  0     S
+Rule  US  2007  max   -  Mar  Sun>=8   2:00  1:00  D
+Rule  US  2007  max   -  Nov  Sun>=1   2:00  0     S
+Zone  Test  -5:00  -   LMT  1900
+            -5:00  US  E%sT
+",
+            )
+            .unwrap();
+        let zone = Zone::new(source.compile("Test").unwrap()).unwrap();
+        let compact = CompactZone::new(&zone);
+        check(
+            &zone,
+            &compact,
+            (-2_300_000_000..2_500_000_000).step_by(86_399),
+        );
+        for &at in zone.transition_times() {
+            check(&zone, &compact, [at - 1, at, at + 1].into_iter());
+        }
+        let full = zone.heap_size();
+        assert!(compact.heap_size() * 2 < full, "{}", compact.heap_size());
+    }
+}
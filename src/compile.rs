@@ -0,0 +1,1234 @@
+//! A compiler for the IANA time zone database's source files, in the manner of `zic`.
+//!
+//! The source files (`africa`, `europe`, `northamerica` and so on) are made of three kinds of
+//! line:
+//!
+//! ```text
+//! Rule  NAME  FROM  TO    -  IN   ON       AT    SAVE  LETTER/S
+//! Zone  NAME  STDOFF  RULES  FORMAT  [UNTIL]
+//! Link  TARGET  LINK-NAME
+//! ```
+//!
+//! A `Zone` line whose UNTIL column is filled in is followed by continuation lines, which are
+//! the same as a `Zone` line without the keyword and name, until one without an UNTIL. Each
+//! line describes one era of the zone's history. Keywords, month and weekday names may be
+//! abbreviated as `zic` allows, and fields may be double-quoted.
+//!
+//! [`ZoneSource`] collects the lines from any number of files and compiles a zone into the
+//! transitions and footer `zic` would write for it, with its default "slim" output.
+//!
+//! Releases since 2017 also ship `tzdata.zi`, the whole database in a single file of the same
+//! syntax with the keywords and names abbreviated, which begins with a `# version` comment.
+
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::civil::{days_from_civil, days_in_month, weekday, SECS_PER_DAY};
+use crate::{
+    CompileError, IsStd, IsUT, LocalTimeTypeRecord, PosixDst, PosixRule, PosixTz, RuleDate,
+    TimeZoneInfo,
+};
+
+pub(crate) const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Years `zic` looks at beyond the explicitly mentioned ones when a zone's future can't be
+/// described by a TZ string: a full 400-year Gregorian cycle, plus a margin.
+const YEARS_OF_OBSERVATIONS: i64 = 400 + 2;
+
+/// How many links [`ZoneSource::compile`] follows before giving up.
+const MAX_LINK_DEPTH: usize = 32;
+
+/// The parsed `Rule`, `Zone` and `Link` lines of one or more tzdata source files.
+#[derive(Debug, Clone, Default)]
+pub struct ZoneSource {
+    rules: BTreeMap<String, Vec<Rule>>,
+    zones: BTreeMap<String, Vec<Era>>,
+    links: BTreeMap<String, String>,
+    version: Option<String>,
+
+    /// The zone whose continuation line is expected next, if any.
+    continuing: Option<String>,
+}
+
+/// One `Rule` line.
+#[derive(Debug, Clone)]
+struct Rule {
+    /// First and last years the rule applies in; `i64::MIN` and `i64::MAX` stand for `min` and
+    /// `max`.
+    from: i64,
+    to: i64,
+    month: u8,
+    day: Day,
+    at: i32,
+    at_clock: Clock,
+    save: i32,
+    is_dst: bool,
+    letters: String,
+}
+
+/// One era of a zone: a `Zone` line or one of its continuation lines.
+#[derive(Debug, Clone)]
+struct Era {
+    std_offset: i32,
+
+    /// The RULES column, or `None` for `-`. This is either the name of a set of rules or a
+    /// fixed amount of daylight saving time, which can only be told apart once all the rules
+    /// are known.
+    rules: Option<String>,
+
+    format: String,
+    until: Option<Until>,
+}
+
+/// The UNTIL column of an era, with the omitted parts defaulted.
+#[derive(Debug, Clone, Copy)]
+struct Until {
+    year: i64,
+    month: u8,
+    day: Day,
+    time: i32,
+    clock: Clock,
+}
+
+/// A rule's ON column.
+#[derive(Debug, Clone, Copy)]
+enum Day {
+    /// A fixed day of the month.
+    Dom(u8),
+    /// `lastSun`: the last given weekday of the month.
+    Last(u8),
+    /// `Sun>=8`: the first given weekday on or after the day.
+    OnOrAfter { weekday: u8, day: u8 },
+    /// `Sun<=25`: the last given weekday on or before the day.
+    OnOrBefore { weekday: u8, day: u8 },
+}
+
+/// The clock a time of day is given on, as chosen by its suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Clock {
+    /// No suffix, or `w`: local wall clock time.
+    Wall,
+    /// `s`: local standard time.
+    Standard,
+    /// `u`, `g` or `z`: universal time.
+    Universal,
+}
+
+impl ZoneSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the text of a source file, adding its lines to those already parsed.
+    ///
+    /// Zones may refer to rules from any file, so a zone can't be compiled until every file it
+    /// depends on has been parsed.
+    pub fn parse(&mut self, text: &str) -> Result<(), CompileError> {
+        let mut line_count = 0;
+        for (idx, line) in text.lines().enumerate() {
+            line_count = idx + 1;
+            if let Some(version) = line.strip_prefix("# version ") {
+                self.version
+                    .get_or_insert_with(|| version.trim().to_owned());
+            }
+            let syntax = |reason| CompileError::Syntax {
+                line: idx + 1,
+                reason,
+            };
+            let fields = split_fields(line).ok_or_else(|| syntax("unterminated quoted field"))?;
+            if fields.is_empty() {
+                continue;
+            }
+            if let Some(name) = self.continuing.take() {
+                let era = parse_era(&fields).map_err(syntax)?;
+                if era.until.is_some() {
+                    self.continuing = Some(name.clone());
+                }
+                self.zones.get_mut(&name).unwrap().push(era);
+                continue;
+            }
+            match lookup(&fields[0], &["Rule", "Zone", "Link"]) {
+                Some(0) => {
+                    let rule = parse_rule(&fields).map_err(syntax)?;
+                    self.rules.entry(fields[1].clone()).or_default().push(rule);
+                }
+                Some(1) => {
+                    if fields.len() < 5 {
+                        return Err(syntax("too few fields in zone line"));
+                    }
+                    let name = &fields[1];
+                    if self.zones.contains_key(name) || self.links.contains_key(name) {
+                        return Err(syntax("duplicate zone name"));
+                    }
+                    let era = parse_era(&fields[2..]).map_err(syntax)?;
+                    if era.until.is_some() {
+                        self.continuing = Some(name.clone());
+                    }
+                    self.zones.insert(name.clone(), vec![era]);
+                }
+                Some(2) => {
+                    let [_, target, name] = &fields[..] else {
+                        return Err(syntax("wrong number of fields in link line"));
+                    };
+                    if self.zones.contains_key(name) || self.links.contains_key(name) {
+                        return Err(syntax("duplicate zone name"));
+                    }
+                    self.links.insert(name.clone(), target.clone());
+                }
+                _ => return Err(syntax("unknown line type")),
+            }
+        }
+        if self.continuing.take().is_some() {
+            return Err(CompileError::Syntax {
+                line: line_count + 1,
+                reason: "expected continuation line not found",
+            });
+        }
+        Ok(())
+    }
+
+    /// Names of the zones parsed so far, not including links, in sorted order.
+    pub fn zone_names(&self) -> impl Iterator<Item = &str> {
+        self.zones.keys().map(String::as_str)
+    }
+
+    /// The links parsed so far, as pairs of the link's name and its target, sorted by name.
+    pub fn links(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.links
+            .iter()
+            .map(|(name, target)| (name.as_str(), target.as_str()))
+    }
+
+    /// The database version, such as `2024a`, from the first `# version` comment parsed.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Compile every zone and link parsed so far, keyed by name.
+    pub fn compile_all(&self) -> Result<BTreeMap<String, TimeZoneInfo>, CompileError> {
+        self.zones
+            .keys()
+            .chain(self.links.keys())
+            .map(|name| Ok((name.clone(), self.compile(name)?)))
+            .collect()
+    }
+
+    /// Compile the zone with the given name, following links, into the data `zic` would write
+    /// for it.
+    pub fn compile(&self, name: &str) -> Result<TimeZoneInfo, CompileError> {
+        let mut zone = name;
+        for _ in 0..MAX_LINK_DEPTH {
+            if let Some(eras) = self.zones.get(zone) {
+                return self.compile_eras(zone, eras);
+            }
+            zone = self
+                .links
+                .get(zone)
+                .ok_or_else(|| CompileError::UnknownZone(zone.to_owned()))?;
+        }
+        Err(CompileError::InvalidZone {
+            zone: name.to_owned(),
+            reason: "too many levels of links",
+        })
+    }
+
+    fn compile_eras(&self, name: &str, eras: &[Era]) -> Result<TimeZoneInfo, CompileError> {
+        let invalid = |reason| CompileError::InvalidZone {
+            zone: name.to_owned(),
+            reason,
+        };
+        let mut resolved = Vec::with_capacity(eras.len());
+        for era in eras {
+            let rules = match &era.rules {
+                None => EraRules::Fixed {
+                    save: 0,
+                    is_dst: false,
+                },
+                Some(rule) => match self.rules.get(rule) {
+                    Some(rules) => EraRules::Named(rules),
+                    None => {
+                        let (save, is_dst) =
+                            parse_save(rule).ok_or_else(|| CompileError::UnknownRule {
+                                zone: name.to_owned(),
+                                rule: rule.clone(),
+                            })?;
+                        EraRules::Fixed { save, is_dst }
+                    }
+                },
+            };
+            resolved.push((era, rules));
+        }
+        let footer = footer(resolved.last().unwrap());
+        Compiler::new(&resolved, footer.is_none())
+            .run(&resolved)
+            .map_err(invalid)?
+            .finish(footer)
+            .map_err(invalid)
+    }
+}
+
+/// An era's RULES column, once resolved.
+#[derive(Clone, Copy)]
+enum EraRules<'a> {
+    Fixed { save: i32, is_dst: bool },
+    Named(&'a [Rule]),
+}
+
+impl EraRules<'_> {
+    fn rules(&self) -> &[Rule] {
+        match self {
+            EraRules::Fixed { .. } => &[],
+            EraRules::Named(rules) => rules,
+        }
+    }
+}
+
+/// The state of `zic`'s `outzone` as it works through a zone's eras.
+struct Compiler {
+    /// Local time types, as UT offset, DST flag and abbreviation.
+    types: Vec<(i32, bool, String)>,
+    /// Transition times, the index of the type each switches to, and whether the transition
+    /// must be kept even if it changes nothing.
+    transitions: Vec<(i64, usize, bool)>,
+    default_type: Option<usize>,
+    min_year: i64,
+    max_year: i64,
+    /// Whether to write out every transition up to `max_year`, because there's no footer.
+    extend: bool,
+}
+
+impl Compiler {
+    fn new(eras: &[(&Era, EraRules<'_>)], extend: bool) -> Self {
+        let mut years = Vec::new();
+        for (idx, (era, rules)) in eras.iter().enumerate() {
+            if idx + 1 < eras.len() {
+                years.extend(era.until.map(|until| until.year));
+            }
+            for rule in rules.rules() {
+                years.extend([rule.from, rule.to]);
+            }
+        }
+        let years = years
+            .into_iter()
+            .filter(|&y| y != i64::MIN && y != i64::MAX);
+        let mut min_year = years.clone().fold(1970, i64::min);
+        let mut max_year = years.fold(1970, i64::max);
+        if extend {
+            min_year -= YEARS_OF_OBSERVATIONS;
+            max_year += YEARS_OF_OBSERVATIONS;
+        } else {
+            // The last era may start partway through the last year mentioned, in which case
+            // the footer only takes over once its rules next apply.
+            max_year += 1;
+        }
+        Self {
+            types: Vec::new(),
+            transitions: Vec::new(),
+            default_type: None,
+            min_year,
+            max_year,
+            extend,
+        }
+    }
+
+    fn add_type(&mut self, ut_off: i32, is_dst: bool, abbr: String) -> usize {
+        let typ = (ut_off, is_dst, abbr);
+        self.types
+            .iter()
+            .position(|t| *t == typ)
+            .unwrap_or_else(|| {
+                self.types.push(typ);
+                self.types.len() - 1
+            })
+    }
+
+    fn run(mut self, eras: &[(&Era, EraRules<'_>)]) -> Result<Self, &'static str> {
+        let mut save;
+        let mut start_time = 0;
+        // The transition which starts the latest run of rules lasting forever.
+        let mut last_at_max: Option<usize> = None;
+        for (idx, &(era, rules)) in eras.iter().enumerate() {
+            let until = era.until.filter(|_| idx + 1 < eras.len());
+            // A guess, which the era's rules may well correct.
+            save = 0;
+            let mut use_start = idx > 0;
+            let std_offset = era.std_offset;
+            let mut start_off = std_offset;
+            let mut start_abbr = None;
+            match rules {
+                EraRules::Fixed {
+                    save: era_save,
+                    is_dst,
+                } => {
+                    save = era_save;
+                    let abbr = format_abbr(&era.format, Some("%s"), is_dst, std_offset + save)
+                        .ok_or("invalid abbreviation format")?;
+                    let typ = self.add_type(std_offset + save, is_dst, abbr);
+                    if use_start {
+                        self.transitions.push((start_time, typ, false));
+                        use_start = false;
+                    } else {
+                        self.default_type = Some(typ);
+                    }
+                }
+                EraRules::Named(rules) => {
+                    let mut prev_rule: Option<&Rule> = None;
+                    for year in self.min_year..=self.max_year {
+                        if until.is_some_and(|until| year > until.year) {
+                            break;
+                        }
+                        let mut todo: Vec<Option<i64>> = rules
+                            .iter()
+                            .map(|rule| {
+                                (rule.from..=rule.to)
+                                    .contains(&year)
+                                    .then(|| rule.local_secs(year))
+                            })
+                            .collect();
+                        loop {
+                            let until_ut = until.map(|until| until.ut_secs(std_offset, save));
+                            // Find the rule which takes effect earliest in the year.
+                            let mut earliest: Option<(usize, i64)> = None;
+                            for (j, rule) in rules.iter().enumerate() {
+                                let Some(local) = todo[j] else {
+                                    continue;
+                                };
+                                let t = local - rule.at_clock.utc_offset(std_offset, save);
+                                match earliest {
+                                    Some((_, k)) if t == k => {
+                                        return Err("two rules for the same instant")
+                                    }
+                                    Some((_, k)) if t > k => {}
+                                    _ => earliest = Some((j, t)),
+                                }
+                            }
+                            let Some((k, ktime)) = earliest else {
+                                break;
+                            };
+                            let rule = &rules[k];
+                            todo[k] = None;
+                            let rule_abbr = || {
+                                format_abbr(
+                                    &era.format,
+                                    Some(&rule.letters),
+                                    rule.is_dst,
+                                    std_offset + rule.save,
+                                )
+                                .ok_or("invalid abbreviation format")
+                            };
+                            if until_ut.is_some_and(|until| ktime >= until) {
+                                if start_abbr.is_none() && std_offset + rule.save == start_off {
+                                    start_abbr = Some(rule_abbr()?);
+                                }
+                                break;
+                            }
+                            save = rule.save;
+                            if use_start && ktime == start_time {
+                                use_start = false;
+                            }
+                            if use_start {
+                                if ktime < start_time {
+                                    start_off = std_offset + save;
+                                    start_abbr = Some(rule_abbr()?);
+                                    continue;
+                                }
+                                if start_abbr.is_none() && start_off == std_offset + save {
+                                    start_abbr = Some(rule_abbr()?);
+                                }
+                            }
+                            // Once the rules lasting forever have started, and no others
+                            // are left, the footer takes over. (`zic` doesn't check for
+                            // others, and loses one-off transitions after the start.)
+                            if until.is_none()
+                                && !self.extend
+                                && rule.to == i64::MAX
+                                && prev_rule.is_some_and(|prev| prev.to == i64::MAX)
+                                && rules.iter().all(|r| r.to == i64::MAX || r.to < year)
+                            {
+                                break;
+                            }
+                            let typ = self.add_type(std_offset + save, rule.is_dst, rule_abbr()?);
+                            if self.default_type.is_none() && !rule.is_dst {
+                                self.default_type = Some(typ);
+                            }
+                            if rule.to == i64::MAX
+                                && last_at_max.is_none_or(|l| ktime >= self.transitions[l].0)
+                            {
+                                last_at_max = Some(self.transitions.len());
+                            }
+                            self.transitions.push((ktime, typ, false));
+                            prev_rule = Some(rule);
+                        }
+                    }
+                }
+            }
+            if use_start {
+                let is_dst = start_off != std_offset;
+                let abbr = match start_abbr {
+                    Some(abbr) => abbr,
+                    None => format_abbr(&era.format, None, is_dst, start_off)
+                        .ok_or("can't determine time zone abbreviation to use after until time")?,
+                };
+                let typ = self.add_type(start_off, is_dst, abbr);
+                if self.default_type.is_none() && !is_dst {
+                    self.default_type = Some(typ);
+                }
+                self.transitions.push((start_time, typ, false));
+            }
+            if let Some(until) = until {
+                start_time = until.ut_secs(std_offset, save);
+            }
+        }
+        if let Some(idx) = last_at_max {
+            self.transitions[idx].2 = true;
+        }
+        Ok(self)
+    }
+
+    fn finish(mut self, footer: Option<(PosixTz, bool)>) -> Result<TimeZoneInfo, &'static str> {
+        let default_type = self.default_type.unwrap_or(0);
+        self.transitions.sort_by_key(|&(at, _, _)| at);
+
+        // Drop transitions which change nothing, and those which would make local time go
+        // backwards past the previous transition, whose type they take over instead.
+        let mut kept: Vec<(i64, usize, bool)> = Vec::with_capacity(self.transitions.len());
+        for &(at, typ, keep) in &self.transitions {
+            if let Some(&(prev_at, prev_typ, _)) = kept.last() {
+                let before = kept
+                    .len()
+                    .checked_sub(2)
+                    .map_or(default_type, |i| kept[i].1);
+                let off = |typ: usize| i64::from(self.types[typ].0);
+                if at + off(prev_typ) <= prev_at + off(before) {
+                    kept.last_mut().unwrap().1 = typ;
+                    continue;
+                }
+                if !keep && self.types[prev_typ] == self.types[typ] {
+                    continue;
+                }
+            }
+            kept.push((at, typ, keep));
+        }
+
+        let (footer, needs_v3) = footer.map_or((None, false), |(tz, v3)| (Some(tz), v3));
+        let version = if needs_v3 { 3 } else { 2 };
+        let mut tz = TimeZoneInfo {
+            version,
+            footer,
+            ..TimeZoneInfo::default()
+        };
+        // Write out only the types in use, with the default type first.
+        let mut indices: Vec<Option<u8>> = vec![None; self.types.len()];
+        let used = core::iter::once(default_type).chain(kept.iter().map(|&(_, typ, _)| typ));
+        for typ in used {
+            if indices[typ].is_some() {
+                continue;
+            }
+            let index =
+                u8::try_from(tz.local_time_types.len()).map_err(|_| "too many local time types")?;
+            let (ut_off_secs, is_dst, abbr) = &self.types[typ];
+            let desig_idx = tz
+                .find_or_add_designation(abbr)
+                .ok_or("too many time zone designations")?;
+            tz.local_time_types.push(LocalTimeTypeRecord {
+                ut_off_secs: *ut_off_secs,
+                is_dst: *is_dst,
+                desig_idx,
+            });
+            tz.is_std.push(IsStd::Standard);
+            tz.is_ut.push(IsUT::UT);
+            indices[typ] = Some(index);
+        }
+        for (at, typ, _) in kept {
+            tz.transition_times.push(at);
+            tz.transition_types.push(indices[typ].unwrap());
+        }
+        Ok(tz)
+    }
+}
+
+impl Rule {
+    /// Seconds from the Unix epoch to the rule's date and AT time in the given year, as if its
+    /// clock were UT.
+    fn local_secs(&self, year: i64) -> i64 {
+        self.day.days(year, self.month) * SECS_PER_DAY + i64::from(self.at)
+    }
+}
+
+impl Until {
+    /// The UT time the era ends, given the standard offset and amount of daylight saving time
+    /// in effect at its end.
+    fn ut_secs(&self, std_offset: i32, save: i32) -> i64 {
+        self.day.days(self.year, self.month) * SECS_PER_DAY + i64::from(self.time)
+            - self.clock.utc_offset(std_offset, save)
+    }
+}
+
+impl Day {
+    /// Days from 1970-01-01 to the day in the given year and month.
+    fn days(self, year: i64, month: u8) -> i64 {
+        let (wday, day, forwards) = match self {
+            Day::Dom(day) => return days_from_civil(year, month, day),
+            Day::Last(wday) => (wday, days_in_month(year, month), false),
+            Day::OnOrAfter { weekday, day } => (weekday, day, true),
+            Day::OnOrBefore { weekday, day } => (weekday, day, false),
+        };
+        // February 29 stands for the 28th in common years.
+        let days = days_from_civil(year, month, day.min(days_in_month(year, month)));
+        let wday = i64::from(wday);
+        let this = i64::from(weekday(days));
+        if forwards {
+            days + (wday - this).rem_euclid(7)
+        } else {
+            days - (this - wday).rem_euclid(7)
+        }
+    }
+
+    /// The day of the month the rule is anchored to, for ordering rules within a year.
+    fn day_of_month(self, month: u8) -> u8 {
+        match self {
+            Day::Dom(day) | Day::OnOrAfter { day, .. } | Day::OnOrBefore { day, .. } => day,
+            Day::Last(_) => days_in_month(2000, month),
+        }
+    }
+}
+
+impl Clock {
+    /// The offset from UT of the clock, given the standard offset and amount of daylight
+    /// saving time in effect.
+    fn utc_offset(self, std_offset: i32, save: i32) -> i64 {
+        match self {
+            Clock::Wall => i64::from(std_offset) + i64::from(save),
+            Clock::Standard => i64::from(std_offset),
+            Clock::Universal => 0,
+        }
+    }
+}
+
+/// Order rules by when they stop applying, as `zic` does to find the ones which describe a
+/// zone's future. A missing rule is earliest.
+fn rule_cmp(a: Option<&Rule>, b: Option<&Rule>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (Some(a), Some(b)) => a.to.cmp(&b.to).then_with(|| {
+            if a.to == i64::MAX {
+                Ordering::Equal
+            } else {
+                (a.month, a.day.day_of_month(a.month)).cmp(&(b.month, b.day.day_of_month(b.month)))
+            }
+        }),
+    }
+}
+
+/// Build the TZ string describing local time after a zone's last era starts, as `zic`'s
+/// `stringzone` does, along with whether it needs version 3 of the format. Returns `None` if it
+/// can't be described by one.
+fn footer(&(era, rules): &(&Era, EraRules<'_>)) -> Option<(PosixTz, bool)> {
+    let mut last: [Option<&Rule>; 2] = [None, None];
+    for rule in rules.rules() {
+        let slot = &mut last[usize::from(rule.is_dst)];
+        match rule_cmp(*slot, Some(rule)) {
+            Ordering::Less => *slot = Some(rule),
+            Ordering::Equal => return None,
+            Ordering::Greater => {}
+        }
+    }
+    let [mut std_rule, mut dst_rule] = last.map(|rule| rule.cloned());
+    let dst_cmp = match rules {
+        EraRules::Named(_) => rule_cmp(dst_rule.as_ref(), std_rule.as_ref()),
+        EraRules::Fixed { is_dst: true, .. } => Ordering::Greater,
+        EraRules::Fixed { is_dst: false, .. } => Ordering::Less,
+    };
+
+    let mut std_offset = era.std_offset;
+    let mut std_format = era.format.as_str();
+    let mut dst_std_offset = era.std_offset;
+    match dst_cmp {
+        // Standard time all year.
+        Ordering::Less => dst_rule = None,
+        // DST all year. Describe it as DST from the start of the year to past its end, and if
+        // DST is positive, fake a negative DST from a made-up standard time.
+        Ordering::Greater => {
+            let save = match (&dst_rule, rules) {
+                (Some(rule), _) => rule.save,
+                (None, EraRules::Fixed { save, .. }) => save,
+                (None, EraRules::Named(_)) => 0,
+            };
+            if save >= 0 {
+                std_offset = era.std_offset + 2 * save;
+                std_format = "XXX";
+                dst_std_offset = std_offset;
+            }
+            let dst_save = if save < 0 { save } else { -save };
+            let letters = |rule: &Option<Rule>| rule.as_ref().map(|r| r.letters.clone());
+            let std_letters = letters(&std_rule).filter(|_| save < 0);
+            dst_rule = Some(Rule {
+                from: i64::MIN,
+                to: i64::MAX,
+                month: 1,
+                day: Day::Dom(1),
+                at: 0,
+                at_clock: Clock::Wall,
+                save: dst_save,
+                is_dst: true,
+                letters: letters(&dst_rule).unwrap_or_else(|| "%s".to_owned()),
+            });
+            std_rule = Some(Rule {
+                from: i64::MIN,
+                to: i64::MAX,
+                month: 12,
+                day: Day::Dom(31),
+                at: SECS_PER_DAY as i32 + dst_save,
+                at_clock: Clock::Wall,
+                save: 0,
+                is_dst: false,
+                letters: std_letters.unwrap_or_else(|| "%s".to_owned()),
+            });
+        }
+        Ordering::Equal => {}
+    }
+
+    let std_letters = std_rule.as_ref().map_or("%s", |rule| rule.letters.as_str());
+    let std_abbr = format_abbr(std_format, Some(std_letters), false, std_offset)?;
+    let valid_offset = |offset: i32| offset.unsigned_abs() < 25 * 60 * 60;
+    if !valid_offset(std_offset) {
+        return None;
+    }
+    let Some(dst_rule) = dst_rule else {
+        let tz = PosixTz {
+            std_abbr,
+            std_offset,
+            dst: None,
+        };
+        return Some((tz, false));
+    };
+    let dst_offset = dst_std_offset + dst_rule.save;
+    let dst_abbr = format_abbr(
+        era.format.as_str(),
+        Some(&dst_rule.letters),
+        true,
+        dst_offset,
+    )?;
+    if !valid_offset(dst_offset) {
+        return None;
+    }
+    let (start, start_v3) = posix_rule(&dst_rule, dst_rule.save, std_offset)?;
+    let (end, end_v3) = posix_rule(std_rule.as_ref()?, dst_rule.save, std_offset)?;
+    let tz = PosixTz {
+        std_abbr,
+        std_offset,
+        dst: Some(PosixDst {
+            abbr: dst_abbr,
+            offset: dst_offset,
+            start,
+            end,
+        }),
+    };
+    let needs_v3 = start_v3 || end_v3 || tz.uses_v3_extensions();
+    Some((tz, needs_v3))
+}
+
+/// Express a rule as a TZ string rule, as `zic`'s `stringrule` does, along with whether it
+/// needs version 3 of the format. `zic` counts a weekday rule which had to be shifted by some
+/// days as needing it, even if the shifted time is within 24 hours.
+fn posix_rule(rule: &Rule, save: i32, std_offset: i32) -> Option<(PosixRule, bool)> {
+    let mut time = rule.at;
+    let mut shifted = false;
+    let date = match rule.day {
+        Day::Dom(day) => {
+            if rule.month == 2 && day == 29 {
+                return None;
+            }
+            let before: u16 = (1..rule.month)
+                .map(|m| u16::from(days_in_month(1970, m)))
+                .sum();
+            // Days in January and February are the same either way, and shorter without a J.
+            if rule.month <= 2 {
+                RuleDate::Zero(before + u16::from(day) - 1)
+            } else {
+                RuleDate::Julian(before + u16::from(day))
+            }
+        }
+        Day::Last(wday) => month_week_day(rule.month, 5, wday),
+        Day::OnOrAfter { weekday: wday, day } => {
+            let offset = (day - 1) % 7;
+            shifted = offset != 0;
+            time += i32::from(offset) * SECS_PER_DAY as i32;
+            month_week_day(rule.month, 1 + (day - 1) / 7, (wday + 7 - offset) % 7)
+        }
+        Day::OnOrBefore { weekday: wday, day } => {
+            if day == days_in_month(2000, rule.month) {
+                month_week_day(rule.month, 5, wday)
+            } else {
+                let offset = day % 7;
+                shifted = offset != 0;
+                if day < 7 {
+                    return None;
+                }
+                time += i32::from(offset) * SECS_PER_DAY as i32;
+                month_week_day(rule.month, day / 7, (wday + 7 - offset) % 7)
+            }
+        }
+    };
+    match rule.at_clock {
+        Clock::Universal => time += std_offset + if rule.is_dst { 0 } else { save },
+        Clock::Standard if !rule.is_dst => time += save,
+        _ => {}
+    }
+    (time.unsigned_abs() < 168 * 60 * 60).then_some((PosixRule { date, time }, shifted || time < 0))
+}
+
+fn month_week_day(month: u8, week: u8, weekday: u8) -> RuleDate {
+    RuleDate::MonthWeekDay {
+        month,
+        week,
+        weekday,
+    }
+}
+
+/// Expand an era's FORMAT column into an abbreviation, as `zic`'s `doabbr` does.
+///
+/// `%s` is replaced by `letters`, or if that is `None`, the abbreviation can't be determined.
+/// `%z` is replaced by the numeric UT offset, and `A/B` picks `B` if `is_dst` is set and `A`
+/// otherwise.
+fn format_abbr(format: &str, letters: Option<&str>, is_dst: bool, ut_off: i32) -> Option<String> {
+    if let Some((std, dst)) = format.split_once('/') {
+        return Some(if is_dst { dst } else { std }.to_owned());
+    }
+    let Some((before, after)) = format.split_once('%') else {
+        return Some(format.to_owned());
+    };
+    let mut abbr = before.to_owned();
+    match after.as_bytes().first() {
+        Some(b's') => abbr.push_str(letters?),
+        Some(b'z') => {
+            let secs = ut_off.unsigned_abs();
+            let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+            if hours >= 100 {
+                return None;
+            }
+            abbr.push(if ut_off < 0 { '-' } else { '+' });
+            abbr.push_str(&alloc::format!("{hours:02}"));
+            if minutes != 0 || seconds != 0 {
+                abbr.push_str(&alloc::format!("{minutes:02}"));
+                if seconds != 0 {
+                    abbr.push_str(&alloc::format!("{seconds:02}"));
+                }
+            }
+        }
+        _ => return None,
+    }
+    abbr.push_str(&after[1..]);
+    Some(abbr)
+}
+
+/// Split a line into whitespace-separated fields, dropping any comment. Double quotes group
+/// characters, including whitespace and `#`, into a field. Returns `None` if a quote isn't
+/// closed.
+pub(crate) fn split_fields(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.peek() {
+            None | Some('#') => return Some(fields),
+            Some(_) => {}
+        }
+        let mut field = String::new();
+        let mut quoted = false;
+        while let Some(c) = chars.next_if(|&c| quoted || !(c.is_whitespace() || c == '#')) {
+            if c == '"' {
+                quoted = !quoted;
+            } else {
+                field.push(c);
+            }
+        }
+        if quoted {
+            return None;
+        }
+        fields.push(field);
+    }
+}
+
+/// Find a word in a table the way `zic` does: an exact match, ignoring case, or else the one
+/// word it is a prefix of.
+pub(crate) fn lookup(word: &str, table: &[&str]) -> Option<usize> {
+    if let Some(idx) = table.iter().position(|w| w.eq_ignore_ascii_case(word)) {
+        return Some(idx);
+    }
+    let is_prefix = |full: &str| {
+        !word.is_empty()
+            && full
+                .get(..word.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(word))
+    };
+    let mut matches = table.iter().enumerate().filter(|(_, w)| is_prefix(w));
+    match (matches.next(), matches.next()) {
+        (Some((idx, _)), None) => Some(idx),
+        _ => None,
+    }
+}
+
+/// Parse the fields of a `Rule` line.
+fn parse_rule(fields: &[String]) -> Result<Rule, &'static str> {
+    let [_, _, from, to, typ, month, day, at, save, letters] = fields else {
+        return Err("wrong number of fields in rule line");
+    };
+    let from = match lookup(from, &["minimum", "maximum"]) {
+        Some(0) => i64::MIN,
+        Some(_) => i64::MAX,
+        None => from.parse().map_err(|_| "invalid starting year")?,
+    };
+    let to = match lookup(to, &["minimum", "maximum", "only"]) {
+        Some(0) => i64::MIN,
+        Some(1) => i64::MAX,
+        Some(_) => from,
+        None => to.parse().map_err(|_| "invalid ending year")?,
+    };
+    if from > to {
+        return Err("starting year greater than ending year");
+    }
+    if !(typ.is_empty() || typ == "-") {
+        return Err("year types are not supported");
+    }
+    let month = parse_month(month)?;
+    let (at, at_clock) = parse_time(at).ok_or("invalid time of day")?;
+    let (save, is_dst) = parse_save(save).ok_or("invalid saved time")?;
+    Ok(Rule {
+        from,
+        to,
+        month,
+        day: parse_day(day, month)?,
+        at,
+        at_clock,
+        save,
+        is_dst,
+        letters: if letters == "-" {
+            String::new()
+        } else {
+            letters.clone()
+        },
+    })
+}
+
+/// Parse the STDOFF, RULES, FORMAT and UNTIL fields of a `Zone` line or continuation line.
+fn parse_era(fields: &[String]) -> Result<Era, &'static str> {
+    if !(3..=7).contains(&fields.len()) {
+        return Err("wrong number of fields in zone line");
+    }
+    let std_offset = parse_hms(&fields[0]).ok_or("invalid UT offset")?;
+    let rules = (fields[1] != "-" && !fields[1].is_empty()).then(|| fields[1].clone());
+    let format = fields[2].clone();
+    if format.matches('%').count() > 1
+        || (format.contains('%') && format.contains('/'))
+        || format
+            .split_once('%')
+            .is_some_and(|(_, after)| !(after.starts_with('s') || after.starts_with('z')))
+    {
+        return Err("invalid abbreviation format");
+    }
+    let until = match fields.get(3..).unwrap_or_default() {
+        [] => None,
+        until => {
+            let year = until[0].parse().map_err(|_| "invalid until year")?;
+            let month = until.get(1).map_or(Ok(1), |m| parse_month(m))?;
+            let day = until
+                .get(2)
+                .map_or(Ok(Day::Dom(1)), |d| parse_day(d, month))?;
+            let (time, clock) = until
+                .get(3)
+                .map_or(Some((0, Clock::Wall)), |t| parse_time(t))
+                .ok_or("invalid until time")?;
+            Some(Until {
+                year,
+                month,
+                day,
+                time,
+                clock,
+            })
+        }
+    };
+    Ok(Era {
+        std_offset,
+        rules,
+        format,
+        until,
+    })
+}
+
+pub(crate) fn parse_month(s: &str) -> Result<u8, &'static str> {
+    lookup(s, &MONTHS)
+        .map(|idx| idx as u8 + 1)
+        .ok_or("invalid month name")
+}
+
+/// Parse an ON column: `5`, `lastSun`, `Sun>=8` or `Sun<=25`.
+fn parse_day(s: &str, month: u8) -> Result<Day, &'static str> {
+    let weekday = |s: &str| {
+        lookup(s, &WEEKDAYS)
+            .map(|idx| idx as u8)
+            .ok_or("invalid weekday name")
+    };
+    let day_of_month = |s: &str| {
+        s.parse::<u8>()
+            .ok()
+            .filter(|&day| (1..=days_in_month(2000, month)).contains(&day))
+            .ok_or("invalid day of month")
+    };
+    if s.len() > 4 && s.is_char_boundary(4) && s[..4].eq_ignore_ascii_case("last") {
+        return Ok(Day::Last(weekday(&s[4..])?));
+    }
+    if let Some((wday, day)) = s.split_once(">=") {
+        Ok(Day::OnOrAfter {
+            weekday: weekday(wday)?,
+            day: day_of_month(day)?,
+        })
+    } else if let Some((wday, day)) = s.split_once("<=") {
+        Ok(Day::OnOrBefore {
+            weekday: weekday(wday)?,
+            day: day_of_month(day)?,
+        })
+    } else {
+        Ok(Day::Dom(day_of_month(s)?))
+    }
+}
+
+/// Split an optional single-letter suffix off a time.
+fn split_suffix(s: &str) -> (&str, Option<u8>) {
+    match s.as_bytes().last() {
+        Some(b) if b.is_ascii_alphabetic() => (&s[..s.len() - 1], Some(b.to_ascii_lowercase())),
+        _ => (s, None),
+    }
+}
+
+/// Parse an AT or UNTIL time of day, with its optional clock suffix.
+fn parse_time(s: &str) -> Option<(i32, Clock)> {
+    let (time, suffix) = split_suffix(s);
+    let clock = match suffix {
+        None | Some(b'w') => Clock::Wall,
+        Some(b's') => Clock::Standard,
+        Some(b'u' | b'g' | b'z') => Clock::Universal,
+        Some(_) => return None,
+    };
+    Some((parse_hms(time)?, clock))
+}
+
+/// Parse a SAVE column, or an amount of saved time in a zone's RULES column, returning the
+/// amount and whether it is daylight saving time. That is the case if it's non-zero, unless a
+/// `d` or `s` suffix says otherwise.
+fn parse_save(s: &str) -> Option<(i32, bool)> {
+    let (time, suffix) = split_suffix(s);
+    let save = parse_hms(time)?;
+    let is_dst = match suffix {
+        None => save != 0,
+        Some(b'd') => true,
+        Some(b's') => false,
+        Some(_) => return None,
+    };
+    Some((save, is_dst))
+}
+
+/// Parse a signed `hh[:mm[:ss]]` time into seconds. An empty string is zero.
+pub(crate) fn parse_hms(s: &str) -> Option<i32> {
+    if s.is_empty() {
+        return Some(0);
+    }
+    let (sign, s) = match s.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, s),
+    };
+    let field = |part: &str, max: i32| {
+        part.bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| part.parse::<i32>().ok())
+            .flatten()
+            .filter(|&value| value <= max)
+    };
+    let mut parts = s.split(':');
+    let hours = field(parts.next()?, i32::MAX / 3600)?;
+    let minutes = parts.next().map_or(Some(0), |part| field(part, 59))?;
+    let seconds = parts.next().map_or(Some(0), |part| field(part, 60))?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60 + seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "\
+# Rule  NAME  FROM  TO    -  IN   ON       AT    SAVE  LETTER/S
+Rule    US    1967  2006  -  Oct  lastSun  2:00  0     S
+Rule    US    1967  1973  -  Apr  lastSun  2:00  1:00  D
+Rule    US    1974  only  -  Jan  6        2:00  1:00  D
+Rule    US    1975  only  -  Feb  lastSun  2:00  1:00  D
+Rule    US    1976  1986  -  Apr  lastSun  2:00  1:00  D
+Rule    US    1987  2006  -  Apr  Sun>=1   2:00  1:00  D
+Rule    US    2007  max   -  Mar  Sun>=8   2:00  1:00  D
+Rule    US    2007  max   -  Nov  Sun>=1   2:00  0     S
+
+Zone America/New_York  -4:56:02 -  LMT  1883 Nov 18 17:00u
+                       -5:00    US E%sT
+Link America/New_York US/Eastern
+
+Zone Etc/Example  1:00  -     %z       1990 Jun 1
+                  1:00  1:00  CET/CEST 2000
+                  2:00  -     \"EET\"  # quoted
+";
+
+    fn at(tz: &TimeZoneInfo, ut: i64) -> (i32, bool, String) {
+        let local = tz.local_time_type_at(ut).unwrap();
+        (local.ut_offset_secs, local.is_dst, local.desig.to_owned())
+    }
+
+    #[test]
+    fn test_compile() {
+        let mut source = ZoneSource::new();
+        source.parse(SOURCE).unwrap();
+        assert_eq!(
+            vec!["America/New_York", "Etc/Example"],
+            source.zone_names().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![("US/Eastern", "America/New_York")],
+            source.links().collect::<Vec<_>>()
+        );
+
+        let ny = source.compile("US/Eastern").unwrap();
+        assert_eq!(2, ny.version);
+        assert_eq!(
+            "EST5EDT,M3.2.0,M11.1.0",
+            ny.footer.as_ref().unwrap().to_string()
+        );
+        // LMT until 1883, then 40 years of two transitions, and the first of 2007.
+        assert_eq!(82, ny.transition_times.len());
+        assert_eq!((-17762, false, "LMT".to_owned()), at(&ny, -3_000_000_000));
+        assert_eq!((-18000, false, "EST".to_owned()), at(&ny, -2_717_650_800));
+        // 1974-01-06 07:00 UT, when the Jan 6 rule took effect.
+        assert_eq!((-18000, false, "EST".to_owned()), at(&ny, 126_687_599));
+        assert_eq!((-14400, true, "EDT".to_owned()), at(&ny, 126_687_600));
+        // 2030-07-01, from the footer.
+        assert_eq!((-14400, true, "EDT".to_owned()), at(&ny, 1_908_518_400));
+
+        let example = source.compile("Etc/Example").unwrap();
+        assert_eq!((3600, false, "+01".to_owned()), at(&example, 0));
+        assert_eq!((7200, true, "CEST".to_owned()), at(&example, 800_000_000));
+        assert_eq!((7200, false, "EET".to_owned()), at(&example, 1_000_000_000));
+        assert_eq!("EET-2", example.footer.as_ref().unwrap().to_string());
+
+        assert_eq!(
+            Err(CompileError::UnknownZone("Etc/Missing".to_owned())),
+            source.compile("Etc/Missing").map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_zi() {
+        // The same zones, as tzdata.zi abbreviates them.
+        let text = "# version 2024a\n\
+                    # This zic input file is in the public domain.\n\
+                    R u 1967 2006 - O lastSu 2 0 S\n\
+                    R u 1967 1973 - Ap lastSu 2 1 D\n\
+                    R u 1974 o - Ja 6 2 1 D\n\
+                    R u 1975 o - F lastSu 2 1 D\n\
+                    R u 1976 1986 - Ap lastSu 2 1 D\n\
+                    R u 1987 2006 - Ap Su>=1 2 1 D\n\
+                    R u 2007 ma - Mar Su>=8 2 1 D\n\
+                    R u 2007 ma - N Su>=1 2 0 S\n\
+                    Z America/New_York -4:56:2 - LMT 1883 N 18 17u\n\
+                    -5 u E%sT\n\
+                    L America/New_York US/Eastern\n";
+        let mut zi = ZoneSource::new();
+        zi.parse(text).unwrap();
+        assert_eq!(Some("2024a"), zi.version());
+
+        let mut source = ZoneSource::new();
+        source.parse(SOURCE).unwrap();
+        assert_eq!(None, source.version());
+        let expected = source.compile("America/New_York").unwrap();
+        let all = zi.compile_all().unwrap();
+        assert_eq!(
+            vec!["America/New_York", "US/Eastern"],
+            all.keys().collect::<Vec<_>>()
+        );
+        for tz in all.values() {
+            assert_eq!(expected.to_bytes(), tz.to_bytes());
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (text, line, reason) in [
+            (
+                "Rule US 1967 2006 - Foo lastSun 2:00 0 S",
+                1,
+                "invalid month name",
+            ),
+            (
+                "\nRule US 1967 2006 - Oct lastS 2:00 0 S",
+                2,
+                "invalid weekday name",
+            ),
+            (
+                "Rule US 1967 2006 - Oct 32 2:00 0 S",
+                1,
+                "invalid day of month",
+            ),
+            (
+                "Zone A/B 1:00 - X 2000\n",
+                2,
+                "expected continuation line not found",
+            ),
+            ("Zone A/B 1:00 - \"X", 1, "unterminated quoted field"),
+            ("Zone A/B 1:00 - X\nLink A/B A/B", 2, "duplicate zone name"),
+            ("Leap 2016 Dec 31 23:59:60 + S", 1, "unknown line type"),
+        ] {
+            assert_eq!(
+                Err(CompileError::Syntax { line, reason }),
+                ZoneSource::new().parse(text),
+                "{text}"
+            );
+        }
+
+        let mut source = ZoneSource::new();
+        source.parse("Zone A/B 1:00 EU CE%sT").unwrap();
+        assert_eq!(
+            Err(CompileError::UnknownRule {
+                zone: "A/B".to_owned(),
+                rule: "EU".to_owned()
+            }),
+            source.compile("A/B").map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! How far a zone's data reaches, for knowing when answers from it stop being trustworthy.
+
+use crate::TimeZoneInfo;
+
+/// The span of time a zone's data covers, from [`TimeZoneInfo::coverage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coverage {
+    /// The UT time of the first recorded transition, if there are any.
+    pub first_transition: Option<i64>,
+
+    /// The UT time of the last recorded transition, if there are any.
+    pub last_transition: Option<i64>,
+
+    /// Whether a footer's TZ string gives local time after the last transition, indefinitely.
+    pub footer_rule: bool,
+
+    /// When the leap-second table expires, for a version 4 file which records it.
+    pub leap_second_expiration: Option<i64>,
+}
+
+impl Coverage {
+    /// The UT time after which the data is no longer authoritative, or `None` if it never
+    /// stops being so.
+    ///
+    /// Without a footer, the type of the last transition is only assumed to continue, so a zone
+    /// with transitions is covered until its last one. Leap-second corrections are unknown
+    /// after the table expires.
+    pub fn horizon(&self) -> Option<i64> {
+        let transitions = self.last_transition.filter(|_| !self.footer_rule);
+        match (transitions, self.leap_second_expiration) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+impl TimeZoneInfo {
+    /// The span of time the zone's data covers.
+    pub fn coverage(&self) -> Coverage {
+        Coverage {
+            first_transition: self.transition_times.first().copied(),
+            last_transition: self.transition_times.last().copied(),
+            footer_rule: self.footer.is_some(),
+            leap_second_expiration: self.leap_second_expiration(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{leap_records, sample};
+
+    #[test]
+    fn test_coverage() {
+        let mut tz = sample();
+        let coverage = tz.coverage();
+        assert_eq!(
+            (Some(100), Some(300), false),
+            (
+                coverage.first_transition,
+                coverage.last_transition,
+                coverage.footer_rule
+            )
+        );
+        assert_eq!(Some(300), coverage.horizon());
+
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        assert_eq!(None, tz.coverage().horizon());
+
+        tz.version = 4;
+        tz.leap_second_records = leap_records(&[(78796800, 1), (94694401, 1)]);
+        assert_eq!(Some(94694401), tz.coverage().horizon());
+        tz.footer = None;
+        assert_eq!(Some(300), tz.coverage().horizon());
+
+        let fixed = TimeZoneInfo::fixed(3600, "CET").unwrap();
+        assert_eq!(None, fixed.coverage().first_transition);
+        assert_eq!(None, fixed.coverage().horizon());
+    }
+}
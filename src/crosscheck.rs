@@ -0,0 +1,220 @@
+//! Checking that the version 1 data block of a version 2+ file agrees with the second one.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{LeapSecondRecord, LocalTimeType, TimeZoneInfo};
+
+/// A way in which the version 1 data block of a version 2+ file isn't the 32-bit projection of
+/// the second data block, found when parsing with
+/// [`ParseOptions::check_v1`](crate::ParseOptions::check_v1).
+///
+/// Times are UT, and transitions are compared by the local time they change to, so the blocks
+/// may number their local time types differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BlockDiscrepancy {
+    /// The two headers gave different versions.
+    Version { v1: u8, v2: u8 },
+
+    /// The local time type in effect at the start of the 32-bit range differs.
+    InitialType,
+
+    /// The second block changes local time at this time, within the 32-bit range, and the
+    /// first block doesn't.
+    TransitionMissing { at: i64 },
+
+    /// The first block changes local time at this time, and the second block doesn't.
+    TransitionExtra { at: i64 },
+
+    /// Both blocks change local time at this time, but to different local time types.
+    TypeMismatch { at: i64 },
+
+    /// The leap seconds within the 32-bit range differ.
+    LeapSeconds,
+}
+
+impl fmt::Display for BlockDiscrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockDiscrepancy::Version { v1, v2 } => {
+                write!(f, "version {v1} in the first header, {v2} in the second")
+            }
+            BlockDiscrepancy::InitialType => {
+                f.write_str("different local time types at the start of the 32-bit range")
+            }
+            BlockDiscrepancy::TransitionMissing { at } => {
+                write!(f, "transition at {at} missing from the version 1 data")
+            }
+            BlockDiscrepancy::TransitionExtra { at } => {
+                write!(f, "transition at {at} only in the version 1 data")
+            }
+            BlockDiscrepancy::TypeMismatch { at } => {
+                write!(f, "transition at {at} to different local time types")
+            }
+            BlockDiscrepancy::LeapSeconds => f.write_str("different leap seconds"),
+        }
+    }
+}
+
+impl TimeZoneInfo {
+    /// Compare the data of this zone's second data block with `v1`, the data of its first,
+    /// returning how they disagree in order of time.
+    ///
+    /// A first data block with no transitions or leap seconds is taken to be one deliberately
+    /// left minimal, as `zic -b slim` writes, and only the versions are compared.
+    pub(crate) fn block_discrepancies(&self, v1: &TimeZoneInfo) -> Vec<BlockDiscrepancy> {
+        let mut discrepancies = Vec::new();
+        if v1.version != self.version {
+            discrepancies.push(BlockDiscrepancy::Version {
+                v1: v1.version,
+                v2: self.version,
+            });
+        }
+        if v1.transition_times.is_empty() && v1.leap_second_records.is_empty() {
+            return discrepancies;
+        }
+
+        let start = i64::from(i32::MIN);
+        if self.local_at(start) != v1.local_at(start) {
+            discrepancies.push(BlockDiscrepancy::InitialType);
+        }
+        let (ours, theirs) = (self.v1_changes(), v1.v1_changes());
+        let (mut i, mut j) = (0, 0);
+        loop {
+            match (ours.get(i), theirs.get(j)) {
+                (Some(&(at, local)), Some(&(v1_at, v1_local))) if at == v1_at => {
+                    if local != v1_local {
+                        discrepancies.push(BlockDiscrepancy::TypeMismatch { at });
+                    }
+                    i += 1;
+                    j += 1;
+                }
+                (Some(&(at, _)), next) if next.is_none_or(|&(v1_at, _)| at < v1_at) => {
+                    discrepancies.push(BlockDiscrepancy::TransitionMissing { at });
+                    i += 1;
+                }
+                (_, Some(&(at, _))) => {
+                    discrepancies.push(BlockDiscrepancy::TransitionExtra { at });
+                    j += 1;
+                }
+                (_, None) => break,
+            }
+        }
+
+        let fits = |leap: &&LeapSecondRecord| i32::try_from(leap.occurrence).is_ok();
+        if !self
+            .leap_second_records
+            .iter()
+            .filter(fits)
+            .eq(v1.leap_second_records.iter().filter(fits))
+        {
+            discrepancies.push(BlockDiscrepancy::LeapSeconds);
+        }
+        discrepancies
+    }
+
+    /// The local time type the transitions give at the given time, ignoring any footer.
+    fn local_at(&self, ut: i64) -> Option<LocalTimeType<'_>> {
+        self.type_idx_at(ut)
+            .filter(|&idx| idx < self.local_time_types.len())
+            .map(|idx| self.local_time_type(idx))
+    }
+
+    /// The transitions after the start of the 32-bit range and within it which change the
+    /// local time type, with the type they change to.
+    fn v1_changes(&self) -> Vec<(i64, LocalTimeType<'_>)> {
+        let range = i64::from(i32::MIN) + 1..=i64::from(i32::MAX);
+        let mut prev = self.local_at(i64::from(i32::MIN));
+        let mut changes = Vec::new();
+        for (&at, &typ) in self.transition_times.iter().zip(&self.transition_types) {
+            let idx = usize::from(typ);
+            if !range.contains(&at) || idx >= self.local_time_types.len() {
+                continue;
+            }
+            let local = self.local_time_type(idx);
+            if prev != Some(local) {
+                changes.push((at, local));
+                prev = Some(local);
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use crate::{Header, ParseError, ParseOptions};
+
+    /// The length of the header and version 1 data block at the start of the given data.
+    fn v1_len(data: &[u8]) -> usize {
+        let hdr = Header::from_array(data[..Header::SIZE].try_into().unwrap());
+        Header::SIZE + hdr.block_len(true) as usize
+    }
+
+    /// The version 1 data block of `v1` followed by the rest of `v2`, both version 2+ data.
+    fn splice(v1: &[u8], v2: &[u8]) -> Vec<u8> {
+        let mut data = v1[..v1_len(v1)].to_vec();
+        data.extend_from_slice(&v2[v1_len(v2)..]);
+        data
+    }
+
+    #[test]
+    fn test_check_v1() {
+        let opts = ParseOptions {
+            check_v1: true,
+            ..ParseOptions::default()
+        };
+        let tz = sample();
+        assert_eq!(
+            tz,
+            TimeZoneInfo::parse_slice(&tz.to_bytes(), &opts).unwrap()
+        );
+
+        // A minimal version 1 block is accepted.
+        let slim = TimeZoneInfo {
+            transition_times: vec![],
+            transition_types: vec![],
+            ..sample()
+        };
+        let data = splice(&slim.to_bytes(), &tz.to_bytes());
+        assert_eq!(tz, TimeZoneInfo::parse_slice(&data, &opts).unwrap());
+
+        let mut other = sample();
+        other.transition_times = vec![100, 250, 300, 400];
+        other.transition_types = vec![1, 0, 0, 1];
+        other.local_time_types[1].ut_off_secs = -6 * 3600;
+        let data = splice(&other.to_bytes(), &tz.to_bytes());
+        // Without the option, the version 1 data is ignored.
+        assert_eq!(
+            tz,
+            TimeZoneInfo::parse_slice(&data, &ParseOptions::default()).unwrap()
+        );
+        match TimeZoneInfo::parse_slice(&data, &opts) {
+            Err(ParseError::BlockMismatch {
+                offset,
+                discrepancies,
+            }) => {
+                assert_eq!(
+                    vec![
+                        BlockDiscrepancy::TypeMismatch { at: 100 },
+                        BlockDiscrepancy::TransitionMissing { at: 200 },
+                        BlockDiscrepancy::TransitionExtra { at: 250 },
+                        BlockDiscrepancy::TransitionMissing { at: 300 },
+                        BlockDiscrepancy::TransitionExtra { at: 400 },
+                    ],
+                    discrepancies
+                );
+                assert_eq!(v1_len(&data), offset as usize);
+            }
+            other => panic!("{other:?}"),
+        }
+        #[cfg(feature = "std")]
+        assert!(matches!(
+            TimeZoneInfo::parse_seek_with(std::io::Cursor::new(&data), &opts),
+            Err(ParseError::BlockMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,1162 @@
+//! Loading zones by IANA name from a zoneinfo directory.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+use crate::android::AndroidTzdata;
+
+use crate::{ParseOptions, TimeZoneInfo, ZoneError, ZoneSource, ZoneTabEntry};
+
+/// Places where operating systems commonly install the compiled zoneinfo database.
+const SYSTEM_PATHS: &[&str] = &[
+    "/usr/share/zoneinfo",
+    "/usr/lib/zoneinfo",
+    "/usr/share/lib/zoneinfo",
+    "/etc/zoneinfo",
+];
+
+/// Where Android keeps its single-file tzdata container, most recently updated first.
+const ANDROID_PATHS: &[&str] = &[
+    "/data/misc/zoneinfo/current/tzdata",
+    "/apex/com.android.tzdata/etc/tz/tzdata",
+    "/system/usr/share/zoneinfo/tzdata",
+];
+
+/// The longest zone name accepted. Real names are well under this.
+const MAX_NAME_LEN: usize = 255;
+
+/// The caches of zones from directories and the bundled snapshot, shared by every database
+/// reading the same one.
+static CACHES: OnceLock<Mutex<HashMap<CacheKey, Arc<ZoneCache>>>> = OnceLock::new();
+
+/// A database of TZif files, laid out as a directory tree named by IANA zone name, like
+/// `/usr/share/zoneinfo`.
+///
+/// Zones are cached once parsed, so getting one again is cheap. The cache is shared by every
+/// database opened on the same directory, or on the bundled snapshot, in the process, and
+/// by clones of an Android database. [`invalidate_cache`](Self::invalidate_cache) empties it,
+/// for when the files change, and [`with_cache`](Self::with_cache) gives a database a
+/// [`ZoneCache`] of its own, which can be bounded.
+#[derive(Debug, Clone)]
+pub struct ZoneDatabase {
+    source: Source,
+    cache: Arc<ZoneCache>,
+}
+
+#[derive(Debug, Clone)]
+enum Source {
+    Dir(PathBuf),
+    Android(Arc<AndroidTzdata>),
+    #[cfg(feature = "bundled")]
+    Bundled,
+    Chain(Vec<ZoneDatabase>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum CacheKey {
+    Dir(PathBuf),
+    #[cfg(feature = "bundled")]
+    Bundled,
+}
+
+/// A cache of parsed zones by name, which a [`ZoneDatabase`] looks in before reading and
+/// parsing a zone, and adds what it parses to.
+///
+/// By default databases share an unbounded cache per source, keeping every zone they've loaded.
+/// A cache with limits, given to a database with [`ZoneDatabase::with_cache`], evicts the
+/// least recently used zones to stay within them instead, for processes which touch many zones
+/// briefly. Sizes are [`TimeZoneInfo::heap_size`]; a zone bigger than the whole budget is kept
+/// on its own until the next is loaded.
+#[derive(Default)]
+pub struct ZoneCache {
+    state: RwLock<CacheState>,
+    max_entries: Option<usize>,
+    max_bytes: Option<usize>,
+    /// Counts up on each use, to stamp cached zones with when they were last used.
+    clock: AtomicU64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    zones: HashMap<String, CachedZone>,
+    bytes: usize,
+    /// The database's links, once they've been asked for.
+    links: Option<Arc<BTreeMap<String, String>>>,
+}
+
+impl CacheState {
+    fn remove(&mut self, name: &str) {
+        if let Some(old) = self.zones.remove(name) {
+            self.bytes -= old.bytes;
+        }
+    }
+}
+
+struct CachedZone {
+    tz: Arc<TimeZoneInfo>,
+    bytes: usize,
+    last_used: AtomicU64,
+}
+
+impl ZoneCache {
+    /// A cache holding at most `max_entries` zones and `max_bytes` bytes of them, where given.
+    pub fn new(max_entries: Option<usize>, max_bytes: Option<usize>) -> Self {
+        Self {
+            max_entries,
+            max_bytes,
+            ..Self::default()
+        }
+    }
+
+    /// The cache for the given source, shared process-wide.
+    fn shared(key: CacheKey) -> Arc<Self> {
+        let caches = CACHES.get_or_init(Default::default);
+        let mut caches = caches.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(caches.entry(key).or_default())
+    }
+
+    /// The number of zones cached.
+    pub fn len(&self) -> usize {
+        self.read().zones.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The total size of the zones cached, in bytes.
+    pub fn bytes(&self) -> usize {
+        self.read().bytes
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, CacheState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, CacheState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, name: &str) -> Option<Arc<TimeZoneInfo>> {
+        let state = self.read();
+        let cached = state.zones.get(name)?;
+        let now = self.clock.fetch_add(1, Ordering::Relaxed);
+        cached.last_used.store(now, Ordering::Relaxed);
+        Some(Arc::clone(&cached.tz))
+    }
+
+    /// Cache the zone, replacing any by the same name if `replace` is set, and return the one
+    /// cached.
+    fn insert(&self, name: &str, tz: Arc<TimeZoneInfo>, replace: bool) -> Arc<TimeZoneInfo> {
+        let mut state = self.write();
+        if let Some(cached) = state.zones.get(name).filter(|_| !replace) {
+            return Arc::clone(&cached.tz);
+        }
+        let bytes = tz.heap_size();
+        let cached = CachedZone {
+            tz: Arc::clone(&tz),
+            bytes,
+            last_used: AtomicU64::new(self.clock.fetch_add(1, Ordering::Relaxed)),
+        };
+        state.bytes += bytes;
+        if let Some(old) = state.zones.insert(name.to_owned(), cached) {
+            state.bytes -= old.bytes;
+        }
+        while state.zones.len() > 1
+            && (self.max_entries.is_some_and(|max| state.zones.len() > max)
+                || self.max_bytes.is_some_and(|max| state.bytes > max))
+        {
+            let lru = state
+                .zones
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used.load(Ordering::Relaxed))
+                .map(|(name, _)| name.clone());
+            let Some(lru) = lru else { break };
+            state.remove(&lru);
+        }
+        tz
+    }
+
+    #[cfg(feature = "watch")]
+    fn remove(&self, name: &str) {
+        self.write().remove(name);
+    }
+
+    fn clear(&self) {
+        *self.write() = CacheState::default();
+    }
+}
+
+impl fmt::Debug for ZoneCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.read();
+        f.debug_struct("ZoneCache")
+            .field("zones", &state.zones.len())
+            .field("bytes", &state.bytes)
+            .field("max_entries", &self.max_entries)
+            .field("max_bytes", &self.max_bytes)
+            .finish()
+    }
+}
+
+impl ZoneDatabase {
+    /// Locate the system's zoneinfo directory, or on Android its tzdata file, if it has one.
+    ///
+    /// As with the C library, a non-empty `TZDIR` environment variable names the directory to
+    /// use instead, whether or not it exists, so that sandboxes and tests can redirect zone
+    /// lookups.
+    pub fn system() -> Option<Self> {
+        Self::system_in(std::env::var_os("TZDIR"))
+    }
+
+    /// [`system`](Self::system), with the given value of `TZDIR`.
+    fn system_in(tzdir: Option<std::ffi::OsString>) -> Option<Self> {
+        if let Some(tzdir) = tzdir.filter(|tzdir| !tzdir.is_empty()) {
+            return Some(Self::open(tzdir));
+        }
+        SYSTEM_PATHS
+            .iter()
+            .map(Path::new)
+            .find(|path| path.is_dir())
+            .map(Self::open)
+            .or_else(|| {
+                ANDROID_PATHS
+                    .iter()
+                    .find_map(|path| Self::android(path).ok())
+            })
+    }
+
+    /// Use the zoneinfo directory at the given path.
+    pub fn open(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        Self {
+            cache: ZoneCache::shared(CacheKey::Dir(root.clone())),
+            source: Source::Dir(root),
+        }
+    }
+
+    /// Load an Android `tzdata` file, which holds every zone in a single indexed container.
+    pub fn android(path: impl AsRef<Path>) -> Result<Self, ZoneError> {
+        let path = path.as_ref();
+        let data = fs::read(path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => ZoneError::NotFound(path.display().to_string()),
+            _ => ZoneError::Io(e),
+        })?;
+        Self::from_android_bytes(data)
+    }
+
+    /// Use the contents of an Android `tzdata` file.
+    pub fn from_android_bytes(data: Vec<u8>) -> Result<Self, ZoneError> {
+        Ok(Self {
+            source: Source::Android(Arc::new(AndroidTzdata::parse(data)?)),
+            cache: Arc::default(),
+        })
+    }
+
+    /// Use the zoneinfo snapshot embedded in the crate, for systems which don't have their own.
+    #[cfg(feature = "bundled")]
+    pub fn bundled() -> Self {
+        Self {
+            source: Source::Bundled,
+            cache: ZoneCache::shared(CacheKey::Bundled),
+        }
+    }
+
+    /// Search the given databases in order, so that each file comes from the first which has
+    /// it, as for a user's override directory ahead of the system's, with the bundled snapshot
+    /// as a last resort.
+    ///
+    /// The database has a cache of its own, rather than sharing those of the ones it searches.
+    /// Its zone names are those of all of them, and its links those of the first `tzdata.zi`
+    /// found.
+    pub fn chain(databases: impl IntoIterator<Item = ZoneDatabase>) -> Self {
+        Self {
+            source: Source::Chain(databases.into_iter().collect()),
+            cache: Arc::default(),
+        }
+    }
+
+    /// The zoneinfo directory, or `None` if the database isn't a directory.
+    pub fn root(&self) -> Option<&Path> {
+        match &self.source {
+            Source::Dir(root) => Some(root),
+            Source::Android(_) | Source::Chain(_) => None,
+            #[cfg(feature = "bundled")]
+            Source::Bundled => None,
+        }
+    }
+
+    /// Load and parse the zone with the given IANA name, like "America/New_York", or give
+    /// the one cached from before.
+    pub fn get(&self, name: &str) -> Result<Arc<TimeZoneInfo>, ZoneError> {
+        if let Some(tz) = self.cache.get(name) {
+            return Ok(tz);
+        }
+        let data = self.read(name)?;
+        let tz = Arc::new(TimeZoneInfo::parse_slice(&data, &ParseOptions::default())?);
+        Ok(self.cache.insert(name, tz, false))
+    }
+
+    /// Use the given cache of parsed zones in place of the one shared by every database on
+    /// the same source. Clones of the database share it.
+    pub fn with_cache(mut self, cache: ZoneCache) -> Self {
+        self.cache = Arc::new(cache);
+        self
+    }
+
+    /// The cache of parsed zones the database uses.
+    pub fn cache(&self) -> &ZoneCache {
+        &self.cache
+    }
+
+    /// Read every cached zone for which `affected` is true again, swapping in the new version,
+    /// or dropping it from the cache if it can no longer be loaded.
+    #[cfg(feature = "watch")]
+    pub(crate) fn reload_cached(&self, affected: impl Fn(&str) -> bool) {
+        // Links can change along with anything else, and are found again when next needed.
+        self.cache.write().links = None;
+        let names: Vec<String> = self
+            .cache
+            .read()
+            .zones
+            .keys()
+            .filter(|name| affected(name))
+            .cloned()
+            .collect();
+        for name in names {
+            let fresh = self
+                .read(&name)
+                .ok()
+                .and_then(|data| TimeZoneInfo::parse_slice(&data, &ParseOptions::default()).ok());
+            match fresh {
+                Some(tz) => {
+                    self.cache.insert(&name, Arc::new(tz), true);
+                }
+                None => self.cache.remove(&name),
+            }
+        }
+    }
+
+    /// Empty the cache of parsed zones, for every database sharing it, so they're read from the
+    /// database again. Call this when the files may have changed, as when tzdata is updated.
+    /// Zones already handed out are unaffected.
+    pub fn invalidate_cache(&self) {
+        self.cache.clear();
+    }
+
+    /// The names of every zone in the database, in order, such as can be passed to
+    /// [`get`](Self::get).
+    ///
+    /// For a directory, these are the files in it, other than the `posix/` and `right/`
+    /// variant trees and `localtime`, which start with the TZif magic. `posixrules`, which
+    /// only supplies rules for TZ strings without any, isn't a zone and is left out too.
+    pub fn zone_names(&self) -> Result<Vec<String>, ZoneError> {
+        let mut names = match &self.source {
+            Source::Dir(root) => {
+                let mut names = Vec::new();
+                collect_names(root, root, &mut names).map_err(ZoneError::Io)?;
+                names
+            }
+            Source::Android(tzdata) => tzdata.names().map(ToOwned::to_owned).collect(),
+            #[cfg(feature = "bundled")]
+            Source::Bundled => crate::bundled::names()
+                .into_iter()
+                .filter(|name| crate::bundled::get(name).is_some_and(|d| d.starts_with(b"TZif")))
+                .map(ToOwned::to_owned)
+                .collect(),
+            Source::Chain(databases) => {
+                let mut names = Vec::new();
+                for db in databases {
+                    names.extend(db.zone_names()?);
+                }
+                names
+            }
+        };
+        names.retain(|name| name != "posixrules");
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    /// Every zone in the database with its name, in the order of
+    /// [`zone_names`](Self::zone_names), each loaded with [`get`](Self::get) only when the
+    /// iterator reaches it.
+    pub fn iter_zones(
+        &self,
+    ) -> Result<impl Iterator<Item = (String, Result<Arc<TimeZoneInfo>, ZoneError>)> + '_, ZoneError>
+    {
+        let names = self.zone_names()?;
+        Ok(names.into_iter().map(|name| {
+            let tz = self.get(&name);
+            (name, tz)
+        }))
+    }
+
+    /// The IANA name of the system's current zone, if it can be determined.
+    ///
+    /// A `TZ` environment variable naming a zone takes precedence. Otherwise, this uses the
+    /// `/etc/localtime` symlink or `/etc/timezone` on Unix systems including macOS, the
+    /// `persist.sys.timezone` property on Android, and the registry's `TimeZoneKeyName` on
+    /// Windows, mapped to an IANA name.
+    #[cfg(feature = "current-zone")]
+    pub fn current_zone_name() -> Option<String> {
+        let from_env = std::env::var("TZ")
+            .ok()
+            .and_then(|tz| name_from_tz(&tz, Self::system().as_ref()));
+        from_env.or_else(platform_zone_name)
+    }
+
+    /// Load and parse the zone for the given Windows time zone ID, like "Pacific Standard
+    /// Time", using its default IANA zone from the CLDR mapping.
+    #[cfg(feature = "windows-zones")]
+    pub fn get_windows(&self, windows_id: &str) -> Result<Arc<TimeZoneInfo>, ZoneError> {
+        let name = crate::windows_to_iana(windows_id)
+            .ok_or_else(|| ZoneError::NotFound(windows_id.to_owned()))?;
+        self.get(name)
+    }
+
+    /// The zone table from `zone1970.tab`, or from the older `zone.tab` if the database lacks
+    /// it, listing the countries and principal location of each zone.
+    pub fn zone_tab(&self) -> Result<Vec<ZoneTabEntry>, ZoneError> {
+        let data = match self.read("zone1970.tab") {
+            Err(ZoneError::NotFound(_)) => self.read("zone.tab")?,
+            result => result?,
+        };
+        Ok(ZoneTabEntry::parse_table(&String::from_utf8_lossy(&data))?)
+    }
+
+    /// The zone table entries for zones used in the country with the given ISO 3166 code.
+    pub fn zones_in_country(&self, country: &str) -> Result<Vec<ZoneTabEntry>, ZoneError> {
+        let mut entries = self.zone_tab()?;
+        entries.retain(|entry| entry.countries.iter().any(|c| c == country));
+        Ok(entries)
+    }
+
+    /// All zone table entries, ordered by the distance of their principal location from the
+    /// given point (in degrees north and east), nearest first.
+    ///
+    /// This is a simple nearest-location search, useful for picking a sensible default zone;
+    /// the nearest location's zone is not necessarily the one in effect at the point itself.
+    pub fn zones_near(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Vec<ZoneTabEntry>, ZoneError> {
+        let mut entries = Vec::from_iter(self.zone_tab()?.into_iter().map(|entry| {
+            let distance = angular_distance(latitude, longitude, entry.latitude, entry.longitude);
+            (distance, entry)
+        }));
+        entries.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Ok(entries.into_iter().map(|(_, entry)| entry).collect())
+    }
+
+    /// The database's source, from the `tzdata.zi` file which accompanies the compiled zones,
+    /// ready to be compiled afresh.
+    pub fn zone_source(&self) -> Result<ZoneSource, ZoneError> {
+        let data = self.read("tzdata.zi")?;
+        let mut source = ZoneSource::new();
+        source.parse(&String::from_utf8_lossy(&data))?;
+        Ok(source)
+    }
+
+    /// The links in the database, from each link's name to its target, which may itself be a
+    /// link.
+    ///
+    /// Links come from the `Link` lines of `tzdata.zi`, which also catches the hard links `zic`
+    /// makes by default, and for a directory, from symlinks to files elsewhere in it. Without
+    /// `tzdata.zi`, hard links can't be told apart from zones. They're found once and cached
+    /// along with the zones, until [`invalidate_cache`](Self::invalidate_cache).
+    pub fn links(&self) -> Result<BTreeMap<String, String>, ZoneError> {
+        Ok((*self.cached_links()?).clone())
+    }
+
+    fn cached_links(&self) -> Result<Arc<BTreeMap<String, String>>, ZoneError> {
+        if let Some(links) = &self.cache.read().links {
+            return Ok(Arc::clone(links));
+        }
+        let links = Arc::new(self.find_links()?);
+        self.cache.write().links = Some(Arc::clone(&links));
+        Ok(links)
+    }
+
+    fn find_links(&self) -> Result<BTreeMap<String, String>, ZoneError> {
+        let mut links: BTreeMap<String, String> = match self.zone_source() {
+            Ok(source) => source
+                .links()
+                .map(|(name, target)| (name.to_owned(), target.to_owned()))
+                .collect(),
+            Err(ZoneError::NotFound(_)) => BTreeMap::new(),
+            Err(e) => return Err(e),
+        };
+        if let Source::Dir(root) = &self.source {
+            let root = fs::canonicalize(root).map_err(ZoneError::Io)?;
+            for name in self.zone_names()? {
+                let path = root.join(&name);
+                if links.contains_key(&name) || !path.is_symlink() {
+                    continue;
+                }
+                let target = fs::canonicalize(&path).map_err(ZoneError::Io)?;
+                let target = target
+                    .strip_prefix(&root)
+                    .ok()
+                    .and_then(Path::to_str)
+                    .map(|target| target.replace(std::path::MAIN_SEPARATOR, "/"));
+                if let Some(target) = target.filter(|target| *target != name) {
+                    links.insert(name, target);
+                }
+            }
+        }
+        Ok(links)
+    }
+
+    /// The name of the zone the given name refers to, following links, so that "US/Pacific"
+    /// gives "America/Los_Angeles". A name which isn't a link is given back as it is, if the
+    /// database has it.
+    pub fn canonical_name(&self, name: &str) -> Result<String, ZoneError> {
+        self.read(name)?;
+        Ok(resolve_link(&*self.cached_links()?, name).to_owned())
+    }
+
+    /// The other names which refer to the same zone as the given name, in order: every link
+    /// which leads to it, and the zone's own name if the given one is a link.
+    pub fn aliases(&self, name: &str) -> Result<Vec<String>, ZoneError> {
+        self.read(name)?;
+        let links = self.cached_links()?;
+        let canonical = resolve_link(&links, name);
+        let mut aliases: Vec<String> = links
+            .keys()
+            .filter(|link| resolve_link(&links, link) == canonical)
+            .cloned()
+            .collect();
+        if canonical != name {
+            aliases.push(canonical.to_owned());
+        }
+        aliases.retain(|alias| alias != name);
+        aliases.sort();
+        Ok(aliases)
+    }
+
+    /// Read a file from the database, refusing names which could refer to anything outside
+    /// of it.
+    fn read(&self, name: &str) -> Result<Cow<'_, [u8]>, ZoneError> {
+        if !is_valid_name(name) {
+            return Err(ZoneError::InvalidName(name.to_owned()));
+        }
+        match &self.source {
+            Source::Dir(root) => match fs::read(root.join(name)) {
+                Ok(data) => Ok(Cow::Owned(data)),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    Err(ZoneError::NotFound(name.to_owned()))
+                }
+                Err(e) => Err(ZoneError::Io(e)),
+            },
+            Source::Android(tzdata) => tzdata
+                .get(name)
+                .map(Cow::Borrowed)
+                .ok_or_else(|| ZoneError::NotFound(name.to_owned())),
+            #[cfg(feature = "bundled")]
+            Source::Bundled => crate::bundled::get(name)
+                .map(Cow::Borrowed)
+                .ok_or_else(|| ZoneError::NotFound(name.to_owned())),
+            Source::Chain(databases) => {
+                for db in databases {
+                    match db.read(name) {
+                        Err(ZoneError::NotFound(_)) => continue,
+                        result => return result,
+                    }
+                }
+                Err(ZoneError::NotFound(name.to_owned()))
+            }
+        }
+    }
+}
+
+impl TimeZoneInfo {
+    /// Load the system's local zone from `/etc/localtime`, along with its IANA name if it can be
+    /// determined.
+    ///
+    /// The name comes from the symlink target of `/etc/localtime` when it points into a
+    /// zoneinfo directory, or else from the contents of `/etc/timezone`.
+    pub fn local() -> Result<(Self, Option<String>), ZoneError> {
+        let localtime = Path::new("/etc/localtime");
+        let data = fs::read(localtime).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => ZoneError::NotFound("localtime".to_owned()),
+            _ => ZoneError::Io(e),
+        })?;
+        let info = Self::parse_slice(&data, &ParseOptions::default())?;
+        Ok((info, localtime_name()))
+    }
+
+    /// Load the zone described by the `TZ` environment variable, as the C library does.
+    ///
+    /// `TZ` may name a zone in the system database, optionally with a leading ':', or give an
+    /// absolute path to a TZif file after a ':'. Otherwise it is read as a POSIX TZ string like
+    /// "EST5EDT,M3.2.0,M11.1.0", giving a zone with no transitions. An empty `TZ` means UTC, and
+    /// if it is unset the local zone from [`local`](Self::local) is used.
+    ///
+    /// With the `bundled` feature, names are looked up in the bundled database on systems
+    /// which don't have their own.
+    pub fn from_env() -> Result<Self, ZoneError> {
+        match std::env::var_os("TZ") {
+            None => Ok(Self::local()?.0),
+            Some(tz) => match tz.to_str() {
+                Some(tz) => Self::from_tz(tz),
+                None => Err(ZoneError::InvalidName(tz.to_string_lossy().into_owned())),
+            },
+        }
+    }
+
+    fn from_tz(tz: &str) -> Result<Self, ZoneError> {
+        if tz.is_empty() {
+            return Ok(Self::utc());
+        }
+
+        let (name, explicit) = match tz.strip_prefix(':') {
+            Some(name) => (name, true),
+            None => (tz, false),
+        };
+        if explicit && name.starts_with('/') {
+            let data = fs::read(name).map_err(|e| match e.kind() {
+                io::ErrorKind::NotFound => ZoneError::NotFound(name.to_owned()),
+                _ => ZoneError::Io(e),
+            })?;
+            return Ok(Self::parse_slice(&data, &ParseOptions::default())?);
+        }
+
+        let db = ZoneDatabase::system();
+        #[cfg(feature = "bundled")]
+        let db = db.or_else(|| Some(ZoneDatabase::bundled()));
+        let from_db = match db {
+            Some(db) => db.get(name).map(Arc::unwrap_or_clone),
+            None => Err(ZoneError::NotFound(name.to_owned())),
+        };
+        match from_db {
+            Err(ZoneError::NotFound(_) | ZoneError::InvalidName(_)) if !explicit => {
+                match tz.parse() {
+                    Ok(posix) => Ok(Self::from_posix(posix)),
+                    Err(_) => from_db,
+                }
+            }
+            result => result,
+        }
+    }
+}
+
+/// Add the names of the TZif files under `dir`, relative to `root`, to `names`.
+fn collect_names(root: &Path, dir: &Path, names: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.strip_prefix(root).ok().and_then(Path::to_str) else {
+            continue;
+        };
+        let name = name.replace(std::path::MAIN_SEPARATOR, "/");
+        if matches!(name.as_str(), "posix" | "right" | "localtime") || !is_valid_name(&name) {
+            continue;
+        }
+        if path.is_dir() {
+            collect_names(root, &path, names)?;
+        } else {
+            let mut magic = [0; 4];
+            let is_tzif = fs::File::open(&path)
+                .and_then(|mut file| io::Read::read_exact(&mut file, &mut magic))
+                .is_ok_and(|()| &magic == b"TZif");
+            if is_tzif {
+                names.push(name);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Follow links from the given name to a name which isn't one, giving up on cycles.
+fn resolve_link<'a>(links: &'a BTreeMap<String, String>, mut name: &'a str) -> &'a str {
+    for _ in 0..links.len() {
+        match links.get(name) {
+            Some(target) => name = target,
+            None => break,
+        }
+    }
+    name
+}
+
+/// The zone name `/etc/localtime` refers to, from its symlink target or `/etc/timezone`.
+fn localtime_name() -> Option<String> {
+    fs::read_link("/etc/localtime")
+        .ok()
+        .and_then(|target| name_from_path(&target))
+        .or_else(|| {
+            let contents = fs::read_to_string("/etc/timezone").ok()?;
+            let name = contents.trim();
+            is_valid_name(name).then(|| name.to_owned())
+        })
+}
+
+/// The zone name a `TZ` environment variable value refers to, if it names a zone rather than
+/// giving a POSIX TZ string.
+#[cfg(feature = "current-zone")]
+fn name_from_tz(tz: &str, db: Option<&ZoneDatabase>) -> Option<String> {
+    let name = tz.strip_prefix(':').unwrap_or(tz);
+    if name.starts_with('/') {
+        return name_from_path(Path::new(name));
+    }
+    let exists = db.is_none_or(|db| db.read(name).is_ok());
+    (is_valid_name(name) && exists).then(|| name.to_owned())
+}
+
+/// The current zone's name according to the operating system's own settings.
+#[cfg(all(feature = "current-zone", unix, not(target_os = "android")))]
+fn platform_zone_name() -> Option<String> {
+    // This also covers macOS, where /etc/localtime links into /var/db/timezone/zoneinfo.
+    localtime_name()
+}
+
+#[cfg(all(feature = "current-zone", target_os = "android"))]
+fn platform_zone_name() -> Option<String> {
+    let output = std::process::Command::new("getprop")
+        .arg("persist.sys.timezone")
+        .output()
+        .ok()?;
+    let name = String::from_utf8(output.stdout).ok()?;
+    let name = name.trim();
+    is_valid_name(name).then(|| name.to_owned())
+}
+
+#[cfg(all(feature = "current-zone", windows))]
+fn platform_zone_name() -> Option<String> {
+    // Windows only records its own zone ID; map it to an IANA name.
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKLM\SYSTEM\CurrentControlSet\Control\TimeZoneInformation",
+            "/v",
+            "TimeZoneKeyName",
+        ])
+        .output()
+        .ok()?;
+    let output = String::from_utf8_lossy(&output.stdout);
+    let line = output
+        .lines()
+        .find(|line| line.trim_start().starts_with("TimeZoneKeyName"))?;
+    let (_, id) = line.split_once("REG_SZ")?;
+    crate::windows_to_iana(id.trim()).map(ToOwned::to_owned)
+}
+
+#[cfg(all(feature = "current-zone", not(any(unix, windows))))]
+fn platform_zone_name() -> Option<String> {
+    None
+}
+
+/// Extract a zone name from a path into a zoneinfo directory, like
+/// "/usr/share/zoneinfo/America/New_York" or "../usr/share/zoneinfo/posix/Europe/Paris".
+fn name_from_path(path: &Path) -> Option<String> {
+    let path = path.to_str()?;
+    let (_, name) = path.rsplit_once("zoneinfo/")?;
+    let name = name
+        .strip_prefix("posix/")
+        .or_else(|| name.strip_prefix("right/"))
+        .unwrap_or(name);
+    is_valid_name(name).then(|| name.to_owned())
+}
+
+/// The central angle in radians between two points on a sphere, given in degrees, by the
+/// haversine formula.
+fn angular_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (lon2 - lon1).to_radians();
+    let h = (dlat / 2.).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.).sin().powi(2);
+    2. * h.sqrt().min(1.).asin()
+}
+
+/// Check that a zone name consists only of the characters IANA uses, split into non-empty
+/// components by '/', none of which are "." or "..".
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    name.len() <= MAX_NAME_LEN
+        && name.split('/').all(|component| {
+            !component.is_empty()
+                && component != "."
+                && component != ".."
+                && component
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"-_+.".contains(&b))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_names() {
+        for good in [
+            "UTC",
+            "America/New_York",
+            "Etc/GMT+5",
+            "America/Port-au-Prince",
+        ] {
+            assert!(is_valid_name(good), "{good}");
+        }
+        for bad in [
+            "",
+            "/etc/passwd",
+            "../etc/passwd",
+            "America/../..",
+            "a//b",
+            "a/",
+            "a\\b",
+        ] {
+            assert!(!is_valid_name(bad), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_name_from_path() {
+        let name = |p: &str| name_from_path(Path::new(p));
+        assert_eq!(
+            Some("America/New_York".to_owned()),
+            name("/usr/share/zoneinfo/America/New_York")
+        );
+        assert_eq!(
+            Some("Europe/Paris".to_owned()),
+            name("../usr/share/zoneinfo/posix/Europe/Paris")
+        );
+        assert_eq!(None, name("/etc/some-other-file"));
+    }
+
+    #[test]
+    fn test_angular_distance() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        assert!(close(0., angular_distance(10., 20., 10., 20.)));
+        assert!(close(
+            core::f64::consts::PI,
+            angular_distance(0., 0., 0., 180.)
+        ));
+        // Across the antimeridian.
+        assert!(close(
+            0.2f64.to_radians(),
+            angular_distance(0., 179.9, 0., -179.9)
+        ));
+    }
+
+    #[cfg(feature = "current-zone")]
+    #[test]
+    fn test_name_from_tz() {
+        assert_eq!(
+            Some("America/New_York".to_owned()),
+            name_from_tz(":America/New_York", None)
+        );
+        assert_eq!(
+            Some("Europe/Paris".to_owned()),
+            name_from_tz(":/usr/share/zoneinfo/Europe/Paris", None)
+        );
+        assert_eq!(None, name_from_tz("EST5EDT,M3.2.0,M11.1.0", None));
+        assert_eq!(None, name_from_tz("", None));
+    }
+
+    #[test]
+    fn test_from_tz() {
+        let utc = TimeZoneInfo::from_tz("").unwrap();
+        assert_eq!("UTC", utc.local_time_type_at(0).unwrap().desig);
+
+        let posix = TimeZoneInfo::from_tz("XST3XDT,M1.1.0,M12.5.0").unwrap();
+        assert!(posix.transition_times.is_empty());
+        assert_eq!(
+            -3 * 3600,
+            posix.local_time_type_at(0).unwrap().ut_offset_secs
+        );
+        assert!(matches!(
+            TimeZoneInfo::from_tz(":XST3XDT,M1.1.0,M12.5.0"),
+            Err(ZoneError::InvalidName(_))
+        ));
+        assert!(matches!(
+            TimeZoneInfo::from_tz("No/Such_Zone"),
+            Err(ZoneError::NotFound(_))
+        ));
+
+        let path = std::env::temp_dir().join(format!("tzif-env-test-{}", std::process::id()));
+        fs::write(&path, sample().to_bytes()).unwrap();
+        let tz = TimeZoneInfo::from_tz(&format!(":{}", path.display())).unwrap();
+        assert_eq!(3, tz.transition_times.len());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_get() {
+        let root = std::env::temp_dir().join(format!("tzif-db-test-{}", std::process::id()));
+        fs::create_dir_all(root.join("Test")).unwrap();
+        fs::write(root.join("Test/Sample"), sample().to_bytes()).unwrap();
+
+        let db = ZoneDatabase::open(&root);
+        assert_eq!(vec!["Test/Sample"], db.zone_names().unwrap());
+        let tz = db.get("Test/Sample").unwrap();
+        assert_eq!(3, tz.transition_times.len());
+        assert!(matches!(
+            db.get("Test/Missing"),
+            Err(ZoneError::NotFound(_))
+        ));
+        assert!(matches!(
+            db.get("../Test/Sample"),
+            Err(ZoneError::InvalidName(_))
+        ));
+
+        assert!(matches!(db.zone_tab(), Err(ZoneError::NotFound(_))));
+        fs::write(
+            root.join("zone.tab"),
+            "# comment\nUS\t+404251-0740023\tAmerica/New_York\tEastern (most areas)\n",
+        )
+        .unwrap();
+        assert_eq!(1, db.zones_in_country("US").unwrap().len());
+        fs::write(
+            root.join("zone1970.tab"),
+            "AE,OM\t+2518+05518\tAsia/Dubai\nUS\t+404251-0740023\tAmerica/New_York\n",
+        )
+        .unwrap();
+        let oman = db.zones_in_country("OM").unwrap();
+        assert_eq!(
+            vec!["Asia/Dubai"],
+            Vec::from_iter(oman.iter().map(|e| &e.zone))
+        );
+
+        let near = db.zones_near(42.36, -71.06).unwrap();
+        assert_eq!("America/New_York", near[0].zone);
+        let near = db.zones_near(23.6, 58.5).unwrap();
+        assert_eq!("Asia/Dubai", near[0].zone);
+
+        assert!(matches!(db.zone_source(), Err(ZoneError::NotFound(_))));
+        fs::write(
+            root.join("tzdata.zi"),
+            "# version 2024a\nZ Test/Fixed 1 - +01\n",
+        )
+        .unwrap();
+        let source = db.zone_source().unwrap();
+        assert_eq!(Some("2024a"), source.version());
+        assert_eq!(vec!["Test/Fixed"], Vec::from_iter(source.zone_names()));
+        fs::write(root.join("tzdata.zi"), "Z Test/Fixed 1 -\n").unwrap();
+        assert!(matches!(db.zone_source(), Err(ZoneError::Compile(_))));
+        // The tables and source aren't zones.
+        assert_eq!(vec!["Test/Sample"], db.zone_names().unwrap());
+
+        // Zones are cached, for every database on the same directory, until invalidated.
+        let other = ZoneDatabase::open(&root);
+        assert!(Arc::ptr_eq(&tz, &other.get("Test/Sample").unwrap()));
+        let mut changed = sample();
+        changed.transition_times.pop();
+        changed.transition_types.pop();
+        fs::write(root.join("Test/Sample"), changed.to_bytes()).unwrap();
+        assert_eq!(3, db.get("Test/Sample").unwrap().transition_times.len());
+        other.invalidate_cache();
+        assert_eq!(2, db.get("Test/Sample").unwrap().transition_times.len());
+        assert_eq!(3, tz.transition_times.len());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_android() {
+        let tzdata = crate::android::tests::build(&[("Test/Sample", &sample().to_bytes())]);
+        let db = ZoneDatabase::from_android_bytes(tzdata).unwrap();
+        assert_eq!(None, db.root());
+        assert_eq!(vec!["Test/Sample"], db.zone_names().unwrap());
+        assert_eq!(3, db.get("Test/Sample").unwrap().transition_times.len());
+        assert!(matches!(
+            db.get("Test/Missing"),
+            Err(ZoneError::NotFound(_))
+        ));
+        assert!(matches!(db.zone_tab(), Err(ZoneError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_chain() {
+        let mut changed = sample();
+        changed.transition_times.pop();
+        changed.transition_types.pop();
+        let data = (sample().to_bytes(), changed.to_bytes());
+        let first = crate::android::tests::build(&[("Test/A", &data.0[..])]);
+        let second = crate::android::tests::build(&[("Test/A", &data.1), ("Test/B", &data.1)]);
+        let db = ZoneDatabase::chain([
+            ZoneDatabase::from_android_bytes(first).unwrap(),
+            ZoneDatabase::from_android_bytes(second).unwrap(),
+        ]);
+        assert_eq!(None, db.root());
+        assert_eq!(vec!["Test/A", "Test/B"], db.zone_names().unwrap());
+        assert_eq!(3, db.get("Test/A").unwrap().transition_times.len());
+        assert_eq!(2, db.get("Test/B").unwrap().transition_times.len());
+        assert!(matches!(
+            db.get("Test/Missing"),
+            Err(ZoneError::NotFound(_))
+        ));
+        assert!(matches!(db.get("../A"), Err(ZoneError::InvalidName(_))));
+        assert!(ZoneDatabase::chain([]).zone_names().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tzdir() {
+        let db = ZoneDatabase::system_in(Some("/nonexistent/zoneinfo".into())).unwrap();
+        assert_eq!(Some(Path::new("/nonexistent/zoneinfo")), db.root());
+        assert!(matches!(db.get("UTC"), Err(ZoneError::NotFound(_))));
+        let default = ZoneDatabase::system_in(None);
+        assert_eq!(
+            default.as_ref().and_then(ZoneDatabase::root),
+            ZoneDatabase::system_in(Some("".into()))
+                .as_ref()
+                .and_then(ZoneDatabase::root)
+        );
+    }
+
+    #[test]
+    fn test_cache() {
+        let data = sample().to_bytes();
+        let names = ["Test/A", "Test/B", "Test/C"];
+        let tzdata = crate::android::tests::build(&names.map(|name| (name, &data[..])));
+        let db = ZoneDatabase::from_android_bytes(tzdata).unwrap();
+
+        // The least recently used zone goes first.
+        let db = db.with_cache(ZoneCache::new(Some(2), None));
+        let a = db.get("Test/A").unwrap();
+        db.get("Test/B").unwrap();
+        assert!(Arc::ptr_eq(&a, &db.get("Test/A").unwrap()));
+        let c = db.get("Test/C").unwrap();
+        assert_eq!(2, db.cache().len());
+        assert!(Arc::ptr_eq(&a, &db.get("Test/A").unwrap()));
+        assert!(Arc::ptr_eq(&c, &db.clone().get("Test/C").unwrap()));
+        assert_eq!(2 * sample().heap_size(), db.cache().bytes());
+
+        // A budget of one and a half zones holds one.
+        let db = db.with_cache(ZoneCache::new(None, Some(sample().heap_size() * 3 / 2)));
+        let a = db.get("Test/A").unwrap();
+        db.get("Test/B").unwrap();
+        assert_eq!(1, db.cache().len());
+        assert!(!Arc::ptr_eq(&a, &db.get("Test/A").unwrap()));
+        db.invalidate_cache();
+        assert!(db.cache().is_empty());
+        assert_eq!(0, db.cache().bytes());
+    }
+
+    #[cfg(feature = "bundled")]
+    #[test]
+    fn test_bundled() {
+        let db = ZoneDatabase::bundled();
+        assert_eq!(None, db.root());
+        let tz = db.get("America/New_York").unwrap();
+        let footer = tz.footer.as_ref().unwrap().to_string();
+        assert_eq!("EST5EDT,M3.2.0,M11.1.0", footer);
+        // Links resolve to the same data as their targets.
+        assert_eq!(
+            db.get("Europe/London").unwrap().to_bytes(),
+            db.get("GB").unwrap().to_bytes()
+        );
+        assert!(matches!(
+            db.get("Test/Missing"),
+            Err(ZoneError::NotFound(_))
+        ));
+        assert!(matches!(db.get("../UTC"), Err(ZoneError::InvalidName(_))));
+        let names = db.zone_names().unwrap();
+        assert!(names.iter().any(|name| name == "America/New_York"));
+        assert!(!names.iter().any(|name| name == "zone1970.tab"));
+        assert!(!names.iter().any(|name| name == "posixrules"));
+        let mut zones = db.iter_zones().unwrap();
+        let (name, tz) = zones.next().unwrap();
+        assert_eq!(names[0], name);
+        assert_eq!(db.get(&name).unwrap(), tz.unwrap());
+        assert_eq!(names.len() - 1, zones.count());
+
+        assert_eq!(
+            "America/Los_Angeles",
+            db.canonical_name("US/Pacific").unwrap()
+        );
+        assert!(db
+            .aliases("America/Los_Angeles")
+            .unwrap()
+            .contains(&"US/Pacific".to_owned()));
+
+        let source = db.zone_source().unwrap();
+        assert!(source.version().is_some());
+        let compiled = source.compile("America/New_York").unwrap();
+        assert_eq!(footer, compiled.footer.unwrap().to_string());
+    }
+
+    #[test]
+    fn test_links() {
+        let root = std::env::temp_dir().join(format!("tzif-links-test-{}", std::process::id()));
+        fs::create_dir_all(root.join("Test")).unwrap();
+        fs::write(root.join("Test/Sample"), sample().to_bytes()).unwrap();
+        // A hard link, as zic makes them, which only tzdata.zi tells apart from a zone.
+        fs::write(root.join("Test/Hard"), sample().to_bytes()).unwrap();
+        fs::write(
+            root.join("tzdata.zi"),
+            "Z Test/Sample -8 - PST\nL Test/Sample Test/Hard\n",
+        )
+        .unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("Hard", root.join("Test/Soft")).unwrap();
+
+        let db = ZoneDatabase::open(&root);
+        assert_eq!("Test/Sample", db.canonical_name("Test/Sample").unwrap());
+        assert_eq!("Test/Sample", db.canonical_name("Test/Hard").unwrap());
+        assert!(matches!(
+            db.canonical_name("Test/Missing"),
+            Err(ZoneError::NotFound(_))
+        ));
+        #[cfg(unix)]
+        {
+            assert_eq!("Test/Hard", db.links().unwrap()["Test/Soft"]);
+            assert_eq!("Test/Sample", db.canonical_name("Test/Soft").unwrap());
+            assert_eq!(
+                vec!["Test/Hard", "Test/Soft"],
+                db.aliases("Test/Sample").unwrap()
+            );
+            assert_eq!(
+                vec!["Test/Hard", "Test/Sample"],
+                db.aliases("Test/Soft").unwrap()
+            );
+        }
+
+        // The links are found once, until the cache is emptied.
+        fs::remove_file(root.join("tzdata.zi")).unwrap();
+        assert_eq!("Test/Sample", db.canonical_name("Test/Hard").unwrap());
+        db.invalidate_cache();
+        assert_eq!("Test/Hard", db.canonical_name("Test/Hard").unwrap());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(feature = "bundled")]
+    #[test]
+    fn test_std_offset_split() {
+        let db = ZoneDatabase::bundled();
+        // British Double Summer Time, mid-1941; Lord Howe Island's half-hour DST, January 2024;
+        // and Ireland's negative DST in winter, January 2024.
+        for (name, ut, std, save) in [
+            ("Europe/London", -900_000_000, 0, 2 * 3600),
+            ("Australia/Lord_Howe", 1_704_067_200, 10 * 3600 + 1800, 1800),
+            ("Europe/Dublin", 1_704_067_200, 3600, -3600),
+        ] {
+            let tz = db.get(name).unwrap();
+            assert_eq!(Some(std), tz.std_offset_at(ut), "{name}");
+            assert_eq!(Some(save), tz.dst_save_at(ut), "{name}");
+            let offset = tz.local_time_type_at(ut).unwrap().ut_offset_secs;
+            assert_eq!(offset, std + save, "{name}");
+        }
+    }
+
+    #[cfg(all(feature = "bundled", feature = "windows-zones"))]
+    #[test]
+    fn test_get_windows() {
+        let db = ZoneDatabase::bundled();
+        let tz = db.get_windows("Pacific Standard Time").unwrap();
+        assert_eq!(
+            db.get("America/Los_Angeles").unwrap().to_bytes(),
+            tz.to_bytes()
+        );
+        assert!(matches!(
+            db.get_windows("Nowhere Standard Time"),
+            Err(ZoneError::NotFound(_))
+        ));
+    }
+}
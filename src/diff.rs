@@ -0,0 +1,341 @@
+//! Comparing two zones by what they say about local time, rather than byte for byte.
+//!
+//! Transitions which don't change the local time type are ignored, and a zone's TZ string
+//! footer stands in for transitions the other zone lists explicitly, so a "slim" file and the
+//! "fat" file covering the same years compare equal.
+
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::civil::{civil_from_days, SECS_PER_DAY};
+use crate::display::Ut;
+use crate::{LeapSecondRecord, LocalTimeType, TimeZoneInfo};
+
+/// A local time type as compared by [`TimeZoneInfo::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffType {
+    pub ut_offset_secs: i32,
+    pub is_dst: bool,
+    pub designation: String,
+}
+
+/// One way in which two zones differ, from [`TimeZoneInfo::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ZoneDifference {
+    Version {
+        old: u8,
+        new: u8,
+    },
+
+    /// The local time type in effect before the first transition differs.
+    InitialType {
+        old: Option<DiffType>,
+        new: Option<DiffType>,
+    },
+
+    /// The old zone changes to a local time type at the given UT time, and the new one doesn't.
+    TransitionRemoved {
+        at: i64,
+        to: DiffType,
+    },
+
+    /// The new zone changes to a local time type at the given UT time, and the old one doesn't.
+    TransitionAdded {
+        at: i64,
+        to: DiffType,
+    },
+
+    /// Both zones change local time type at the given UT time, to different types.
+    TransitionChanged {
+        at: i64,
+        old: DiffType,
+        new: DiffType,
+    },
+
+    LeapSecondRemoved(LeapSecondRecord),
+
+    LeapSecondAdded(LeapSecondRecord),
+
+    /// The TZ strings which govern local time after the last transition differ.
+    Footer {
+        old: Option<String>,
+        new: Option<String>,
+    },
+}
+
+impl TimeZoneInfo {
+    /// Compare this zone, as the old one, with another.
+    ///
+    /// Transitions are compared up to the later of the two zones' last transitions, with a
+    /// zone's footer supplying its transitions after its own last one. Differences are
+    /// returned in order: version, initial type, transitions by time, leap seconds, footer.
+    pub fn diff(&self, new: &TimeZoneInfo) -> Vec<ZoneDifference> {
+        let mut diffs = Vec::new();
+        if self.version != new.version {
+            diffs.push(ZoneDifference::Version {
+                old: self.version,
+                new: new.version,
+            });
+        }
+
+        let first = |tz: &TimeZoneInfo| tz.transition_times.first().copied();
+        let last = |tz: &TimeZoneInfo| tz.transition_times.last().copied();
+        let start = first(self).into_iter().chain(first(new)).min();
+        let end = last(self).into_iter().chain(last(new)).max();
+        let (old_initial, old_changes) = self.changes(start, end);
+        let (new_initial, new_changes) = new.changes(start, end);
+        if old_initial != new_initial {
+            diffs.push(ZoneDifference::InitialType {
+                old: old_initial,
+                new: new_initial,
+            });
+        }
+
+        let (mut old_changes, mut new_changes) = (
+            old_changes.into_iter().peekable(),
+            new_changes.into_iter().peekable(),
+        );
+        loop {
+            let diff = match (old_changes.peek(), new_changes.peek()) {
+                (None, None) => break,
+                (Some((old_at, _)), Some((new_at, _))) if old_at == new_at => {
+                    let ((at, old), (_, new)) =
+                        (old_changes.next().unwrap(), new_changes.next().unwrap());
+                    if old == new {
+                        continue;
+                    }
+                    ZoneDifference::TransitionChanged { at, old, new }
+                }
+                (Some((old_at, _)), Some((new_at, _))) if new_at < old_at => {
+                    let (at, to) = new_changes.next().unwrap();
+                    ZoneDifference::TransitionAdded { at, to }
+                }
+                (Some(_), _) => {
+                    let (at, to) = old_changes.next().unwrap();
+                    ZoneDifference::TransitionRemoved { at, to }
+                }
+                (None, Some(_)) => {
+                    let (at, to) = new_changes.next().unwrap();
+                    ZoneDifference::TransitionAdded { at, to }
+                }
+            };
+            diffs.push(diff);
+        }
+
+        let (old_leaps, new_leaps) = (&self.leap_second_records, &new.leap_second_records);
+        diffs.extend(
+            old_leaps
+                .iter()
+                .filter(|leap| !new_leaps.contains(leap))
+                .map(|&leap| ZoneDifference::LeapSecondRemoved(leap)),
+        );
+        diffs.extend(
+            new_leaps
+                .iter()
+                .filter(|leap| !old_leaps.contains(leap))
+                .map(|&leap| ZoneDifference::LeapSecondAdded(leap)),
+        );
+
+        let (old_footer, new_footer) = (
+            self.footer.as_ref().map(ToString::to_string),
+            new.footer.as_ref().map(ToString::to_string),
+        );
+        if old_footer != new_footer {
+            diffs.push(ZoneDifference::Footer {
+                old: old_footer,
+                new: new_footer,
+            });
+        }
+        diffs
+    }
+
+    /// The local time type before `start`, or before the first transition if the zone has
+    /// any, and the UT times at which the type changes through `end`, including those the
+    /// footer gives after the last transition.
+    fn changes(
+        &self,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> (Option<DiffType>, Vec<(i64, DiffType)>) {
+        let initial = match (&self.footer, self.local_time_types.is_empty()) {
+            (Some(footer), _) if self.transition_times.is_empty() => {
+                Some(footer.local_time_type_at(start.map_or(i64::MIN, |t| t - 1)))
+            }
+            (_, false) => Some(self.local_time_type(0)),
+            (_, true) => None,
+        }
+        .map(DiffType::from);
+
+        let mut prev = initial.clone();
+        let mut changes = Vec::new();
+        let mut push = |at: i64, typ: DiffType| {
+            if prev.as_ref() != Some(&typ) {
+                prev = Some(typ.clone());
+                changes.push((at, typ));
+            }
+        };
+        for (&at, &idx) in self.transition_times.iter().zip(&self.transition_types) {
+            push(at, self.local_time_type(usize::from(idx)).into());
+        }
+
+        let (Some(footer), Some(end)) = (&self.footer, end) else {
+            return (initial, changes);
+        };
+        let after = match (self.transition_times.last(), start) {
+            (Some(&last), _) => last,
+            (None, Some(start)) => start - 1,
+            (None, None) => end,
+        };
+        let year = |t: i64| civil_from_days(t.div_euclid(SECS_PER_DAY)).0;
+        let mut footer_times = Vec::new();
+        for y in year(after)..=year(end) {
+            footer_times.extend(footer.transitions(y).into_iter().flatten().map(|(t, _)| t));
+        }
+        footer_times.sort_unstable();
+        for t in footer_times {
+            if t > after && t <= end {
+                push(t, footer.local_time_type_at(t).into());
+            }
+        }
+        (initial, changes)
+    }
+}
+
+impl From<LocalTimeType<'_>> for DiffType {
+    fn from(local: LocalTimeType<'_>) -> Self {
+        Self {
+            ut_offset_secs: local.ut_offset_secs,
+            is_dst: local.is_dst,
+            designation: local.desig.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for DiffType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        LocalTimeType {
+            desig: &self.designation,
+            ut_offset_secs: self.ut_offset_secs,
+            is_dst: self.is_dst,
+        }
+        .fmt(f)
+    }
+}
+
+impl fmt::Display for ZoneDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opt =
+            |typ: &Option<DiffType>| typ.as_ref().map_or("none".to_owned(), ToString::to_string);
+        match self {
+            ZoneDifference::Version { old, new } => write!(f, "version {old} -> {new}"),
+            ZoneDifference::InitialType { old, new } => {
+                write!(f, "initial type {} -> {}", opt(old), opt(new))
+            }
+            ZoneDifference::TransitionRemoved { at, to } => {
+                write!(f, "- {}: transition to {to}", Ut(*at))
+            }
+            ZoneDifference::TransitionAdded { at, to } => {
+                write!(f, "+ {}: transition to {to}", Ut(*at))
+            }
+            ZoneDifference::TransitionChanged { at, old, new } => {
+                write!(f, "~ {}: transition to {old} -> {new}", Ut(*at))
+            }
+            ZoneDifference::LeapSecondRemoved(leap) => write!(
+                f,
+                "- leap second at {}, correction {}",
+                leap.occurrence, leap.correction
+            ),
+            ZoneDifference::LeapSecondAdded(leap) => write!(
+                f,
+                "+ leap second at {}, correction {}",
+                leap.occurrence, leap.correction
+            ),
+            ZoneDifference::Footer { old, new } => write!(
+                f,
+                "footer {:?} -> {:?}",
+                old.as_deref().unwrap_or(""),
+                new.as_deref().unwrap_or("")
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use crate::ZoneSource;
+
+    const SOURCE: &str = "\
+Rule  US  2007  max  -  Mar  Sun>=8  2:00  1:00  D
+Rule  US  2007  max  -  Nov  Sun>=1  2:00  0     S
+Zone  Old  -5:00  -   LMT  2000
+           -5:00  US  E%sT
+Zone  New  -5:00  -   LMT  2000
+           -5:00  US  E%sT  2030
+           -5:00  -   EST
+";
+
+    #[test]
+    fn test_diff() {
+        let tz = sample();
+        assert_eq!(Vec::<ZoneDifference>::new(), tz.diff(&sample()));
+
+        // A transition which doesn't change the type is ignored.
+        let mut redundant = sample();
+        redundant.transition_times.insert(1, 150);
+        redundant.transition_types.insert(1, 1);
+        assert_eq!(Vec::<ZoneDifference>::new(), tz.diff(&redundant));
+
+        let mut changed = sample();
+        changed.transition_times[2] = 350;
+        changed.local_time_types[0].ut_off_secs = -9 * 3600;
+        let diffs = tz.diff(&changed);
+        assert_eq!(4, diffs.len(), "{diffs:?}");
+        assert!(matches!(diffs[0], ZoneDifference::InitialType { .. }));
+        assert!(matches!(
+            diffs[1],
+            ZoneDifference::TransitionChanged { at: 200, .. }
+        ));
+        assert!(matches!(
+            diffs[2],
+            ZoneDifference::TransitionRemoved { at: 300, .. }
+        ));
+        assert!(matches!(
+            diffs[3],
+            ZoneDifference::TransitionAdded { at: 350, .. }
+        ));
+        assert_eq!(
+            "~ 1970-01-01 00:03:20 UT: transition to PST (UTC-08:00) -> PST (UTC-09:00)",
+            diffs[1].to_string()
+        );
+    }
+
+    #[test]
+    fn test_diff_footer() {
+        let mut source = ZoneSource::new();
+        source.parse(SOURCE).unwrap();
+        let (old, new) = (
+            source.compile("Old").unwrap(),
+            source.compile("New").unwrap(),
+        );
+        let diffs = old.diff(&new);
+        assert_eq!(
+            Some(&ZoneDifference::Footer {
+                old: Some("EST5EDT,M3.2.0,M11.1.0".to_owned()),
+                new: Some("EST5".to_owned()),
+            }),
+            diffs.last()
+        );
+        // Old's footer supplies its transitions through the years New lists.
+        assert!(
+            diffs[..diffs.len() - 1]
+                .iter()
+                .all(|diff| matches!(diff, ZoneDifference::Version { .. })),
+            "{diffs:?}"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Human-readable formatting of times, local time types and transitions.
+
+use core::fmt;
+
+use crate::civil::{civil_from_days, SECS_PER_DAY};
+use crate::{LocalTimeType, LocalTimeTypeOwned, Time, TimeTransition};
+
+impl fmt::Display for Time {
+    /// The date and time, and which clock it's on, as in "2024-03-10 02:00:00 wall (UTC-08:00)".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Time::UT(t) => write!(f, "{}", Ut(t)),
+            Time::LocalWall {
+                local,
+                ut_offset_secs,
+            } => write!(
+                f,
+                "{} wall (UTC{})",
+                DateTime(local),
+                Offset(ut_offset_secs)
+            ),
+            Time::LocalStandard {
+                local,
+                ut_offset_secs,
+            } => write!(
+                f,
+                "{} standard (UTC{})",
+                DateTime(local),
+                Offset(ut_offset_secs)
+            ),
+        }
+    }
+}
+
+impl fmt::Display for LocalTimeType<'_> {
+    /// The designation and UT offset, as in "PDT (UTC-07:00, DST)".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (UTC{}", self.desig, Offset(self.ut_offset_secs))?;
+        f.write_str(if self.is_dst { ", DST)" } else { ")" })
+    }
+}
+
+impl fmt::Display for LocalTimeTypeOwned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_borrowed().fmt(f)
+    }
+}
+
+impl fmt::Display for TimeTransition<'_> {
+    /// The time and the local time type which takes effect, as in
+    /// "2024-03-10 10:00:00 UT -> PDT (UTC-07:00, DST)".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.at_time, self.local)
+    }
+}
+
+/// Displays seconds since the epoch as a date and time.
+pub(crate) struct DateTime(pub i64);
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = civil_from_days(self.0.div_euclid(SECS_PER_DAY));
+        let secs = self.0.rem_euclid(SECS_PER_DAY);
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )
+    }
+}
+
+/// Displays a UT timestamp as a date and time.
+pub(crate) struct Ut(pub i64);
+
+impl fmt::Display for Ut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} UT", DateTime(self.0))
+    }
+}
+
+/// Displays a UT offset as in "-04:00", or "-04:56:02" if it isn't a whole number of minutes.
+pub(crate) struct Offset(pub i32);
+
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let off = self.0.unsigned_abs();
+        let sign = if self.0 < 0 { '-' } else { '+' };
+        write!(f, "{sign}{:02}:{:02}", off / 3600, off / 60 % 60)?;
+        if !off.is_multiple_of(60) {
+            write!(f, ":{:02}", off % 60)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_display() {
+        let tz = sample();
+        let tr = tz.iter_transitions().next().unwrap();
+        assert_eq!(
+            "1970-01-01 00:01:40 UT -> PDT (UTC-07:00, DST)",
+            tr.to_string()
+        );
+        assert_eq!("PST (UTC-08:00)", tr.prev.to_string());
+        assert_eq!("PST (UTC-08:00)", tr.prev.to_owned_type().to_string());
+
+        let wall = Time::LocalWall {
+            local: 1_710_036_000,
+            ut_offset_secs: -8 * 3600,
+        };
+        assert_eq!("2024-03-10 02:00:00 wall (UTC-08:00)", wall.to_string());
+        let std = Time::LocalStandard {
+            local: -1,
+            ut_offset_secs: -(4 * 3600 + 56 * 60 + 2),
+        };
+        assert_eq!(
+            "1969-12-31 23:59:59 standard (UTC-04:56:02)",
+            std.to_string()
+        );
+    }
+}
@@ -0,0 +1,562 @@
+//! Zones parsed from TZif at compile time by the [`tzif!`](crate::tzif!) macro.
+//!
+//! The parsing here is all `const fn`, so that the macro can evaluate it in constants, and
+//! invalid data is a compile error rather than a run-time one. It's stricter than
+//! [`TimeZoneInfo::parse_slice`]: any data that parser would need to be lenient about to read
+//! is rejected.
+
+use crate::posix::{DEFAULT_RULE_TIME, MAX_OFFSET_HOURS, MAX_RULE_HOURS};
+use crate::{
+    ConsistencyError, Header, LocalTimeType, PosixRule, RuleDate, StaticFooter, StaticSpan,
+    StaticZone, TimeZoneInfo,
+};
+
+/// A zone parsed from TZif at compile time by [`tzif!`](crate::tzif!), held in static data.
+///
+/// Looking up a time neither parses nor allocates: the changes are a static table as in a
+/// [`StaticZone`], and the footer's TZ string was parsed along with the rest. Leap seconds
+/// aren't kept, so the times of a "right" zone's changes count them, as in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EmbeddedZone {
+    /// The zone's changes and footer.
+    pub zone: StaticZone,
+}
+
+impl EmbeddedZone {
+    /// The local time type in effect at the given UT timestamp.
+    pub fn local_time_type_at(&self, ut: i64) -> LocalTimeType<'static> {
+        self.zone.local_time_type_at(ut)
+    }
+
+    /// The UT offset in effect at the given UT timestamp, in seconds.
+    pub fn offset_at(&self, ut: i64) -> i32 {
+        self.local_time_type_at(ut).ut_offset_secs
+    }
+}
+
+impl TryFrom<&EmbeddedZone> for TimeZoneInfo {
+    type Error = ConsistencyError;
+
+    /// A zone with the embedded zone's changes as its transitions and its footer.
+    fn try_from(zone: &EmbeddedZone) -> Result<Self, ConsistencyError> {
+        Self::try_from(&zone.zone)
+    }
+}
+
+/// A zone parsed at compile time from TZif data, as a `&'static` [`EmbeddedZone`].
+///
+/// `tzif!("America/Chicago")` looks the zone up by name in the snapshot embedded by the
+/// `bundled` feature. `tzif!("Local", include_bytes!("/etc/localtime"))` parses the given bytes,
+/// which can be any constant `&'static [u8]`, and names the zone as given. Either way the data
+/// is parsed as the program is compiled, so a missing zone or invalid data fails the build, and
+/// nothing is parsed or allocated at run time.
+#[macro_export]
+macro_rules! tzif {
+    ($name:literal) => {
+        $crate::tzif!($name, $crate::__private::bundled($name))
+    };
+    ($name:literal, $data:expr) => {{
+        const DATA: &'static [u8] = $data;
+        const CHANGES: [(i64, $crate::StaticSpan); $crate::__private::change_count(DATA)] =
+            $crate::__private::changes(DATA);
+        static ZONE: $crate::EmbeddedZone = $crate::__private::zone($name, DATA, &CHANGES);
+        &ZONE
+    }};
+}
+
+/// What [`tzif!`](crate::tzif!) expands to calls, which isn't otherwise public API.
+#[doc(hidden)]
+pub mod private {
+    use super::*;
+
+    /// The TZif data of the zone with the given name in the bundled snapshot.
+    #[cfg(feature = "bundled")]
+    pub const fn bundled(name: &str) -> &'static [u8] {
+        crate::bundled::get_const(name)
+    }
+
+    #[cfg(not(feature = "bundled"))]
+    pub const fn bundled(_name: &str) -> &'static [u8] {
+        panic!("tzif! needs the bundled feature to look zones up by name; pass the data as well")
+    }
+
+    /// The number of transitions in the TZif data.
+    pub const fn change_count(data: &'static [u8]) -> usize {
+        block(data).timecnt
+    }
+
+    /// The transitions in the TZif data, each with the local time type from then on.
+    pub const fn changes<const N: usize>(data: &'static [u8]) -> [(i64, StaticSpan); N] {
+        let b = block(data);
+        let mut out = [(
+            0,
+            StaticSpan {
+                ut_offset: 0,
+                is_dst: false,
+                designation: "",
+            },
+        ); N];
+        let mut i = 0;
+        while i < N {
+            let at = if b.time_size == 8 {
+                be_u64(data, b.start + 8 * i) as i64
+            } else {
+                be_u32(data, b.start + 4 * i) as i32 as i64
+            };
+            if i > 0 && at <= out[i - 1].0 {
+                panic!("TZif transition times out of order");
+            }
+            out[i] = (at, span(data, b, data[b.types() + i] as usize));
+            i += 1;
+        }
+        out
+    }
+
+    /// The zone of the TZif data, with the given name and its [`changes`].
+    pub const fn zone(
+        name: &'static str,
+        data: &'static [u8],
+        changes: &'static [(i64, StaticSpan)],
+    ) -> EmbeddedZone {
+        let b = block(data);
+        EmbeddedZone {
+            zone: StaticZone {
+                name,
+                first: span(data, b, 0),
+                rest: changes,
+                footer: match footer(data, b) {
+                    Some(footer) => Some(footer_rule(footer)),
+                    None => None,
+                },
+            },
+        }
+    }
+}
+
+/// Where the parts of the data block a zone is read from are, and how many there are.
+#[derive(Clone, Copy)]
+struct Block {
+    version: u8,
+    time_size: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+
+    /// Where the block starts, with the transition times.
+    start: usize,
+    end: usize,
+}
+
+impl Block {
+    const fn types(self) -> usize {
+        self.start + self.timecnt * self.time_size
+    }
+
+    const fn records(self) -> usize {
+        self.types() + self.timecnt
+    }
+
+    const fn designations(self) -> usize {
+        self.records() + 6 * self.typecnt
+    }
+}
+
+/// The block to read a zone from: the second, if there is one.
+const fn block(data: &[u8]) -> Block {
+    let v1 = header(data, 0, 4);
+    if v1.version == 1 {
+        v1
+    } else {
+        header(data, v1.end, 8)
+    }
+}
+
+/// The block whose header is at `at`.
+const fn header(data: &[u8], at: usize, time_size: usize) -> Block {
+    if data.len() < at + Header::SIZE {
+        panic!("truncated TZif header");
+    }
+    if !bytes_eq(subslice(data, at, 4), b"TZif") {
+        panic!("not TZif data");
+    }
+    let version = match data[at + 4] {
+        0 => 1,
+        b'2' => 2,
+        b'3' => 3,
+        b'4' => 4,
+        _ => panic!("unknown TZif version"),
+    };
+    let isutcnt = be_u32(data, at + 20) as usize;
+    let isstdcnt = be_u32(data, at + 24) as usize;
+    let leapcnt = be_u32(data, at + 28) as usize;
+    let timecnt = be_u32(data, at + 32) as usize;
+    let typecnt = be_u32(data, at + 36) as usize;
+    let charcnt = be_u32(data, at + 40) as usize;
+    if typecnt == 0 {
+        panic!("TZif data without local time types");
+    }
+    let start = at + Header::SIZE;
+    let end = start
+        + timecnt * (time_size + 1)
+        + typecnt * 6
+        + charcnt
+        + leapcnt * (time_size + 4)
+        + isstdcnt
+        + isutcnt;
+    if data.len() < end {
+        panic!("truncated TZif data block");
+    }
+    Block {
+        version,
+        time_size,
+        timecnt,
+        typecnt,
+        charcnt,
+        start,
+        end,
+    }
+}
+
+/// The local time type with the given index.
+const fn span(data: &'static [u8], b: Block, idx: usize) -> StaticSpan {
+    if idx >= b.typecnt {
+        panic!("TZif transition to a local time type that doesn't exist");
+    }
+    let record = b.records() + 6 * idx;
+    let designations = subslice(data, b.designations(), b.charcnt);
+    let desig_idx = data[record + 5] as usize;
+    let mut end = desig_idx;
+    while end < b.charcnt && designations[end] != 0 {
+        end += 1;
+    }
+    if end >= b.charcnt {
+        panic!("TZif designation index out of range or unterminated");
+    }
+    StaticSpan {
+        ut_offset: be_u32(data, record) as i32,
+        is_dst: data[record + 4] != 0,
+        designation: to_str(subslice(designations, desig_idx, end - desig_idx)),
+    }
+}
+
+/// The footer's TZ string after the block, if it isn't empty.
+const fn footer(data: &'static [u8], b: Block) -> Option<&'static str> {
+    if b.version == 1 {
+        return None;
+    }
+    if b.end >= data.len() || data[b.end] != b'\n' {
+        panic!("TZif footer missing");
+    }
+    let start = b.end + 1;
+    let mut end = start;
+    while end < data.len() && data[end] != b'\n' {
+        end += 1;
+    }
+    if end == data.len() {
+        panic!("TZif footer unterminated");
+    }
+    if end == start {
+        None
+    } else {
+        Some(to_str(subslice(data, start, end - start)))
+    }
+}
+
+/// Parse a footer's TZ string, like [`PosixTz`](crate::PosixTz)'s `FromStr` but at compile
+/// time.
+const fn footer_rule(tz: &'static str) -> StaticFooter {
+    let mut p = TzParser {
+        s: tz.as_bytes(),
+        pos: 0,
+    };
+    let std = StaticSpan {
+        designation: p.abbr(),
+        ut_offset: -p.hms(MAX_OFFSET_HOURS),
+        is_dst: false,
+    };
+    if p.at_end() {
+        return StaticFooter { std, dst: None };
+    }
+    let designation = p.abbr();
+    let ut_offset = if p.at_end() || p.peek() == b',' {
+        std.ut_offset + 60 * 60
+    } else {
+        -p.hms(MAX_OFFSET_HOURS)
+    };
+    let dst = StaticSpan {
+        ut_offset,
+        is_dst: true,
+        designation,
+    };
+    let (start, end) = if p.eat(b',') {
+        let start = p.rule();
+        p.expect(b',');
+        (start, p.rule())
+    } else {
+        (us_rule(3, 2), us_rule(11, 1))
+    };
+    if !p.at_end() {
+        invalid_tz();
+    }
+    StaticFooter {
+        std,
+        dst: Some((dst, start, end)),
+    }
+}
+
+/// The current US rule for the given week of the given month, as used when a TZ string omits
+/// its rules.
+const fn us_rule(month: u8, week: u8) -> PosixRule {
+    PosixRule {
+        date: RuleDate::MonthWeekDay {
+            month,
+            week,
+            weekday: 0,
+        },
+        time: DEFAULT_RULE_TIME,
+    }
+}
+
+const fn invalid_tz() -> ! {
+    panic!("invalid TZ string in TZif footer")
+}
+
+/// A `const` version of the TZ string parser in `posix`, which panics at the first error.
+struct TzParser {
+    s: &'static [u8],
+    pos: usize,
+}
+
+impl TzParser {
+    const fn at_end(&self) -> bool {
+        self.pos == self.s.len()
+    }
+
+    /// The next byte, or NUL at the end, which is never valid.
+    const fn peek(&self) -> u8 {
+        if self.at_end() {
+            0
+        } else {
+            self.s[self.pos]
+        }
+    }
+
+    const fn eat(&mut self, b: u8) -> bool {
+        let found = self.peek() == b;
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    const fn expect(&mut self, b: u8) {
+        if !self.eat(b) {
+            invalid_tz();
+        }
+    }
+
+    const fn abbr(&mut self) -> &'static str {
+        let quoted = self.eat(b'<');
+        let start = self.pos;
+        loop {
+            let b = self.peek();
+            let valid = if quoted {
+                b.is_ascii_alphanumeric() || b == b'+' || b == b'-'
+            } else {
+                b.is_ascii_alphabetic()
+            };
+            if !valid {
+                break;
+            }
+            self.pos += 1;
+        }
+        let abbr = subslice(self.s, start, self.pos - start);
+        if quoted {
+            self.expect(b'>');
+        }
+        if abbr.len() < 3 {
+            invalid_tz();
+        }
+        to_str(abbr)
+    }
+
+    const fn number(&mut self, max: u32) -> u32 {
+        let start = self.pos;
+        let mut value = 0u32;
+        while self.peek().is_ascii_digit() {
+            value = value * 10 + (self.peek() - b'0') as u32;
+            if value > max {
+                invalid_tz();
+            }
+            self.pos += 1;
+        }
+        if self.pos == start {
+            invalid_tz();
+        }
+        value
+    }
+
+    const fn hms(&mut self, max_hours: u32) -> i32 {
+        let negative = if self.eat(b'-') {
+            true
+        } else {
+            self.eat(b'+');
+            false
+        };
+        let mut secs = self.number(max_hours) * 60 * 60;
+        if self.eat(b':') {
+            secs += self.number(59) * 60;
+            if self.eat(b':') {
+                secs += self.number(59);
+            }
+        }
+        let secs = secs as i32;
+        if negative {
+            -secs
+        } else {
+            secs
+        }
+    }
+
+    const fn rule(&mut self) -> PosixRule {
+        let date = if self.eat(b'J') {
+            let n = self.number(365);
+            if n == 0 {
+                invalid_tz();
+            }
+            RuleDate::Julian(n as u16)
+        } else if self.eat(b'M') {
+            let month = self.number(12);
+            self.expect(b'.');
+            let week = self.number(5);
+            self.expect(b'.');
+            let weekday = self.number(6);
+            if month == 0 || week == 0 {
+                invalid_tz();
+            }
+            RuleDate::MonthWeekDay {
+                month: month as u8,
+                week: week as u8,
+                weekday: weekday as u8,
+            }
+        } else {
+            RuleDate::Zero(self.number(365) as u16)
+        };
+        let time = if self.eat(b'/') {
+            self.hms(MAX_RULE_HOURS)
+        } else {
+            DEFAULT_RULE_TIME
+        };
+        PosixRule { date, time }
+    }
+}
+
+pub(crate) const fn be_u32(data: &[u8], at: usize) -> u32 {
+    u32::from_be_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+}
+
+const fn be_u64(data: &[u8], at: usize) -> u64 {
+    (be_u32(data, at) as u64) << 32 | be_u32(data, at + 4) as u64
+}
+
+/// The `len` bytes from `start`.
+pub(crate) const fn subslice(data: &[u8], start: usize, len: usize) -> &[u8] {
+    let (_, rest) = data.split_at(start);
+    let (sub, _) = rest.split_at(len);
+    sub
+}
+
+pub(crate) const fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn to_str(bytes: &'static [u8]) -> &'static str {
+    match core::str::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(_) => panic!("TZif designation or footer isn't UTF-8"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use crate::{Bloat, PosixTz, WriteOptions};
+
+    /// The sample zone with a footer, as slim TZif.
+    const SAMPLE: &[u8] = b"\
+        TZif2\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x02\x00\x00\
+        \x00\x02\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x02\x00\x00\x00\x08\
+        \xff\xff\x8f\x80\x00\x00\xff\xff\x9d\x90\x01\x04PST\x00PDT\x00\x01\x01\x01\x01\
+        TZif2\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x02\x00\x00\
+        \x00\x02\x00\x00\x00\x00\x00\x00\x00\x03\x00\x00\x00\x02\x00\x00\x00\x08\
+        \x00\x00\x00\x00\x00\x00\x00d\x00\x00\x00\x00\x00\x00\x00\xc8\x00\x00\x00\x00\x00\x00\x01,\
+        \x01\x00\x01\
+        \xff\xff\x8f\x80\x00\x00\xff\xff\x9d\x90\x01\x04PST\x00PDT\x00\x01\x01\x01\x01\
+        \nPST8PDT,M3.2.0,M11.1.0\n";
+
+    #[test]
+    fn test_tzif_macro() {
+        let zone = crate::tzif!("Sample", SAMPLE);
+        let mut tz = sample();
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        let slim = WriteOptions { bloat: Bloat::Slim };
+        assert_eq!(tz.to_bytes_with(&slim), SAMPLE);
+
+        assert_eq!("Sample", zone.zone.name);
+        assert_eq!(3, zone.zone.rest.len());
+        for ut in [0, 100, 150, 200, 299, 300, 1_700_000_000, 1_720_000_000] {
+            assert_eq!(
+                tz.local_time_type_at(ut),
+                Some(zone.local_time_type_at(ut)),
+                "{ut}"
+            );
+        }
+        assert_eq!(-7 * 3600, zone.offset_at(1_720_000_000));
+        // The indicators aren't kept.
+        let back = TimeZoneInfo::try_from(zone).unwrap();
+        assert_eq!(tz.transition_times, back.transition_times);
+        assert_eq!(tz.local_time_types, back.local_time_types);
+        assert_eq!(tz.footer, back.footer);
+
+        let rule = footer_rule("<+0330>-3:30<+0430>,J79/24,J263/24");
+        assert_eq!("+0330", rule.std.designation);
+        assert_eq!(12_600, rule.std.ut_offset);
+        let tz: PosixTz = "<+0330>-3:30<+0430>,J79/24,J263/24".parse().unwrap();
+        let (dst, start, end) = rule.dst.unwrap();
+        let posix = tz.dst.unwrap();
+        assert_eq!(
+            (posix.offset, posix.start, posix.end),
+            (dst.ut_offset, start, end)
+        );
+        assert_eq!(None, footer_rule("UTC0").dst);
+    }
+
+    #[cfg(feature = "bundled")]
+    #[test]
+    fn test_tzif_macro_bundled() {
+        let zone = crate::tzif!("America/Chicago");
+        let tz = crate::TimeZoneInfo::parse_slice(
+            crate::bundled::get("America/Chicago").unwrap(),
+            &crate::ParseOptions::default(),
+        )
+        .unwrap();
+        for ut in (-3_000_000_000..4_000_000_000).step_by(86_400 * 7 + 3_600) {
+            assert_eq!(
+                tz.local_time_type_at(ut),
+                Some(zone.local_time_type_at(ut)),
+                "{ut}"
+            );
+        }
+    }
+}
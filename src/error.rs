@@ -0,0 +1,489 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::BlockDiscrepancy;
+
+/// An error encountered while parsing TZif data.
+///
+/// Every variant records the byte offset (from the start of the input) of the item that could
+/// not be parsed.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The input ended before the item at `offset` could be read in full.
+    Truncated { offset: u64 },
+
+    /// The underlying reader returned an error other than end-of-file.
+    #[cfg(feature = "std")]
+    Io { offset: u64, source: io::Error },
+
+    /// The header did not start with the magic bytes b"TZif".
+    BadMagic { offset: u64 },
+
+    /// The header's version byte is not one this crate understands.
+    UnsupportedVersion { offset: u64, version: u8 },
+
+    /// A header count field which must be either zero or equal to `typecnt` was neither.
+    CountMismatch { offset: u64, field: &'static str },
+
+    /// A header count field was zero where RFC 8536 requires it not to be.
+    ZeroCount { offset: u64, field: &'static str },
+
+    /// A header count field exceeded the limit configured in
+    /// [`ParseOptions`](crate::ParseOptions).
+    LimitExceeded {
+        offset: u64,
+        field: &'static str,
+        value: u32,
+        limit: u32,
+    },
+
+    /// A transition time was not later than the one preceding it.
+    UnsortedTransitions { offset: u64 },
+
+    /// A local time type record's DST indicator was not zero or one.
+    InvalidDstIndicator { offset: u64, value: u8 },
+
+    /// A standard/wall indicator was not zero or one.
+    InvalidStdWallIndicator { offset: u64, value: u8 },
+
+    /// A UT/local indicator was not zero or one.
+    InvalidUtLocalIndicator { offset: u64, value: u8 },
+
+    /// A local time type was marked as both UT and wall clock time, which is not allowed.
+    UniversalWall { offset: u64 },
+
+    /// A transition type refers to a local time type which doesn't exist.
+    TransitionTypeOutOfRange { offset: u64, index: u8 },
+
+    /// The footer following a version 2+ data block was missing or not a valid TZ string.
+    InvalidFooter { offset: u64 },
+
+    /// A leap-second record broke the rules on how leap seconds follow one another.
+    InvalidLeapSecond { offset: u64 },
+
+    /// The designation table didn't end with a NUL.
+    UnterminatedDesignations { offset: u64 },
+
+    /// A local time type's designation index was past the end of the designation table.
+    DesignationOutOfRange { offset: u64, index: u8 },
+
+    /// A designation wasn't UTF-8, or in strict mode, wasn't three to six ASCII alphanumerics,
+    /// '-' or '+'.
+    InvalidDesignation { offset: u64 },
+
+    /// The version 1 data block didn't agree with the second data block, whose header is at
+    /// `offset`. Only reported with [`ParseOptions::check_v1`](crate::ParseOptions::check_v1).
+    BlockMismatch {
+        offset: u64,
+        discrepancies: Vec<BlockDiscrepancy>,
+    },
+}
+
+impl ParseError {
+    #[cfg(feature = "std")]
+    pub(crate) fn from_io(offset: u64, source: io::Error) -> Self {
+        if source.kind() == io::ErrorKind::UnexpectedEof {
+            ParseError::Truncated { offset }
+        } else {
+            ParseError::Io { offset, source }
+        }
+    }
+
+    /// The byte offset in the input where parsing failed.
+    pub fn offset(&self) -> u64 {
+        match *self {
+            #[cfg(feature = "std")]
+            ParseError::Io { offset, .. } => offset,
+            ParseError::Truncated { offset }
+            | ParseError::BadMagic { offset }
+            | ParseError::UnsupportedVersion { offset, .. }
+            | ParseError::CountMismatch { offset, .. }
+            | ParseError::ZeroCount { offset, .. }
+            | ParseError::LimitExceeded { offset, .. }
+            | ParseError::UnsortedTransitions { offset }
+            | ParseError::InvalidDstIndicator { offset, .. }
+            | ParseError::InvalidStdWallIndicator { offset, .. }
+            | ParseError::InvalidUtLocalIndicator { offset, .. }
+            | ParseError::UniversalWall { offset }
+            | ParseError::TransitionTypeOutOfRange { offset, .. }
+            | ParseError::InvalidFooter { offset }
+            | ParseError::InvalidLeapSecond { offset }
+            | ParseError::UnterminatedDesignations { offset }
+            | ParseError::DesignationOutOfRange { offset, .. }
+            | ParseError::InvalidDesignation { offset }
+            | ParseError::BlockMismatch { offset, .. } => offset,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Truncated { .. } => write!(f, "unexpected end of input")?,
+            #[cfg(feature = "std")]
+            ParseError::Io { source, .. } => write!(f, "I/O error: {source}")?,
+            ParseError::BadMagic { .. } => write!(f, "unrecognized magic in header")?,
+            ParseError::UnsupportedVersion { version, .. } => {
+                write!(f, "unsupported version {version:#x}")?
+            }
+            ParseError::CountMismatch { field, .. } => {
+                write!(f, "{field} not zero or equal to typecnt")?
+            }
+            ParseError::ZeroCount { field, .. } => write!(f, "{field} must not be zero")?,
+            ParseError::LimitExceeded {
+                field,
+                value,
+                limit,
+                ..
+            } => write!(f, "{field} of {value} exceeds limit of {limit}")?,
+            ParseError::UnsortedTransitions { .. } => {
+                write!(f, "transition times not in ascending order")?
+            }
+            ParseError::InvalidDstIndicator { value, .. } => {
+                write!(f, "is_dst not zero or one ({value})")?
+            }
+            ParseError::InvalidStdWallIndicator { value, .. } => {
+                write!(f, "std/wall not zero or one ({value})")?
+            }
+            ParseError::InvalidUtLocalIndicator { value, .. } => {
+                write!(f, "ut/local not zero or one ({value})")?
+            }
+            ParseError::UniversalWall { .. } => {
+                write!(f, "transition times can't be universal + wall")?
+            }
+            ParseError::TransitionTypeOutOfRange { index, .. } => {
+                write!(f, "transition type {index} out of range")?
+            }
+            ParseError::InvalidFooter { .. } => write!(f, "invalid TZ string footer")?,
+            ParseError::InvalidLeapSecond { .. } => write!(f, "invalid leap-second record")?,
+            ParseError::UnterminatedDesignations { .. } => {
+                write!(f, "designations not NUL-terminated")?
+            }
+            ParseError::DesignationOutOfRange { index, .. } => {
+                write!(f, "designation index {index} out of range")?
+            }
+            ParseError::InvalidDesignation { .. } => write!(f, "invalid designation")?,
+            ParseError::BlockMismatch { discrepancies, .. } => {
+                f.write_str("version 1 data disagrees with the second data block: ")?;
+                for (i, discrepancy) in discrepancies.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{discrepancy}")?;
+                }
+            }
+        }
+        write!(f, " at offset {}", self.offset())
+    }
+}
+
+impl core::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            ParseError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ParseError> for io::Error {
+    fn from(e: ParseError) -> io::Error {
+        let kind = match &e {
+            ParseError::Truncated { .. } => io::ErrorKind::UnexpectedEof,
+            ParseError::Io { source, .. } => source.kind(),
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, e)
+    }
+}
+
+/// An error parsing a POSIX TZ string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PosixTzError {
+    /// Byte position in the string of the item that could not be parsed.
+    pub position: usize,
+}
+
+impl fmt::Display for PosixTzError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid TZ string at position {}", self.position)
+    }
+}
+
+impl core::error::Error for PosixTzError {}
+
+/// An error parsing a `zone1970.tab` or `zone.tab` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneTabError {
+    /// The 1-based number of the line which could not be parsed.
+    pub line: usize,
+}
+
+impl fmt::Display for ZoneTabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid zone table entry on line {}", self.line)
+    }
+}
+
+impl core::error::Error for ZoneTabError {}
+
+/// An error parsing a `leap-seconds.list` or tzdata `leapseconds` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeapSecondsError {
+    /// The 1-based number of the line which could not be parsed.
+    pub line: usize,
+}
+
+impl fmt::Display for LeapSecondsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid leap second entry on line {}", self.line)
+    }
+}
+
+impl core::error::Error for LeapSecondsError {}
+
+/// A local date and time which doesn't map to a single instant in a zone, from
+/// [`TimeZoneInfo::from_civil`](crate::TimeZoneInfo::from_civil).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LocalTimeError {
+    /// The fields aren't a valid date and time, like February 30.
+    InvalidDate,
+
+    /// The time was skipped by a transition moving clocks forward. `earlier` and `later` are
+    /// the instants [`Disambiguation::Earlier`](crate::Disambiguation::Earlier) and
+    /// [`Disambiguation::Later`](crate::Disambiguation::Later) would give.
+    Skipped { earlier: i64, later: i64 },
+
+    /// The time occurs twice, because a transition moved clocks back.
+    Ambiguous { earlier: i64, later: i64 },
+}
+
+impl fmt::Display for LocalTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocalTimeError::InvalidDate => f.write_str("invalid date or time"),
+            LocalTimeError::Skipped { .. } => f.write_str("local time skipped by a transition"),
+            LocalTimeError::Ambiguous { .. } => f.write_str("local time repeated by a transition"),
+        }
+    }
+}
+
+impl core::error::Error for LocalTimeError {}
+
+/// Something which makes a [`TimeZoneInfo`](crate::TimeZoneInfo) inconsistent, so that it
+/// can't be a [`Zone`](crate::Zone). Indices are of the transition, local time type or
+/// leap-second record at fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConsistencyError {
+    /// The version isn't one from 1 to 4.
+    UnsupportedVersion(u8),
+
+    /// There are no local time types.
+    NoLocalTimeTypes,
+
+    /// There are more local time types than a transition type can refer to.
+    TooManyLocalTimeTypes,
+
+    /// An array didn't have the length the others call for.
+    LengthMismatch { field: &'static str },
+
+    /// A transition time wasn't later than the one before it.
+    UnsortedTransitions { index: usize },
+
+    /// A transition type referred to a local time type which doesn't exist.
+    TransitionTypeOutOfRange { index: usize },
+
+    /// There's no local time type with the given index.
+    LocalTimeTypeOutOfRange { index: usize },
+
+    /// A local time type's offset was -2<sup>31</sup>, which RFC 8536 doesn't allow.
+    InvalidOffset { index: usize },
+
+    /// The designation table didn't end with a NUL.
+    UnterminatedDesignations,
+
+    /// A local time type's designation index was past the end of the designation table.
+    DesignationOutOfRange { index: usize },
+
+    /// A local time type's designation wasn't UTF-8.
+    InvalidDesignation { index: usize },
+
+    /// A new designation would start too far into the table for a local time type to refer to.
+    DesignationTableFull,
+
+    /// A local time type was marked as both UT and wall clock time.
+    UniversalWall { index: usize },
+
+    /// A leap-second record broke the rules on how leap seconds follow one another.
+    InvalidLeapSecond { index: usize },
+
+    /// The zone has a footer, which its version is too low to have.
+    FooterNeedsVersion,
+}
+
+impl fmt::Display for ConsistencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsistencyError::UnsupportedVersion(version) => {
+                write!(f, "unsupported version {version}")
+            }
+            ConsistencyError::NoLocalTimeTypes => f.write_str("no local time types"),
+            ConsistencyError::TooManyLocalTimeTypes => f.write_str("too many local time types"),
+            ConsistencyError::LengthMismatch { field } => write!(f, "{field} has the wrong length"),
+            ConsistencyError::UnsortedTransitions { index } => {
+                write!(f, "transition {index} not later than the one before")
+            }
+            ConsistencyError::TransitionTypeOutOfRange { index } => {
+                write!(f, "transition {index} has a type out of range")
+            }
+            ConsistencyError::LocalTimeTypeOutOfRange { index } => {
+                write!(f, "local time type {index} out of range")
+            }
+            ConsistencyError::InvalidOffset { index } => {
+                write!(f, "local time type {index} has an invalid offset")
+            }
+            ConsistencyError::UnterminatedDesignations => {
+                f.write_str("designations not NUL-terminated")
+            }
+            ConsistencyError::DesignationOutOfRange { index } => {
+                write!(
+                    f,
+                    "local time type {index} has a designation index out of range"
+                )
+            }
+            ConsistencyError::InvalidDesignation { index } => {
+                write!(f, "local time type {index} has an invalid designation")
+            }
+            ConsistencyError::DesignationTableFull => f.write_str("designation table full"),
+            ConsistencyError::UniversalWall { index } => {
+                write!(f, "local time type {index} is universal + wall")
+            }
+            ConsistencyError::InvalidLeapSecond { index } => {
+                write!(f, "invalid leap-second record {index}")
+            }
+            ConsistencyError::FooterNeedsVersion => f.write_str("version too low for the footer"),
+        }
+    }
+}
+
+impl core::error::Error for ConsistencyError {}
+
+/// An error parsing or compiling tzdata source with a [`ZoneSource`](crate::ZoneSource).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CompileError {
+    /// A line of source could not be parsed.
+    Syntax {
+        /// The 1-based number of the line within the text passed to
+        /// [`ZoneSource::parse`](crate::ZoneSource::parse).
+        line: usize,
+        reason: &'static str,
+    },
+
+    /// No zone or link by that name has been parsed.
+    UnknownZone(String),
+
+    /// A zone's RULES column names a rule which has not been parsed and is not an amount of
+    /// time.
+    UnknownRule { zone: String, rule: String },
+
+    /// The zone's source parsed, but describes something `zic` would refuse to compile.
+    InvalidZone { zone: String, reason: &'static str },
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::Syntax { line, reason } => write!(f, "{reason} on line {line}"),
+            CompileError::UnknownZone(name) => write!(f, "unknown zone {name:?}"),
+            CompileError::UnknownRule { zone, rule } => {
+                write!(f, "zone {zone:?} refers to unknown rule {rule:?}")
+            }
+            CompileError::InvalidZone { zone, reason } => {
+                write!(f, "can't compile zone {zone:?}: {reason}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for CompileError {}
+
+/// An error looking up a zone in a [`ZoneDatabase`](crate::ZoneDatabase).
+#[cfg(feature = "std")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ZoneError {
+    /// The zone name is not a valid IANA zone name.
+    InvalidName(String),
+
+    /// No zone by that name exists in the database.
+    NotFound(String),
+
+    /// The zone file could not be read.
+    Io(io::Error),
+
+    /// The zone file could not be parsed.
+    Parse(ParseError),
+
+    /// The database's zone table could not be parsed.
+    Tab(ZoneTabError),
+
+    /// The database's `tzdata.zi` source could not be parsed.
+    Compile(CompileError),
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ZoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZoneError::InvalidName(name) => write!(f, "invalid zone name {name:?}"),
+            ZoneError::NotFound(name) => write!(f, "zone {name:?} not found"),
+            ZoneError::Io(e) => write!(f, "error reading zone: {e}"),
+            ZoneError::Parse(e) => write!(f, "error parsing zone: {e}"),
+            ZoneError::Tab(e) => write!(f, "error parsing zone table: {e}"),
+            ZoneError::Compile(e) => write!(f, "error parsing zone source: {e}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ZoneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ZoneError::Io(e) => Some(e),
+            ZoneError::Parse(e) => Some(e),
+            ZoneError::Tab(e) => Some(e),
+            ZoneError::Compile(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ParseError> for ZoneError {
+    fn from(e: ParseError) -> Self {
+        ZoneError::Parse(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ZoneTabError> for ZoneError {
+    fn from(e: ZoneTabError) -> Self {
+        ZoneError::Tab(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<CompileError> for ZoneError {
+    fn from(e: CompileError) -> Self {
+        ZoneError::Compile(e)
+    }
+}
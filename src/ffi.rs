@@ -0,0 +1,189 @@
+//! A C interface to parsing zones and looking up offsets in them.
+//!
+//! The functions here are declared in `include/tzif.h`, which the build generates from this
+//! module with cbindgen when the `ffi` feature is enabled. To build a shared library for C,
+//! use `cargo rustc --release --features ffi --crate-type cdylib`.
+
+#![allow(unsafe_code)]
+
+use core::ffi::c_char;
+use core::ptr;
+
+use crate::{ParseOptions, TimeZoneInfo, Zone};
+
+/// A parsed zone, from `tzif_parse`.
+pub struct TzifZone {
+    zone: Zone,
+}
+
+/// A transition, from `tzif_transition`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TzifTransition {
+    /// The UT time of the transition, in seconds since the epoch.
+    pub at: i64,
+    /// The UT offset from then on, in seconds.
+    pub ut_offset: i32,
+    /// Whether DST is in effect from then on.
+    pub is_dst: bool,
+    /// The designation from then on, a NUL-terminated string which lives as long as the zone.
+    pub designation: *const c_char,
+}
+
+/// Parse `len` bytes of TZif data at `data`, returning the zone, or null if the data isn't
+/// valid. The zone must be freed with `tzif_free`.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, or may be null if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn tzif_parse(data: *const u8, len: usize) -> *mut TzifZone {
+    let data = match data.is_null() {
+        true => &[][..],
+        // SAFETY: the caller ensures data points to len bytes.
+        false => unsafe { core::slice::from_raw_parts(data, len) },
+    };
+    TimeZoneInfo::parse_slice(data, &ParseOptions::default())
+        .ok()
+        .and_then(|info| Zone::new(info).ok())
+        .map_or(ptr::null_mut(), |zone| {
+            Box::into_raw(Box::new(TzifZone { zone }))
+        })
+}
+
+/// Free a zone from `tzif_parse`. Null is ignored.
+///
+/// # Safety
+///
+/// `zone` must be null or a zone from `tzif_parse` which hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn tzif_free(zone: *mut TzifZone) {
+    if !zone.is_null() {
+        // SAFETY: the caller ensures the zone came from tzif_parse, which boxed it.
+        drop(unsafe { Box::from_raw(zone) });
+    }
+}
+
+/// Look up the UT offset in effect at the UT time `ut`, in seconds since the epoch, storing it
+/// in `*offset` and whether DST is in effect in `*is_dst`, either of which may be null.
+/// Returns whether the zone has an offset to give.
+///
+/// # Safety
+///
+/// `zone` must be a live zone from `tzif_parse`, and `offset` and `is_dst` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn tzif_offset_at(
+    zone: *const TzifZone,
+    ut: i64,
+    offset: *mut i32,
+    is_dst: *mut bool,
+) -> bool {
+    // SAFETY: the caller ensures the zone is live.
+    let zone = unsafe { &(*zone).zone };
+    let Some(local) = zone.local_time_type_at(ut) else {
+        return false;
+    };
+    // SAFETY: the caller ensures these are null or writable.
+    unsafe {
+        if !offset.is_null() {
+            *offset = local.ut_offset_secs;
+        }
+        if !is_dst.is_null() {
+            *is_dst = local.is_dst;
+        }
+    }
+    true
+}
+
+/// The number of transitions recorded in the zone, not counting those its footer gives.
+///
+/// # Safety
+///
+/// `zone` must be a live zone from `tzif_parse`.
+#[no_mangle]
+pub unsafe extern "C" fn tzif_transition_count(zone: *const TzifZone) -> usize {
+    // SAFETY: the caller ensures the zone is live.
+    unsafe { (*zone).zone.transition_times().len() }
+}
+
+/// Store the transition with index `idx` in `*out`, returning false if there's no such
+/// transition. Transitions are in order of time, so calling this with each index from 0 up to
+/// `tzif_transition_count` iterates over them.
+///
+/// # Safety
+///
+/// `zone` must be a live zone from `tzif_parse`, and `out` writable.
+#[no_mangle]
+pub unsafe extern "C" fn tzif_transition(
+    zone: *const TzifZone,
+    idx: usize,
+    out: *mut TzifTransition,
+) -> bool {
+    // SAFETY: the caller ensures the zone is live.
+    let zone = unsafe { &(*zone).zone };
+    let (Some(&at), Some(&typ)) = (
+        zone.transition_times().get(idx),
+        zone.transition_types().get(idx),
+    ) else {
+        return false;
+    };
+    let record = zone.local_time_types()[usize::from(typ)];
+    // Zone ensures the designation is in the table and NUL-terminated.
+    let designation = zone.time_zone_designations()[usize::from(record.desig_idx)..].as_ptr();
+    // SAFETY: the caller ensures out is writable.
+    unsafe {
+        *out = TzifTransition {
+            at,
+            ut_offset: record.ut_off_secs,
+            is_dst: record.is_dst,
+            designation: designation.cast(),
+        };
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use core::ffi::CStr;
+
+    #[test]
+    fn test_ffi() {
+        let data = sample().to_bytes();
+        // SAFETY: the zone is live and the pointers are to locals, throughout.
+        unsafe {
+            let zone = tzif_parse(data.as_ptr(), data.len());
+            assert!(!zone.is_null());
+            let (mut offset, mut is_dst) = (0, false);
+            assert!(tzif_offset_at(zone, 150, &mut offset, &mut is_dst));
+            assert_eq!((-7 * 3600, true), (offset, is_dst));
+            assert!(tzif_offset_at(zone, 250, &mut offset, ptr::null_mut()));
+            assert_eq!(-8 * 3600, offset);
+
+            assert_eq!(3, tzif_transition_count(zone));
+            let mut transition = core::mem::zeroed();
+            assert!(tzif_transition(zone, 1, &mut transition));
+            assert_eq!(
+                (200, -8 * 3600, false),
+                (transition.at, transition.ut_offset, transition.is_dst)
+            );
+            assert_eq!(c"PST", CStr::from_ptr(transition.designation));
+            assert!(!tzif_transition(zone, 3, &mut transition));
+            tzif_free(zone);
+
+            assert!(tzif_parse(data.as_ptr(), 10).is_null());
+            assert!(tzif_parse(ptr::null(), 0).is_null());
+            tzif_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_header() {
+        // The checked-in header must match the one the build generates.
+        assert_eq!(
+            include_str!(concat!(env!("OUT_DIR"), "/tzif.h")),
+            include_str!("../include/tzif.h")
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! The wall-clock times skipped or repeated when a transition moves local clocks.
+
+use crate::{LocalTimeType, TimeTransition, TimeZoneInfo};
+
+/// Local times which never happen, because a transition moved clocks forward past them.
+///
+/// Local times are seconds since the epoch on the local wall clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gap<'a> {
+    /// The UT time of the transition.
+    pub at: i64,
+
+    /// The first local time skipped.
+    pub start: i64,
+
+    /// The local time clocks move forward to, which is the first one after the gap.
+    pub end: i64,
+
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub before: LocalTimeType<'a>,
+
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub after: LocalTimeType<'a>,
+}
+
+/// Local times which happen twice, because a transition moved clocks back over them.
+///
+/// Local times are seconds since the epoch on the local wall clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Overlap<'a> {
+    /// The UT time of the transition.
+    pub at: i64,
+
+    /// The local time clocks move back to, which is the first one repeated.
+    pub start: i64,
+
+    /// The local time after the last one repeated.
+    pub end: i64,
+
+    /// The local time type of the first occurrence of each repeated time.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub earlier: LocalTimeType<'a>,
+
+    /// The local time type of the second occurrence.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub later: LocalTimeType<'a>,
+}
+
+impl TimeZoneInfo {
+    /// The gaps in local time at each of the zone's transitions which moves clocks forward.
+    ///
+    /// Only recorded transitions are considered, not those the footer gives after them.
+    pub fn gaps(&self) -> impl Iterator<Item = Gap<'_>> + '_ {
+        self.iter_transitions().filter_map(|tr| {
+            let (at, from, to) = local_span(&tr)?;
+            (to > from).then_some(Gap {
+                at,
+                start: from,
+                end: to,
+                before: tr.prev,
+                after: tr.local,
+            })
+        })
+    }
+
+    /// The overlaps in local time at each of the zone's transitions which moves clocks back.
+    ///
+    /// Only recorded transitions are considered, not those the footer gives after them.
+    pub fn overlaps(&self) -> impl Iterator<Item = Overlap<'_>> + '_ {
+        self.iter_transitions().filter_map(|tr| {
+            let (at, from, to) = local_span(&tr)?;
+            (to < from).then_some(Overlap {
+                at,
+                start: to,
+                end: from,
+                earlier: tr.prev,
+                later: tr.local,
+            })
+        })
+    }
+}
+
+/// The UT time of a transition, and the local time just before it on the old and new clocks.
+fn local_span(tr: &TimeTransition<'_>) -> Option<(i64, i64, i64)> {
+    let at = tr.at_time.to_ut();
+    let from = at.checked_add(i64::from(tr.prev.ut_offset_secs))?;
+    let to = at.checked_add(i64::from(tr.local.ut_offset_secs))?;
+    Some((at, from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::sample;
+
+    #[test]
+    fn test_gaps_and_overlaps() {
+        let tz = sample();
+        let gaps: Vec<_> = tz
+            .gaps()
+            .map(|gap| {
+                (
+                    gap.at,
+                    gap.start,
+                    gap.end,
+                    gap.before.desig,
+                    gap.after.desig,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (100, 100 - 8 * 3600, 100 - 7 * 3600, "PST", "PDT"),
+                (300, 300 - 8 * 3600, 300 - 7 * 3600, "PST", "PDT"),
+            ],
+            gaps
+        );
+
+        let overlaps: Vec<_> = tz
+            .overlaps()
+            .map(|o| (o.at, o.start, o.end, o.earlier.desig, o.later.desig))
+            .collect();
+        assert_eq!(
+            vec![(200, 200 - 8 * 3600, 200 - 7 * 3600, "PDT", "PST")],
+            overlaps
+        );
+
+        // A transition which only changes the designation is neither.
+        let mut renamed = sample();
+        renamed.local_time_types[1].ut_off_secs = -8 * 3600;
+        assert_eq!(0, renamed.gaps().count());
+        assert_eq!(0, renamed.overlaps().count());
+    }
+}
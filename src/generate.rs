@@ -0,0 +1,253 @@
+//! Crafted zones exercising the edge cases of TZif, as a corpus for testing readers: the crate's
+//! own fuzz targets and property tests, and other datetime code.
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::{IsStd, IsUT, LeapSecondTable, LocalTimeTypeRecord, TimeZoneInfo, ZoneSource};
+
+/// An edge case of TZif which a [`generate`]d zone exercises.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EdgeCase {
+    /// No transitions at all, so that the footer alone says when DST is.
+    NoTransitions,
+
+    /// 256 local time types, as many as a transition can refer to, with a transition to each
+    /// before, within and after the 32-bit range, a 256-byte designation table, and indicators
+    /// for every type.
+    MaxCounts,
+
+    /// UT offsets which aren't whole minutes, as local mean time and Amsterdam's time until 1937
+    /// had, +00:19:32 and +01:19:32 in summer.
+    SubMinuteOffsets,
+
+    /// Negative DST, a winter time an hour behind the summer's standard time, as Ireland has
+    /// observed since 1971.
+    NegativeDst,
+
+    /// DST two hours ahead of standard time, as Britain's double summer time in the Second
+    /// World War, next to DST one hour ahead.
+    DoubleDst,
+
+    /// Leap seconds, as in a "right" zone, including a negative one, and the expiration of the
+    /// table, which makes it version 4.
+    LeapSeconds,
+
+    /// Version 1, with only 32-bit times and no footer.
+    V1Only,
+}
+
+impl EdgeCase {
+    pub const ALL: [EdgeCase; 7] = [
+        EdgeCase::NoTransitions,
+        EdgeCase::MaxCounts,
+        EdgeCase::SubMinuteOffsets,
+        EdgeCase::NegativeDst,
+        EdgeCase::DoubleDst,
+        EdgeCase::LeapSeconds,
+        EdgeCase::V1Only,
+    ];
+
+    /// The edge case's name, such as `negative-dst`, which `tzif generate` names its file.
+    pub fn name(self) -> &'static str {
+        match self {
+            EdgeCase::NoTransitions => "no-transitions",
+            EdgeCase::MaxCounts => "max-counts",
+            EdgeCase::SubMinuteOffsets => "sub-minute-offsets",
+            EdgeCase::NegativeDst => "negative-dst",
+            EdgeCase::DoubleDst => "double-dst",
+            EdgeCase::LeapSeconds => "leap-seconds",
+            EdgeCase::V1Only => "v1-only",
+        }
+    }
+
+    /// The edge case with the given [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|case| case.name() == name)
+    }
+}
+
+/// Ireland's rules since 1971, in which standard time is summer time, and winter time is DST of
+/// minus an hour.
+const EIRE: &str = "\
+Rule  Eire  1971  only  -  Oct  31       2:00u  -1:00  -
+Rule  Eire  1972  1980  -  Mar  Sun>=16  2:00u  0      -
+Rule  Eire  1972  1980  -  Oct  Sun>=23  2:00u  -1:00  -
+Rule  Eire  1981  max   -  Mar  lastSun  1:00u  0      -
+Rule  Eire  1981  1989  -  Oct  Sun>=23  1:00u  -1:00  -
+Rule  Eire  1990  1995  -  Oct  Sun>=22  1:00u  -1:00  -
+Rule  Eire  1996  max   -  Oct  lastSun  1:00u  -1:00  -
+Zone  Test  1:00  -     IST  1971 Oct 31 2:00u
+            1:00  Eire  IST/GMT
+";
+
+/// Britain's rules from 1940, with double summer time in the middle of each year of the war.
+const DOUBLE: &str = "\
+Rule  GB  1940  only  -  Feb  25       2:00s  1:00  BST
+Rule  GB  1941  1943  -  May  Sun>=2   1:00s  2:00  BDST
+Rule  GB  1941  1943  -  Aug  Sun>=9   1:00s  1:00  BST
+Rule  GB  1944  only  -  Apr  Sun>=2   1:00s  2:00  BDST
+Rule  GB  1944  only  -  Sep  17       1:00s  1:00  BST
+Rule  GB  1945  only  -  Apr  2        1:00s  2:00  BDST
+Rule  GB  1945  only  -  Jul  15       1:00s  1:00  BST
+Rule  GB  1945  only  -  Oct  7        2:00s  0     GMT
+Rule  GB  1946  max   -  Mar  lastSun  1:00u  1:00  BST
+Rule  GB  1946  max   -  Oct  lastSun  1:00u  0     GMT
+Zone  Test  0:00  GB  %s
+";
+
+/// Amsterdam's history, simplified to fixed offsets, with its summer time of 1916.
+const AMSTERDAM: &str = "\
+Zone  Test  0:19:32  -     LMT    1835
+            0:19:32  -     AMT    1916 May  1  0:00
+            0:19:32  1:00  NST    1916 Oct  1  0:00
+            0:19:32  -     AMT    1937 Jul  1
+            0:20     -     +0020  1940 May 16  0:00
+            1:00     -     CET
+";
+
+/// Eastern time in the US since 2007.
+const US: &str = "\
+Rule  US  2007  max  -  Mar  Sun>=8  2:00  1:00  D
+Rule  US  2007  max  -  Nov  Sun>=1  2:00  0     S
+Zone  Test  -5:00  US  E%sT
+";
+
+/// Leap seconds at the ends of 1972-06-30, 1972-12-31 and 1973-12-31, the first three there
+/// were, then a made-up negative one at the end of 2016-12-31.
+const LEAPSECONDS: &str = "\
+Leap     1972  Jun  30  23:59:60  +  S
+Leap     1972  Dec  31  23:59:60  +  S
+Leap     1973  Dec  31  23:59:60  +  S
+Leap     2016  Dec  31  23:59:59  -  S
+Expires  2026  Jun  28  00:00:00
+";
+
+/// Compile the zone `Test` from the given source text.
+fn compile(source: &str) -> TimeZoneInfo {
+    let mut zones = ZoneSource::new();
+    zones.parse(source).expect("generated zone source parses");
+    zones.compile("Test").expect("generated zone compiles")
+}
+
+/// A zone exercising the given edge case.
+///
+/// Each is valid TZif, which [`to_bytes`](TimeZoneInfo::to_bytes) writes and parsing reads back
+/// as the same zone, but is made to trip up readers which cut corners.
+pub fn generate(case: EdgeCase) -> TimeZoneInfo {
+    match case {
+        EdgeCase::NoTransitions => TimeZoneInfo::from_posix(
+            "EST5EDT,M3.2.0,M11.1.0"
+                .parse()
+                .expect("generated TZ string parses"),
+        ),
+        EdgeCase::MaxCounts => max_counts(),
+        EdgeCase::SubMinuteOffsets => compile(AMSTERDAM),
+        EdgeCase::NegativeDst => compile(EIRE),
+        EdgeCase::DoubleDst => compile(DOUBLE),
+        EdgeCase::LeapSeconds => {
+            let mut tz = compile(EIRE);
+            let table = LeapSecondTable::parse_leapseconds(LEAPSECONDS)
+                .expect("generated leap seconds parse");
+            tz.set_leap_seconds(&table);
+            tz
+        }
+        EdgeCase::V1Only => {
+            let (tz, _) = compile(US).to_version(1).expect("version 1 is known");
+            tz
+        }
+    }
+}
+
+fn max_counts() -> TimeZoneInfo {
+    // 64 designations of three characters and a NUL.
+    let mut time_zone_designations = Vec::with_capacity(256);
+    for i in 0..64 {
+        time_zone_designations.extend_from_slice(format!("T{i:02}\0").as_bytes());
+    }
+    let local_time_types = (0..=255u8)
+        .map(|i| LocalTimeTypeRecord {
+            ut_off_secs: (i32::from(i) - 128) * 300,
+            is_dst: i % 2 == 1,
+            desig_idx: i % 64 * 4,
+        })
+        .collect();
+    // 512 transitions evenly spread from 2^33 seconds before the epoch to as long after.
+    let transition_times = (0..512).map(|i| (i - 256) << 25).collect();
+    let transition_types = (0..512).map(|i| (i % 256) as u8).collect();
+    let (is_std, is_ut) = (0..256)
+        .map(|i| match i % 3 {
+            0 => (IsStd::Wall, IsUT::Local),
+            1 => (IsStd::Standard, IsUT::Local),
+            _ => (IsStd::Standard, IsUT::UT),
+        })
+        .unzip();
+    TimeZoneInfo {
+        version: 2,
+        transition_times,
+        transition_types,
+        local_time_types,
+        time_zone_designations,
+        is_std,
+        is_ut,
+        ..TimeZoneInfo::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseOptions;
+
+    #[test]
+    fn test_generate() {
+        let strict = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        for case in EdgeCase::ALL {
+            let tz = generate(case);
+            let parsed = TimeZoneInfo::parse_slice(&tz.to_bytes(), &strict).unwrap();
+            assert_eq!(tz, parsed, "{case:?}");
+            assert_eq!(Some(case), EdgeCase::from_name(case.name()));
+        }
+        assert_eq!(None, EdgeCase::from_name("nonsense"));
+
+        let tz = generate(EdgeCase::NoTransitions);
+        assert!(tz.transition_times.is_empty());
+        assert_eq!(Some("EDT"), tz.designation_at(1_720_000_000));
+
+        let tz = generate(EdgeCase::MaxCounts);
+        assert_eq!(256, tz.local_time_types.len());
+        assert_eq!(256, tz.time_zone_designations.len());
+        assert!(tz.transition_times.iter().any(|&t| t < i64::from(i32::MIN)));
+        assert!(tz.transition_times.iter().any(|&t| t > i64::from(i32::MAX)));
+
+        let tz = generate(EdgeCase::SubMinuteOffsets);
+        // 1874 and 1916-07-01.
+        assert_eq!(Some(1172), tz.offset_at(-3_000_000_000));
+        assert_eq!(Some(4772), tz.offset_at(-1_688_428_800));
+
+        // 2024-01-01 and 2024-07-01.
+        let tz = generate(EdgeCase::NegativeDst);
+        assert_eq!(Some(0), tz.offset_at(1_704_067_200));
+        assert!(tz.is_dst_at(1_704_067_200));
+        assert_eq!(Some(3600), tz.offset_at(1_719_792_000));
+        assert!(!tz.is_dst_at(1_719_792_000));
+
+        // 1942-06-01.
+        let tz = generate(EdgeCase::DoubleDst);
+        assert_eq!(Some("BDST"), tz.designation_at(-870_566_400));
+        assert_eq!(Some(7200), tz.offset_at(-870_566_400));
+
+        let tz = generate(EdgeCase::LeapSeconds);
+        assert!(tz.is_right());
+        assert_eq!(4, tz.version);
+        assert_eq!(Some(1_782_604_800), tz.leap_second_table().expires);
+
+        let tz = generate(EdgeCase::V1Only);
+        assert_eq!(1, tz.version);
+        assert_eq!(None, tz.footer);
+    }
+}
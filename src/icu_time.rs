@@ -0,0 +1,62 @@
+//! Interoperability with ICU4X's `icu_time` crate, so that parsed zones can stand in for its
+//! compiled time zone data.
+
+use ::icu_time::zone::models::AtTime;
+use ::icu_time::zone::{UtcOffset, ZoneNameTimestamp};
+use ::icu_time::TimeZone;
+
+use crate::TimeZoneInfo;
+
+impl TimeZoneInfo {
+    /// The UT offset in effect at the given UT timestamp, as an ICU4X offset.
+    ///
+    /// Returns `None` if the zone has no local time types, or if the offset is beyond the
+    /// ±18 hours ICU4X allows.
+    pub fn icu_offset_at(&self, ut: i64) -> Option<UtcOffset> {
+        UtcOffset::try_from_seconds(self.offset_at(ut)?).ok()
+    }
+
+    /// An ICU4X time zone for formatting the given UT timestamp, with the offset in effect then
+    /// taken from this zone rather than ICU4X's own data.
+    ///
+    /// `id` is the zone's BCP-47 identifier, which ICU4X's `IanaParser` gives for an IANA
+    /// name; the offset is left unset where [`icu_offset_at`](Self::icu_offset_at) gives none.
+    pub fn to_icu(&self, id: TimeZone, ut: i64) -> ::icu_time::TimeZoneInfo<AtTime> {
+        id.with_offset(self.icu_offset_at(ut))
+            .with_zone_name_timestamp(ZoneNameTimestamp::from_epoch_seconds(ut))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_icu_offset_at() {
+        let tz = sample();
+        assert_eq!(
+            Some(UtcOffset::try_from_seconds(-8 * 3600).unwrap()),
+            tz.icu_offset_at(250)
+        );
+        assert_eq!(
+            Some(-7 * 3600),
+            tz.icu_offset_at(350).map(UtcOffset::to_seconds)
+        );
+        assert_eq!(None, TimeZoneInfo::default().icu_offset_at(0));
+
+        let icu = tz.to_icu(TimeZone::UNKNOWN, 1_700_000_000);
+        assert_eq!(TimeZone::UNKNOWN, icu.id());
+        assert_eq!(tz.icu_offset_at(1_700_000_000), icu.offset());
+        assert_eq!(
+            ZoneNameTimestamp::from_epoch_seconds(1_700_000_000),
+            icu.zone_name_timestamp()
+        );
+
+        // Beyond what ICU4X allows.
+        let mut tz = sample();
+        tz.local_time_types[1].ut_off_secs = 25 * 3600;
+        assert_eq!(None, tz.icu_offset_at(150));
+        assert_eq!(None, tz.to_icu(TimeZone::UNKNOWN, 150).offset());
+    }
+}
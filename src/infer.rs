@@ -0,0 +1,175 @@
+//! Working out a footer TZ string from a zone's transitions, for data which lacks one.
+
+use alloc::borrow::ToOwned;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::civil::{
+    civil_from_days, days_from_civil, days_in_month, is_leap, weekday, SECS_PER_DAY,
+};
+use crate::{LocalTimeType, PosixDst, PosixRule, PosixTz, RuleDate, TimeZoneInfo};
+
+/// How many of the last transitions a DST rule must give, two years' worth, before it's taken
+/// to be the one the zone follows.
+const MIN_PATTERN: usize = 4;
+
+impl TimeZoneInfo {
+    /// The TZ string which best continues the zone's recorded transitions, to use as the footer
+    /// of a version 2+ file written from data which has none, as `zic` writes one from its
+    /// rules.
+    ///
+    /// If the last transitions follow a yearly DST rule for at least two years, the result has
+    /// the rule which gives the most of them, preferring `Mm.w.d` dates, and "last" over a
+    /// fourth week, to `Jn` dates. Otherwise it's the local time type in effect after the last
+    /// transition, with no DST. A zone which stopped observing DST after following a rule for
+    /// years looks the same as one still following it, so it gets the rule too.
+    ///
+    /// Returns `None` if the zone has no local time types, if it ends on DST with no rule to
+    /// leave it, or if the result can't be written as a TZ string.
+    pub fn infer_footer(&self) -> Option<PosixTz> {
+        let last = match self.transition_types.last() {
+            Some(&idx) => self.local_time_type(usize::from(idx)),
+            None if self.local_time_types.is_empty() => return None,
+            None => self.local_time_type(0),
+        };
+        let tz = match self.infer_rule() {
+            Some(tz) => tz,
+            None if last.is_dst => return None,
+            None => PosixTz {
+                std_abbr: last.desig.to_owned(),
+                std_offset: last.ut_offset_secs,
+                dst: None,
+            },
+        };
+        // Anything which doesn't survive being written out and read back can't be a footer.
+        let parsed: Option<PosixTz> = tz.to_string().parse().ok();
+        (parsed.as_ref() == Some(&tz)).then_some(tz)
+    }
+
+    /// The DST rule which gives the most of the last transitions, if it gives enough of them.
+    /// Transitions which change nothing, like those `zic` adds at the ends of the 32-bit range,
+    /// are ignored.
+    fn infer_rule(&self) -> Option<PosixTz> {
+        let trailing: Vec<(i64, LocalTimeType<'_>, LocalTimeType<'_>)> = self
+            .transition_times
+            .iter()
+            .zip(self.iter_transitions())
+            .rev()
+            .map(|(&at, tr)| (at, tr.prev, tr.local))
+            .filter(|(_, prev, local)| prev != local)
+            .collect();
+        let (&(last_at, last_prev, last), &(prev_at, _, _)) = (trailing.first()?, trailing.get(1)?);
+        if last_prev.is_dst == last.is_dst {
+            return None;
+        }
+        let (std, dst) = if last.is_dst {
+            (last_prev, last)
+        } else {
+            (last, last_prev)
+        };
+        let (start, end) = if last.is_dst {
+            (last_at, prev_at)
+        } else {
+            (prev_at, last_at)
+        };
+
+        // POSIX gives the start in standard time and the end in daylight saving time.
+        let starts = rule_candidates(start + i64::from(std.ut_offset_secs));
+        let ends = rule_candidates(end + i64::from(dst.ut_offset_secs));
+        let mut best: Option<(usize, PosixTz)> = None;
+        for start in &starts {
+            for end in &ends {
+                let tz = PosixTz {
+                    std_abbr: std.desig.to_owned(),
+                    std_offset: std.ut_offset_secs,
+                    dst: Some(PosixDst {
+                        abbr: dst.desig.to_owned(),
+                        offset: dst.ut_offset_secs,
+                        start: *start,
+                        end: *end,
+                    }),
+                };
+                let gives = |&&(at, prev, local): &&(i64, _, _)| {
+                    tz.local_time_type_at(at - 1) == prev && tz.local_time_type_at(at) == local
+                };
+                let given = trailing.iter().take_while(gives).count();
+                if best.as_ref().is_none_or(|(most, _)| given > *most) {
+                    best = Some((given, tz));
+                }
+            }
+        }
+        best.filter(|(given, _)| *given >= MIN_PATTERN)
+            .map(|(_, tz)| tz)
+    }
+}
+
+/// The rules which could give a transition at the given local time, in order of preference:
+/// those on the same day, then those on the day after or before, with a time of day outside
+/// 0 to 24 hours as version 3 allows.
+fn rule_candidates(local: i64) -> Vec<PosixRule> {
+    let mut rules = Vec::new();
+    for shift in [0, 1, -1] {
+        let days = local.div_euclid(SECS_PER_DAY) + shift;
+        let time = (local - days * SECS_PER_DAY) as i32;
+        let (year, month, day) = civil_from_days(days);
+        let month_week_day = |week| RuleDate::MonthWeekDay {
+            month,
+            week,
+            weekday: weekday(days),
+        };
+
+        let mut dates = Vec::new();
+        if day + 7 > days_in_month(year, month) {
+            dates.push(month_week_day(5));
+        }
+        if day <= 28 {
+            dates.push(month_week_day((day - 1) / 7 + 1));
+        }
+        if (month, day) != (2, 29) {
+            let yday = days - days_from_civil(year, 1, 1) + 1;
+            let yday = yday - i64::from(is_leap(year) && month > 2);
+            dates.push(RuleDate::Julian(yday as u16));
+        }
+        rules.extend(dates.into_iter().map(|date| PosixRule { date, time }));
+    }
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::sample;
+    use crate::{PosixTz, TimeZoneInfo};
+
+    #[test]
+    fn test_infer_footer() {
+        for s in [
+            "PST8PDT,M3.2.0,M11.1.0",
+            "CET-1CEST,M3.5.0,M10.5.0/3",
+            "AEST-10AEDT,M10.1.0/2,M4.1.0/3",
+            "IST-2IDT,M3.4.4/26,M10.5.0",
+            "<-03>3<-02>,M3.5.0/-2,M10.5.0/-1",
+        ] {
+            let footer: PosixTz = s.parse().unwrap();
+            // The transitions the footer gives up to 2037, with no footer.
+            let (v1, _) = TimeZoneInfo::from_posix(footer.clone())
+                .to_version(1)
+                .unwrap();
+            let inferred = v1.infer_footer().unwrap();
+            for year in 2038..2100 {
+                assert_eq!(
+                    footer.transitions(year),
+                    inferred.transitions(year),
+                    "{s} -> {inferred} in {year}"
+                );
+            }
+        }
+
+        // A single year of DST isn't a pattern.
+        let mut tz = sample();
+        tz.transition_times.pop();
+        tz.transition_types.pop();
+        assert_eq!(Some("PST8".parse().unwrap()), tz.infer_footer());
+        tz.transition_types = vec![0, 1];
+        assert_eq!(None, tz.infer_footer());
+    }
+}
@@ -0,0 +1,103 @@
+//! Interoperability with the `jiff` crate.
+
+use alloc::vec::Vec;
+
+use ::jiff::tz::TimeZone;
+use ::jiff::Timestamp;
+
+use crate::{IsStd, IsUT, LocalTimeTypeRecord, TimeZoneInfo};
+
+impl TimeZoneInfo {
+    /// Convert to a `jiff` time zone with the given IANA name.
+    pub fn to_jiff(&self, name: &str) -> Result<TimeZone, ::jiff::Error> {
+        TimeZone::tzif(name, &self.to_bytes())
+    }
+
+    /// Build a zone from a `jiff` time zone, recording all of its transitions before `until`.
+    ///
+    /// Returns `None` if more than 256 distinct local time types would be needed.
+    pub fn from_jiff(tz: &TimeZone, until: Timestamp) -> Option<Self> {
+        let mut result = Self {
+            version: 2,
+            ..Self::default()
+        };
+
+        let initial = tz.to_offset_info(Timestamp::MIN);
+        result.add_type(
+            initial.offset().seconds(),
+            initial.dst().is_dst(),
+            initial.abbreviation(),
+        )?;
+
+        for tr in tz.following(Timestamp::MIN) {
+            // jiff can yield the final transition repeatedly for zones without a TZ string.
+            let repeated = result
+                .transition_times
+                .last()
+                .is_some_and(|&last| tr.timestamp().as_second() <= last);
+            if tr.timestamp() >= until || repeated {
+                break;
+            }
+            let typ =
+                result.add_type(tr.offset().seconds(), tr.dst().is_dst(), tr.abbreviation())?;
+            result.transition_times.push(tr.timestamp().as_second());
+            result.transition_types.push(typ);
+        }
+
+        // Transition times from jiff are always UT.
+        result.is_std = Vec::from_iter(result.local_time_types.iter().map(|_| IsStd::Standard));
+        result.is_ut = Vec::from_iter(result.local_time_types.iter().map(|_| IsUT::UT));
+        Some(result)
+    }
+
+    /// Find or add a local time type (and its designation), returning its index.
+    fn add_type(&mut self, ut_off_secs: i32, is_dst: bool, desig: &str) -> Option<u8> {
+        let desig_idx = self.find_or_add_designation(desig)?;
+        let record = LocalTimeTypeRecord {
+            ut_off_secs,
+            is_dst,
+            desig_idx,
+        };
+        let existing = self.local_time_types.iter().position(|r| {
+            (r.ut_off_secs, r.is_dst, r.desig_idx) == (ut_off_secs, is_dst, desig_idx)
+        });
+        let idx = match existing {
+            Some(idx) => idx,
+            None => {
+                self.local_time_types.push(record);
+                self.local_time_types.len() - 1
+            }
+        };
+        u8::try_from(idx).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_jiff_round_trip() {
+        let tz = sample();
+        let jiff_tz = tz.to_jiff("Test/Sample").unwrap();
+        assert_eq!(Some("Test/Sample"), jiff_tz.iana_name());
+        let offset = |t| {
+            jiff_tz
+                .to_offset(Timestamp::from_second(t).unwrap())
+                .seconds()
+        };
+        assert_eq!(-8 * 3600, offset(0));
+        assert_eq!(-7 * 3600, offset(150));
+        assert_eq!(-8 * 3600, offset(250));
+
+        let back = TimeZoneInfo::from_jiff(&jiff_tz, Timestamp::MAX).unwrap();
+        assert_eq!(tz.transition_times, back.transition_times);
+        let summary = |tz: &TimeZoneInfo| {
+            tz.iter_transitions()
+                .map(|tr| (tr.local.desig.to_owned(), tr.local.ut_offset_secs))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summary(&tz), summary(&back));
+    }
+}
@@ -0,0 +1,186 @@
+//! A stable, human-readable JSON representation of a zone.
+//!
+//! The schema looks like this (all timestamps are seconds since the Unix epoch, UT):
+//!
+//! ```json
+//! {
+//!   "version": 2,
+//!   "local_time_types": [
+//!     { "ut_offset": -28800, "is_dst": false, "designation": "PST", "is_std": true, "is_ut": true }
+//!   ],
+//!   "transitions": [
+//!     { "at": 9972000, "type": 1 }
+//!   ],
+//!   "leap_seconds": [
+//!     { "occurrence": 78796800, "correction": 1 }
+//!   ],
+//!   "footer": "PST8PDT,M3.2.0,M11.1.0"
+//! }
+//! ```
+//!
+//! `is_std` and `is_ut` are omitted when the zone has no standard/wall or UT/local indicators,
+//! and `footer` is omitted when the zone has no TZ string.
+//! Designations are resolved to strings, so loading rebuilds the designation table rather than
+//! reproducing the original bytes.
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{IsStd, IsUT, LeapSecondRecord, LocalTimeTypeRecord, TimeZoneInfo};
+
+#[derive(Serialize, Deserialize)]
+struct Document<'a> {
+    version: u8,
+    #[serde(borrow)]
+    local_time_types: Vec<LocalTimeTypeJson<'a>>,
+    transitions: Vec<TransitionJson>,
+    leap_seconds: Vec<LeapSecondJson>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    footer: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LocalTimeTypeJson<'a> {
+    ut_offset: i32,
+    is_dst: bool,
+    #[serde(borrow)]
+    designation: Cow<'a, str>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    is_std: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    is_ut: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransitionJson {
+    at: i64,
+    #[serde(rename = "type")]
+    typ: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LeapSecondJson {
+    occurrence: i64,
+    correction: i32,
+}
+
+impl TimeZoneInfo {
+    /// Render the zone as pretty-printed JSON, in the schema described in the module docs.
+    pub fn to_json(&self) -> String {
+        let local_time_types = (0..self.local_time_types.len())
+            .map(|idx| {
+                let local = self.local_time_type(idx);
+                LocalTimeTypeJson {
+                    ut_offset: local.ut_offset_secs,
+                    is_dst: local.is_dst,
+                    designation: Cow::Borrowed(local.desig),
+                    is_std: self.is_std.get(idx).map(|&s| s == IsStd::Standard),
+                    is_ut: self.is_ut.get(idx).map(|&u| u == IsUT::UT),
+                }
+            })
+            .collect();
+        let doc = Document {
+            version: self.version,
+            local_time_types,
+            transitions: self
+                .transition_times
+                .iter()
+                .zip(&self.transition_types)
+                .map(|(&at, &typ)| TransitionJson { at, typ })
+                .collect(),
+            leap_seconds: self
+                .leap_second_records
+                .iter()
+                .map(|leap| LeapSecondJson {
+                    occurrence: leap.occurrence,
+                    correction: leap.correction,
+                })
+                .collect(),
+            footer: self.footer.as_ref().map(ToString::to_string),
+        };
+        // Serializing these types can't fail.
+        serde_json::to_string_pretty(&doc).unwrap()
+    }
+
+    /// Load a zone from JSON produced by [`to_json`](Self::to_json).
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let doc: Document<'_> = serde_json::from_str(json)?;
+        let mut result = Self {
+            version: doc.version,
+            ..Self::default()
+        };
+
+        let has_std = doc.local_time_types.iter().any(|t| t.is_std.is_some());
+        let has_ut = doc.local_time_types.iter().any(|t| t.is_ut.is_some());
+        for typ in &doc.local_time_types {
+            let desig_idx = result
+                .find_or_add_designation(&typ.designation)
+                .ok_or_else(|| serde_json::Error::custom("designation table too large"))?;
+            result.local_time_types.push(LocalTimeTypeRecord {
+                ut_off_secs: typ.ut_offset,
+                is_dst: typ.is_dst,
+                desig_idx,
+            });
+            if has_std {
+                result.is_std.push(match typ.is_std {
+                    Some(true) => IsStd::Standard,
+                    _ => IsStd::Wall,
+                });
+            }
+            if has_ut {
+                result.is_ut.push(match typ.is_ut {
+                    Some(true) => IsUT::UT,
+                    _ => IsUT::Local,
+                });
+            }
+        }
+
+        for tr in &doc.transitions {
+            if tr.typ as usize >= result.local_time_types.len() {
+                return Err(serde_json::Error::custom(format_args!(
+                    "transition type {} out of range",
+                    tr.typ
+                )));
+            }
+            result.transition_times.push(tr.at);
+            result.transition_types.push(tr.typ);
+        }
+
+        result.leap_second_records = doc
+            .leap_seconds
+            .iter()
+            .map(|leap| LeapSecondRecord::new(leap.occurrence, leap.correction))
+            .collect();
+
+        if let Some(footer) = &doc.footer {
+            result.footer = Some(footer.parse().map_err(serde_json::Error::custom)?);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut tz = sample();
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        let json = tz.to_json();
+        assert!(json.contains(r#""designation": "PDT""#));
+        assert!(json.contains(r#""footer": "PST8PDT,M3.2.0,M11.1.0""#));
+        let back = TimeZoneInfo::from_json(&json).unwrap();
+        assert_eq!(tz.to_bytes(), back.to_bytes());
+        assert_eq!(json, back.to_json());
+
+        let bad = json.replace(r#""type": 1"#, r#""type": 7"#);
+        assert!(TimeZoneInfo::from_json(&bad).is_err());
+    }
+}
@@ -0,0 +1,982 @@
+//! Leap seconds, from the leap-second records of "right" zones.
+//!
+//! Each record gives the time a leap second occurs and the total correction from then on. The
+//! times are on the zone's own leap-second-counting scale, which runs at a constant 10 seconds
+//! behind TAI, so a record's time plus 10 is its TAI time.
+//!
+//! Leap-second tables are published in two text formats. The IERS/NIST `leap-seconds.list`
+//! has lines of NTP timestamps and the TAI − UTC in effect from then on:
+//!
+//! ```text
+//! #@  3991593600
+//! 2272060800  10  # 1 Jan 1972
+//! 2287785600  11  # 1 Jul 1972
+//! ```
+//!
+//! where `#@` gives the NTP time the table expires. The tzdata `leapseconds` file, which is
+//! generated from it for `zic`, has a line per leap second, at its UTC time:
+//!
+//! ```text
+//! Leap  1972  Jun  30  23:59:60  +  S
+//! Expires  2026  Jun  28  00:00:00
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::civil::{checked_days_from_civil, civil_from_days, SECS_PER_DAY};
+use crate::compile::{lookup, parse_hms, parse_month, split_fields, MONTHS};
+use crate::{LeapSecondRecord, LeapSecondsError, LocalTimeType, TimeZoneInfo};
+
+/// TAI − UTC when leap seconds began, at the start of 1972; the leap-second records count
+/// corrections on top of this.
+const TAI_MINUS_UTC_1972: i32 = 10;
+
+/// Seconds from the NTP epoch, 1900-01-01, to the Unix epoch.
+const NTP_EPOCH_OFFSET: i64 = 2_208_988_800;
+
+/// The start of 1972, when UTC took its current definition.
+const START_OF_1972: i64 = 63_072_000;
+
+/// A table of leap seconds, as read from a `leap-seconds.list` or `leapseconds` file, or from a
+/// zone's leap-second records.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeapSecondTable {
+    /// Leap-second records in the form of
+    /// [`TimeZoneInfo::leap_second_records`](crate::TimeZoneInfo::leap_second_records), without
+    /// any expiration record.
+    pub records: Vec<LeapSecondRecord>,
+
+    /// The UTC time the table expires, after which there may be leap seconds it doesn't know
+    /// of.
+    pub expires: Option<i64>,
+}
+
+impl LeapSecondTable {
+    /// Parse the text of an IERS/NIST `leap-seconds.list` file.
+    ///
+    /// The last-update (`#$`) and hash (`#h`) lines are ignored.
+    pub fn parse_leap_seconds_list(text: &str) -> Result<Self, LeapSecondsError> {
+        let mut table = Self::default();
+        let mut builder = Builder::default();
+        for (idx, line) in text.lines().enumerate() {
+            let err = || LeapSecondsError { line: idx + 1 };
+            if let Some(expires) = line.strip_prefix("#@") {
+                let ntp: i64 = expires.trim().parse().map_err(|_| err())?;
+                table.expires = Some(ntp.checked_sub(NTP_EPOCH_OFFSET).ok_or_else(err)?);
+                continue;
+            }
+            let data = line.split_once('#').map_or(line, |(data, _)| data);
+            let fields: Vec<&str> = data.split_whitespace().collect();
+            let [ntp, tai_minus_utc] = fields[..] else {
+                if fields.is_empty() {
+                    continue;
+                }
+                return Err(err());
+            };
+            let (Ok(ntp), Ok(tai_minus_utc)) = (ntp.parse::<i64>(), tai_minus_utc.parse::<i32>())
+            else {
+                return Err(err());
+            };
+            let (Some(correction), Some(utc)) = (
+                tai_minus_utc.checked_sub(TAI_MINUS_UTC_1972),
+                ntp.checked_sub(NTP_EPOCH_OFFSET),
+            ) else {
+                return Err(err());
+            };
+            if builder.correction.is_none() && correction == 0 {
+                // The start of UTC, not a leap second.
+                builder.correction = Some(0);
+                builder.last = utc;
+                continue;
+            }
+            // A negative leap second is the second before the new correction applies.
+            let skipped = if correction < builder.current(correction) {
+                utc.checked_sub(1).ok_or_else(err)?
+            } else {
+                utc
+            };
+            builder.push(skipped, correction).ok_or_else(err)?;
+        }
+        table.records = builder.records;
+        Ok(table)
+    }
+
+    /// Parse the text of a tzdata `leapseconds` file.
+    ///
+    /// The expiration is taken from an `Expires` line or, since tzdata distributes that line
+    /// commented out, an `#expires` comment giving a Unix timestamp. Only stationary (`S`) leap
+    /// seconds, at a UTC time, are supported.
+    pub fn parse_leapseconds(text: &str) -> Result<Self, LeapSecondsError> {
+        let mut table = Self::default();
+        let mut builder = Builder {
+            correction: Some(0),
+            ..Builder::default()
+        };
+        let mut expires_comment = None;
+        for (idx, line) in text.lines().enumerate() {
+            let err = || LeapSecondsError { line: idx + 1 };
+            if let Some(expires) = line.strip_prefix("#expires") {
+                let posix = expires
+                    .split_whitespace()
+                    .next()
+                    .and_then(|t| t.parse().ok());
+                expires_comment = Some(posix.ok_or_else(err)?);
+                continue;
+            }
+            let fields = split_fields(line).ok_or_else(err)?;
+            let Some(first) = fields.first() else {
+                continue;
+            };
+            match (lookup(first, &["Leap", "Expires"]), &fields[1..]) {
+                (Some(0), [year, month, day, time, correction, rolling]) => {
+                    let time = parse_date_time(year, month, day, time).ok_or_else(err)?;
+                    let correction = match correction.as_str() {
+                        "+" => builder.current(0) + 1,
+                        "-" => builder.current(0) - 1,
+                        _ => return Err(err()),
+                    };
+                    if lookup(rolling, &["Stationary", "Rolling"]) != Some(0) {
+                        return Err(err());
+                    }
+                    builder.push(time, correction).ok_or_else(err)?;
+                }
+                (Some(1), [year, month, day, time]) => {
+                    table.expires = Some(parse_date_time(year, month, day, time).ok_or_else(err)?);
+                }
+                _ => return Err(err()),
+            }
+        }
+        table.records = builder.records;
+        table.expires = table.expires.or(expires_comment);
+        Ok(table)
+    }
+
+    /// Write the table in the `leap-seconds.list` format.
+    ///
+    /// No hash line is written, so readers which check it should be given the IERS file
+    /// itself.
+    pub fn to_leap_seconds_list(&self) -> String {
+        let mut out = String::new();
+        if let Some(expires) = self.expires {
+            let _ = writeln!(out, "#@\t{}", expires + NTP_EPOCH_OFFSET);
+        }
+        let mut line = |utc: i64, correction: i32| {
+            let (year, month, day) = civil_from_days(utc.div_euclid(SECS_PER_DAY));
+            let _ = writeln!(
+                out,
+                "{}\t{}\t# {day} {} {year}",
+                utc + NTP_EPOCH_OFFSET,
+                TAI_MINUS_UTC_1972 + correction,
+                &MONTHS[usize::from(month) - 1][..3],
+            );
+        };
+        if self.initial_correction() == Some(0) {
+            line(START_OF_1972, 0);
+        }
+        for leap in self.leaps() {
+            line(leap.utc_after(), leap.correction);
+        }
+        out
+    }
+
+    /// Write the table in the tzdata `leapseconds` format.
+    pub fn to_leapseconds(&self) -> String {
+        let mut out = String::new();
+        for leap in self.leaps() {
+            // A positive leap second is 23:59:60 of the day before the new correction applies.
+            let positive = leap.correction > leap.prev;
+            let (date, mut time) = date_time(leap.skipped - i64::from(positive));
+            time.2 += u8::from(positive);
+            let _ = writeln!(
+                out,
+                "Leap\t{date}\t{:02}:{:02}:{:02}\t{}\tS",
+                time.0,
+                time.1,
+                time.2,
+                if positive { '+' } else { '-' },
+            );
+        }
+        if let Some(expires) = self.expires {
+            let (date, (hours, minutes, seconds)) = date_time(expires);
+            let _ = writeln!(out, "Expires\t{date}\t{hours:02}:{minutes:02}:{seconds:02}");
+        }
+        out
+    }
+
+    /// The leap-second records to store in a zone, including the version 4 expiration record.
+    pub fn to_records(&self) -> Vec<LeapSecondRecord> {
+        let mut records = self.records.clone();
+        if let (Some(expires), Some(last)) = (self.expires, self.records.last()) {
+            let occurrence = expires + i64::from(last.correction);
+            records.push(LeapSecondRecord::new(occurrence, last.correction));
+        }
+        records
+    }
+
+    /// The correction in effect before the first record.
+    fn initial_correction(&self) -> Option<i32> {
+        let first = self.records.first()?.correction;
+        Some(first - first.signum())
+    }
+
+    /// The table's leap seconds in UTC terms.
+    fn leaps(&self) -> impl Iterator<Item = Leap> + '_ {
+        let mut prev = self.initial_correction().unwrap_or_default();
+        self.records.iter().map(move |record| {
+            let LeapSecondRecord {
+                occurrence,
+                correction,
+            } = *record;
+            let leap = Leap {
+                skipped: occurrence - i64::from(prev),
+                prev,
+                correction,
+            };
+            prev = correction;
+            leap
+        })
+    }
+}
+
+/// Converts to and from smeared time, which spreads each leap second evenly over the 24 hours
+/// from noon UTC before it to noon UTC after, the way Google and AWS do.
+///
+/// Smeared time, like POSIX time, counts every day as 86,400 seconds, and it is the same as UTC
+/// outside the smears. Times are in nanoseconds since the epoch, so range from 1677 to 2262.
+/// Conversions within a smear round down to the nanosecond, so a round trip may come back a
+/// nanosecond out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeapSmear {
+    table: LeapSecondTable,
+}
+
+impl LeapSmear {
+    /// The length of each smear, centred on the leap second.
+    pub const WINDOW_SECS: i64 = SECS_PER_DAY;
+
+    /// Smear the leap seconds of the given table.
+    pub fn new(table: LeapSecondTable) -> Self {
+        Self { table }
+    }
+
+    /// Convert a smeared time to TAI, in nanoseconds since 1970-01-01 00:00:00 TAI.
+    pub fn smeared_to_tai(&self, smeared: i64) -> i64 {
+        let mut correction = self.table.initial_correction().unwrap_or_default();
+        for leap in self.table.leaps() {
+            let start = (leap.utc_after() - Self::WINDOW_SECS / 2) * NANOS_PER_SEC;
+            if smeared < start {
+                break;
+            }
+            if smeared < start + Self::WINDOW_SECS * NANOS_PER_SEC {
+                // TAI runs faster or slower by the leap second over the window.
+                let smear = i128::from(smeared - start) * i128::from(leap.correction - leap.prev)
+                    / i128::from(Self::WINDOW_SECS);
+                return smeared + tai_nanos(leap.prev) + smear as i64;
+            }
+            correction = leap.correction;
+        }
+        smeared + tai_nanos(correction)
+    }
+
+    /// Convert a TAI time, in nanoseconds since 1970-01-01 00:00:00 TAI, to smeared time.
+    pub fn tai_to_smeared(&self, tai: i64) -> i64 {
+        let mut correction = self.table.initial_correction().unwrap_or_default();
+        for leap in self.table.leaps() {
+            let start = (leap.utc_after() - Self::WINDOW_SECS / 2) * NANOS_PER_SEC;
+            let tai_start = start + tai_nanos(leap.prev);
+            if tai < tai_start {
+                break;
+            }
+            let tai_len =
+                (Self::WINDOW_SECS + i64::from(leap.correction - leap.prev)) * NANOS_PER_SEC;
+            if tai < tai_start + tai_len {
+                let smeared = i128::from(tai - tai_start)
+                    * i128::from(Self::WINDOW_SECS * NANOS_PER_SEC)
+                    / i128::from(tai_len);
+                return start + smeared as i64;
+            }
+            correction = leap.correction;
+        }
+        tai - tai_nanos(correction)
+    }
+
+    /// Convert a smeared time to UTC, in nanoseconds since the epoch not counting leap seconds.
+    /// As with [`TimeZoneInfo::tai_to_utc`], a positive leap second reads as the second before.
+    pub fn smeared_to_utc(&self, smeared: i64) -> i64 {
+        let tai = self.smeared_to_tai(smeared);
+        let mut correction = self.table.initial_correction().unwrap_or_default();
+        for &LeapSecondRecord {
+            occurrence,
+            correction: total,
+        } in &self.table.records
+        {
+            if (occurrence + i64::from(TAI_MINUS_UTC_1972)) * NANOS_PER_SEC > tai {
+                break;
+            }
+            correction = total;
+        }
+        tai - tai_nanos(correction)
+    }
+
+    /// Convert a UTC time, in nanoseconds since the epoch not counting leap seconds, to
+    /// smeared time.
+    pub fn utc_to_smeared(&self, utc: i64) -> i64 {
+        let mut correction = self.table.initial_correction().unwrap_or_default();
+        for leap in self.table.leaps() {
+            if leap.skipped * NANOS_PER_SEC > utc {
+                break;
+            }
+            correction = leap.correction;
+        }
+        self.tai_to_smeared(utc + tai_nanos(correction))
+    }
+}
+
+const NANOS_PER_SEC: i64 = 1_000_000_000;
+
+/// TAI − UTC in nanoseconds, for the given correction.
+fn tai_nanos(correction: i32) -> i64 {
+    i64::from(TAI_MINUS_UTC_1972 + correction) * NANOS_PER_SEC
+}
+
+/// The least time between leap seconds RFC 8536 allows: 28 days, less one second in case the
+/// second is a negative leap second.
+const MIN_LEAP_SECOND_GAP: i64 = 28 * SECS_PER_DAY - 1;
+
+/// Check leap-second records against RFC 8536: the first occurs at a nonnegative time with a
+/// correction of +1 or -1, and each after it comes at least 28 days later and changes the
+/// correction by exactly one. Version 4 allows the first correction to be anything, for a table
+/// truncated at the start, and the last record to repeat the correction before it, as the
+/// table's expiration. Returns the index of the first record which breaks the rules.
+pub(crate) fn check_leap_seconds(records: &[LeapSecondRecord], v4: bool) -> Result<(), usize> {
+    match (0..records.len()).find(|&idx| !leap_second_ok(records, idx, v4)) {
+        Some(idx) => Err(idx),
+        None => Ok(()),
+    }
+}
+
+/// Whether the leap-second record with the given index follows the rules of
+/// [`check_leap_seconds`], given the record before it.
+pub(crate) fn leap_second_ok(records: &[LeapSecondRecord], idx: usize, v4: bool) -> bool {
+    let next = records[idx];
+    let Some(&prev) = idx.checked_sub(1).and_then(|prev| records.get(prev)) else {
+        return next.occurrence >= 0 && (v4 || next.correction.unsigned_abs() == 1);
+    };
+    // Differences too big to represent are as invalid as any other big jump.
+    let (Some(change), Some(gap)) = (
+        next.correction.checked_sub(prev.correction),
+        next.occurrence.checked_sub(prev.occurrence),
+    ) else {
+        return false;
+    };
+    match change.unsigned_abs() {
+        1 => gap >= MIN_LEAP_SECOND_GAP,
+        0 => v4 && idx == records.len() - 1 && gap > 0,
+        _ => false,
+    }
+}
+
+/// A leap second in UTC terms.
+struct Leap {
+    /// The UTC time, not counting leap seconds, of the first second with the new correction
+    /// for a positive leap second, or of the skipped second for a negative one.
+    skipped: i64,
+    prev: i32,
+    correction: i32,
+}
+
+impl Leap {
+    /// The UTC time from which the new correction applies.
+    fn utc_after(&self) -> i64 {
+        if self.correction < self.prev {
+            self.skipped + 1
+        } else {
+            self.skipped
+        }
+    }
+}
+
+/// Accumulates leap-second records from leap seconds given in UTC terms.
+#[derive(Default)]
+struct Builder {
+    records: Vec<LeapSecondRecord>,
+    /// The correction in effect, if known.
+    correction: Option<i32>,
+    /// The UTC time of the last leap second.
+    last: i64,
+}
+
+impl Builder {
+    /// The correction in effect, assuming it is one short of `next` if nothing is known.
+    fn current(&self, next: i32) -> i32 {
+        self.correction.unwrap_or(next - next.signum())
+    }
+
+    /// Add a leap second at the given UTC time, as in [`Leap::skipped`]. Returns `None` unless
+    /// it changes the correction by one and comes after the previous one.
+    fn push(&mut self, skipped: i64, correction: i32) -> Option<()> {
+        let prev = self.current(correction);
+        if correction.checked_sub(prev)?.unsigned_abs() != 1
+            || (self.correction.is_some() && skipped <= self.last)
+        {
+            return None;
+        }
+        let occurrence = skipped.checked_add(i64::from(prev))?;
+        self.records
+            .push(LeapSecondRecord::new(occurrence, correction));
+        self.correction = Some(correction);
+        self.last = skipped;
+        Some(())
+    }
+}
+
+/// Parse a date and a time of day, which may be 24:00:00 or end in a 60th second, into a UTC
+/// time.
+fn parse_date_time(year: &str, month: &str, day: &str, time: &str) -> Option<i64> {
+    let year: i64 = year.parse().ok()?;
+    let month = parse_month(month).ok()?;
+    let day: u8 = day.parse().ok()?;
+    if !(1..=crate::civil::days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    let time = parse_hms(time).filter(|time| (0..=24 * 3600).contains(time))?;
+    checked_days_from_civil(year, month, day)?
+        .checked_mul(SECS_PER_DAY)?
+        .checked_add(i64::from(time))
+}
+
+/// Split a UTC time into a `leapseconds`-style date and an hours, minutes, seconds time of day.
+fn date_time(utc: i64) -> (String, (u8, u8, u8)) {
+    let (year, month, day) = civil_from_days(utc.div_euclid(SECS_PER_DAY));
+    let time = utc.rem_euclid(SECS_PER_DAY);
+    let date = format!("{year}\t{}\t{day}", &MONTHS[usize::from(month) - 1][..3]);
+    (
+        date,
+        (
+            (time / 3600) as u8,
+            (time / 60 % 60) as u8,
+            (time % 60) as u8,
+        ),
+    )
+}
+
+impl TimeZoneInfo {
+    /// TAI − UTC in seconds at the given UTC time, in seconds since the Unix epoch not counting
+    /// leap seconds. Returns `None` if the zone has no leap-second records.
+    ///
+    /// Before the first record, the correction is taken to be one leap second short of it,
+    /// which is zero unless the table has been truncated at the start.
+    pub fn tai_minus_utc_at(&self, utc: i64) -> Option<i32> {
+        let mut correction = self.initial_correction()?;
+        for &LeapSecondRecord {
+            occurrence,
+            correction: total,
+        } in &self.leap_second_records
+        {
+            // The time a leap second occurs at is counted with the leap seconds before it.
+            if occurrence - i64::from(correction) > utc {
+                break;
+            }
+            correction = total;
+        }
+        Some(TAI_MINUS_UTC_1972 + correction)
+    }
+
+    /// Convert a UTC time, in seconds since the Unix epoch not counting leap seconds, to a TAI
+    /// time, in seconds since 1970-01-01 00:00:00 TAI.
+    pub fn utc_to_tai(&self, utc: i64) -> Option<i64> {
+        Some(utc + i64::from(self.tai_minus_utc_at(utc)?))
+    }
+
+    /// Convert a TAI time, in seconds since 1970-01-01 00:00:00 TAI, to a UTC time in seconds
+    /// since the Unix epoch not counting leap seconds.
+    ///
+    /// A positive leap second has no UTC time of its own, and gives the time of the second
+    /// before it, like a POSIX clock which repeats 23:59:59.
+    pub fn tai_to_utc(&self, tai: i64) -> Option<i64> {
+        let mut correction = self.initial_correction()?;
+        for &LeapSecondRecord {
+            occurrence,
+            correction: total,
+        } in &self.leap_second_records
+        {
+            if occurrence + i64::from(TAI_MINUS_UTC_1972) > tai {
+                break;
+            }
+            correction = total;
+        }
+        Some(tai - i64::from(TAI_MINUS_UTC_1972 + correction))
+    }
+
+    /// Whether this is a "right" zone, as `zic -L` compiles: one with leap-second records, whose
+    /// transition times count the leap seconds before them, so are TAI − 10 rather than POSIX
+    /// times.
+    ///
+    /// The other lookups take times in the zone's own scale. For a right zone, POSIX times, such
+    /// as a system clock gives, need converting with [`posix_to_time`](Self::posix_to_time)
+    /// first, or looking up with [`local_time_type_at_posix`](Self::local_time_type_at_posix).
+    pub fn is_right(&self) -> bool {
+        !self.leap_second_records.is_empty()
+    }
+
+    /// Convert a POSIX time to the zone's own time scale, like tzcode's `posix2time`: for a
+    /// right zone, this adds the leap seconds before it, and otherwise it gives the time back.
+    pub fn posix_to_time(&self, posix: i64) -> i64 {
+        self.utc_to_tai(posix)
+            .map_or(posix, |tai| tai - i64::from(TAI_MINUS_UTC_1972))
+    }
+
+    /// Convert a time in the zone's own scale to a POSIX time, like tzcode's `time2posix`: for a
+    /// right zone, this takes away the leap seconds before it, and otherwise it gives the time
+    /// back. A positive leap second gives the POSIX time of the second before it.
+    pub fn time_to_posix(&self, time: i64) -> i64 {
+        self.tai_to_utc(time + i64::from(TAI_MINUS_UTC_1972))
+            .unwrap_or(time)
+    }
+
+    /// The local time type in effect at the given POSIX time, which is the same as
+    /// [`local_time_type_at`](Self::local_time_type_at) gives except in a right zone.
+    ///
+    /// After the last transition, a right zone's footer TZ string is taken to give POSIX times,
+    /// as tzcode takes it, so the rule changes come at the local times it says.
+    pub fn local_time_type_at_posix(&self, posix: i64) -> Option<LocalTimeType<'_>> {
+        let time = self.posix_to_time(posix);
+        if let Some(footer) = &self.footer {
+            if self
+                .transition_times
+                .last()
+                .is_none_or(|&last| time >= last)
+            {
+                return Some(footer.local_time_type_at(posix));
+            }
+        }
+        self.type_idx_at(time).map(|idx| self.local_time_type(idx))
+    }
+
+    /// The "posix" equivalent of a right zone, as `zic` compiles without `-L`: the transition
+    /// times as POSIX times, and no leap-second records. Any other zone is given back as it is.
+    pub fn to_posix(&self) -> Self {
+        let mut posix = self.clone();
+        posix.set_leap_seconds(&LeapSecondTable::default());
+        posix
+    }
+
+    /// The zone's leap seconds, leaving out the expiration record of a version 4 file.
+    pub fn iter_leap_seconds(&self) -> core::slice::Iter<'_, LeapSecondRecord> {
+        let records = &self.leap_second_records[..];
+        match self.leap_second_expiration() {
+            Some(_) => records[..records.len() - 1].iter(),
+            None => records.iter(),
+        }
+    }
+
+    /// The zone's leap-second records as a table, with the expiration of a version 4 file.
+    pub fn leap_second_table(&self) -> LeapSecondTable {
+        let expires = self.leap_second_expiration();
+        let records: Vec<_> = self.iter_leap_seconds().copied().collect();
+        LeapSecondTable {
+            expires: expires
+                .zip(records.last())
+                .map(|(t, last)| t - i64::from(last.correction)),
+            records,
+        }
+    }
+
+    /// Replace the zone's leap seconds, making it a "right" zone if it had none.
+    ///
+    /// Transition times, which count the leap seconds before them, are moved to account for
+    /// the new table. A table with an expiration makes the zone version 4.
+    pub fn set_leap_seconds(&mut self, table: &LeapSecondTable) {
+        let utc: Vec<i64> = self
+            .transition_times
+            .iter()
+            .map(|&t| {
+                self.tai_to_utc(t + i64::from(TAI_MINUS_UTC_1972))
+                    .unwrap_or(t)
+            })
+            .collect();
+        self.leap_second_records = table.to_records();
+        self.transition_times = utc
+            .into_iter()
+            .map(|utc| {
+                self.utc_to_tai(utc)
+                    .map_or(utc, |tai| tai - i64::from(TAI_MINUS_UTC_1972))
+            })
+            .collect();
+        if table.expires.is_some() && !table.records.is_empty() {
+            self.version = self.version.max(4);
+        }
+    }
+
+    /// The correction in effect before the first leap-second record.
+    fn initial_correction(&self) -> Option<i32> {
+        let first = self.leap_second_records.first()?.correction;
+        Some(first - first.signum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{leap_records, sample};
+
+    const LEAP_SECONDS_LIST: &str = "\
+#$\t3960835200
+#@\t3991593600
+2272060800\t10\t# 1 Jan 1972
+2287785600\t11\t# 1 Jul 1972
+2303683200\t12\t# 1 Jan 1973
+#h\t49db2447 571e5e1b 2f002a53 9c8da8e4 39b8e49e
+";
+
+    const LEAPSECONDS: &str = "\
+# Leap\tYEAR\tMON\tDAY\t23:59:60\t+\tS
+Leap\t1972\tJun\t30\t23:59:60\t+\tS
+Leap\t1972\tDec\t31\t23:59:60\t+\tS
+#Expires 2026\tJun\t28\t00:00:00
+#expires 1782604800 (2026-06-28 00:00:00 UTC)
+";
+
+    #[test]
+    fn test_leap_second_files() {
+        let table = LeapSecondTable {
+            records: leap_records(&[(78796800, 1), (94694401, 2)]),
+            expires: Some(1782604800),
+        };
+        let list = LeapSecondTable::parse_leap_seconds_list(LEAP_SECONDS_LIST).unwrap();
+        assert_eq!(table, list);
+        assert_eq!(
+            table,
+            LeapSecondTable::parse_leapseconds(LEAPSECONDS).unwrap()
+        );
+        assert_eq!(
+            "#@\t3991593600\n\
+             2272060800\t10\t# 1 Jan 1972\n\
+             2287785600\t11\t# 1 Jul 1972\n\
+             2303683200\t12\t# 1 Jan 1973\n",
+            table.to_leap_seconds_list()
+        );
+        assert_eq!(
+            "Leap\t1972\tJun\t30\t23:59:60\t+\tS\n\
+             Leap\t1972\tDec\t31\t23:59:60\t+\tS\n\
+             Expires\t2026\tJun\t28\t00:00:00\n",
+            table.to_leapseconds()
+        );
+        assert_eq!(
+            leap_records(&[(78796800, 1), (94694401, 2), (1782604802, 2)]),
+            table.to_records()
+        );
+
+        // A negative leap second, and a table truncated at the start.
+        let negative = "Leap\t1972\tJun\t30\t23:59:60\t+\tS\n\
+                        Leap\t1973\tJun\t30\t23:59:59\t-\tS\n";
+        let table = LeapSecondTable::parse_leapseconds(negative).unwrap();
+        assert_eq!(
+            leap_records(&[(78796800, 1), (110332800, 0)]),
+            table.records
+        );
+        assert_eq!(negative, table.to_leapseconds());
+        let round_trip =
+            LeapSecondTable::parse_leap_seconds_list(&table.to_leap_seconds_list()).unwrap();
+        assert_eq!(table, round_trip);
+        let truncated = LeapSecondTable::parse_leap_seconds_list("3692217600\t37\n").unwrap();
+        assert_eq!(leap_records(&[(1483228800 + 26, 27)]), truncated.records);
+        assert_eq!(
+            "3692217600\t37\t# 1 Jan 2017\n",
+            truncated.to_leap_seconds_list()
+        );
+
+        for (bad, line) in [
+            ("2272060800\t10\n2287785600\t12\n", 2),
+            ("2272060800\t10\n2287785600\n", 2),
+            ("2287785600\t11\n2272060800\t12\n", 2),
+            ("#@\tsoon\n", 1),
+            ("#@\t-9223372036854775808\n", 1),
+            ("-9223372036854775808\t11\n", 1),
+            ("2272060800\t-2147483648\n", 1),
+        ] {
+            let err = LeapSecondTable::parse_leap_seconds_list(bad).unwrap_err();
+            assert_eq!(LeapSecondsError { line }, err, "{bad}");
+        }
+        for (bad, line) in [
+            ("Leap\t1972\tJun\t31\t23:59:60\t+\tS\n", 1),
+            ("Leap\t1972\tJun\t30\t23:59:60\t+\tR\n", 1),
+            ("\nLeap\t1972\tJun\t30\t23:59:60\t*\tS\n", 2),
+            ("Expires\t2026\tJun\n", 1),
+            ("Leap\t300000000000000\tJun\t30\t23:59:60\t+\tS\n", 1),
+        ] {
+            let err = LeapSecondTable::parse_leapseconds(bad).unwrap_err();
+            assert_eq!(LeapSecondsError { line }, err, "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_leap_smear() {
+        const SEC: i64 = NANOS_PER_SEC;
+        let (jul_1972, jul_1973) = (78796800, 110332800);
+        let smear = LeapSmear::new(LeapSecondTable {
+            records: leap_records(&[(jul_1972, 1), (jul_1973, 0)]),
+            expires: None,
+        });
+        let noon_before = (jul_1972 - 43200) * SEC;
+        let noon_after = (jul_1972 + 43200) * SEC;
+        for utc in [
+            0,
+            noon_before,
+            noon_after,
+            (jul_1973 + 43200) * SEC,
+            1 << 62,
+        ] {
+            assert_eq!(utc, smear.utc_to_smeared(utc));
+            assert_eq!(utc, smear.smeared_to_utc(utc));
+        }
+        assert_eq!(noon_before + 10 * SEC, smear.smeared_to_tai(noon_before));
+        assert_eq!(noon_after + 11 * SEC, smear.smeared_to_tai(noon_after));
+
+        // Half the leap second has been smeared by midnight.
+        let midnight = jul_1972 * SEC;
+        assert_eq!(
+            midnight + 10 * SEC + SEC / 2,
+            smear.smeared_to_tai(midnight)
+        );
+        assert_eq!(
+            midnight,
+            smear.tai_to_smeared(midnight + 10 * SEC + SEC / 2)
+        );
+        // The leap second itself is just short of midnight.
+        let leap = smear.tai_to_smeared(midnight + 10 * SEC + SEC / 4);
+        assert!(midnight - SEC < leap && leap < midnight, "{leap}");
+        let utc = smear.smeared_to_utc(leap);
+        assert!((utc - (midnight - SEC + SEC / 4)).abs() <= 1, "{utc}");
+
+        // A negative leap second runs TAI slow.
+        let midnight = jul_1973 * SEC;
+        assert_eq!(
+            midnight + 11 * SEC - SEC / 2,
+            smear.smeared_to_tai(midnight)
+        );
+        let smeared = smear.utc_to_smeared(midnight);
+        assert!(
+            (smeared - (midnight - SEC / 2)).abs() < SEC / 1000,
+            "{smeared}"
+        );
+
+        for t in (noon_before..noon_after).step_by(997 * SEC as usize + 1) {
+            let round_trip = smear.tai_to_smeared(smear.smeared_to_tai(t));
+            assert!((round_trip - t).abs() <= 1, "{t}");
+            let smeared = smear.utc_to_smeared(t);
+            assert!((smeared - t).abs() <= SEC / 2, "{t}");
+        }
+    }
+
+    #[test]
+    fn test_check_leap_seconds() {
+        let check = |records: &[(i64, i32)], v4| check_leap_seconds(&leap_records(records), v4);
+        assert_eq!(Ok(()), check(&[], false));
+        assert_eq!(Ok(()), check(&[(78796800, 1), (94694401, 2)], false));
+        assert_eq!(Ok(()), check(&[(78796800, -1), (94694399, -2)], false));
+        assert_eq!(Err(0), check(&[(-1, 1)], true));
+        assert_eq!(Err(0), check(&[(78796800, 2)], false));
+        assert_eq!(Err(1), check(&[(78796800, 1), (78796801, 2)], false));
+        assert_eq!(Err(1), check(&[(78796800, 1), (94694401, 3)], false));
+
+        // Version 4 allows a truncated start and a final expiration record.
+        assert_eq!(Ok(()), check(&[(1483228826, 27), (1782604827, 27)], true));
+        assert_eq!(Err(1), check(&[(78796800, 1), (1782604801, 1)], false));
+        assert_eq!(Err(0), check(&[(1483228826, 27)], false));
+        let repeated = [(78796800, 1), (94694401, 1), (126230402, 2)];
+        assert_eq!(Err(1), check(&repeated, true));
+
+        let mut tz = sample();
+        tz.version = 4;
+        tz.leap_second_records = leap_records(&[(78796800, 1), (94694401, 2), (1782604802, 2)]);
+        assert_eq!(2, tz.iter_leap_seconds().len());
+        let parsed = TimeZoneInfo::parse(&tz.to_bytes()[..]).unwrap();
+        assert_eq!(tz.leap_second_records, parsed.leap_second_records);
+
+        tz.version = 3;
+        assert_eq!(3, tz.iter_leap_seconds().len());
+        let err = TimeZoneInfo::parse(&tz.to_bytes()[..]).unwrap_err();
+        assert!(
+            matches!(err, crate::ParseError::InvalidLeapSecond { .. }),
+            "{err}"
+        );
+
+        // Records whose differences overflow are invalid, not a panic, in either parser.
+        assert_eq!(Err(1), check(&[(100, 1), (i64::MIN, 2)], false));
+        assert_eq!(Err(1), check(&[(100, i32::MIN), (100_000_000, 5)], true));
+        assert_eq!(Err(0), check(&[(100, i32::MIN)], false));
+        // The writer won't produce such records, so patch them into a file with valid ones.
+        let patch = |data: &mut Vec<u8>, from: &[u8], to: &[u8]| {
+            for idx in 0..data.len() - from.len() {
+                if data[idx..].starts_with(from) {
+                    data[idx..idx + to.len()].copy_from_slice(to);
+                }
+            }
+        };
+        tz.version = 2;
+        tz.leap_second_records = leap_records(&[(100, 1), (0x1234_5678_9abc, 2)]);
+        let mut v2 = tz.to_bytes();
+        patch(
+            &mut v2,
+            &0x1234_5678_9abc_i64.to_be_bytes(),
+            &i64::MIN.to_be_bytes(),
+        );
+        tz.version = 4;
+        tz.leap_second_records = leap_records(&[(100, 0x1234_5678), (100_000_000, 5)]);
+        let mut v4 = tz.to_bytes();
+        patch(
+            &mut v4,
+            &0x1234_5678_i32.to_be_bytes(),
+            &i32::MIN.to_be_bytes(),
+        );
+        let strict = crate::ParseOptions {
+            strict: true,
+            ..crate::ParseOptions::default()
+        };
+        for data in [v2, v4] {
+            let err = TimeZoneInfo::parse_slice(&data, &strict).unwrap_err();
+            assert!(
+                matches!(err, crate::ParseError::InvalidLeapSecond { .. }),
+                "{err}"
+            );
+            let (_, warnings) = TimeZoneInfo::parse_slice_lossy(&data);
+            assert!(
+                warnings
+                    .iter()
+                    .any(|w| matches!(w, crate::ParseError::InvalidLeapSecond { .. })),
+                "{warnings:?}"
+            );
+            // The default parser rejects them too, unless it falls back to the version 1 block.
+            let lenient = TimeZoneInfo::parse(&data[..]);
+            assert!(lenient.map_or(true, |tz| tz.leap_second_records.len() < 2));
+        }
+    }
+
+    #[test]
+    fn test_set_leap_seconds() {
+        let mut tz = sample();
+        tz.transition_times = vec![0, 78796800, 94694400];
+        let table = LeapSecondTable {
+            records: leap_records(&[(78796800, 1), (94694401, 2)]),
+            expires: Some(1782604800),
+        };
+        tz.set_leap_seconds(&table);
+        assert_eq!(vec![0, 78796801, 94694402], tz.transition_times);
+        assert_eq!(4, tz.version);
+        assert_eq!(table, tz.leap_second_table());
+
+        tz.set_leap_seconds(&LeapSecondTable::default());
+        assert_eq!(vec![0, 78796800, 94694400], tz.transition_times);
+        assert!(tz.leap_second_records.is_empty());
+    }
+
+    #[test]
+    fn test_tai_conversion() {
+        let mut tz = sample();
+        assert_eq!(None, tz.tai_minus_utc_at(0));
+
+        // Positive leap seconds at the ends of 1972-06-30 and 1972-12-31, then a negative one
+        // at the end of 1973-06-30.
+        let (jul_1972, jan_1973, jul_1973) = (78796800, 94694400, 110332800);
+        tz.leap_second_records =
+            leap_records(&[(jul_1972, 1), (jan_1973 + 1, 2), (jul_1973 + 1, 1)]);
+        assert_eq!(Some(10), tz.tai_minus_utc_at(0));
+        assert_eq!(Some(10), tz.tai_minus_utc_at(jul_1972 - 1));
+        assert_eq!(Some(11), tz.tai_minus_utc_at(jul_1972));
+        assert_eq!(Some(11), tz.tai_minus_utc_at(jan_1973 - 1));
+        assert_eq!(Some(12), tz.tai_minus_utc_at(jan_1973));
+        // 23:59:59 is skipped on 1973-06-30.
+        assert_eq!(Some(12), tz.tai_minus_utc_at(jul_1973 - 2));
+        assert_eq!(Some(11), tz.tai_minus_utc_at(jul_1973 - 1));
+
+        for utc in [
+            0,
+            jul_1972 - 1,
+            jul_1972,
+            jan_1973,
+            jul_1973 - 2,
+            jul_1973,
+            1 << 31,
+        ] {
+            let tai = tz.utc_to_tai(utc).unwrap();
+            assert_eq!(Some(utc), tz.tai_to_utc(tai), "{utc}");
+        }
+        // The leap second itself reads as the second before.
+        let leap = tz.utc_to_tai(jul_1972).unwrap() - 1;
+        assert_eq!(Some(jul_1972 - 1), tz.tai_to_utc(leap));
+        assert_eq!(Some(jul_1972 - 1), tz.tai_to_utc(leap - 1));
+        // And the TAI second after 23:59:58 is midnight when a second is skipped.
+        let skip = tz.utc_to_tai(jul_1973 - 2).unwrap();
+        assert_eq!(Some(jul_1973), tz.tai_to_utc(skip + 1));
+
+        // A table truncated at the start.
+        tz.leap_second_records = leap_records(&[(1483228800 + 26, 27)]);
+        assert_eq!(Some(36), tz.tai_minus_utc_at(1483228799));
+        assert_eq!(Some(37), tz.tai_minus_utc_at(1483228800));
+    }
+
+    #[test]
+    fn test_right_zone() {
+        let posix = sample();
+        assert!(!posix.is_right());
+        assert_eq!(150, posix.posix_to_time(150));
+        assert_eq!(150, posix.time_to_posix(150));
+        assert_eq!(
+            posix.local_time_type_at(250),
+            posix.local_time_type_at_posix(250)
+        );
+        assert_eq!(posix, posix.to_posix());
+
+        // Transitions a second after each of two leap seconds.
+        let mut right = sample();
+        right.transition_times = vec![0, 78796801, 94694402];
+        right.set_leap_seconds(&LeapSecondTable {
+            records: leap_records(&[(78796800, 1), (94694401, 2)]),
+            expires: None,
+        });
+        assert!(right.is_right());
+        assert_eq!(vec![0, 78796802, 94694404], right.transition_times);
+        assert_eq!(94694404, right.posix_to_time(94694402));
+        assert_eq!(94694402, right.time_to_posix(94694404));
+        // The leap second reads as the second before it.
+        assert_eq!(78796799, right.time_to_posix(78796800));
+        for (posix, desig) in [
+            (-1, "PST"),
+            (0, "PDT"),
+            (78796800, "PDT"),
+            (78796801, "PST"),
+            (94694401, "PST"),
+            (94694402, "PDT"),
+            (1 << 31, "PDT"),
+        ] {
+            assert_eq!(posix, right.time_to_posix(right.posix_to_time(posix)));
+            assert_eq!(
+                Some(desig),
+                right.local_time_type_at_posix(posix).map(|t| t.desig),
+                "{posix}"
+            );
+        }
+        // Looked up directly, a POSIX time lands before the transition.
+        assert_eq!(Some("PST"), right.designation_at(94694402));
+
+        let back = right.to_posix();
+        assert!(!back.is_right());
+        assert_eq!(vec![0, 78796801, 94694402], back.transition_times);
+
+        // The footer gives POSIX times: 2024-03-10 10:00 UT, when DST starts in the US.
+        right.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        assert_eq!(
+            Some("PDT"),
+            right.local_time_type_at_posix(1710064800).map(|t| t.desig)
+        );
+        assert_eq!(
+            Some("PST"),
+            right.local_time_type_at_posix(1710064799).map(|t| t.desig)
+        );
+    }
+}
@@ -1,272 +1,645 @@
 //! Time Zone Information Format (TZif), RFC 8536
+//!
+//! With the default `std` feature disabled, this crate is `#![no_std]` and only requires `alloc`;
+//! use [`TimeZoneInfo::parse_slice`] to parse in-memory data in that configuration. Nothing
+//! outside the `std`-only features assumes a filesystem, so the crate builds for
+//! `wasm32-unknown-unknown`, where the `bundled-compressed` feature embeds the zones to look up.
 
-use std::io::{self, Read};
-use std::time::{SystemTime, UNIX_EPOCH};
+#![cfg_attr(not(feature = "std"), no_std)]
+// Unsafe code is confined to the modules of the features which need it.
+#![cfg_attr(
+    not(any(feature = "ffi", feature = "libc", feature = "mmap")),
+    forbid(unsafe_code)
+)]
+#![cfg_attr(
+    any(feature = "ffi", feature = "libc", feature = "mmap"),
+    deny(unsafe_code)
+)]
 
-#[repr(transparent)]
-#[derive(Copy, Clone)]
-struct Bu32(u32);
+extern crate alloc;
 
-impl From<Bu32> for u32 {
-    fn from(big: Bu32) -> u32 {
-        u32::from_be(big.0)
-    }
-}
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::ops::Range;
 
-impl From<u32> for Bu32 {
-    fn from(native: u32) -> Bu32 {
-        Bu32(u32::to_be(native))
-    }
-}
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
 
-impl std::fmt::Debug for Bu32 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", u32::from(*self))
-    }
-}
+#[cfg(feature = "std")]
+mod android;
+#[cfg(feature = "archive")]
+mod archive;
+#[cfg(any(feature = "bundled", feature = "bundled-compressed"))]
+mod bundled;
+mod calendar;
+#[cfg(feature = "chrono")]
+mod chrono;
+mod civil;
+#[cfg(feature = "cldr")]
+mod cldr;
+mod codegen;
+mod compact;
+mod compile;
+mod coverage;
+mod crosscheck;
+#[cfg(feature = "std")]
+mod db;
+mod diff;
+mod display;
+mod embedded;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fold;
+mod generate;
+#[cfg(feature = "icu_time")]
+mod icu_time;
+mod infer;
+#[cfg(feature = "jiff")]
+mod jiff;
+#[cfg(feature = "json")]
+mod json;
+mod leap;
+#[cfg(all(feature = "libc", unix))]
+mod libc;
+mod local;
+mod lookup;
+mod lossy;
+#[cfg(feature = "mmap")]
+mod memmap2;
+mod normalize;
+#[cfg(feature = "watch")]
+mod notify;
+mod offsets;
+mod parse;
+mod posix;
+#[cfg(feature = "postcard")]
+mod postcard;
+#[cfg(feature = "proptest")]
+mod proptest;
+#[cfg(feature = "rayon")]
+mod rayon;
+#[cfg(feature = "std")]
+mod release;
+mod report;
+mod schedule;
+#[cfg(feature = "std")]
+mod stream;
+mod summary;
+mod tab;
+#[cfg(feature = "time")]
+mod time;
+#[cfg(feature = "tokio")]
+mod tokio;
+mod truncate;
+mod validate;
+#[cfg(feature = "std")]
+mod verify;
+mod version;
+#[cfg(feature = "windows-zones")]
+mod windows;
+mod write;
+mod zone;
 
-#[repr(packed)]
-#[derive(Debug)]
-struct Header {
-    /// Must be the byte string b"TZif"
-    magic: [u8; 4],
+#[cfg(feature = "archive")]
+pub use archive::{ArchivedZone, ZoneArchive};
+#[cfg(feature = "bundled-compressed")]
+pub use bundled::{bundled_zone, bundled_zone_names};
+pub use calendar::{CalendarDuration, WallClockPolicy};
+#[cfg(feature = "chrono")]
+pub use chrono::{TzifOffset, TzifZone};
+#[cfg(feature = "cldr")]
+pub use cldr::{DisplayNames, ZoneDisplayNames};
+#[cfg(feature = "std")]
+pub use codegen::embed;
+pub use codegen::{to_rust_source, StaticFooter, StaticSpan, StaticZone};
+pub use compact::CompactZone;
+pub use compile::ZoneSource;
+pub use coverage::Coverage;
+pub use crosscheck::BlockDiscrepancy;
+#[cfg(feature = "std")]
+pub use db::{ZoneCache, ZoneDatabase};
+pub use diff::{DiffType, ZoneDifference};
+#[doc(hidden)]
+pub use embedded::private as __private;
+pub use embedded::EmbeddedZone;
+#[cfg(feature = "std")]
+pub use error::ZoneError;
+pub use error::{
+    CompileError, ConsistencyError, LeapSecondsError, LocalTimeError, ParseError, PosixTzError,
+    ZoneTabError,
+};
+pub use fold::{Gap, Overlap};
+pub use generate::{generate, EdgeCase};
+pub use leap::{LeapSecondTable, LeapSmear};
+#[cfg(all(feature = "libc", unix))]
+pub use libc::{compare_with_libc, LibcMismatch};
+pub use local::{CivilDateTime, Disambiguation, LocalTransition};
+pub use lookup::{Lookup, LookupSource};
+#[cfg(feature = "watch")]
+pub use notify::ZoneWatcher;
+pub use offsets::OffsetTable;
+#[cfg(feature = "std")]
+pub use parse::peek_header;
+pub use parse::{peek_header_slice, ParseOptions};
+pub use posix::{PosixDst, PosixRule, PosixTz, RuleDate};
+#[cfg(feature = "rayon")]
+pub use rayon::LoadStats;
+#[cfg(feature = "std")]
+pub use release::{diff_trees, NameChange, TreeEntry};
+pub use report::Report;
+pub use schedule::DstSchedule;
+#[cfg(feature = "std")]
+pub use stream::{StreamTransition, TzifStream};
+pub use summary::ZoneSummary;
+pub use tab::ZoneTabEntry;
+pub use validate::{Finding, Severity};
+#[cfg(feature = "std")]
+pub use verify::{verify_tree, FileReport, FileStatus};
+pub use version::VersionLoss;
+#[cfg(feature = "windows-zones")]
+pub use windows::{iana_to_windows, windows_to_iana};
+pub use write::{Bloat, WriteOptions};
+pub use zone::Zone;
 
-    /// Version. Either 0, b'2' or b'3'.
-    ver: u8,
+/// The fixed-size header which begins each TZif data block.
+///
+/// All multi-byte fields are stored big-endian in the file; here they are in native byte order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Header {
+    /// Must be the byte string b"TZif"
+    pub magic: [u8; 4],
 
-    _reserved: [u8; 15],
+    /// Version. Either 0, b'2', b'3' or b'4'.
+    pub ver: u8,
 
     /// Number of UT/local indicators contained in the data block.
     ///
     /// Must be either 0 or equal to [`typecnt`](Header::typecnt).
-    isutcnt: Bu32,
+    pub isutcnt: u32,
 
     /// Number of standard/wall indicators contained in the data block.
     ///
     /// Must be either 0 or equal to [`typecnt`](Header::typecnt).
-    isstdcnt: Bu32,
+    pub isstdcnt: u32,
 
     /// Number of leap-second records contained in the data block.
-    leapcnt: Bu32,
+    pub leapcnt: u32,
 
     /// Number of transition times contained in the data block.
-    timecnt: Bu32,
+    pub timecnt: u32,
 
     /// Number of local time type records contained in the data block.
     ///
     /// Must not be zero.
-    typecnt: Bu32,
+    pub typecnt: u32,
 
     /// Total number of bytes used by the set of time zone designations contained in the data
     /// block, including the triling NUL byte at the end of the last time zone designation.
     ///
     /// Must not be zero.
-    charcnt: Bu32,
+    pub charcnt: u32,
 }
 
 impl Header {
-    pub fn from_array(bytes: [u8; 44]) -> Self {
-        unsafe { std::mem::transmute(bytes) }
+    /// Size of the header in bytes.
+    pub const SIZE: usize = 44;
+
+    /// Decode a header from its on-disk representation. No validation is done here.
+    pub fn from_array(bytes: [u8; Self::SIZE]) -> Self {
+        let count = |offset: usize| {
+            u32::from_be_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+        Self {
+            magic: [bytes[0], bytes[1], bytes[2], bytes[3]],
+            ver: bytes[4],
+            isutcnt: count(20),
+            isstdcnt: count(24),
+            leapcnt: count(28),
+            timecnt: count(32),
+            typecnt: count(36),
+            charcnt: count(40),
+        }
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeZoneInfo {
     pub version: u8,
     pub transition_times: Vec<i64>,
     pub transition_types: Vec<u8>,
     pub local_time_types: Vec<LocalTimeTypeRecord>,
     pub time_zone_designations: Vec<u8>,
-    pub leap_second_records: Vec<(i64, i32)>,
+    pub leap_second_records: Vec<LeapSecondRecord>,
     pub is_std: Vec<IsStd>,
     pub is_ut: Vec<IsUT>,
+
+    /// The TZ string from the footer of a version 2+ file, which governs local time after the
+    /// last transition.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub footer: Option<PosixTz>,
+
+    /// The footer's TZ string exactly as it was read, where that isn't how
+    /// [`footer`](Self::footer) is written: where it's spelled differently, or where it
+    /// couldn't be parsed, outside strict mode. It's written in place of `footer` while it
+    /// still parses to the same thing.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub raw_footer: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IsStd {
     Standard,
     Wall,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IsUT {
     UT,
     Local,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LocalTimeTypeRecord {
     pub ut_off_secs: i32,
     pub is_dst: bool,
     pub desig_idx: u8,
 }
 
-fn bogus<T, E>(inner: E) -> io::Result<T>
-where
-    E: Into<Box<dyn std::error::Error + Send + Sync>>,
-{
-    Err(io::Error::new(io::ErrorKind::InvalidData, inner))
+/// A leap second, or for version 4 the table's expiration, in a zone's leap-second records.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeapSecondRecord {
+    /// The time the leap second occurs, counting the leap seconds before it.
+    pub occurrence: i64,
+
+    /// The total correction from then on, in seconds.
+    pub correction: i32,
+}
+
+impl LeapSecondRecord {
+    pub fn new(occurrence: i64, correction: i32) -> Self {
+        Self {
+            occurrence,
+            correction,
+        }
+    }
 }
 
 impl TimeZoneInfo {
-    pub fn parse(mut reader: impl Read) -> io::Result<Self> {
-        let v1_result = Self::parse_internal(&mut reader, true)?;
-        if v1_result.version == 1 {
-            return Ok(v1_result);
+    pub fn iter_transitions(&self) -> TransitionIterator<'_> {
+        TransitionIterator {
+            tzif: self,
+            idx: 0,
+            end: self.transition_times.len(),
         }
-        Self::parse_internal(&mut reader, false).or(Ok(v1_result))
     }
 
-    fn parse_internal(mut reader: impl Read, v1: bool) -> io::Result<Self> {
-        let mut hbuf = [0u8; 44];
-        reader.read_exact(&mut hbuf[..])?;
-        let hdr = Header::from_array(hbuf);
+    /// The transitions with UT times in the given range, found by binary search rather than by
+    /// iterating from the first transition. Transitions the footer gives after the last one
+    /// aren't included.
+    pub fn iter_transitions_in(&self, range: Range<i64>) -> TransitionIterator<'_> {
+        let idx = self.partition_transitions(|ut| ut < range.start);
+        let end = self.partition_transitions(|ut| ut < range.end);
+        TransitionIterator {
+            tzif: self,
+            idx,
+            end: end.max(idx),
+        }
+    }
 
-        if &hdr.magic != b"TZif" {
-            return bogus("unrecognized magic in header");
+    /// The intervals between transitions, each with the local time type in effect during it.
+    ///
+    /// The first interval runs from the beginning of time to the first transition, with the
+    /// first local time type, and the last from the last transition on. A zone with local time
+    /// types but no transitions has one interval covering all time. After the last transition,
+    /// a zone with a TZ string footer follows the footer rather than the last interval's type;
+    /// see [`local_time_type_at`](Self::local_time_type_at).
+    pub fn iter_intervals(&self) -> IntervalIterator<'_> {
+        let len = if self.local_time_types.is_empty() {
+            0
+        } else {
+            self.transition_times.len() + 1
+        };
+        IntervalIterator {
+            tzif: self,
+            idx: 0,
+            end: len,
         }
+    }
+
+    /// Every local time type record, resolved, in order of index, whether or not a transition
+    /// uses it. The first is the type in effect before the first transition.
+    pub fn iter_local_time_types(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = LocalTimeType<'_>> + ExactSizeIterator + '_ {
+        (0..self.local_time_types.len()).map(|idx| self.local_time_type(idx))
+    }
 
-        if hdr.isstdcnt.0 != 0 && hdr.isstdcnt.0 != hdr.typecnt.0 {
-            return bogus("isstdcnt not zero or equal to typecnt");
+    /// The footer's TZ string as it was read, or else as [`footer`](Self::footer) is written,
+    /// for handing to another POSIX TZ implementation.
+    pub fn footer_str(&self) -> Option<Cow<'_, str>> {
+        match (&self.raw_footer, &self.footer) {
+            (Some(raw), _) => Some(Cow::Borrowed(raw)),
+            (None, Some(footer)) => Some(Cow::Owned(footer.to_string())),
+            (None, None) => None,
         }
-        if hdr.isutcnt.0 != 0 && hdr.isutcnt.0 != hdr.typecnt.0 {
-            return bogus("isutcnt not zero or equal to typecnt");
+    }
+
+    /// The version byte of the header as written to or read from a file: 0 for version 1, or
+    /// the version's ASCII digit.
+    pub fn version_byte(&self) -> u8 {
+        match self.version {
+            1 => 0,
+            v => b'0' + v,
         }
+    }
 
-        let mut result = Self {
-            version: match hdr.ver {
-                0 => 1,
-                b'2' => 2,
-                b'3' => 3,
-                _ => return bogus(format!("unsupported version {:#x}", hdr.ver)),
-            },
-            ..Self::default()
-        };
+    /// The zone's last transition, if it has any.
+    pub fn last_transition(&self) -> Option<TimeTransition<'_>> {
+        self.iter_transitions().next_back()
+    }
 
-        for _ in 0..hdr.timecnt.into() {
-            let t = read_time(v1, &mut reader)?;
-            result.transition_times.push(t);
+    /// Find the first transition that takes effect strictly after the given UT timestamp.
+    pub fn next_transition(&self, after: i64) -> Option<TimeTransition<'_>> {
+        let idx = self.partition_transitions(|ut| ut <= after);
+        if idx < self.transition_times.len() {
+            Some(self.transition(idx))
+        } else {
+            None
         }
+    }
 
-        result
-            .transition_types
-            .resize(u32::from(hdr.timecnt) as usize, 0);
-        reader.read_exact(&mut result.transition_types)?;
+    /// Find the last transition that took effect strictly before the given UT timestamp.
+    pub fn prev_transition(&self, before: i64) -> Option<TimeTransition<'_>> {
+        let idx = self.partition_transitions(|ut| ut < before);
+        if idx > 0 {
+            Some(self.transition(idx - 1))
+        } else {
+            None
+        }
+    }
 
-        for _ in 0..hdr.typecnt.into() {
-            let mut buf = [0u8; 4];
-            reader.read_exact(&mut buf)?;
-            let ut_off_secs = i32::from_be_bytes(buf);
+    /// When the leap-second table expires, for a version 4 file which records it.
+    ///
+    /// Version 4 marks the expiration with a final leap-second record whose correction is the
+    /// same as the one before it: not a leap second, but the time the table is good until.
+    /// Version 4 also allows the first record's correction to be other than +1 or -1, for a
+    /// table which has been truncated at the start.
+    pub fn leap_second_expiration(&self) -> Option<i64> {
+        if self.version < 4 {
+            return None;
+        }
+        match self.leap_second_records[..] {
+            [.., prev, last] if prev.correction == last.correction => Some(last.occurrence),
+            _ => None,
+        }
+    }
 
-            let mut isdst_idx = [0u8; 2];
-            reader.read_exact(&mut isdst_idx)?;
-            if !(0..=1).contains(&isdst_idx[0]) {
-                return bogus("is_dst not zero or one");
+    /// Binary search over the transitions, which are sorted by time. Returns the index of the
+    /// first transition for which `pred` (given the transition's UT time) returns false.
+    pub(crate) fn partition_transitions(&self, pred: impl Fn(i64) -> bool) -> usize {
+        let mut lo = 0;
+        let mut hi = self.transition_times.len();
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if pred(self.transition_times[mid]) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
             }
+        }
+        lo
+    }
 
-            let record = LocalTimeTypeRecord {
-                ut_off_secs,
-                is_dst: isdst_idx[0] == 1,
-                desig_idx: isdst_idx[1],
-            };
-            result.local_time_types.push(record);
-        }
-
-        result
-            .time_zone_designations
-            .resize(u32::from(hdr.charcnt) as usize, 0);
-        reader.read_exact(&mut result.time_zone_designations)?;
-
-        for _ in 0..hdr.leapcnt.into() {
-            let t = read_time(v1, &mut reader)?;
-            let mut buf = [0u8; 4];
-            reader.read_exact(&mut buf)?;
-            let off = i32::from_be_bytes(buf);
-            result.leap_second_records.push((t, off));
-        }
-
-        let mut buf = vec![0; u32::from(hdr.isstdcnt) as usize];
-        reader.read_exact(&mut buf)?;
-        for b in buf {
-            result.is_std.push(match b {
-                0 => IsStd::Wall,
-                1 => IsStd::Standard,
-                _ => return bogus("std/wall not zero or one"),
-            });
-        }
-
-        buf = vec![0; u32::from(hdr.isutcnt) as usize];
-        reader.read_exact(&mut buf)?;
-        for b in buf {
-            result.is_ut.push(match b {
-                0 => IsUT::Local,
-                1 => IsUT::UT,
-                _ => return bogus("ut/local not zero or one"),
-            });
-        }
-
-        for i in 0..result.is_std.len().max(result.is_ut.len()) {
-            let is_std = result.is_std.get(i).unwrap_or(&IsStd::Wall);
-            let is_ut = result.is_ut.get(i).unwrap_or(&IsUT::Local);
-            if (is_std, is_ut) == (&IsStd::Wall, &IsUT::UT) {
-                return bogus("transition times can't be universal + wall");
+    /// The local time type in effect at the given UT timestamp, if the zone has any.
+    ///
+    /// On or after the last transition, this comes from the footer's TZ string if there is one.
+    pub fn local_time_type_at(&self, ut: i64) -> Option<LocalTimeType<'_>> {
+        if let Some(footer) = &self.footer {
+            let after_last = self.transition_times.last().is_none_or(|&last| ut >= last);
+            if after_last {
+                return Some(footer.local_time_type_at(ut));
             }
         }
+        self.type_idx_at(ut).map(|idx| self.local_time_type(idx))
+    }
+
+    /// Whether DST is in effect at the given UT timestamp. Zones with no local time types
+    /// never observe it.
+    pub fn is_dst_at(&self, ut: i64) -> bool {
+        self.local_time_type_at(ut)
+            .is_some_and(|local| local.is_dst)
+    }
+
+    /// The time zone designation (abbreviation) in effect at the given UT timestamp.
+    pub fn designation_at(&self, ut: i64) -> Option<&str> {
+        self.local_time_type_at(ut).map(|local| local.desig)
+    }
 
-        for typ_idx in &result.transition_types {
-            if *typ_idx as usize > result.local_time_types.len() {
-                return bogus("one or more transition types out of range");
+    /// The standard time UT offset in effect at the given UT timestamp, in seconds, whether or
+    /// not DST is in effect then. With [`dst_save_at`](Self::dst_save_at), this splits the UT
+    /// offset as `struct tm`'s `tm_gmtoff` less the DST saving would.
+    ///
+    /// During DST, this is the offset of the last standard local time type before it. After
+    /// the last transition, it comes from the footer if there is one.
+    #[doc(alias = "standard_offset_at")]
+    pub fn std_offset_at(&self, ut: i64) -> Option<i32> {
+        let local = self.local_time_type_at(ut)?;
+        if !local.is_dst {
+            return Some(local.ut_offset_secs);
+        }
+        if let Some(footer) = &self.footer {
+            if self.transition_times.last().is_none_or(|&last| ut >= last) {
+                return Some(footer.std_offset);
             }
         }
+        Some(self.std_offset_before(self.partition_transitions(|t| t <= ut)))
+    }
 
-        Ok(result)
+    /// How far DST moves clocks forward at the given UT timestamp, in seconds: the UT offset in
+    /// effect less [`std_offset_at`](Self::std_offset_at). This is 0 outside of DST.
+    #[doc(alias = "dst_saving_at")]
+    pub fn dst_save_at(&self, ut: i64) -> Option<i32> {
+        let local = self.local_time_type_at(ut)?;
+        Some(local.ut_offset_secs - self.std_offset_at(ut)?)
     }
 
-    pub fn iter_transitions(&self) -> TransitionIterator<'_> {
-        TransitionIterator { tzif: self, idx: 0 }
+    /// Index of the local time type in effect at the given UT timestamp, if there is one.
+    ///
+    /// Before the first transition, the first local time type is in effect.
+    pub(crate) fn type_idx_at(&self, ut: i64) -> Option<usize> {
+        let idx = self.partition_transitions(|t| t <= ut);
+        if idx > 0 {
+            Some(self.transition_types[idx - 1] as usize)
+        } else if self.local_time_types.is_empty() {
+            None
+        } else {
+            Some(0)
+        }
+    }
+
+    pub(crate) fn transition(&self, idx: usize) -> TimeTransition<'_> {
+        let at_ts = self.transition_times[idx];
+        let typ_idx = self.transition_types[idx] as usize;
+        // Before the first transition, the first local time type is in effect.
+        let prev_idx = match idx {
+            0 => 0,
+            _ => self.transition_types[idx - 1] as usize,
+        };
+        let std_offset = self.std_offset_before(idx);
+        let local = self.local_time_type(typ_idx);
+        TimeTransition {
+            at_time: self.adj_time(at_ts, typ_idx, prev_idx, std_offset),
+            local,
+            prev: self.local_time_type(prev_idx),
+            save_secs: match local.is_dst {
+                true => local.ut_offset_secs.saturating_sub(std_offset),
+                false => 0,
+            },
+        }
+    }
+
+    /// The standard time offset in effect before the transition with the given index: that of
+    /// the last standard local time type transitioned to before it.
+    pub(crate) fn std_offset_before(&self, idx: usize) -> i32 {
+        self.transition_types[..idx]
+            .iter()
+            .rev()
+            .map(|&idx| &self.local_time_types[idx as usize])
+            .find(|typ| !typ.is_dst)
+            .map_or_else(|| self.initial_std_offset(), |typ| typ.ut_off_secs)
     }
 
-    pub fn at(&self, t: SystemTime) -> Option<LocalTimeType<'_>> {
+    /// The standard time offset before any transition to a standard time type: the first
+    /// standard local time type's, or if there's none, the first type's.
+    pub(crate) fn initial_std_offset(&self) -> i32 {
+        self.local_time_types
+            .iter()
+            .find(|typ| !typ.is_dst)
+            .or(self.local_time_types.first())
+            .map_or(0, |typ| typ.ut_off_secs)
+    }
+
+    /// The local time type in effect at the given system time, with the interval it holds for;
+    /// see [`lookup`](Self::lookup).
+    #[cfg(feature = "std")]
+    pub fn at(&self, t: SystemTime) -> Option<Lookup<'_>> {
         let ut = match t.duration_since(UNIX_EPOCH) {
             Ok(d) => d.as_secs() as i64,
             Err(e) => -(e.duration().as_secs() as i64),
         };
-        let mut prev = None;
-        for tr in self.iter_transitions() {
-            if tr.at_time.to_ut(&tr.local) > ut {
-                return prev;
-            }
-            prev = Some(tr.local);
-        }
-        None
+        self.lookup(ut)
     }
 
-    fn local_time_type(&self, idx: usize) -> LocalTimeType<'_> {
+    pub(crate) fn local_time_type(&self, idx: usize) -> LocalTimeType<'_> {
         let typ = &self.local_time_types[idx];
-
-        let dstart = typ.desig_idx as usize;
-        let mut dend = dstart;
-        while let Some(b) = self.time_zone_designations.get(dend) {
-            if *b == 0 {
-                break;
-            }
-            dend += 1;
-        }
-        let desig = std::str::from_utf8(&self.time_zone_designations[dstart..dend]).unwrap();
-
         LocalTimeType {
-            desig,
+            desig: self.designation(idx).unwrap_or(""),
             ut_offset_secs: typ.ut_off_secs,
             is_dst: typ.is_dst,
         }
     }
 
-    fn adj_time(&self, ts: i64, typ_idx: usize) -> Time {
-        match (self.is_std[typ_idx], self.is_ut[typ_idx]) {
+    /// The designations in the designation table, in order, whether or not a local time type
+    /// uses them. Any which aren't UTF-8 are skipped.
+    pub fn designations(&self) -> impl Iterator<Item = &str> {
+        let table = match self.time_zone_designations.split_last() {
+            Some((0, rest)) => rest,
+            _ => &self.time_zone_designations[..],
+        };
+        table
+            .split(|&b| b == 0)
+            .filter(|_| !table.is_empty())
+            .filter_map(|desig| core::str::from_utf8(desig).ok())
+    }
+
+    /// The designation of the local time type with the given index.
+    ///
+    /// Returns `None` if there's no such type. A type whose designation index is outside the
+    /// table, or whose designation isn't UTF-8, has an empty designation; these are rejected
+    /// when parsing in strict mode.
+    pub fn designation(&self, idx: usize) -> Option<&str> {
+        let typ = self.local_time_types.get(idx)?;
+        let desig = self
+            .designation_bytes(usize::from(typ.desig_idx))
+            .unwrap_or(b"");
+        Some(core::str::from_utf8(desig).unwrap_or(""))
+    }
+
+    /// The designation starting at the given index in the table, up to the next NUL or the end
+    /// of the table, or `None` if the index is outside the table.
+    pub(crate) fn designation_bytes(&self, idx: usize) -> Option<&[u8]> {
+        let rest = self.time_zone_designations.get(idx..)?;
+        if rest.is_empty() {
+            return None;
+        }
+        let len = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+        Some(&rest[..len])
+    }
+
+    /// Find the given designation in the designation table, or append it, returning its index.
+    ///
+    /// Returns `None` if the index would not fit in a local time type record.
+    pub(crate) fn find_or_add_designation(&mut self, desig: &str) -> Option<u8> {
+        let table = &self.time_zone_designations;
+        let mut start = 0;
+        while start < table.len() {
+            let end = table[start..]
+                .iter()
+                .position(|&b| b == 0)
+                .map_or(table.len(), |n| start + n);
+            if &table[start..end] == desig.as_bytes() {
+                return u8::try_from(start).ok();
+            }
+            start = end + 1;
+        }
+        let idx = u8::try_from(table.len()).ok()?;
+        self.time_zone_designations
+            .extend_from_slice(desig.as_bytes());
+        self.time_zone_designations.push(0);
+        Some(idx)
+    }
+
+    /// Express the UT time `ts` of a transition to type `typ_idx` the way its indicators say it
+    /// was specified, given the type in effect before it and the standard time offset then.
+    pub(crate) fn adj_time(
+        &self,
+        ts: i64,
+        typ_idx: usize,
+        prev_idx: usize,
+        std_offset: i32,
+    ) -> Time {
+        // Missing indicators default to wall clock and local time.
+        let is_std = self.is_std.get(typ_idx).copied().unwrap_or(IsStd::Wall);
+        let is_ut = self.is_ut.get(typ_idx).copied().unwrap_or(IsUT::Local);
+        match (is_std, is_ut) {
             (IsStd::Standard, IsUT::UT) => Time::UT(ts),
-            (IsStd::Standard, IsUT::Local) => Time::LocalStandard(ts),
-            (IsStd::Wall, IsUT::UT) => panic!("transition time can't be wall+universal"),
-            (IsStd::Wall, IsUT::Local) => Time::LocalWall(ts),
+            (IsStd::Standard, IsUT::Local) => Time::LocalStandard {
+                local: ts + i64::from(std_offset),
+                ut_offset_secs: std_offset,
+            },
+            // Wall clock time can't be UT, so such an indicator is taken as local time, as the
+            // lossy parser does.
+            (IsStd::Wall, _) => {
+                let ut_offset_secs = self.local_time_types[prev_idx].ut_off_secs;
+                Time::LocalWall {
+                    local: ts + i64::from(ut_offset_secs),
+                    ut_offset_secs,
+                }
+            }
         }
     }
 }
@@ -274,70 +647,234 @@ impl TimeZoneInfo {
 pub struct TransitionIterator<'a> {
     tzif: &'a TimeZoneInfo,
     idx: usize,
+    /// One past the index of the last transition not yet yielded from the back.
+    end: usize,
 }
 
 impl<'a> Iterator for TransitionIterator<'a> {
     type Item = TimeTransition<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.idx >= self.tzif.transition_times.len() {
+        if self.idx >= self.end {
+            return None;
+        }
+
+        let transition = self.tzif.transition(self.idx);
+        self.idx += 1;
+        Some(transition)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.idx;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for TransitionIterator<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.idx >= self.end {
             return None;
         }
 
-        let at_ts = self.tzif.transition_times[self.idx];
-        let typ_idx = self.tzif.transition_types[self.idx];
-        let local = self.tzif.local_time_type(typ_idx as usize);
-        let at_time = self.tzif.adj_time(at_ts, typ_idx as usize);
+        self.end -= 1;
+        Some(self.tzif.transition(self.end))
+    }
+}
+
+impl ExactSizeIterator for TransitionIterator<'_> {}
+
+impl FusedIterator for TransitionIterator<'_> {}
+
+/// Iterator over the intervals between a zone's transitions; see
+/// [`TimeZoneInfo::iter_intervals`].
+pub struct IntervalIterator<'a> {
+    tzif: &'a TimeZoneInfo,
+    idx: usize,
+    end: usize,
+}
+
+impl<'a> IntervalIterator<'a> {
+    fn interval(&self, idx: usize) -> Interval<'a> {
+        let times = &self.tzif.transition_times;
+        let typ_idx = match idx {
+            0 => 0,
+            _ => usize::from(self.tzif.transition_types[idx - 1]),
+        };
+        Interval {
+            start: idx.checked_sub(1).map(|prev| times[prev]),
+            end: times.get(idx).copied(),
+            local: self.tzif.local_time_type(typ_idx),
+        }
+    }
+}
+
+impl<'a> Iterator for IntervalIterator<'a> {
+    type Item = Interval<'a>;
 
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.idx >= self.end {
+            return None;
+        }
+
+        let interval = self.interval(self.idx);
         self.idx += 1;
-        Some(TimeTransition { at_time, local })
+        Some(interval)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.idx;
+        (len, Some(len))
     }
 }
 
-#[derive(Debug)]
+impl DoubleEndedIterator for IntervalIterator<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.idx >= self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(self.interval(self.end))
+    }
+}
+
+impl ExactSizeIterator for IntervalIterator<'_> {}
+
+impl FusedIterator for IntervalIterator<'_> {}
+
+/// A span of time between transitions, from [`TimeZoneInfo::iter_intervals`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Interval<'a> {
+    /// The UT time the interval starts, inclusive, or `None` for the first interval.
+    pub start: Option<i64>,
+
+    /// The UT time the interval ends, exclusive, or `None` for the last interval.
+    pub end: Option<i64>,
+
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub local: LocalTimeType<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeTransition<'a> {
     pub at_time: Time,
+
+    /// The local time type which takes effect.
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub local: LocalTimeType<'a>,
+
+    /// The local time type in effect before the transition.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub prev: LocalTimeType<'a>,
+
+    /// How far DST moves clocks forward from standard time after the transition, in seconds,
+    /// as [`TimeZoneInfo::dst_save_at`] gives it: the new type's UT offset less that of the
+    /// last standard type before it, or 0 if the new type is standard time.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub save_secs: i32,
+}
+
+impl TimeTransition<'_> {
+    /// How far the transition moves local clocks, in seconds: positive when they go forward.
+    pub fn offset_delta_secs(&self) -> i32 {
+        self.local.ut_offset_secs - self.prev.ut_offset_secs
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LocalTimeType<'a> {
     pub desig: &'a str,
     pub ut_offset_secs: i32,
     pub is_dst: bool,
 }
 
-#[derive(Debug)]
+impl LocalTimeType<'_> {
+    /// Copy the designation, giving a local time type which doesn't borrow from the zone.
+    pub fn to_owned_type(&self) -> LocalTimeTypeOwned {
+        LocalTimeTypeOwned {
+            desig: self.desig.to_owned(),
+            ut_offset_secs: self.ut_offset_secs,
+            is_dst: self.is_dst,
+        }
+    }
+}
+
+/// A [`LocalTimeType`] which owns its designation, so it can outlive the zone it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalTimeTypeOwned {
+    pub desig: String,
+    pub ut_offset_secs: i32,
+    pub is_dst: bool,
+}
+
+impl LocalTimeTypeOwned {
+    /// Borrow this as a [`LocalTimeType`].
+    pub fn as_borrowed(&self) -> LocalTimeType<'_> {
+        LocalTimeType {
+            desig: &self.desig,
+            ut_offset_secs: self.ut_offset_secs,
+            is_dst: self.is_dst,
+        }
+    }
+}
+
+impl From<LocalTimeType<'_>> for LocalTimeTypeOwned {
+    fn from(local: LocalTimeType<'_>) -> Self {
+        local.to_owned_type()
+    }
+}
+
+impl<'a> From<&'a LocalTimeTypeOwned> for LocalTimeType<'a> {
+    fn from(local: &'a LocalTimeTypeOwned) -> Self {
+        local.as_borrowed()
+    }
+}
+
+/// The time of a transition, in the form its standard/wall and UT/local indicators say it was
+/// specified in.
+///
+/// TZif files always store transition times in UT; the local forms are on the clock in effect
+/// just before the transition, as rules in tzdata source are written. Each carries the offset
+/// it was computed with, so converting back to UT needs nothing else. Equality compares the
+/// form as well as the time, so compare [`to_ut`](Self::to_ut) for the instant alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Time {
-    LocalWall(i64),
-    LocalStandard(i64),
+    /// Local wall clock time, with the UT offset of the local time type before the transition.
+    LocalWall {
+        local: i64,
+        ut_offset_secs: i32,
+    },
+
+    /// Local standard time, with the standard time UT offset before the transition.
+    LocalStandard {
+        local: i64,
+        ut_offset_secs: i32,
+    },
+
     UT(i64),
 }
 
 impl Time {
-    pub fn to_ut(&self, local: &LocalTimeType<'_>) -> i64 {
-        match self {
-            Time::UT(t) => *t,
-            Time::LocalStandard(t) => t + i64::from(local.ut_offset_secs),
-            Time::LocalWall(t) => {
-                t + i64::from(local.ut_offset_secs) + if local.is_dst { 60 * 60 } else { 0 }
+    pub fn to_ut(&self) -> i64 {
+        match *self {
+            Time::UT(t) => t,
+            Time::LocalWall {
+                local,
+                ut_offset_secs,
             }
+            | Time::LocalStandard {
+                local,
+                ut_offset_secs,
+            } => local - i64::from(ut_offset_secs),
         }
     }
 }
 
-fn read_time(v1: bool, mut reader: impl Read) -> io::Result<i64> {
-    Ok(if v1 {
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf)?;
-        i64::from(i32::from_be_bytes(buf))
-    } else {
-        let mut buf = [0u8; 8];
-        reader.read_exact(&mut buf)?;
-        i64::from_be_bytes(buf)
-    })
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -347,7 +884,7 @@ mod tests {
         #[rustfmt::skip]
         let bytes = [
             0x54, 0x5a, 0x69, 0x66,
-            
+
             0x00, 0x00, 0x00, 0x00,
             0x00, 0x00, 0x00, 0x00,
             0x00, 0x00, 0x00, 0x00,
@@ -365,11 +902,694 @@ mod tests {
         println!("{hdr:#?}");
         assert_eq!(b"TZif", &hdr.magic);
         assert_eq!(0, hdr.ver);
-        assert_eq!(1u32, hdr.isutcnt.into());
-        assert_eq!(1u32, hdr.isstdcnt.into());
-        assert_eq!(27u32, hdr.leapcnt.into());
-        assert_eq!(0u32, hdr.timecnt.into());
-        assert_eq!(1u32, hdr.typecnt.into());
-        assert_eq!(4u32, hdr.charcnt.into());
+        assert_eq!(1u32, hdr.isutcnt);
+        assert_eq!(1u32, hdr.isstdcnt);
+        assert_eq!(27u32, hdr.leapcnt);
+        assert_eq!(0u32, hdr.timecnt);
+        assert_eq!(1u32, hdr.typecnt);
+        assert_eq!(4u32, hdr.charcnt);
+    }
+
+    pub(crate) fn leap_records(records: &[(i64, i32)]) -> Vec<LeapSecondRecord> {
+        records
+            .iter()
+            .map(|&(occurrence, correction)| LeapSecondRecord::new(occurrence, correction))
+            .collect()
+    }
+
+    pub(crate) fn sample() -> TimeZoneInfo {
+        TimeZoneInfo {
+            version: 2,
+            transition_times: vec![100, 200, 300],
+            transition_types: vec![1, 0, 1],
+            local_time_types: vec![
+                LocalTimeTypeRecord {
+                    ut_off_secs: -8 * 3600,
+                    is_dst: false,
+                    desig_idx: 0,
+                },
+                LocalTimeTypeRecord {
+                    ut_off_secs: -7 * 3600,
+                    is_dst: true,
+                    desig_idx: 4,
+                },
+            ],
+            time_zone_designations: b"PST\0PDT\0".to_vec(),
+            leap_second_records: vec![],
+            is_std: vec![IsStd::Standard; 2],
+            is_ut: vec![IsUT::UT; 2],
+            footer: None,
+            raw_footer: None,
+        }
+    }
+
+    /// A v1 file with a single "UTC" local time type and no transitions.
+    fn minimal_v1() -> Vec<u8> {
+        let mut bytes = b"TZif".to_vec();
+        bytes.extend_from_slice(&[0; 16]);
+        for count in [0u32, 0, 0, 0, 1, 4] {
+            bytes.extend_from_slice(&count.to_be_bytes());
+        }
+        bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        bytes.extend_from_slice(b"UTC\0");
+        bytes
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let good = minimal_v1();
+        let tz = TimeZoneInfo::parse(&good[..]).unwrap();
+        assert_eq!(1, tz.version);
+        assert_eq!(1, tz.local_time_types.len());
+
+        let mut bad = good.clone();
+        bad[0] = b'X';
+        assert!(matches!(
+            TimeZoneInfo::parse(&bad[..]),
+            Err(ParseError::BadMagic { offset: 0 })
+        ));
+
+        let mut bad = good.clone();
+        bad[4] = b'9';
+        assert!(matches!(
+            TimeZoneInfo::parse(&bad[..]),
+            Err(ParseError::UnsupportedVersion {
+                offset: 4,
+                version: b'9'
+            })
+        ));
+
+        // Read leniently as the newest version known, keeping the version it gives.
+        let lenient = ParseOptions {
+            lenient_version: true,
+            ..ParseOptions::default()
+        };
+        let mut tz = sample();
+        tz.version = 5;
+        let future = tz.to_bytes();
+        assert_eq!(b'5', future[4]);
+        assert_eq!(tz, TimeZoneInfo::parse_slice(&future, &lenient).unwrap());
+        assert_eq!(4, tz.to_version(4).unwrap().0.version);
+        assert!(matches!(
+            TimeZoneInfo::parse_slice(&future, &ParseOptions::default()),
+            Err(ParseError::UnsupportedVersion { version: b'5', .. })
+        ));
+        assert!(TimeZoneInfo::parse_slice(&bad, &lenient).is_ok());
+        bad[4] = b'A';
+        assert!(TimeZoneInfo::parse_slice(&bad, &lenient).is_err());
+
+        let mut bad = good.clone();
+        bad[48] = 2;
+        assert!(matches!(
+            TimeZoneInfo::parse(&bad[..]),
+            Err(ParseError::InvalidDstIndicator {
+                offset: 48,
+                value: 2
+            })
+        ));
+
+        // The first transition type of the sample's version 1 block, set to one past the end.
+        let mut bad = sample().to_bytes();
+        bad[56] = 2;
+        assert!(matches!(
+            TimeZoneInfo::parse(&bad[..]),
+            Err(ParseError::TransitionTypeOutOfRange {
+                offset: 56,
+                index: 2
+            })
+        ));
+
+        let truncated = &good[..good.len() - 1];
+        assert!(matches!(
+            TimeZoneInfo::parse_slice(truncated, &ParseOptions::default()),
+            Err(ParseError::Truncated { offset: 50 })
+        ));
+        let err = TimeZoneInfo::parse(truncated).unwrap_err();
+        assert!(matches!(err, ParseError::Truncated { offset: 50 }));
+        assert_eq!(
+            std::io::ErrorKind::UnexpectedEof,
+            std::io::Error::from(err).kind()
+        );
+    }
+
+    #[test]
+    fn test_raw_footer() {
+        let with_footer = |footer: &str| {
+            let mut bytes = sample().to_bytes();
+            bytes.truncate(bytes.len() - 1);
+            bytes.extend_from_slice(footer.as_bytes());
+            bytes.push(b'\n');
+            TimeZoneInfo::parse_slice(&bytes, &ParseOptions::default()).unwrap()
+        };
+        let tz = with_footer("PST8PDT,M3.2.0,M11.1.0");
+        assert_eq!(None, tz.raw_footer);
+        assert_eq!(Some("PST8PDT,M3.2.0,M11.1.0"), tz.footer_str().as_deref());
+        assert_eq!(b'2', tz.version_byte());
+
+        // Spelled out in full, which is written back as it was.
+        let tz = with_footer("<PST>8:00<PDT>,M3.2.0/2,M11.1.0/2");
+        assert_eq!(
+            "PST8PDT,M3.2.0,M11.1.0",
+            tz.footer.as_ref().unwrap().to_string()
+        );
+        assert_eq!(
+            Some("<PST>8:00<PDT>,M3.2.0/2,M11.1.0/2"),
+            tz.footer_str().as_deref()
+        );
+        let back = TimeZoneInfo::parse_slice(&tz.to_bytes(), &ParseOptions::default()).unwrap();
+        assert_eq!(tz, back);
+
+        let tz = with_footer("not a TZ string");
+        assert_eq!(None, tz.footer);
+        assert_eq!(Some("not a TZ string"), tz.footer_str().as_deref());
+        assert_eq!(None, sample().footer_str());
+        assert_eq!(
+            0,
+            TimeZoneInfo {
+                version: 1,
+                ..sample()
+            }
+            .version_byte()
+        );
+    }
+
+    #[test]
+    fn test_footer_read_at_once() {
+        struct Counting<'a>(&'a [u8], usize);
+        impl std::io::Read for Counting<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.1 += 1;
+                self.0.read(buf)
+            }
+        }
+        let mut tz = sample();
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        let bytes = tz.to_bytes();
+        let mut reader = Counting(&bytes, 0);
+        assert_eq!(tz, TimeZoneInfo::parse(&mut reader).unwrap());
+        // A read or two per block, rather than one per byte of the footer.
+        assert!(reader.1 < 10, "{} reads", reader.1);
+    }
+
+    #[test]
+    fn test_iter_local_time_types() {
+        let mut tz = sample();
+        tz.transition_types = vec![1, 1, 1];
+        let types: Vec<_> = tz.iter_local_time_types().collect();
+        assert_eq!(2, types.len());
+        assert_eq!(
+            LocalTimeType {
+                desig: "PST",
+                ut_offset_secs: -8 * 3600,
+                is_dst: false,
+            },
+            types[0]
+        );
+        assert_eq!(
+            Some("PDT"),
+            tz.iter_local_time_types().next_back().map(|t| t.desig)
+        );
+    }
+
+    #[test]
+    fn test_designations() {
+        let tz = sample();
+        assert_eq!(vec!["PST", "PDT"], tz.designations().collect::<Vec<_>>());
+        assert_eq!(Some("PDT"), tz.designation(1));
+        assert_eq!(None, tz.designation(2));
+
+        // The designation index is at offset 49, and the table at 50.
+        let strict = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        let mut bad = minimal_v1();
+        bad[49] = 4;
+        assert!(matches!(
+            TimeZoneInfo::parse(&bad[..]),
+            Err(ParseError::DesignationOutOfRange {
+                offset: 49,
+                index: 4
+            })
+        ));
+
+        let mut bad = minimal_v1();
+        bad[53] = b'C';
+        assert_eq!(
+            Some("UTCC"),
+            TimeZoneInfo::parse(&bad[..]).unwrap().designation(0)
+        );
+        assert!(matches!(
+            TimeZoneInfo::parse_with(&bad[..], &strict),
+            Err(ParseError::UnterminatedDesignations { offset: 53 })
+        ));
+
+        let mut bad = minimal_v1();
+        bad[51] = 0xff;
+        assert!(matches!(
+            TimeZoneInfo::parse(&bad[..]),
+            Err(ParseError::InvalidDesignation { offset: 50 })
+        ));
+        bad[51] = b'_';
+        assert!(TimeZoneInfo::parse(&bad[..]).is_ok());
+        assert!(matches!(
+            TimeZoneInfo::parse_with(&bad[..], &strict),
+            Err(ParseError::InvalidDesignation { offset: 50 })
+        ));
+    }
+
+    #[test]
+    fn test_peek_header() {
+        let bytes = sample().to_bytes();
+        let header = peek_header(std::io::Cursor::new(&bytes)).unwrap();
+        assert_eq!(Some(2), header.version());
+        assert_eq!((3, 2, 8), (header.timecnt, header.typecnt, header.charcnt));
+        assert_eq!(header, peek_header_slice(&bytes).unwrap());
+
+        let v1 = peek_header_slice(&minimal_v1()).unwrap();
+        assert_eq!((Some(1), 0, 4), (v1.version(), v1.timecnt, v1.charcnt));
+
+        // The second header is past the end.
+        let truncated = &bytes[..Header::SIZE + 10];
+        assert!(matches!(
+            peek_header_slice(truncated),
+            Err(ParseError::Truncated { offset: 54 })
+        ));
+        assert!(matches!(
+            peek_header(std::io::Cursor::new(truncated)),
+            Err(ParseError::Truncated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_corrupt_bytes_never_panic() {
+        let good = sample().to_bytes();
+        for i in 0..good.len() {
+            for value in [0x00, 0x01, 0x02, 0x03, 0x7f, 0x80, 0xc3, 0xff] {
+                let mut bad = good.clone();
+                bad[i] = value;
+                let Ok(tz) = TimeZoneInfo::parse(&bad[..]) else {
+                    continue;
+                };
+                for tr in tz.iter_transitions() {
+                    let _ = (tr.local.desig, tr.prev.desig);
+                }
+                let _ = tz.designations().count();
+                let _ = tz.local_time_type_at(250);
+                let _ = tz.to_bytes();
+            }
+        }
+
+        // Data built by hand, or deserialized, isn't checked at all.
+        let mut tz = sample();
+        tz.time_zone_designations = vec![0xff, 0xfe, 0];
+        tz.local_time_types[1].desig_idx = 200;
+        assert_eq!(Some(""), tz.designation(0));
+        assert_eq!(Some(""), tz.designation(1));
+        assert_eq!(0, tz.designations().count());
+    }
+
+    #[test]
+    fn test_leap_second_expiration() {
+        let mut tz = sample();
+        tz.version = 4;
+        tz.leap_second_records = leap_records(&[(78796800, 1), (94694401, 2), (1735689602, 2)]);
+        let parsed = TimeZoneInfo::parse(&tz.to_bytes()[..]).unwrap();
+        assert_eq!(4, parsed.version);
+        assert_eq!(Some(1735689602), parsed.leap_second_expiration());
+
+        tz.leap_second_records.pop();
+        assert_eq!(None, tz.leap_second_expiration());
+        tz.version = 3;
+        tz.leap_second_records
+            .push(LeapSecondRecord::new(1735689602, 2));
+        assert_eq!(None, tz.leap_second_expiration());
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let mut huge = minimal_v1();
+        huge[32..36].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            TimeZoneInfo::parse(&huge[..]),
+            Err(ParseError::LimitExceeded {
+                offset: 32,
+                field: "timecnt",
+                ..
+            })
+        ));
+
+        let mut empty = minimal_v1();
+        empty[36..44].fill(0);
+        empty.truncate(44);
+        assert!(TimeZoneInfo::parse(&empty[..]).is_ok());
+        let strict = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        assert!(matches!(
+            TimeZoneInfo::parse_with(&empty[..], &strict),
+            Err(ParseError::ZeroCount {
+                offset: 36,
+                field: "typecnt"
+            })
+        ));
+
+        // With the limits lifted, a header claiming gigabytes of data is only as costly as the
+        // input it comes with.
+        let unlimited = ParseOptions {
+            max_timecnt: u32::MAX,
+            max_typecnt: u32::MAX,
+            max_charcnt: u32::MAX,
+            max_leapcnt: u32::MAX,
+            ..ParseOptions::default()
+        };
+        let mut hostile = minimal_v1();
+        hostile.truncate(44);
+        for offset in (28..44).step_by(4) {
+            hostile[offset..offset + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        }
+        assert!(matches!(
+            TimeZoneInfo::parse_with(&hostile[..], &unlimited),
+            Err(ParseError::Truncated { offset: 44 })
+        ));
+        assert!(matches!(
+            TimeZoneInfo::parse_slice(&hostile, &unlimited),
+            Err(ParseError::Truncated { offset: 44 })
+        ));
+    }
+
+    #[test]
+    fn test_parse_seek() {
+        let mut tz = sample();
+        tz.transition_times[0] = -1 << 40;
+        let mut bytes = tz.to_bytes();
+        let from_reader = TimeZoneInfo::parse(&bytes[..]).unwrap();
+        let from_seek = TimeZoneInfo::parse_seek(std::io::Cursor::new(&bytes)).unwrap();
+        assert_eq!(2, from_seek.version);
+        assert_eq!(tz.transition_times, from_reader.transition_times);
+        assert_eq!(tz.transition_times, from_seek.transition_times);
+
+        // Corrupt the v2 header's magic; lenient parsing falls back to the v1 data, which lacks
+        // the transition that doesn't fit in 32 bits.
+        let v2_start = Header::SIZE
+            + Header::from_array(bytes[..44].try_into().unwrap()).block_len(true) as usize;
+        bytes[v2_start] = b'X';
+        let fallback = TimeZoneInfo::parse_seek(std::io::Cursor::new(&bytes)).unwrap();
+        assert_eq!(vec![200, 300], fallback.transition_times);
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let mut first = sample();
+        first.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        let second = TimeZoneInfo {
+            version: 1,
+            ..sample()
+        };
+        let third = TimeZoneInfo::fixed(3600, "CET").unwrap();
+        let mut bytes = Vec::new();
+        for tz in [&first, &second, &third] {
+            bytes.extend(tz.to_bytes());
+        }
+        let zones = TimeZoneInfo::parse_all(&bytes[..]).unwrap();
+        assert_eq!(vec![first.clone(), second, third.clone()], zones);
+        assert!(TimeZoneInfo::parse_all(&[][..]).unwrap().is_empty());
+
+        // A footer which is missing doesn't take the next record's magic with it.
+        let mut no_footer = first.to_bytes();
+        no_footer.truncate(no_footer.len() - "\nPST8PDT,M3.2.0,M11.1.0\n".len());
+        no_footer.extend(first.to_bytes());
+        let zones = TimeZoneInfo::parse_all(&no_footer[..]).unwrap();
+        assert_eq!(None, zones[0].footer);
+        assert_eq!(first, zones[1]);
+        assert!(TimeZoneInfo::parse_slice_all(
+            &no_footer,
+            &ParseOptions {
+                strict: true,
+                ..ParseOptions::default()
+            }
+        )
+        .is_err());
+
+        // Trailing data which isn't another record, and a record cut short in its header.
+        let len = bytes.len();
+        bytes.extend_from_slice(&[b'x'; Header::SIZE]);
+        assert!(matches!(
+            TimeZoneInfo::parse_all(&bytes[..]),
+            Err(ParseError::BadMagic { offset }) if offset as usize == len
+        ));
+        bytes.truncate(len - third.to_bytes().len() + 10);
+        assert!(matches!(
+            TimeZoneInfo::parse_all(&bytes[..]),
+            Err(ParseError::Truncated { .. })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let tz = sample();
+        let json = serde_json::to_string(&tz).unwrap();
+        let back: TimeZoneInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(tz, back);
+
+        let tr = tz.iter_transitions().next().unwrap();
+        let json = serde_json::to_string(&tr).unwrap();
+        let back: TimeTransition<'_> = serde_json::from_str(&json).unwrap();
+        assert_eq!(tr, back);
+    }
+
+    #[test]
+    fn test_eq_hash() {
+        use std::collections::HashSet;
+
+        let tz = sample();
+        let back = TimeZoneInfo::parse_slice(&tz.to_bytes(), &ParseOptions::default()).unwrap();
+        assert_eq!(tz, back);
+        let mut other = tz.clone();
+        other.transition_times[0] = 101;
+        assert_ne!(tz, other);
+
+        let zones: HashSet<TimeZoneInfo> = [tz.clone(), back, other].into_iter().collect();
+        assert_eq!(2, zones.len());
+        let transitions: HashSet<TimeTransition<'_>> = tz.iter_transitions().collect();
+        assert_eq!(3, transitions.len());
+        // Times compare by form, not just by the instant they give.
+        assert_ne!(
+            Time::UT(0),
+            Time::LocalWall {
+                local: 0,
+                ut_offset_secs: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_local_time_type_owned() {
+        let owned = {
+            let tz = sample();
+            tz.local_time_type_at(150).unwrap().to_owned_type()
+        };
+        assert_eq!("PDT", owned.desig);
+        assert_eq!(-7 * 3600, owned.ut_offset_secs);
+        assert!(owned.is_dst);
+
+        let tz = sample();
+        let local = tz.local_time_type_at(150).unwrap();
+        assert_eq!(local, owned.as_borrowed());
+        assert_eq!(owned, LocalTimeTypeOwned::from(local));
+        assert_eq!(local, LocalTimeType::from(&owned));
+    }
+
+    #[test]
+    fn test_iter_transitions_in() {
+        let tz = sample();
+        let ut = |range: Range<i64>| -> Vec<i64> {
+            tz.iter_transitions_in(range)
+                .map(|tr| tr.at_time.to_ut())
+                .collect()
+        };
+
+        assert_eq!(vec![100, 200, 300], ut(i64::MIN..i64::MAX));
+        assert_eq!(vec![200], ut(101..300));
+        assert_eq!(vec![200, 300], ut(200..301));
+        assert_eq!(Vec::<i64>::new(), ut(201..300));
+        let (start, end) = (300, 100);
+        assert_eq!(Vec::<i64>::new(), ut(start..end));
+        assert_eq!(1, tz.iter_transitions_in(101..300).len());
+        let last = tz.iter_transitions_in(0..250).next_back().unwrap();
+        assert_eq!(200, last.at_time.to_ut());
+        assert_eq!("PDT", last.prev.desig);
+    }
+
+    #[test]
+    fn test_next_prev_transition() {
+        let tz = sample();
+        let ut = |tr: Option<TimeTransition<'_>>| tr.map(|tr| tr.at_time.to_ut());
+
+        assert_eq!(Some(100), ut(tz.next_transition(0)));
+        assert_eq!(Some(200), ut(tz.next_transition(100)));
+        assert_eq!(None, ut(tz.next_transition(300)));
+
+        assert_eq!(None, ut(tz.prev_transition(100)));
+        assert_eq!(Some(100), ut(tz.prev_transition(101)));
+        assert_eq!(Some(300), ut(tz.prev_transition(i64::MAX)));
+    }
+
+    #[test]
+    fn test_transition_iterator() {
+        let tz = sample();
+        let ut = |tr: TimeTransition<'_>| tr.at_time.to_ut();
+
+        let mut iter = tz.iter_transitions();
+        assert_eq!(3, iter.len());
+        assert_eq!(Some(300), iter.next_back().map(ut));
+        assert_eq!(Some(100), iter.next().map(ut));
+        assert_eq!(1, iter.len());
+        assert_eq!(Some(200), iter.next_back().map(ut));
+        assert_eq!(0, iter.len());
+        assert!(iter.next().is_none());
+        assert!(iter.next_back().is_none());
+
+        let reversed: Vec<_> = tz.iter_transitions().rev().map(ut).collect();
+        assert_eq!(vec![300, 200, 100], reversed);
+        assert_eq!(Some(300), tz.last_transition().map(ut));
+        assert!(TimeZoneInfo::default().last_transition().is_none());
+    }
+
+    #[test]
+    fn test_transition_time_indicators() {
+        // Wall clock and standard time indicators, as in files zic writes from rules with
+        // "2:00" and "2:00s" AT times.
+        let mut tz = sample();
+        tz.is_std = vec![IsStd::Wall, IsStd::Standard];
+        tz.is_ut = vec![IsUT::Local; 2];
+        let times: Vec<_> = tz.iter_transitions().map(|tr| tr.at_time).collect();
+        // The indicators go with the type transitioned to: PDT's times are standard time, the
+        // PST clock before them, and PST's are on the wall clock before them, which is PDT.
+        assert!(matches!(
+            times[0],
+            Time::LocalStandard {
+                local: -28700,
+                ut_offset_secs: -28800
+            }
+        ));
+        assert!(matches!(
+            times[1],
+            Time::LocalWall {
+                local: -25000,
+                ut_offset_secs: -25200
+            }
+        ));
+        assert!(matches!(
+            times[2],
+            Time::LocalStandard {
+                local: -28500,
+                ut_offset_secs: -28800
+            }
+        ));
+        let ut: Vec<_> = times.iter().map(Time::to_ut).collect();
+        assert_eq!(tz.transition_times, ut);
+        assert_eq!(
+            Some(200),
+            tz.next_transition(100).map(|tr| tr.at_time.to_ut())
+        );
+        assert_eq!("PST", tz.local_time_type_at(250).unwrap().desig);
+
+        // A UT indicator without a standard one is taken as local wall clock time.
+        tz.is_std = vec![IsStd::Wall; 2];
+        tz.is_ut = vec![IsUT::UT; 2];
+        let times: Vec<_> = tz.iter_transitions().map(|tr| tr.at_time).collect();
+        assert!(matches!(
+            times[1],
+            Time::LocalWall {
+                local: -25000,
+                ut_offset_secs: -25200
+            }
+        ));
+        tz.is_std.clear();
+        assert_eq!(3, tz.iter_transitions().rev().count());
+    }
+
+    #[test]
+    fn test_transition_prev() {
+        let tz = sample();
+        let transitions: Vec<_> = tz
+            .iter_transitions()
+            .map(|tr| (tr.prev.desig, tr.local.desig, tr.offset_delta_secs()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("PST", "PDT", 3600),
+                ("PDT", "PST", -3600),
+                ("PST", "PDT", 3600)
+            ],
+            transitions
+        );
+    }
+
+    #[test]
+    fn test_point_queries() {
+        let mut tz = sample();
+        // A double summer time after the last transition, on top of PDT.
+        tz.local_time_types.push(LocalTimeTypeRecord {
+            ut_off_secs: -6 * 3600,
+            is_dst: true,
+            desig_idx: 4,
+        });
+        tz.transition_times.push(400);
+        tz.transition_types.push(2);
+
+        assert!(!tz.is_dst_at(0));
+        assert!(tz.is_dst_at(150));
+        assert_eq!(Some("PST"), tz.designation_at(250));
+        assert_eq!(Some(-8 * 3600), tz.std_offset_at(0));
+        assert_eq!(Some(0), tz.dst_save_at(0));
+        assert_eq!(Some(-8 * 3600), tz.std_offset_at(450));
+        assert_eq!(Some(2 * 3600), tz.dst_save_at(450));
+        let saves: Vec<i32> = tz.iter_transitions().map(|tr| tr.save_secs).collect();
+        assert_eq!(vec![3600, 0, 3600, 2 * 3600], saves);
+
+        tz.footer = Some("MST7MDT,M3.2.0,M11.1.0".parse().unwrap());
+        assert_eq!(Some(-7 * 3600), tz.std_offset_at(1 << 30));
+
+        // Lord Howe Island moves its clocks by half an hour.
+        let lord_howe =
+            TimeZoneInfo::from_posix("<+1030>-10:30<+11>-11,M10.1.0,M4.1.0".parse().unwrap());
+        assert_eq!(Some(1800), lord_howe.dst_save_at(1_704_067_200));
+        assert_eq!(Some(0), lord_howe.dst_save_at(1_719_792_000));
+        assert!(!TimeZoneInfo::default().is_dst_at(0));
+        assert_eq!(None, TimeZoneInfo::default().designation_at(0));
+    }
+
+    #[test]
+    fn test_iter_intervals() {
+        let tz = sample();
+        let intervals: Vec<_> = tz
+            .iter_intervals()
+            .map(|i| (i.start, i.end, i.local.desig))
+            .collect();
+        assert_eq!(
+            vec![
+                (None, Some(100), "PST"),
+                (Some(100), Some(200), "PDT"),
+                (Some(200), Some(300), "PST"),
+                (Some(300), None, "PDT"),
+            ],
+            intervals
+        );
+        assert_eq!(4, tz.iter_intervals().len());
+        assert_eq!(
+            Some((Some(300), None)),
+            tz.iter_intervals().next_back().map(|i| (i.start, i.end))
+        );
+
+        let mut fixed = sample();
+        fixed.transition_times.clear();
+        fixed.transition_types.clear();
+        let all: Vec<_> = fixed.iter_intervals().map(|i| (i.start, i.end)).collect();
+        assert_eq!(vec![(None, None)], all);
+        assert_eq!(0, TimeZoneInfo::default().iter_intervals().len());
     }
 }
@@ -0,0 +1,197 @@
+//! Checking this crate's answers against the C library's `localtime_r` and `mktime`.
+
+#![allow(unsafe_code)]
+
+use std::ffi::{CStr, OsString};
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::{CivilDateTime, Disambiguation, LocalTimeTypeOwned, TimeZoneInfo, ZoneError};
+
+/// Held while `TZ` is changed, so comparisons on different threads don't interfere.
+static TZ_LOCK: Mutex<()> = Mutex::new(());
+
+/// A difference between this crate and the C library, from [`compare_with_libc`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LibcMismatch {
+    /// `localtime_r` gave a different local time, or local time type, for the UT time `ut`.
+    Localtime {
+        ut: i64,
+        ours: (CivilDateTime, LocalTimeTypeOwned),
+        libc: (CivilDateTime, LocalTimeTypeOwned),
+    },
+
+    /// `mktime`, asked to work out whether DST is in effect, gave a different UT time for the
+    /// local time, or `None` where it failed.
+    Mktime {
+        local: CivilDateTime,
+        ours: i64,
+        libc: Option<i64>,
+    },
+}
+
+/// Compare this crate's reading of the TZif file at `path` with the C library's, returning
+/// every mismatch found.
+///
+/// UT times are sampled every `step` seconds through `range`, and either side of every change
+/// of local time type in it, including those the footer gives. At each, the local time and
+/// local time type must match what `localtime_r` gives, and converting that local time back
+/// with [`Disambiguation::Compatible`] must give what `mktime` does. Times `time_t` can't hold
+/// are skipped.
+///
+/// This points the `TZ` environment variable at the file while it runs, so other threads
+/// shouldn't use the C library's local time functions meanwhile.
+pub fn compare_with_libc(
+    path: impl AsRef<Path>,
+    range: Range<i64>,
+    step: i64,
+) -> Result<Vec<LibcMismatch>, ZoneError> {
+    let path = fs::canonicalize(path).map_err(ZoneError::Io)?;
+    let tz = TimeZoneInfo::parse(fs::File::open(&path).map_err(ZoneError::Io)?)?;
+
+    let mut samples: Vec<i64> = (range.start..range.end)
+        .step_by(usize::try_from(step.max(1)).unwrap_or(usize::MAX))
+        .collect();
+    if range.start < range.end {
+        for (at, _, _) in tz.changes_between(range.start, range.end - 1) {
+            samples.extend([at - 1, at]);
+        }
+    }
+    samples.sort_unstable();
+    samples.dedup();
+
+    let mut tz_var = OsString::from(":");
+    tz_var.push(&path);
+    let _lock = TZ_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let saved = std::env::var_os("TZ");
+    std::env::set_var("TZ", &tz_var);
+    tzset();
+
+    let mut mismatches = Vec::new();
+    for ut in samples {
+        let Some((civil, libc_type)) = localtime(ut) else {
+            continue;
+        };
+        let Some(local) = tz.local_time_type_at(ut) else {
+            continue;
+        };
+        let ours = (tz.to_civil(ut), local.to_owned_type());
+        if ours != (civil, libc_type.clone()) {
+            mismatches.push(LibcMismatch::Localtime {
+                ut,
+                ours,
+                libc: (civil, libc_type),
+            });
+            continue;
+        }
+        if let Ok(ours) = tz.from_civil(civil, Disambiguation::Compatible) {
+            let libc = mktime(civil);
+            if libc != Some(ours) {
+                mismatches.push(LibcMismatch::Mktime {
+                    local: civil,
+                    ours,
+                    libc,
+                });
+            }
+        }
+    }
+
+    match saved {
+        Some(saved) => std::env::set_var("TZ", saved),
+        None => std::env::remove_var("TZ"),
+    }
+    tzset();
+    Ok(mismatches)
+}
+
+/// Make the C library read `TZ` again.
+fn tzset() {
+    // The libc crate doesn't declare tzset, which is in every C library this builds for.
+    extern "C" {
+        fn tzset();
+    }
+    // SAFETY: tzset only reads the environment, which is only changed under TZ_LOCK here.
+    unsafe { tzset() }
+}
+
+/// The local time and local time type `localtime_r` gives for the UT time, if it can.
+fn localtime(ut: i64) -> Option<(CivilDateTime, LocalTimeTypeOwned)> {
+    let t = ::libc::time_t::try_from(ut).ok()?;
+    // SAFETY: tm is plain data, for which all zeroes is valid, and localtime_r only writes to
+    // it. tm_zone is checked for null before it's read, and it points to a NUL-terminated
+    // string which lives at least until the next call to tzset.
+    let (tm, desig) = unsafe {
+        let mut tm: ::libc::tm = std::mem::zeroed();
+        if ::libc::localtime_r(&t, &mut tm).is_null() || tm.tm_zone.is_null() {
+            return None;
+        }
+        let desig = CStr::from_ptr(tm.tm_zone).to_string_lossy().into_owned();
+        (tm, desig)
+    };
+    let civil = CivilDateTime::new(
+        i64::from(tm.tm_year) + 1900,
+        u8::try_from(tm.tm_mon + 1).ok()?,
+        u8::try_from(tm.tm_mday).ok()?,
+        u8::try_from(tm.tm_hour).ok()?,
+        u8::try_from(tm.tm_min).ok()?,
+        u8::try_from(tm.tm_sec).ok()?,
+    );
+    let local = LocalTimeTypeOwned {
+        desig,
+        ut_offset_secs: i32::try_from(tm.tm_gmtoff).ok()?,
+        is_dst: tm.tm_isdst > 0,
+    };
+    Some((civil, local))
+}
+
+/// The UT time `mktime` gives for the local time, leaving it to work out whether DST is in
+/// effect.
+fn mktime(local: CivilDateTime) -> Option<i64> {
+    // SAFETY: as in localtime; mktime only reads and normalizes the fields of tm.
+    let mut tm: ::libc::tm = unsafe { std::mem::zeroed() };
+    tm.tm_year = i32::try_from(local.year - 1900).ok()?;
+    tm.tm_mon = i32::from(local.month) - 1;
+    tm.tm_mday = i32::from(local.day);
+    tm.tm_hour = i32::from(local.hour);
+    tm.tm_min = i32::from(local.minute);
+    tm.tm_sec = i32::from(local.second);
+    tm.tm_isdst = -1;
+    // A failed call leaves tm_wday alone, which tells failure apart from a result of -1.
+    tm.tm_wday = -1;
+    // SAFETY: tm is a valid, initialized struct tm.
+    let t = unsafe { ::libc::mktime(&mut tm) };
+    #[allow(clippy::useless_conversion)] // time_t is 32 bits on some platforms.
+    (tm.tm_wday >= 0).then_some(i64::from(t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ZoneSource;
+
+    #[test]
+    fn test_compare_with_libc() {
+        let mut source = ZoneSource::new();
+        source
+            .parse(
+                "\
+Rule  US  2007  max  -  Mar  Sun>=8  2:00  1:00  D
+Rule  US  2007  max  -  Nov  Sun>=1  2:00  0     S
+Zone  Test  -5:00  -   LMT  2010
+            -5:00  US  E%sT
+",
+            )
+            .unwrap();
+        let tz = source.compile("Test").unwrap();
+        let path = std::env::temp_dir().join(format!("tzif-libc-test-{}", std::process::id()));
+        fs::write(&path, tz.to_bytes()).unwrap();
+
+        // 2005 to 2030, sampling every nine hours or so.
+        let mismatches = compare_with_libc(&path, 1_104_537_600..1_893_456_000, 32_401);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(Vec::<LibcMismatch>::new(), mismatches.unwrap());
+    }
+}
@@ -0,0 +1,492 @@
+//! Converting between instants and local civil date and time in a zone, without needing a
+//! date/time crate.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::civil::{checked_days_from_civil, civil_from_days, days_in_month, SECS_PER_DAY};
+use crate::{LocalTimeError, LocalTimeType, TimeZoneInfo};
+
+/// Two days is more than the largest UT offset in practice, so every transition which could
+/// affect a given local time is within this of it.
+const SEARCH_WINDOW: i64 = 2 * SECS_PER_DAY;
+
+/// A date and time of day in the proleptic Gregorian calendar, with no zone attached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CivilDateTime {
+    pub year: i64,
+
+    /// 1 to 12.
+    pub month: u8,
+
+    /// 1 to 31.
+    pub day: u8,
+
+    /// 0 to 23.
+    pub hour: u8,
+
+    /// 0 to 59.
+    pub minute: u8,
+
+    /// 0 to 59. Leap seconds can't be represented.
+    pub second: u8,
+}
+
+/// How [`TimeZoneInfo::from_civil`] picks an instant for a local time which was skipped or
+/// repeated by a transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Disambiguation {
+    /// The earlier of two repeated times, and for a skipped time, the instant after the gap
+    /// which is as far into it as the time is: 02:30 in a gap from 02:00 to 03:00 gives 03:30.
+    /// This is what `mktime` and most other libraries do.
+    #[default]
+    Compatible,
+
+    /// The earlier of two repeated times, and for a skipped time, the instant before the gap
+    /// which is as far before its end as the time is: 02:30 in a gap from 02:00 to 03:00 gives
+    /// 01:30.
+    Earlier,
+
+    /// The later of two repeated times, and for a skipped time, the same as `Compatible`.
+    Later,
+
+    /// Fail with a [`LocalTimeError`] rather than choose.
+    Reject,
+}
+
+/// A change of local time type as seen on the wall clock, from
+/// [`TimeZoneInfo::transition_in_local_day`] and its week and month counterparts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalTransition<'a> {
+    /// The UT time of the change.
+    pub at: i64,
+
+    /// The wall-clock time the change happens at, on the clock in effect before it: 02:00 for
+    /// both the start and the end of DST in the US.
+    pub before: CivilDateTime,
+
+    /// The wall-clock time right after the change: 03:00 when DST starts in the US, and 01:00
+    /// when it ends.
+    pub after: CivilDateTime,
+
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub prev: LocalTimeType<'a>,
+
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub local: LocalTimeType<'a>,
+}
+
+/// How a local time maps to instants in a zone.
+pub(crate) enum Resolution {
+    Single(i64),
+    Ambiguous {
+        earlier: i64,
+        later: i64,
+    },
+
+    /// With the UT time of the transition which skipped it: the first instant after the gap.
+    Skipped {
+        earlier: i64,
+        later: i64,
+        transition: i64,
+    },
+}
+
+impl CivilDateTime {
+    pub fn new(year: i64, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Self {
+        Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+
+    /// The date and time the given number of seconds after 1970-01-01 00:00:00.
+    pub fn from_timestamp(secs: i64) -> Self {
+        let (year, month, day) = civil_from_days(secs.div_euclid(SECS_PER_DAY));
+        let time = secs.rem_euclid(SECS_PER_DAY);
+        Self {
+            year,
+            month,
+            day,
+            hour: (time / 3600) as u8,
+            minute: (time / 60 % 60) as u8,
+            second: (time % 60) as u8,
+        }
+    }
+
+    /// The number of seconds after 1970-01-01 00:00:00 this is, or `None` if the fields aren't
+    /// a valid date and time.
+    pub fn timestamp(&self) -> Option<i64> {
+        let valid = (1..=12).contains(&self.month)
+            && (1..=days_in_month(self.year, self.month)).contains(&self.day)
+            && self.hour < 24
+            && self.minute < 60
+            && self.second < 60;
+        if !valid {
+            return None;
+        }
+        let days = checked_days_from_civil(self.year, self.month, self.day)?;
+        let time = i64::from(self.hour) * 3600 + i64::from(self.minute) * 60;
+        days.checked_mul(SECS_PER_DAY)?
+            .checked_add(time + i64::from(self.second))
+    }
+}
+
+impl fmt::Display for CivilDateTime {
+    /// In ISO 8601 format, as in "2024-03-10 02:30:00".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+impl TimeZoneInfo {
+    /// The local date and time in this zone at the given UT timestamp. Zones with no local time
+    /// types are UTC.
+    pub fn to_civil(&self, ut: i64) -> CivilDateTime {
+        let offset = self
+            .local_time_type_at(ut)
+            .map_or(0, |local| local.ut_offset_secs);
+        CivilDateTime::from_timestamp(ut.saturating_add(i64::from(offset)))
+    }
+
+    /// The UT timestamp at which the given local date and time occurs in this zone, choosing
+    /// between two for a repeated time or making one up for a skipped time as `policy` says.
+    ///
+    /// This takes the footer's transitions into account after the last recorded one.
+    pub fn from_civil(
+        &self,
+        civil: CivilDateTime,
+        policy: Disambiguation,
+    ) -> Result<i64, LocalTimeError> {
+        let local = civil.timestamp().ok_or(LocalTimeError::InvalidDate)?;
+        match (self.resolve(local), policy) {
+            (Resolution::Single(ut), _) => Ok(ut),
+            (Resolution::Ambiguous { earlier, .. }, Disambiguation::Compatible)
+            | (Resolution::Ambiguous { earlier, .. }, Disambiguation::Earlier)
+            | (Resolution::Skipped { earlier, .. }, Disambiguation::Earlier) => Ok(earlier),
+            (Resolution::Ambiguous { later, .. }, Disambiguation::Later)
+            | (Resolution::Skipped { later, .. }, Disambiguation::Compatible)
+            | (Resolution::Skipped { later, .. }, Disambiguation::Later) => Ok(later),
+            (Resolution::Ambiguous { earlier, later }, Disambiguation::Reject) => {
+                Err(LocalTimeError::Ambiguous { earlier, later })
+            }
+            (Resolution::Skipped { earlier, later, .. }, Disambiguation::Reject) => {
+                Err(LocalTimeError::Skipped { earlier, later })
+            }
+        }
+    }
+
+    /// The change of local time type during the given local calendar day, if there is one, with
+    /// its wall-clock times either side.
+    ///
+    /// A change belongs to the day its wall-clock time falls in on the clock in effect before
+    /// it, so a change at midnight belongs to the day starting then, even if it sets clocks
+    /// back into the day before. If there are two changes in the day, the first is given.
+    /// Returns `None` for an invalid date. This takes the footer's transitions into account
+    /// after the last recorded one.
+    pub fn transition_in_local_day(
+        &self,
+        year: i64,
+        month: u8,
+        day: u8,
+    ) -> Option<LocalTransition<'_>> {
+        self.transitions_in_local_days(year, month, day, 1)
+            .into_iter()
+            .next()
+    }
+
+    /// The changes of local time type during the seven local calendar days starting with the
+    /// given one, in order, as [`transition_in_local_day`](Self::transition_in_local_day) finds
+    /// them.
+    pub fn transitions_in_local_week(
+        &self,
+        year: i64,
+        month: u8,
+        day: u8,
+    ) -> Vec<LocalTransition<'_>> {
+        self.transitions_in_local_days(year, month, day, 7)
+    }
+
+    /// The changes of local time type during the given local calendar month, in order, as
+    /// [`transition_in_local_day`](Self::transition_in_local_day) finds them.
+    pub fn transitions_in_local_month(&self, year: i64, month: u8) -> Vec<LocalTransition<'_>> {
+        if !(1..=12).contains(&month) {
+            return Vec::new();
+        }
+        let days = days_in_month(year, month);
+        self.transitions_in_local_days(year, month, 1, i64::from(days))
+    }
+
+    /// The changes of local time type during the `days` local calendar days starting with the
+    /// given one.
+    fn transitions_in_local_days(
+        &self,
+        year: i64,
+        month: u8,
+        day: u8,
+        days: i64,
+    ) -> Vec<LocalTransition<'_>> {
+        let Some(start) = CivilDateTime::new(year, month, day, 0, 0, 0).timestamp() else {
+            return Vec::new();
+        };
+        let end = start.saturating_add(days * SECS_PER_DAY);
+        let wall =
+            |at: i64, local: &LocalTimeType<'_>| at.saturating_add(i64::from(local.ut_offset_secs));
+        self.changes_between(
+            start.saturating_sub(SEARCH_WINDOW + 1),
+            end.saturating_add(SEARCH_WINDOW),
+        )
+        .into_iter()
+        .filter(|(at, prev, local)| prev != local && (start..end).contains(&wall(*at, prev)))
+        .map(|(at, prev, local)| LocalTransition {
+            at,
+            before: CivilDateTime::from_timestamp(wall(at, &prev)),
+            after: CivilDateTime::from_timestamp(wall(at, &local)),
+            prev,
+            local,
+        })
+        .collect()
+    }
+
+    /// Find the instants at which the given local time, in seconds since the epoch, occurs.
+    pub(crate) fn resolve(&self, local: i64) -> Resolution {
+        let from = local.saturating_sub(SEARCH_WINDOW);
+        let to = local.saturating_add(SEARCH_WINDOW);
+        let Some(first) = self.local_time_type_at(from) else {
+            return Resolution::Single(local);
+        };
+        let changes = self.changes_between(from, to);
+
+        // The local time occurs at `local - offset` for each offset in effect then.
+        let mut offsets: Vec<i32> = core::iter::once(first.ut_offset_secs)
+            .chain(changes.iter().map(|(_, _, after)| after.ut_offset_secs))
+            .collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+        let mut instants: Vec<i64> = offsets
+            .into_iter()
+            .map(|offset| (local.saturating_sub(i64::from(offset)), offset))
+            .filter(|&(ut, offset)| {
+                self.local_time_type_at(ut)
+                    .is_some_and(|local| local.ut_offset_secs == offset)
+            })
+            .map(|(ut, _)| ut)
+            .collect();
+        instants.sort_unstable();
+        match instants[..] {
+            [ut] => return Resolution::Single(ut),
+            [earlier, .., later] => return Resolution::Ambiguous { earlier, later },
+            [] => {}
+        }
+
+        // Otherwise the local time falls in the gap left by a transition moving clocks forward.
+        let (transition, before, after) = changes
+            .iter()
+            .find(|(at, before, after)| {
+                let gap = at.saturating_add(i64::from(before.ut_offset_secs))
+                    ..at.saturating_add(i64::from(after.ut_offset_secs));
+                gap.contains(&local)
+            })
+            .copied()
+            .unwrap_or((
+                local.saturating_sub(i64::from(first.ut_offset_secs)),
+                first,
+                first,
+            ));
+        Resolution::Skipped {
+            earlier: local.saturating_sub(i64::from(after.ut_offset_secs)),
+            later: local.saturating_sub(i64::from(before.ut_offset_secs)),
+            transition,
+        }
+    }
+
+    /// The changes of local time type with UT times in `(from, to]`, each with the types
+    /// before and after, from the recorded transitions and then the footer.
+    pub(crate) fn changes_between(
+        &self,
+        from: i64,
+        to: i64,
+    ) -> Vec<(i64, LocalTimeType<'_>, LocalTimeType<'_>)> {
+        let start = self.partition_transitions(|t| t <= from);
+        let end = self.partition_transitions(|t| t <= to);
+        let mut changes: Vec<_> = (start..end)
+            .map(|idx| {
+                let tr = self.transition(idx);
+                (self.transition_times[idx], tr.prev, tr.local)
+            })
+            .collect();
+
+        let Some(footer) = &self.footer else {
+            return changes;
+        };
+        let last = self.transition_times.last().copied();
+        let from = last.map_or(from, |last| from.max(last));
+        if from >= to {
+            return changes;
+        }
+        let year = |t: i64| civil_from_days(t.div_euclid(SECS_PER_DAY)).0;
+        let mut footer_changes = Vec::new();
+        for y in year(from) - 1..=year(to) + 1 {
+            for (at, _) in footer.transitions(y).into_iter().flatten() {
+                if from < at && at <= to {
+                    footer_changes.push((
+                        at,
+                        footer.local_time_type_at(at - 1),
+                        footer.local_time_type_at(at),
+                    ));
+                }
+            }
+        }
+        footer_changes.sort_by_key(|&(at, _, _)| at);
+        changes.extend(footer_changes);
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_civil() {
+        let civil = CivilDateTime::new(2024, 2, 29, 23, 59, 59);
+        let secs = civil.timestamp().unwrap();
+        assert_eq!(1709251199, secs);
+        assert_eq!(civil, CivilDateTime::from_timestamp(secs));
+        assert_eq!("2024-02-29 23:59:59", civil.to_string());
+        assert_eq!(None, CivilDateTime::new(2023, 2, 29, 0, 0, 0).timestamp());
+        assert_eq!(None, CivilDateTime::new(2024, 1, 1, 24, 0, 0).timestamp());
+        assert_eq!(
+            CivilDateTime::new(1969, 12, 31, 23, 59, 59),
+            CivilDateTime::from_timestamp(-1)
+        );
+        for year in [i64::MAX, i64::MIN, 1 << 40, -(1 << 40)] {
+            assert_eq!(None, CivilDateTime::new(year, 1, 1, 0, 0, 0).timestamp());
+            assert_eq!(
+                Err(LocalTimeError::InvalidDate),
+                sample().from_civil(
+                    CivilDateTime::new(year, 12, 31, 0, 0, 0),
+                    Disambiguation::Compatible
+                )
+            );
+        }
+        let last = CivilDateTime::from_timestamp(i64::MAX);
+        assert_eq!(Some(i64::MAX), last.timestamp());
+    }
+
+    #[test]
+    fn test_to_from_civil() {
+        let mut tz = sample();
+        tz.transition_times.clear();
+        tz.transition_types.clear();
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        let civil = |h, m| CivilDateTime::new(2024, 3, 10, h, m, 0);
+        // DST starts at 2024-03-10 10:00 UT, when clocks go from 02:00 to 03:00.
+        let spring = 1710064800;
+        assert_eq!(civil(1, 59), tz.to_civil(spring - 60));
+        assert_eq!(civil(3, 0), tz.to_civil(spring));
+
+        let ok = |c, policy| tz.from_civil(c, policy);
+        assert_eq!(Ok(spring - 60), ok(civil(1, 59), Disambiguation::Reject));
+        assert_eq!(Ok(spring), ok(civil(3, 0), Disambiguation::Reject));
+        assert_eq!(
+            Ok(spring + 1800),
+            ok(civil(2, 30), Disambiguation::Compatible)
+        );
+        assert_eq!(Ok(spring - 1800), ok(civil(2, 30), Disambiguation::Earlier));
+        assert_eq!(
+            Err(LocalTimeError::Skipped {
+                earlier: spring - 1800,
+                later: spring + 1800
+            }),
+            ok(civil(2, 30), Disambiguation::Reject)
+        );
+
+        // DST ends at 2024-11-03 09:00 UT, when clocks go from 02:00 back to 01:00.
+        let fall = 1730624400;
+        let repeated = CivilDateTime::new(2024, 11, 3, 1, 30, 0);
+        assert_eq!(
+            Ok(fall - 1800),
+            tz.from_civil(repeated, Disambiguation::Compatible)
+        );
+        assert_eq!(
+            Ok(fall + 1800),
+            tz.from_civil(repeated, Disambiguation::Later)
+        );
+        assert_eq!(
+            Err(LocalTimeError::Ambiguous {
+                earlier: fall - 1800,
+                later: fall + 1800
+            }),
+            tz.from_civil(repeated, Disambiguation::Reject)
+        );
+
+        assert_eq!(
+            Err(LocalTimeError::InvalidDate),
+            tz.from_civil(
+                CivilDateTime::new(2024, 13, 1, 0, 0, 0),
+                Disambiguation::Compatible
+            )
+        );
+        let utc = TimeZoneInfo::default();
+        assert_eq!(
+            Ok(0),
+            utc.from_civil(
+                CivilDateTime::new(1970, 1, 1, 0, 0, 0),
+                Disambiguation::Reject
+            )
+        );
+    }
+
+    #[test]
+    fn test_transition_in_local_day() {
+        let mut tz = sample();
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+
+        let spring = tz.transition_in_local_day(2024, 3, 10).unwrap();
+        assert_eq!(1710064800, spring.at);
+        assert_eq!(CivilDateTime::new(2024, 3, 10, 2, 0, 0), spring.before);
+        assert_eq!(CivilDateTime::new(2024, 3, 10, 3, 0, 0), spring.after);
+        assert_eq!(("PST", "PDT"), (spring.prev.desig, spring.local.desig));
+        let fall = tz.transition_in_local_day(2024, 11, 3).unwrap();
+        assert_eq!(CivilDateTime::new(2024, 11, 3, 2, 0, 0), fall.before);
+        assert_eq!(CivilDateTime::new(2024, 11, 3, 1, 0, 0), fall.after);
+        assert_eq!(None, tz.transition_in_local_day(2024, 3, 9));
+        assert_eq!(None, tz.transition_in_local_day(2024, 3, 11));
+        assert_eq!(None, tz.transition_in_local_day(2024, 2, 30));
+
+        assert_eq!(vec![spring], tz.transitions_in_local_week(2024, 3, 4));
+        assert!(tz.transitions_in_local_week(2024, 3, 11).is_empty());
+        assert_eq!(vec![spring], tz.transitions_in_local_month(2024, 3));
+        assert_eq!(vec![fall], tz.transitions_in_local_month(2024, 11));
+        assert!(tz.transitions_in_local_month(2024, 12).is_empty());
+        assert!(tz.transitions_in_local_month(2024, 13).is_empty());
+
+        // The recorded transitions, at 100, 200 and 300 seconds UT, are all on 1969-12-31.
+        let recorded = tz.transitions_in_local_week(1969, 12, 28);
+        assert_eq!(
+            vec![100, 200, 300],
+            recorded.iter().map(|tr| tr.at).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            CivilDateTime::new(1969, 12, 31, 16, 1, 40),
+            recorded[0].before
+        );
+        assert_eq!(
+            CivilDateTime::new(1969, 12, 31, 17, 1, 40),
+            recorded[0].after
+        );
+        assert_eq!(Some(recorded[0]), tz.transition_in_local_day(1969, 12, 31));
+    }
+}
@@ -0,0 +1,133 @@
+//! Looking up the local time type at a time along with how long it holds, for callers which
+//! cache the answer.
+
+use crate::{LocalTimeType, TimeZoneInfo};
+
+/// Where the answer to a [`TimeZoneInfo::lookup`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LookupSource {
+    /// The recorded transitions, or the first local time type before them.
+    Transitions,
+
+    /// The footer's TZ string, on or after the last transition.
+    Footer,
+}
+
+/// The local time type in effect at a time, with the interval it's in effect for, from
+/// [`TimeZoneInfo::lookup`].
+///
+/// Every UT time in `since..until` gets the same answer, so it can be cached for that long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lookup<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub local: LocalTimeType<'a>,
+
+    /// The UT time of the change into the local time type, or `None` if it has been in effect
+    /// since the earliest time.
+    pub since: Option<i64>,
+
+    /// The UT time of the next change, or `None` if there is none.
+    pub until: Option<i64>,
+
+    /// The index of the last transition at or before the time, which governs it unless the
+    /// footer does. `None` before the first transition.
+    pub transition: Option<usize>,
+
+    pub source: LookupSource,
+}
+
+impl Lookup<'_> {
+    /// Whether the given UT time is in the interval the answer holds for.
+    pub fn contains(&self, ut: i64) -> bool {
+        self.since.is_none_or(|since| since <= ut) && self.until.is_none_or(|until| ut < until)
+    }
+}
+
+impl TimeZoneInfo {
+    /// The local time type in effect at the given UT timestamp, as
+    /// [`local_time_type_at`](Self::local_time_type_at) gives it, along with the interval it
+    /// holds for and where it came from.
+    ///
+    /// The interval runs between changes of local time type, counting each transition as one
+    /// even if it leaves the type as it was. Returns `None` if the zone has no local time types.
+    pub fn lookup(&self, ut: i64) -> Option<Lookup<'_>> {
+        let idx = self.partition_transitions(|t| t <= ut);
+        let transition = idx.checked_sub(1);
+        if let Some(footer) = &self.footer {
+            if idx == self.transition_times.len() {
+                let (prev, next) = footer.changes_around(ut);
+                return Some(Lookup {
+                    local: footer.local_time_type_at(ut),
+                    since: prev.max(self.transition_times.last().copied()),
+                    until: next,
+                    transition,
+                    source: LookupSource::Footer,
+                });
+            }
+        }
+        let typ = self.type_idx_at(ut)?;
+        Some(Lookup {
+            local: self.local_time_type(typ),
+            since: transition.map(|idx| self.transition_times[idx]),
+            until: self.transition_times.get(idx).copied(),
+            transition,
+            source: LookupSource::Transitions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_lookup() {
+        let tz = sample();
+        for (ut, since, until, transition) in [
+            (i64::MIN, None, Some(100), None),
+            (99, None, Some(100), None),
+            (100, Some(100), Some(200), Some(0)),
+            (250, Some(200), Some(300), Some(1)),
+            (i64::MAX, Some(300), None, Some(2)),
+        ] {
+            let lookup = tz.lookup(ut).unwrap();
+            assert_eq!(tz.local_time_type_at(ut), Some(lookup.local), "{ut}");
+            assert_eq!(
+                (since, until, transition, LookupSource::Transitions),
+                (lookup.since, lookup.until, lookup.transition, lookup.source),
+                "{ut}"
+            );
+            assert!(lookup.contains(ut));
+        }
+        assert!(!tz.lookup(150).unwrap().contains(200));
+        assert_eq!(None, TimeZoneInfo::default().lookup(0));
+
+        // 2024-07-01, in DST from 2024-03-10 to 2024-11-03, after a last transition in 2010.
+        let mut tz = sample();
+        tz.transition_times[2] = 1_262_304_000;
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        let lookup = tz.lookup(1_719_792_000).unwrap();
+        assert_eq!(LookupSource::Footer, lookup.source);
+        assert_eq!("PDT", lookup.local.desig);
+        assert_eq!(Some(1_710_064_800), lookup.since);
+        assert_eq!(Some(1_730_624_400), lookup.until);
+        assert_eq!(Some(2), lookup.transition);
+
+        // The footer takes over from the last transition.
+        let lookup = tz.lookup(1_262_304_000).unwrap();
+        assert_eq!(LookupSource::Footer, lookup.source);
+        assert_eq!(Some(1_262_304_000), lookup.since);
+        assert_eq!(Some(1_268_560_800), lookup.until);
+
+        let tz = TimeZoneInfo::fixed(3600, "CET").unwrap();
+        let lookup = tz.lookup(0).unwrap();
+        assert_eq!(
+            (None, None, None),
+            (lookup.since, lookup.until, lookup.transition)
+        );
+        assert_eq!(LookupSource::Footer, lookup.source);
+    }
+}
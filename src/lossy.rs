@@ -0,0 +1,410 @@
+//! Salvaging what can be read from damaged TZif data.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use std::io::Read;
+
+use crate::leap::check_leap_seconds;
+use crate::parse::{read_footer, Cursor, SliceReader};
+use crate::{
+    Header, IsStd, IsUT, LeapSecondRecord, LocalTimeTypeRecord, ParseError, ParseOptions,
+    TimeZoneInfo,
+};
+
+/// The most local time types a transition's index can refer to.
+const MAX_TYPES: u32 = 256;
+
+impl TimeZoneInfo {
+    /// Read as much of the TZif data as can be read, rather than failing at the first problem,
+    /// returning the zone salvaged along with each problem met, as the error
+    /// [`parse`](Self::parse) would have failed with. See
+    /// [`parse_slice_lossy`](Self::parse_slice_lossy) for what's kept.
+    #[cfg(feature = "std")]
+    pub fn parse_lossy(mut reader: impl Read) -> (Self, Vec<ParseError>) {
+        let mut data = Vec::new();
+        let read_error = reader
+            .read_to_end(&mut data)
+            .err()
+            .map(|e| ParseError::from_io(data.len() as u64, e));
+        let (tz, mut warnings) = Self::parse_slice_lossy(&data);
+        warnings.splice(0..0, read_error);
+        (tz, warnings)
+    }
+
+    /// Read as much of the in-memory TZif data as can be read, rather than failing at the
+    /// first problem, returning the zone salvaged along with each problem met, as the error
+    /// [`parse_slice`](Self::parse_slice) would have failed with.
+    ///
+    /// Data which ends early keeps what came before the end: transitions with their types,
+    /// local time types, as much of the designation table as there is, and leap seconds, while
+    /// standard/wall and UT/local indicators are dropped unless there's one for every type.
+    /// Transitions out of order or referring to a type which doesn't exist are dropped, as are
+    /// leap seconds from the first invalid one on and indicators of a kind with an invalid
+    /// value; UT/local indicators go with the standard/wall ones they depend on.
+    /// DST indicators other than 0 are taken as DST, and a type marked as both UT and wall
+    /// clock time is taken as local. A version 2+ file whose second header is missing or
+    /// damaged gives its version 1 data.
+    pub fn parse_slice_lossy(data: &[u8]) -> (Self, Vec<ParseError>) {
+        let mut salvage = Salvage {
+            cursor: Cursor::new(data, 0),
+            warnings: Vec::new(),
+            truncated: false,
+        };
+        let Some(hdr) = salvage.header() else {
+            return (Self::default(), salvage.warnings);
+        };
+
+        // An unknown version is taken as the newest known if another header follows the
+        // version 1 data block where a version 2+ file has it.
+        let second_at = Header::SIZE as u64 + hdr.block_len(true);
+        let version = hdr.version().unwrap_or_else(|| {
+            salvage.warnings.push(ParseError::UnsupportedVersion {
+                offset: 4,
+                version: hdr.ver,
+            });
+            let second = usize::try_from(second_at)
+                .ok()
+                .and_then(|at| data.get(at..));
+            if second.is_some_and(|second| second.starts_with(b"TZif")) {
+                4
+            } else {
+                1
+            }
+        });
+
+        let mark = salvage.warnings.len();
+        let mut v1_result = salvage.block(&hdr, 0, true);
+        v1_result.version = version;
+        if version == 1 || salvage.truncated {
+            return (v1_result, salvage.warnings);
+        }
+        let v1_warnings = salvage.warnings.split_off(mark);
+
+        let hdr_offset = salvage.cursor.offset();
+        let hdr = salvage.header().filter(|hdr| &hdr.magic == b"TZif");
+        let Some(hdr) = hdr else {
+            salvage.warnings.extend(v1_warnings);
+            return (v1_result, salvage.warnings);
+        };
+        let mut result = salvage.block(&hdr, hdr_offset, false);
+        result.version = version;
+        if !salvage.truncated {
+            let pos = salvage.cursor.offset() as usize;
+            let strict = ParseOptions {
+                strict: true,
+                ..ParseOptions::default()
+            };
+            let footer = read_footer(&mut SliceReader { data, pos }, &strict);
+            (result.footer, result.raw_footer) = footer.unwrap_or_else(|e| {
+                salvage.warnings.push(e);
+                let lenient = &ParseOptions::default();
+                read_footer(&mut SliceReader { data, pos }, lenient).unwrap_or_default()
+            });
+        }
+        (result, salvage.warnings)
+    }
+}
+
+/// Reads what it can from TZif data, noting each problem.
+struct Salvage<'a> {
+    cursor: Cursor<'a>,
+    warnings: Vec<ParseError>,
+    /// Whether the data has ended, so that nothing more can be read.
+    truncated: bool,
+}
+
+impl<'a> Salvage<'a> {
+    /// Read something, unless the data has ended, in which case nothing more is read.
+    fn read<T>(
+        &mut self,
+        read: impl FnOnce(&mut Cursor<'a>) -> Result<T, ParseError>,
+    ) -> Option<T> {
+        if self.truncated {
+            return None;
+        }
+        match read(&mut self.cursor) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.truncated = true;
+                self.warnings.push(e);
+                None
+            }
+        }
+    }
+
+    /// Read up to `count` items of `size` bytes each, with their offsets, for as long as the
+    /// data lasts.
+    fn items<T>(
+        &mut self,
+        count: u32,
+        size: usize,
+        mut read: impl FnMut(&mut Cursor<'a>) -> Result<T, ParseError>,
+    ) -> Vec<(u64, T)> {
+        let mut items = Vec::with_capacity(self.cursor.capacity_for(count, size));
+        for _ in 0..count {
+            let offset = self.cursor.offset();
+            match self.read(&mut read) {
+                Some(item) => items.push((offset, item)),
+                None => break,
+            }
+        }
+        items
+    }
+
+    fn header(&mut self) -> Option<Header> {
+        let offset = self.cursor.offset();
+        let bytes = self.read(|c| c.bytes(Header::SIZE))?;
+        let hdr = Header::from_array(bytes.try_into().ok()?);
+        if &hdr.magic != b"TZif" {
+            self.warnings.push(ParseError::BadMagic { offset });
+        }
+        Some(hdr)
+    }
+
+    /// Read the data block `hdr` describes, keeping what's usable.
+    fn block(&mut self, hdr: &Header, hdr_offset: u64, v1: bool) -> TimeZoneInfo {
+        let time_size = if v1 { 4 } else { 8 };
+        let mut result = TimeZoneInfo::default();
+
+        let times = self.items(hdr.timecnt, time_size, |c| c.time(v1));
+        let types = self.items(hdr.timecnt, 1, Cursor::u8);
+
+        let typecnt = if hdr.typecnt > MAX_TYPES {
+            self.warnings.push(ParseError::LimitExceeded {
+                offset: hdr_offset + 36,
+                field: "typecnt",
+                value: hdr.typecnt,
+                limit: MAX_TYPES,
+            });
+            MAX_TYPES
+        } else {
+            hdr.typecnt
+        };
+        let records_offset = self.cursor.offset();
+        let records = self.items(typecnt, 6, |c| Ok((c.i32()?, c.u8()?, c.u8()?)));
+        for (offset, (ut_off_secs, is_dst, desig_idx)) in records {
+            if is_dst > 1 {
+                self.warnings.push(ParseError::InvalidDstIndicator {
+                    offset: offset + 4,
+                    value: is_dst,
+                });
+            }
+            result.local_time_types.push(LocalTimeTypeRecord {
+                ut_off_secs,
+                is_dst: is_dst != 0,
+                desig_idx,
+            });
+        }
+        let extra = (hdr.typecnt - typecnt) as usize * 6;
+        self.read(|c| c.bytes(extra));
+
+        let desig_offset = self.cursor.offset();
+        if !self.truncated {
+            let len = self.cursor.remaining().min(hdr.charcnt as usize);
+            result.time_zone_designations =
+                self.read(|c| c.bytes(len)).unwrap_or_default().to_vec();
+            // Note where the data ends, if it's within the table.
+            if len < hdr.charcnt as usize {
+                self.read(|c| c.bytes(1));
+            }
+        }
+        for (i, typ) in result.local_time_types.iter().enumerate() {
+            let idx = usize::from(typ.desig_idx);
+            match result.designation_bytes(idx) {
+                Some(desig) if core::str::from_utf8(desig).is_err() => {
+                    self.warnings.push(ParseError::InvalidDesignation {
+                        offset: desig_offset + idx as u64,
+                    });
+                }
+                None if !result.time_zone_designations.is_empty() => {
+                    self.warnings.push(ParseError::DesignationOutOfRange {
+                        offset: records_offset + i as u64 * 6 + 5,
+                        index: typ.desig_idx,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let leaps = self.items(hdr.leapcnt, time_size + 4, |c| Ok((c.time(v1)?, c.i32()?)));
+        result.leap_second_records =
+            Vec::from_iter(leaps.iter().map(|&(_, (occurrence, correction))| {
+                LeapSecondRecord::new(occurrence, correction)
+            }));
+        if let Err(idx) = check_leap_seconds(&result.leap_second_records, hdr.ver >= b'4') {
+            self.warnings.push(ParseError::InvalidLeapSecond {
+                offset: leaps[idx].0,
+            });
+            result.leap_second_records.truncate(idx);
+        }
+
+        result.is_std = self.indicators(
+            hdr,
+            hdr_offset + 24,
+            "isstdcnt",
+            hdr.isstdcnt,
+            |offset, b| match b {
+                0 => Ok(IsStd::Wall),
+                1 => Ok(IsStd::Standard),
+                value => Err(ParseError::InvalidStdWallIndicator { offset, value }),
+            },
+        );
+        let isut_offset = self.cursor.offset();
+        result.is_ut = self.indicators(
+            hdr,
+            hdr_offset + 20,
+            "isutcnt",
+            hdr.isutcnt,
+            |offset, b| match b {
+                0 => Ok(IsUT::Local),
+                1 => Ok(IsUT::UT),
+                value => Err(ParseError::InvalidUtLocalIndicator { offset, value }),
+            },
+        );
+        // Without the standard/wall indicators, UT ones would be taken as wall clock time.
+        if result.is_std.len() != hdr.isstdcnt as usize {
+            result.is_ut.clear();
+        }
+        for (i, is_ut) in result.is_ut.iter_mut().enumerate() {
+            let is_std = result.is_std.get(i).unwrap_or(&IsStd::Wall);
+            if (is_std, &*is_ut) == (&IsStd::Wall, &IsUT::UT) {
+                self.warnings.push(ParseError::UniversalWall {
+                    offset: isut_offset + i as u64,
+                });
+                *is_ut = IsUT::Local;
+            }
+        }
+
+        for (&(offset, at), &(types_offset, typ)) in times.iter().zip(&types) {
+            if usize::from(typ) >= result.local_time_types.len() {
+                self.warnings.push(ParseError::TransitionTypeOutOfRange {
+                    offset: types_offset,
+                    index: typ,
+                });
+            } else if result
+                .transition_times
+                .last()
+                .is_some_and(|&prev| prev >= at)
+            {
+                self.warnings
+                    .push(ParseError::UnsortedTransitions { offset });
+            } else {
+                result.transition_times.push(at);
+                result.transition_types.push(typ);
+            }
+        }
+        result
+    }
+
+    /// Read an array of indicators, giving none unless there's a valid one for every local
+    /// time type.
+    fn indicators<T>(
+        &mut self,
+        hdr: &Header,
+        count_offset: u64,
+        field: &'static str,
+        count: u32,
+        decode: impl Fn(u64, u8) -> Result<T, ParseError>,
+    ) -> Vec<T> {
+        let values = self.items(count, 1, Cursor::u8);
+        if count != 0 && count != hdr.typecnt {
+            self.warnings.push(ParseError::CountMismatch {
+                offset: count_offset,
+                field,
+            });
+            return Vec::new();
+        }
+        if values.len() < count as usize {
+            return Vec::new();
+        }
+        let decoded: Result<Vec<T>, ParseError> = values
+            .into_iter()
+            .map(|(offset, value)| decode(offset, value))
+            .collect();
+        decoded.unwrap_or_else(|e| {
+            self.warnings.push(e);
+            Vec::new()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    /// Where the second header starts in `sample()`'s TZif data.
+    fn second_header() -> usize {
+        let bytes = sample().to_bytes();
+        Header::SIZE + Header::from_array(bytes[..44].try_into().unwrap()).block_len(true) as usize
+    }
+
+    #[test]
+    fn test_parse_lossy() {
+        let mut tz = sample();
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        let bytes = tz.to_bytes();
+        let (parsed, warnings) = TimeZoneInfo::parse_lossy(&bytes[..]);
+        assert_eq!(tz, parsed);
+        assert!(warnings.is_empty(), "{warnings:?}");
+
+        // In the second block: a header, then 3 transitions of 9 bytes, 2 types of 6 bytes,
+        // 8 bytes of designations and 2 of each indicator.
+        let block = second_header() + Header::SIZE;
+        let indicators = block + 3 * 9 + 2 * 6 + 8;
+
+        // Cut off in the UT/local indicators, which go, along with the footer after them.
+        let (parsed, warnings) = TimeZoneInfo::parse_slice_lossy(&bytes[..indicators + 3]);
+        assert_eq!(tz.transition_times, parsed.transition_times);
+        assert_eq!(tz.local_time_types, parsed.local_time_types);
+        assert_eq!(tz.is_std, parsed.is_std);
+        assert!(parsed.is_ut.is_empty());
+        assert_eq!(None, parsed.footer);
+        assert!(matches!(
+            warnings[..],
+            [ParseError::Truncated { offset }] if offset as usize == indicators + 3
+        ));
+
+        // Cut off in the designations, which are kept as far as they go.
+        let (parsed, warnings) = TimeZoneInfo::parse_slice_lossy(&bytes[..indicators - 2]);
+        assert_eq!(b"PST\0PD", &parsed.time_zone_designations[..]);
+        assert_eq!(Some("PD"), parsed.designation(1));
+        assert_eq!(3, parsed.transition_times.len());
+        assert_eq!(1, warnings.len());
+
+        // A bad type index, an invalid indicator and a broken footer.
+        let mut bad = bytes.clone();
+        bad[block + 3 * 8 + 1] = 7;
+        bad[indicators] = 2;
+        bad[indicators + 4 + 1] = b'!';
+        let (parsed, warnings) = TimeZoneInfo::parse_slice_lossy(&bad);
+        assert_eq!(vec![100, 300], parsed.transition_times);
+        assert!(parsed.is_std.is_empty() && parsed.is_ut.is_empty());
+        assert_eq!(None, parsed.footer);
+        assert_eq!(Some("!ST8PDT,M3.2.0,M11.1.0"), parsed.raw_footer.as_deref());
+        assert!(matches!(
+            warnings[..],
+            [
+                ParseError::InvalidStdWallIndicator { value: 2, .. },
+                ParseError::TransitionTypeOutOfRange { index: 7, .. },
+                ParseError::InvalidFooter { .. },
+            ]
+        ));
+
+        // A damaged second header gives the version 1 data.
+        let mut bad = bytes.clone();
+        bad[second_header()] = b'X';
+        let (parsed, warnings) = TimeZoneInfo::parse_slice_lossy(&bad);
+        assert_eq!(tz.transition_times, parsed.transition_times);
+        assert_eq!(None, parsed.footer);
+        assert!(matches!(warnings[..], [ParseError::BadMagic { .. }]));
+
+        let (parsed, warnings) = TimeZoneInfo::parse_slice_lossy(b"TZif");
+        assert_eq!(TimeZoneInfo::default(), parsed);
+        assert!(matches!(
+            warnings[..],
+            [ParseError::Truncated { offset: 0 }]
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+//! Parsing TZif files by mapping them into memory with the `memmap2` crate.
+
+#![allow(unsafe_code)]
+
+use std::fs::File;
+use std::path::Path;
+
+use ::memmap2::Mmap;
+
+use crate::{ParseOptions, TimeZoneInfo, ZoneError};
+
+impl TimeZoneInfo {
+    /// Parse the TZif file at `path` by mapping it into memory and parsing it in place, as
+    /// [`parse_slice`](Self::parse_slice) does, rather than reading it into a buffer.
+    ///
+    /// The file must not be changed while it's being parsed; zoneinfo files are normally
+    /// replaced whole rather than changed in place, which is safe.
+    pub fn parse_mmap(path: impl AsRef<Path>) -> Result<Self, ZoneError> {
+        Self::parse_mmap_with(path, &ParseOptions::default())
+    }
+
+    pub fn parse_mmap_with(path: impl AsRef<Path>, opts: &ParseOptions) -> Result<Self, ZoneError> {
+        let file = File::open(path).map_err(ZoneError::Io)?;
+        if file.metadata().map_err(ZoneError::Io)?.len() == 0 {
+            // Empty files can't be mapped on every platform.
+            return Ok(Self::parse_slice(&[], opts)?);
+        }
+        // SAFETY: the mapping is only read, and only while the file isn't changed, as the
+        // caller is told to ensure.
+        let map = unsafe { Mmap::map(&file) }.map_err(ZoneError::Io)?;
+        Ok(Self::parse_slice(&map, opts)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use crate::ParseError;
+    use std::fs;
+
+    #[test]
+    fn test_parse_mmap() {
+        let path = std::env::temp_dir().join(format!("tzif-mmap-test-{}", std::process::id()));
+        fs::write(&path, sample().to_bytes()).unwrap();
+        assert_eq!(sample(), TimeZoneInfo::parse_mmap(&path).unwrap());
+        let opts = ParseOptions {
+            max_timecnt: 2,
+            ..ParseOptions::default()
+        };
+        assert!(matches!(
+            TimeZoneInfo::parse_mmap_with(&path, &opts),
+            Err(ZoneError::Parse(ParseError::LimitExceeded { .. }))
+        ));
+        fs::write(&path, b"").unwrap();
+        assert!(matches!(
+            TimeZoneInfo::parse_mmap(&path),
+            Err(ZoneError::Parse(ParseError::Truncated { offset: 0 }))
+        ));
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            TimeZoneInfo::parse_mmap(&path),
+            Err(ZoneError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,165 @@
+//! Removing redundancy from a zone without changing what it means.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::{IsStd, IsUT, LocalTimeTypeRecord, TimeZoneInfo};
+
+/// Everything about a local time type which is observable.
+#[derive(PartialEq)]
+struct TypeKey {
+    ut_off_secs: i32,
+    is_dst: bool,
+    desig: String,
+    is_std: Option<IsStd>,
+    is_ut: Option<IsUT>,
+}
+
+impl TypeKey {
+    /// Whether the two give the same local time, whatever their indicators.
+    fn same_local(&self, other: &Self) -> bool {
+        (self.ut_off_secs, self.is_dst, &self.desig)
+            == (other.ut_off_secs, other.is_dst, &other.desig)
+    }
+}
+
+impl TimeZoneInfo {
+    /// Remove redundant data, leaving the local time in effect at every instant the same.
+    ///
+    /// Local time types which are identical, indicators included, are merged, and transitions
+    /// to the same offset, DST flag and designation as the type already in effect are dropped,
+    /// except for the last transition of a zone with a footer, which says when the footer takes
+    /// over. Types nothing refers to any more are then removed, apart from the first, which is
+    /// in effect before the first transition, and the designation table is rebuilt with just the
+    /// designations still in use.
+    pub fn normalize(&mut self) {
+        if self.local_time_types.is_empty() {
+            return;
+        }
+
+        // The first type identical to each.
+        let keys: Vec<TypeKey> = (0..self.local_time_types.len())
+            .map(|idx| {
+                let local = self.local_time_type(idx);
+                TypeKey {
+                    ut_off_secs: local.ut_offset_secs,
+                    is_dst: local.is_dst,
+                    desig: local.desig.to_string(),
+                    is_std: self.is_std.get(idx).copied(),
+                    is_ut: self.is_ut.get(idx).copied(),
+                }
+            })
+            .collect();
+        let canonical: Vec<usize> = keys
+            .iter()
+            .map(|key| keys.iter().position(|other| other == key).unwrap())
+            .collect();
+
+        let last = self.transition_times.len().checked_sub(1);
+        let mut prev = canonical[0];
+        let mut transitions = Vec::with_capacity(self.transition_times.len());
+        for (idx, (&at, &typ)) in self
+            .transition_times
+            .iter()
+            .zip(&self.transition_types)
+            .enumerate()
+        {
+            let typ = canonical[typ as usize];
+            let needed = self.footer.is_some() && Some(idx) == last;
+            if keys[prev].same_local(&keys[typ]) && !needed {
+                continue;
+            }
+            transitions.push((at, typ));
+            prev = typ;
+        }
+
+        // The types still in use, in their original order, and their new indices.
+        let mut remap = alloc::vec![None; keys.len()];
+        let mut used: Vec<usize> = core::iter::once(canonical[0])
+            .chain(transitions.iter().map(|&(_, typ)| typ))
+            .collect();
+        used.sort_unstable();
+        used.dedup();
+        for (new, &old) in used.iter().enumerate() {
+            remap[old] = Some(new as u8);
+        }
+
+        self.time_zone_designations.clear();
+        self.local_time_types = Vec::with_capacity(used.len());
+        for &old in &used {
+            let key = &keys[old];
+            // The new table is never larger than the old one, so this always fits.
+            let desig_idx = self.find_or_add_designation(&key.desig).unwrap_or(0);
+            self.local_time_types.push(LocalTimeTypeRecord {
+                ut_off_secs: key.ut_off_secs,
+                is_dst: key.is_dst,
+                desig_idx,
+            });
+        }
+        if !self.is_std.is_empty() {
+            self.is_std = used
+                .iter()
+                .filter_map(|&old| self.is_std.get(old).copied())
+                .collect();
+        }
+        if !self.is_ut.is_empty() {
+            self.is_ut = used
+                .iter()
+                .filter_map(|&old| self.is_ut.get(old).copied())
+                .collect();
+        }
+
+        self.transition_times = transitions.iter().map(|&(at, _)| at).collect();
+        self.transition_types = transitions
+            .iter()
+            .map(|&(_, typ)| remap[typ].unwrap())
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::sample;
+    use crate::{LocalTimeTypeRecord, TimeZoneInfo};
+
+    /// The offset, DST flag and designation in effect at each time from 0 to 500.
+    fn observed(tz: &TimeZoneInfo) -> Vec<Option<(i32, bool, String)>> {
+        (0..500)
+            .step_by(10)
+            .map(|t| tz.local_time_type_at(t))
+            .map(|l| l.map(|l| (l.ut_offset_secs, l.is_dst, l.desig.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut tz = sample();
+        // A copy of PDT with its own designation, and transitions which change nothing.
+        tz.time_zone_designations.extend_from_slice(b"XXX\0PDT\0");
+        tz.local_time_types.push(LocalTimeTypeRecord {
+            ut_off_secs: -7 * 3600,
+            is_dst: true,
+            desig_idx: 12,
+        });
+        tz.is_std.push(tz.is_std[1]);
+        tz.is_ut.push(tz.is_ut[1]);
+        tz.transition_times = vec![50, 100, 150, 200, 300, 400];
+        tz.transition_types = vec![0, 1, 2, 0, 2, 1];
+        let expected = observed(&tz);
+
+        tz.normalize();
+        assert_eq!(vec![100, 200, 300], tz.transition_times);
+        assert_eq!(vec![1, 0, 1], tz.transition_types);
+        assert_eq!(2, tz.local_time_types.len());
+        assert_eq!(b"PST\0PDT\0", &tz.time_zone_designations[..]);
+        assert_eq!(2, tz.is_std.len());
+        assert_eq!(expected, observed(&tz));
+
+        // The last transition stays when a footer takes over after it.
+        tz.transition_times.push(400);
+        tz.transition_types.push(1);
+        tz.footer = Some("PST8".parse().unwrap());
+        tz.normalize();
+        assert_eq!(vec![100, 200, 300, 400], tz.transition_times);
+    }
+}
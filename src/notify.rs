@@ -0,0 +1,109 @@
+//! Watching a zoneinfo directory for changes with the `notify` crate.
+
+use std::io;
+use std::path::Path;
+
+use ::notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{ZoneDatabase, ZoneError};
+
+/// Keeps a [`ZoneDatabase`]'s cache up to date with its directory, from
+/// [`ZoneDatabase::watch`]. Dropping it stops watching.
+#[derive(Debug)]
+pub struct ZoneWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl ZoneDatabase {
+    /// Watch the database's directory, so that when a file in it changes, the cached zone it
+    /// holds is parsed again and swapped in. Handles given out before then keep the old
+    /// version. A zone which can't be loaded any more is dropped from the cache, and loading
+    /// it again reports why.
+    ///
+    /// Changes are picked up on a background thread, shortly after they happen. Only
+    /// databases opened on a directory can be watched.
+    pub fn watch(&self) -> Result<ZoneWatcher, ZoneError> {
+        let root = self.root().ok_or_else(|| {
+            ZoneError::Io(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "only a directory database can be watched",
+            ))
+        })?;
+        // Events carry absolute paths.
+        let root = root.canonicalize().map_err(ZoneError::Io)?;
+        let db = self.clone();
+        let watch_root = root.clone();
+        let mut watcher = ::notify::recommended_watcher(move |event: ::notify::Result<Event>| {
+            let Ok(event) = event else {
+                // Events may have been missed, so nothing cached can be trusted.
+                db.reload_cached(|_| true);
+                return;
+            };
+            for path in &event.paths {
+                reload(&db, &watch_root, path);
+            }
+        })
+        .map_err(|e| ZoneError::Io(io::Error::other(e)))?;
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .map_err(|e| ZoneError::Io(io::Error::other(e)))?;
+        Ok(ZoneWatcher { _watcher: watcher })
+    }
+}
+
+/// Reload the cached zones a change to `path` may affect: the zone at that path, or every zone
+/// under it if it's a directory.
+fn reload(db: &ZoneDatabase, root: &Path, path: &Path) {
+    let Some(name) = path.strip_prefix(root).ok().and_then(Path::to_str) else {
+        return;
+    };
+    let name = name.replace(std::path::MAIN_SEPARATOR, "/");
+    if name.is_empty() {
+        db.reload_cached(|_| true);
+    } else {
+        db.reload_cached(|cached| {
+            cached == name
+                || cached
+                    .strip_prefix(&*name)
+                    .is_some_and(|s| s.starts_with('/'))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use std::fs;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_watch() {
+        let root = std::env::temp_dir().join(format!("tzif-watch-test-{}", std::process::id()));
+        fs::create_dir_all(root.join("Test")).unwrap();
+        fs::write(root.join("Test/Sample"), sample().to_bytes()).unwrap();
+        let db = ZoneDatabase::open(&root);
+        let before = db.get("Test/Sample").unwrap();
+        let _watcher = db.watch().unwrap();
+
+        let mut changed = sample();
+        changed.transition_times.pop();
+        changed.transition_types.pop();
+        fs::write(root.join("Test/Sample"), changed.to_bytes()).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while db.get("Test/Sample").unwrap().transition_times.len() == 3 {
+            assert!(Instant::now() < deadline, "change not picked up");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(2, db.get("Test/Sample").unwrap().transition_times.len());
+        assert_eq!(3, before.transition_times.len());
+
+        assert!(
+            ZoneDatabase::from_android_bytes(crate::android::tests::build(&[]))
+                .unwrap()
+                .watch()
+                .is_err()
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+//! A table of UT offsets laid out for looking up many timestamps quickly.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{PosixTz, TimeZoneInfo};
+
+/// The UT offsets of a zone, precomputed for fast lookups, from
+/// [`TimeZoneInfo::offset_table`].
+///
+/// The times the offset changes are held in one sorted array and the offset from each in
+/// another, so a lookup is a binary search over contiguous `i64`s and a single read. Changes
+/// the footer gives are worked out ahead of time for the range the table was made for; outside
+/// it, lookups which fall to the footer compute it as [`TimeZoneInfo::offset_at`] does, so they
+/// give the same answers, only more slowly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OffsetTable {
+    times: Vec<i64>,
+    offsets: Vec<i32>,
+    /// The offset before the first change.
+    initial: Option<i32>,
+    /// Where the footer takes over from the transitions: the last transition time, or the
+    /// earliest time if there are none.
+    footer_from: i64,
+    footer: Option<PosixTz>,
+    range: Range<i64>,
+}
+
+impl TimeZoneInfo {
+    /// The UT offset in effect at the given UT timestamp, in seconds, if the zone has any local
+    /// time types. This is the offset of [`local_time_type_at`](Self::local_time_type_at)
+    /// without looking up its designation.
+    pub fn offset_at(&self, ut: i64) -> Option<i32> {
+        if let Some(footer) = &self.footer {
+            if self.transition_times.last().is_none_or(|&last| ut >= last) {
+                return Some(footer.local_time_type_at(ut).ut_offset_secs);
+            }
+        }
+        let idx = self.type_idx_at(ut)?;
+        self.local_time_types.get(idx).map(|typ| typ.ut_off_secs)
+    }
+
+    /// Precompute the zone's UT offsets for looking up many timestamps, with the footer's
+    /// changes expanded for UT times in `range`.
+    pub fn offset_table(&self, range: Range<i64>) -> OffsetTable {
+        let initial = self.local_time_types.first().map(|typ| typ.ut_off_secs);
+        let footer_from = self.transition_times.last().copied().unwrap_or(i64::MIN);
+        let mut times = self.transition_times.clone();
+        let mut offsets: Vec<i32> = self
+            .transition_types
+            .iter()
+            .map(|&typ| {
+                self.local_time_types
+                    .get(usize::from(typ))
+                    .map_or(0, |typ| typ.ut_off_secs)
+            })
+            .collect();
+
+        if let Some(footer) = &self.footer {
+            // The footer applies from the last transition on, even where it disagrees with it.
+            if let Some(last) = offsets.last_mut() {
+                *last = footer.local_time_type_at(footer_from).ut_offset_secs;
+            }
+            if range.start < range.end {
+                let start = range.start.max(footer_from);
+                if times.last().is_none_or(|&last| start > last) {
+                    times.push(start);
+                    offsets.push(footer.local_time_type_at(start).ut_offset_secs);
+                }
+                for (at, _, after) in self.changes_between(start, range.end - 1) {
+                    if at > footer_from {
+                        times.push(at);
+                        offsets.push(after.ut_offset_secs);
+                    }
+                }
+            }
+        }
+
+        OffsetTable {
+            times,
+            offsets,
+            initial,
+            footer_from,
+            footer: self.footer.clone(),
+            range,
+        }
+    }
+}
+
+impl OffsetTable {
+    /// The UT offset in effect at the given UT timestamp, in seconds, as
+    /// [`TimeZoneInfo::offset_at`] gives it.
+    #[inline]
+    pub fn offset_at(&self, ut: i64) -> Option<i32> {
+        if let Some(footer) = &self.footer {
+            if ut >= self.footer_from && !self.range.contains(&ut) {
+                return Some(footer.local_time_type_at(ut).ut_offset_secs);
+            }
+        }
+        match self.times.partition_point(|&t| t <= ut) {
+            0 => self.initial,
+            idx => Some(self.offsets[idx - 1]),
+        }
+    }
+
+    /// The number of changes of offset in the table, including the footer's which were
+    /// precomputed.
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_offset_table() {
+        let tz = sample();
+        let table = tz.offset_table(0..1000);
+        assert_eq!(3, table.len());
+        for ut in [i64::MIN, -1, 0, 99, 100, 199, 200, 300, 1 << 40, i64::MAX] {
+            assert_eq!(tz.offset_at(ut), table.offset_at(ut), "{ut}");
+            assert_eq!(
+                tz.local_time_type_at(ut).map(|local| local.ut_offset_secs),
+                tz.offset_at(ut)
+            );
+        }
+
+        // 2000 to 2030, with the footer taking over from 2010.
+        let mut tz = sample();
+        tz.transition_times[2] = 1_262_304_000;
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        let range = 946_684_800..1_893_456_000;
+        let table = tz.offset_table(range.clone());
+        assert_eq!(3 + 2 * 20, table.len());
+        let posix = TimeZoneInfo::from_posix("EST5EDT,M3.2.0,M11.1.0".parse().unwrap());
+        let posix_table = posix.offset_table(range);
+        for ut in (0..2_000_000_000).step_by(3_599) {
+            assert_eq!(tz.offset_at(ut), table.offset_at(ut), "{ut}");
+            assert_eq!(posix.offset_at(ut), posix_table.offset_at(ut), "{ut}");
+        }
+        assert_eq!(Some(-7 * 3600), table.offset_at(1_720_000_000));
+        assert_eq!(Some(-5 * 3600), posix_table.offset_at(1_700_000_000));
+    }
+}
@@ -0,0 +1,748 @@
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::leap::check_leap_seconds;
+use crate::{
+    Header, IsStd, IsUT, LeapSecondRecord, LocalTimeTypeRecord, ParseError, PosixTz, TimeZoneInfo,
+};
+
+/// The longest footer TZ string accepted. Real ones are well under this.
+pub(crate) const MAX_FOOTER_LEN: usize = 1024;
+
+/// The most memory set aside for a data block before reading it. Beyond this, buffers grow as
+/// input actually arrives, so a short file whose header claims a huge block fails as truncated
+/// without allocating for what it claims.
+#[cfg(feature = "std")]
+const MAX_PREALLOC: usize = 64 * 1024;
+
+/// Options controlling how TZif data is parsed.
+///
+/// The default limits are well above anything tzdata produces. Whatever the limits, memory is
+/// only allocated for as much data as the input actually holds, so parsing untrusted input is
+/// safe with any of them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseOptions {
+    /// Maximum number of transition times accepted from a header.
+    pub max_timecnt: u32,
+
+    /// Maximum number of local time type records accepted from a header.
+    pub max_typecnt: u32,
+
+    /// Maximum number of bytes of time zone designations accepted from a header.
+    pub max_charcnt: u32,
+
+    /// Maximum number of leap-second records accepted from a header.
+    pub max_leapcnt: u32,
+
+    /// Require strict conformance to RFC 8536.
+    ///
+    /// When set, zero `typecnt` or `charcnt`, unsorted transition times, and designations which
+    /// are out of range, not NUL-terminated or not made of the characters RFC 8536 recommends
+    /// are rejected, and a version 2+ file whose second data block fails to parse is an error.
+    /// When unset, these are accepted, and a broken second data block falls back to the version
+    /// 1 data.
+    pub strict: bool,
+
+    /// Check that a version 2+ file's version 1 data block agrees with the second data block.
+    ///
+    /// When set, the version 1 data must be the second block's transitions and leap seconds
+    /// which fit in 32 bits, or none at all, or parsing fails with
+    /// [`ParseError::BlockMismatch`] listing every way they differ; a second data block which
+    /// fails to parse is an error too, rather than falling back to the version 1 data. This
+    /// catches broken writers, which readers that only look at one block would not notice.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub check_v1: bool,
+
+    /// Accept version bytes `'5'` to `'9'`, from versions of TZif newer than this crate.
+    ///
+    /// When set, such files are read as version 4, the newest this crate knows, on the
+    /// assumption that later versions extend it as each before has extended the last. The
+    /// result's [`version`](TimeZoneInfo::version) is the one the file gives, so writing it
+    /// back keeps the version byte; [`to_version`](TimeZoneInfo::to_version) brings it down to
+    /// a known one. When unset, such files fail with [`ParseError::UnsupportedVersion`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lenient_version: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_timecnt: 1 << 20,
+            max_typecnt: 256,
+            max_charcnt: 1 << 16,
+            max_leapcnt: 1 << 16,
+            strict: false,
+            check_v1: false,
+            lenient_version: false,
+        }
+    }
+}
+
+/// Whether a designation follows RFC 8536's recommendation of three to six ASCII alphanumerics,
+/// '-' or '+', which POSIX TZ strings need.
+pub(crate) fn is_rfc_designation(desig: &[u8]) -> bool {
+    (3..=6).contains(&desig.len())
+        && desig
+            .iter()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'+'))
+}
+
+/// A source of TZif bytes which keeps track of the current byte offset, for error reporting.
+pub(crate) trait Source {
+    /// Read the next `len` bytes of input. Fewer bytes are returned only if the input ends.
+    fn read_block(&mut self, len: usize) -> Result<Cow<'_, [u8]>, ParseError>;
+
+    fn offset(&self) -> u64;
+}
+
+/// Wraps a reader and keeps track of the current byte offset.
+#[cfg(feature = "std")]
+pub(crate) struct OffsetReader<R> {
+    pub inner: R,
+    pub offset: u64,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> Source for OffsetReader<R> {
+    fn read_block(&mut self, len: usize) -> Result<Cow<'_, [u8]>, ParseError> {
+        let mut buf = Vec::with_capacity(len.min(MAX_PREALLOC));
+        (&mut self.inner)
+            .take(len as u64)
+            .read_to_end(&mut buf)
+            .map_err(|e| ParseError::from_io(self.offset, e))?;
+        self.offset += buf.len() as u64;
+        Ok(Cow::Owned(buf))
+    }
+
+    fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Seek> OffsetReader<R> {
+    /// Seek to the given offset, which is relative to where the reader was when parsing began.
+    pub fn seek(&mut self, offset: u64) -> Result<(), ParseError> {
+        let delta = offset as i64 - self.offset as i64;
+        self.inner
+            .seek(SeekFrom::Current(delta))
+            .map_err(|e| ParseError::from_io(self.offset, e))?;
+        self.offset = offset;
+        Ok(())
+    }
+}
+
+/// Reads from an in-memory buffer without copying.
+pub(crate) struct SliceReader<'a> {
+    pub data: &'a [u8],
+    pub pos: usize,
+}
+
+impl Source for SliceReader<'_> {
+    fn read_block(&mut self, len: usize) -> Result<Cow<'_, [u8]>, ParseError> {
+        let start = self.pos;
+        self.pos = self.data.len().min(start.saturating_add(len));
+        Ok(Cow::Borrowed(&self.data[start..self.pos]))
+    }
+
+    fn offset(&self) -> u64 {
+        self.pos as u64
+    }
+}
+
+/// Decodes values from a block of bytes which was read from the input at offset `base`.
+pub(crate) struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+    base: u64,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(data: &'a [u8], base: u64) -> Self {
+        Self { data, pos: 0, base }
+    }
+
+    /// Input offset of the next byte to be decoded.
+    pub fn offset(&self) -> u64 {
+        self.base + self.pos as u64
+    }
+
+    /// How many of `count` items of `size` bytes each there's input left for, to reserve space
+    /// for no more than can actually be decoded.
+    pub fn capacity_for(&self, count: u32, size: usize) -> usize {
+        (count as usize).min((self.data.len() - self.pos) / size)
+    }
+
+    /// How many bytes of input are left.
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        let src = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or(ParseError::Truncated {
+                offset: self.offset(),
+            })?;
+        self.pos += len;
+        Ok(src)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ParseError> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.bytes(N)?);
+        Ok(buf)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, ParseError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    pub fn i32(&mut self) -> Result<i32, ParseError> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    pub fn time(&mut self, v1: bool) -> Result<i64, ParseError> {
+        if v1 {
+            Ok(i64::from(self.i32()?))
+        } else {
+            Ok(i64::from_be_bytes(self.array()?))
+        }
+    }
+}
+
+impl Header {
+    /// Check the header's magic, version and counts, returning the version number.
+    pub(crate) fn validate(&self, hdr_offset: u64, opts: &ParseOptions) -> Result<u8, ParseError> {
+        if &self.magic != b"TZif" {
+            return Err(ParseError::BadMagic { offset: hdr_offset });
+        }
+
+        if self.isstdcnt != 0 && self.isstdcnt != self.typecnt {
+            return Err(ParseError::CountMismatch {
+                offset: hdr_offset + 24,
+                field: "isstdcnt",
+            });
+        }
+        if self.isutcnt != 0 && self.isutcnt != self.typecnt {
+            return Err(ParseError::CountMismatch {
+                offset: hdr_offset + 20,
+                field: "isutcnt",
+            });
+        }
+
+        for (field, offset, value, limit) in [
+            ("leapcnt", 28, self.leapcnt, opts.max_leapcnt),
+            ("timecnt", 32, self.timecnt, opts.max_timecnt),
+            ("typecnt", 36, self.typecnt, opts.max_typecnt),
+            ("charcnt", 40, self.charcnt, opts.max_charcnt),
+        ] {
+            if value > limit {
+                return Err(ParseError::LimitExceeded {
+                    offset: hdr_offset + offset,
+                    field,
+                    value,
+                    limit,
+                });
+            }
+            if opts.strict && value == 0 && matches!(field, "typecnt" | "charcnt") {
+                return Err(ParseError::ZeroCount {
+                    offset: hdr_offset + offset,
+                    field,
+                });
+            }
+        }
+
+        match self.version() {
+            Some(version) => Ok(version),
+            None if opts.lenient_version && (b'5'..=b'9').contains(&self.ver) => {
+                Ok(self.ver - b'0')
+            }
+            None => Err(ParseError::UnsupportedVersion {
+                offset: hdr_offset + 4,
+                version: self.ver,
+            }),
+        }
+    }
+
+    /// The version number the header's version byte gives, from 1 to 4, or `None` if it isn't
+    /// one this crate understands.
+    pub fn version(&self) -> Option<u8> {
+        match self.ver {
+            0 => Some(1),
+            b'2' => Some(2),
+            b'3' => Some(3),
+            b'4' => Some(4),
+            _ => None,
+        }
+    }
+
+    /// Size in bytes of the data block following this header, using 32-bit times if `v1`.
+    pub(crate) fn block_len(&self, v1: bool) -> u64 {
+        let time_size = if v1 { 4 } else { 8 };
+        u64::from(self.timecnt) * (time_size + 1)
+            + u64::from(self.typecnt) * 6
+            + u64::from(self.charcnt)
+            + u64::from(self.leapcnt) * (time_size + 4)
+            + u64::from(self.isstdcnt)
+            + u64::from(self.isutcnt)
+    }
+}
+
+/// Read and decode a header, without validating it.
+pub(crate) fn read_header(reader: &mut impl Source) -> Result<Header, ParseError> {
+    let hdr_offset = reader.offset();
+    let block = reader.read_block(Header::SIZE)?;
+    let mut cursor = Cursor::new(&block, hdr_offset);
+    Ok(Header::from_array(cursor.array()?))
+}
+
+/// Read the header of the newest data block in TZif data, without reading the data blocks: for
+/// a version 2+ file, this seeks past the version 1 data block to the second header.
+///
+/// The header is checked as [`TimeZoneInfo::parse`] would with the default options, so the
+/// counts are those a full parse works from. This is a quick way to index a large tree.
+#[cfg(feature = "std")]
+pub fn peek_header(reader: impl Read + Seek) -> Result<Header, ParseError> {
+    let mut reader = OffsetReader {
+        inner: reader,
+        offset: 0,
+    };
+    let opts = ParseOptions::default();
+    let header = read_header(&mut reader)?;
+    if header.validate(0, &opts)? == 1 {
+        return Ok(header);
+    }
+    let hdr_offset = Header::SIZE as u64 + header.block_len(true);
+    reader.seek(hdr_offset)?;
+    let header = read_header(&mut reader)?;
+    header.validate(hdr_offset, &opts)?;
+    Ok(header)
+}
+
+/// Read the header of the newest data block in in-memory TZif data, like [`peek_header`].
+pub fn peek_header_slice(data: &[u8]) -> Result<Header, ParseError> {
+    let opts = ParseOptions::default();
+    let at = |offset: u64| -> Result<Header, ParseError> {
+        // Past the end, this reads nothing and reports the data as truncated there.
+        let pos = usize::try_from(offset).map_or(data.len(), |pos| pos.min(data.len()));
+        let header = read_header(&mut SliceReader { data, pos })?;
+        header.validate(offset, &opts)?;
+        Ok(header)
+    };
+    let header = at(0)?;
+    if header.version() == Some(1) {
+        return Ok(header);
+    }
+    at(Header::SIZE as u64 + header.block_len(true))
+}
+
+/// Where the TZif record starting at `start` in `data` ends: after its last data block and the
+/// footer following it, if there is one.
+///
+/// A record whose first header is missing or invalid is taken to run to the end of the data, and
+/// one whose second header is missing or invalid to end before that header, so that parsing
+/// reports the problem.
+fn record_end(data: &[u8], start: usize, opts: &ParseOptions) -> usize {
+    // The end of the data block after the header at `offset`, and the header's version.
+    let block_end = |offset: usize, v1: bool| -> Option<(usize, u8)> {
+        let bytes = data.get(offset..)?.get(..Header::SIZE)?;
+        let header = Header::from_array(bytes.try_into().unwrap());
+        let version = header.validate(offset as u64, opts).ok()?;
+        let len = usize::try_from(header.block_len(v1)).ok()?;
+        Some(((offset + Header::SIZE).saturating_add(len), version))
+    };
+    let Some((v1_end, version)) = block_end(start, true) else {
+        return data.len();
+    };
+    if version == 1 {
+        return v1_end.min(data.len());
+    }
+    let Some((end, _)) = block_end(v1_end, false) else {
+        return v1_end.min(data.len());
+    };
+
+    // The footer is a newline, TZ string and newline, and is found as `read_footer` finds it. A
+    // missing one is left for parsing to ignore or reject, and an unterminated one runs on.
+    if data.get(end) != Some(&b'\n') {
+        return end.min(data.len());
+    }
+    data[end + 1..]
+        .iter()
+        .take(MAX_FOOTER_LEN + 2)
+        .position(|&b| b == b'\n')
+        .map_or(data.len(), |len| end + len + 2)
+}
+
+/// Read the footer which follows a version 2+ data block: a TZ string between two newlines.
+///
+/// An empty TZ string gives `None`. A missing or invalid footer is an error if `strict` is set,
+/// and is otherwise ignored. The TZ string is also given as it was read, if that isn't how the
+/// parsed one is written, for [`TimeZoneInfo::raw_footer`].
+pub(crate) fn read_footer(
+    reader: &mut impl Source,
+    opts: &ParseOptions,
+) -> Result<(Option<PosixTz>, Option<String>), ParseError> {
+    let offset = reader.offset();
+    // The longest footer accepted is read in one go, and anything read past its end is ignored,
+    // as nothing follows a footer.
+    let block = reader.read_block(MAX_FOOTER_LEN + 2)?;
+    let tz = match block.split_first() {
+        Some((b'\n', rest)) => rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|end| &rest[..end]),
+        _ => None,
+    };
+    let tz = match tz {
+        Some(tz) => tz,
+        None if opts.strict => return Err(ParseError::InvalidFooter { offset }),
+        None => return Ok((None, None)),
+    };
+    if tz.is_empty() {
+        return Ok((None, None));
+    }
+    let raw = core::str::from_utf8(tz).ok();
+    match raw.and_then(|tz| tz.parse::<PosixTz>().ok()) {
+        Some(tz) => {
+            let raw = raw
+                .filter(|&raw| *raw != tz.to_string())
+                .map(ToOwned::to_owned);
+            Ok((Some(tz), raw))
+        }
+        None if opts.strict => Err(ParseError::InvalidFooter { offset: offset + 1 }),
+        None => Ok((None, raw.map(ToOwned::to_owned))),
+    }
+}
+
+impl TimeZoneInfo {
+    /// Check that every local time type's designation can be read: that its index is within the
+    /// table and it's UTF-8. An empty table, which is only accepted outside strict mode, gives
+    /// every type an empty designation. In strict mode, the table must also end with a NUL, and
+    /// each designation must be made of the characters RFC 8536 recommends.
+    fn check_designations(
+        &self,
+        records_offset: u64,
+        desig_offset: u64,
+        strict: bool,
+    ) -> Result<(), ParseError> {
+        let table = &self.time_zone_designations;
+        if strict && table.last().is_some_and(|&b| b != 0) {
+            return Err(ParseError::UnterminatedDesignations {
+                offset: desig_offset + table.len() as u64 - 1,
+            });
+        }
+        for (i, typ) in self.local_time_types.iter().enumerate() {
+            let idx = usize::from(typ.desig_idx);
+            let Some(desig) = self.designation_bytes(idx) else {
+                if table.is_empty() {
+                    continue;
+                }
+                return Err(ParseError::DesignationOutOfRange {
+                    offset: records_offset + i as u64 * 6 + 5,
+                    index: typ.desig_idx,
+                });
+            };
+            let valid = if strict {
+                is_rfc_designation(desig)
+            } else {
+                core::str::from_utf8(desig).is_ok()
+            };
+            if !valid {
+                return Err(ParseError::InvalidDesignation {
+                    offset: desig_offset + idx as u64,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn parse(reader: impl Read) -> Result<Self, ParseError> {
+        Self::parse_with(reader, &ParseOptions::default())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn parse_with(reader: impl Read, opts: &ParseOptions) -> Result<Self, ParseError> {
+        let mut reader = OffsetReader {
+            inner: reader,
+            offset: 0,
+        };
+        Self::parse_source(&mut reader, opts)
+    }
+
+    /// Parse TZif data from a seekable reader.
+    ///
+    /// For version 2+ files, this skips over the version 1 data block rather than decoding it;
+    /// it is only read if the second data block fails to parse and `strict` is not set.
+    #[cfg(feature = "std")]
+    pub fn parse_seek(reader: impl Read + Seek) -> Result<Self, ParseError> {
+        Self::parse_seek_with(reader, &ParseOptions::default())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn parse_seek_with(
+        reader: impl Read + Seek,
+        opts: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let mut reader = OffsetReader {
+            inner: reader,
+            offset: 0,
+        };
+        let hdr = read_header(&mut reader)?;
+        let version = hdr.validate(0, opts)?;
+        if version == 1 {
+            reader.seek(0)?;
+            return Self::parse_internal(&mut reader, true, opts);
+        }
+        if opts.check_v1 {
+            reader.seek(0)?;
+            return Self::parse_source(&mut reader, opts);
+        }
+
+        reader.seek(Header::SIZE as u64 + hdr.block_len(true))?;
+        match Self::parse_internal(&mut reader, false, opts) {
+            Ok(mut result) => {
+                (result.footer, result.raw_footer) = read_footer(&mut reader, opts)?;
+                Ok(result)
+            }
+            Err(e) if opts.strict => Err(e),
+            Err(_) => {
+                reader.seek(0)?;
+                Self::parse_internal(&mut reader, true, opts)
+            }
+        }
+    }
+
+    /// Parse TZif data from an in-memory buffer. This is available without the `std` feature.
+    pub fn parse_slice(data: &[u8], opts: &ParseOptions) -> Result<Self, ParseError> {
+        let mut reader = SliceReader { data, pos: 0 };
+        Self::parse_source(&mut reader, opts)
+    }
+
+    /// Parse every TZif record in a stream of them, one after another, as some packaging and
+    /// export pipelines produce. The reader is read to its end.
+    ///
+    /// Each record runs from its `TZif` magic to the end of its last data block, or of the
+    /// footer after it for version 2+, and is parsed on its own as
+    /// [`parse_slice`](Self::parse_slice) would. Anything after a record which isn't another
+    /// fails with [`ParseError::BadMagic`]; empty input gives no zones.
+    #[cfg(feature = "std")]
+    pub fn parse_all(reader: impl Read) -> Result<Vec<Self>, ParseError> {
+        Self::parse_all_with(reader, &ParseOptions::default())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn parse_all_with(
+        mut reader: impl Read,
+        opts: &ParseOptions,
+    ) -> Result<Vec<Self>, ParseError> {
+        let mut data = Vec::new();
+        reader
+            .read_to_end(&mut data)
+            .map_err(|e| ParseError::from_io(data.len() as u64, e))?;
+        Self::parse_slice_all(&data, opts)
+    }
+
+    /// Parse every TZif record in in-memory data, like [`parse_all`](Self::parse_all). Error
+    /// offsets are from the start of `data`.
+    pub fn parse_slice_all(data: &[u8], opts: &ParseOptions) -> Result<Vec<Self>, ParseError> {
+        let mut zones = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let end = record_end(data, pos, opts);
+            let mut reader = SliceReader {
+                data: &data[..end],
+                pos,
+            };
+            zones.push(Self::parse_source(&mut reader, opts)?);
+            pos = end;
+        }
+        Ok(zones)
+    }
+
+    fn parse_source(reader: &mut impl Source, opts: &ParseOptions) -> Result<Self, ParseError> {
+        let v1_result = Self::parse_internal(reader, true, opts)?;
+        if v1_result.version == 1 {
+            return Ok(v1_result);
+        }
+        let offset = reader.offset();
+        match Self::parse_internal(reader, false, opts) {
+            Ok(mut result) => {
+                (result.footer, result.raw_footer) = read_footer(reader, opts)?;
+                if opts.check_v1 {
+                    let discrepancies = result.block_discrepancies(&v1_result);
+                    if !discrepancies.is_empty() {
+                        return Err(ParseError::BlockMismatch {
+                            offset,
+                            discrepancies,
+                        });
+                    }
+                }
+                Ok(result)
+            }
+            Err(e) if opts.strict || opts.check_v1 => Err(e),
+            Err(_) => Ok(v1_result),
+        }
+    }
+
+    fn parse_internal(
+        reader: &mut impl Source,
+        v1: bool,
+        opts: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let hdr_offset = reader.offset();
+        let hdr = read_header(reader)?;
+        let version = hdr.validate(hdr_offset, opts)?;
+
+        let block_offset = reader.offset();
+        let block = reader.read_block(hdr.block_len(v1) as usize)?;
+        let mut result =
+            Self::decode_block(&hdr, &mut Cursor::new(&block, block_offset), v1, opts)?;
+        result.version = version;
+        Ok(result)
+    }
+
+    /// Decode the data block described by `hdr`.
+    pub(crate) fn decode_block(
+        hdr: &Header,
+        cursor: &mut Cursor<'_>,
+        v1: bool,
+        opts: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let mut result = Self::default();
+
+        result
+            .transition_times
+            .reserve(cursor.capacity_for(hdr.timecnt, if v1 { 4 } else { 8 }));
+        for _ in 0..hdr.timecnt {
+            let offset = cursor.offset();
+            let t = cursor.time(v1)?;
+            if opts.strict
+                && result
+                    .transition_times
+                    .last()
+                    .is_some_and(|&prev| prev >= t)
+            {
+                return Err(ParseError::UnsortedTransitions { offset });
+            }
+            result.transition_times.push(t);
+        }
+
+        let types_offset = cursor.offset();
+        result.transition_types = cursor.bytes(hdr.timecnt as usize)?.to_vec();
+
+        let records_offset = cursor.offset();
+        result
+            .local_time_types
+            .reserve(cursor.capacity_for(hdr.typecnt, 6));
+        for _ in 0..hdr.typecnt {
+            let ut_off_secs = cursor.i32()?;
+
+            let isdst_offset = cursor.offset();
+            let is_dst = match cursor.u8()? {
+                0 => false,
+                1 => true,
+                value => {
+                    return Err(ParseError::InvalidDstIndicator {
+                        offset: isdst_offset,
+                        value,
+                    })
+                }
+            };
+
+            let record = LocalTimeTypeRecord {
+                ut_off_secs,
+                is_dst,
+                desig_idx: cursor.u8()?,
+            };
+            result.local_time_types.push(record);
+        }
+
+        let desig_offset = cursor.offset();
+        result.time_zone_designations = cursor.bytes(hdr.charcnt as usize)?.to_vec();
+        result.check_designations(records_offset, desig_offset, opts.strict)?;
+
+        let leaps_offset = cursor.offset();
+        result
+            .leap_second_records
+            .reserve(cursor.capacity_for(hdr.leapcnt, if v1 { 8 } else { 12 }));
+        for _ in 0..hdr.leapcnt {
+            let t = cursor.time(v1)?;
+            let off = cursor.i32()?;
+            result
+                .leap_second_records
+                .push(LeapSecondRecord::new(t, off));
+        }
+        let record_len = if v1 { 8 } else { 12 };
+        check_leap_seconds(&result.leap_second_records, hdr.ver >= b'4').map_err(|idx| {
+            ParseError::InvalidLeapSecond {
+                offset: leaps_offset + idx as u64 * record_len,
+            }
+        })?;
+
+        let isstd_offset = cursor.offset();
+        for (i, b) in cursor.bytes(hdr.isstdcnt as usize)?.iter().enumerate() {
+            result.is_std.push(match b {
+                0 => IsStd::Wall,
+                1 => IsStd::Standard,
+                &value => {
+                    return Err(ParseError::InvalidStdWallIndicator {
+                        offset: isstd_offset + i as u64,
+                        value,
+                    })
+                }
+            });
+        }
+
+        let isut_offset = cursor.offset();
+        for (i, b) in cursor.bytes(hdr.isutcnt as usize)?.iter().enumerate() {
+            result.is_ut.push(match b {
+                0 => IsUT::Local,
+                1 => IsUT::UT,
+                &value => {
+                    return Err(ParseError::InvalidUtLocalIndicator {
+                        offset: isut_offset + i as u64,
+                        value,
+                    })
+                }
+            });
+        }
+
+        for i in 0..result.is_std.len().max(result.is_ut.len()) {
+            let is_std = result.is_std.get(i).unwrap_or(&IsStd::Wall);
+            let is_ut = result.is_ut.get(i).unwrap_or(&IsUT::Local);
+            if (is_std, is_ut) == (&IsStd::Wall, &IsUT::UT) {
+                return Err(ParseError::UniversalWall {
+                    offset: isut_offset + i as u64,
+                });
+            }
+        }
+
+        for (i, typ_idx) in result.transition_types.iter().enumerate() {
+            if *typ_idx as usize >= result.local_time_types.len() {
+                return Err(ParseError::TransitionTypeOutOfRange {
+                    offset: types_offset + i as u64,
+                    index: *typ_idx,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl TryFrom<&[u8]> for TimeZoneInfo {
+    type Error = ParseError;
+
+    /// Parse TZif data with the default options, as [`TimeZoneInfo::parse_slice`] does.
+    fn try_from(data: &[u8]) -> Result<Self, ParseError> {
+        Self::parse_slice(data, &ParseOptions::default())
+    }
+}
@@ -0,0 +1,770 @@
+//! POSIX TZ strings, as found in the footer of version 2+ TZif files and in the `TZ`
+//! environment variable.
+//!
+//! A TZ string like `EST5EDT,M3.2.0,M11.1.0` describes a standard time, and optionally a
+//! daylight saving time along with the rules for when it starts and ends each year. Version 3
+//! files extend the syntax to allow rule times which are negative or exceed 24 hours.
+
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::civil::{days_from_civil, days_in_month, is_leap, weekday, SECS_PER_DAY};
+use crate::{IsStd, IsUT, LocalTimeType, LocalTimeTypeRecord, PosixTzError, TimeZoneInfo};
+
+/// A parsed POSIX TZ string.
+///
+/// Offsets here are seconds east of UT, like [`LocalTimeTypeRecord::ut_off_secs`]; note that
+/// the TZ string syntax itself uses the opposite sign.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PosixTz {
+    pub std_abbr: String,
+    pub std_offset: i32,
+    pub dst: Option<PosixDst>,
+}
+
+/// The daylight saving time part of a POSIX TZ string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PosixDst {
+    pub abbr: String,
+    pub offset: i32,
+
+    /// When DST starts each year, in local standard time.
+    pub start: PosixRule,
+
+    /// When DST ends each year, in local daylight saving time.
+    pub end: PosixRule,
+}
+
+/// A yearly transition rule: a day of the year, and a local time of day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PosixRule {
+    pub date: RuleDate,
+
+    /// Seconds after local midnight of `date`. Defaults to 02:00:00.
+    pub time: i32,
+}
+
+/// The day of the year a [`PosixRule`] applies on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RuleDate {
+    /// `Jn`: day 1 through 365, never counting February 29.
+    Julian(u16),
+
+    /// `n`: day 0 through 365, counting February 29 in leap years.
+    Zero(u16),
+
+    /// `Mm.w.d`: day `weekday` (0 is Sunday) of week `week` (1 through 5, where 5 means the
+    /// last) of month `month`.
+    MonthWeekDay { month: u8, week: u8, weekday: u8 },
+}
+
+/// The time DST rules apply at when a TZ string doesn't say.
+pub(crate) const DEFAULT_RULE_TIME: i32 = 2 * 60 * 60;
+
+/// Largest hours value accepted in a rule time, as allowed by version 3 TZ strings.
+pub(crate) const MAX_RULE_HOURS: u32 = 167;
+
+/// Largest hours value accepted in an offset, as version 3 extends offsets to the same range as
+/// rule times.
+pub(crate) const MAX_OFFSET_HOURS: u32 = 167;
+
+/// Largest offset before version 3, ±24:59:59, in seconds.
+const MAX_V2_OFFSET: i32 = 24 * 60 * 60 + 59 * 60 + 59;
+
+/// Years beyond this distance from 1970 are clamped, to keep the arithmetic from overflowing.
+pub(crate) const MAX_YEAR: i64 = 1 << 32;
+
+impl PosixTz {
+    /// The local time type in effect at the given UT timestamp.
+    pub fn local_time_type_at(&self, ut: i64) -> LocalTimeType<'_> {
+        let std = LocalTimeType {
+            desig: &self.std_abbr,
+            ut_offset_secs: self.std_offset,
+            is_dst: false,
+        };
+        let Some(dst) = &self.dst else {
+            return std;
+        };
+
+        if is_dst_at(self.std_offset, dst.offset, &dst.start, &dst.end, ut) {
+            LocalTimeType {
+                desig: &dst.abbr,
+                ut_offset_secs: dst.offset,
+                is_dst: true,
+            }
+        } else {
+            std
+        }
+    }
+
+    /// The UT times DST starts and ends in the given year, each paired with whether DST is in
+    /// effect afterwards. Returns `None` if the zone doesn't observe DST.
+    pub(crate) fn transitions(&self, year: i64) -> Option<[(i64, bool); 2]> {
+        let dst = self.dst.as_ref()?;
+        Some(rule_transitions(
+            self.std_offset,
+            dst.offset,
+            &dst.start,
+            &dst.end,
+            year,
+        ))
+    }
+
+    /// The UT times of the last change between standard time and DST at or before `ut`, and of
+    /// the first after it. Both are `None` if the zone doesn't observe DST.
+    pub(crate) fn changes_around(&self, ut: i64) -> (Option<i64>, Option<i64>) {
+        let local = ut.saturating_add(i64::from(self.std_offset));
+        let (year, _, _) = crate::civil::civil_from_days(local.div_euclid(SECS_PER_DAY));
+        let year = year.clamp(-MAX_YEAR, MAX_YEAR);
+        let (mut prev, mut next) = (None, None);
+        for y in year - 1..=year + 1 {
+            for (t, _) in self.transitions(y).into_iter().flatten() {
+                if t <= ut {
+                    prev = prev.max(Some(t));
+                } else if next.is_none_or(|next| t < next) {
+                    next = Some(t);
+                }
+            }
+        }
+        (prev, next)
+    }
+
+    /// Whether the TZ string uses the extensions of version 3: an offset beyond ±24:59:59, or
+    /// a rule time outside the 0 to 24 hours allowed before, up to ±167 hours. A zone with
+    /// such a footer must be written as version 3 or later.
+    pub fn uses_v3_extensions(&self) -> bool {
+        let offset = |offset: i32| !(-MAX_V2_OFFSET..=MAX_V2_OFFSET).contains(&offset);
+        offset(self.std_offset)
+            || self.dst.as_ref().is_some_and(|dst| {
+                offset(dst.offset)
+                    || [dst.start, dst.end]
+                        .iter()
+                        .any(|rule| !(0..=24 * 60 * 60).contains(&rule.time))
+            })
+    }
+}
+
+/// The UT times DST starts and ends in the given year, under the given standard and DST offsets
+/// and rules, each paired with whether DST is in effect afterwards.
+fn rule_transitions(
+    std_offset: i32,
+    dst_offset: i32,
+    start: &PosixRule,
+    end: &PosixRule,
+    year: i64,
+) -> [(i64, bool); 2] {
+    let start = start.local_secs(year) - i64::from(std_offset);
+    let end = end.local_secs(year) - i64::from(dst_offset);
+    [(start, true), (end, false)]
+}
+
+/// Whether DST is in effect at the given UT timestamp, under the given standard and DST offsets
+/// and rules for when DST starts and ends.
+pub(crate) fn is_dst_at(
+    std_offset: i32,
+    dst_offset: i32,
+    start: &PosixRule,
+    end: &PosixRule,
+    ut: i64,
+) -> bool {
+    // Transitions near the start or end of a year can fall in the neighbouring one.
+    let local = ut.saturating_add(i64::from(std_offset));
+    let (year, _, _) = crate::civil::civil_from_days(local.div_euclid(SECS_PER_DAY));
+    let year = year.clamp(-MAX_YEAR, MAX_YEAR);
+    let mut is_dst = false;
+    let mut latest = i64::MIN;
+    for y in year - 1..=year + 1 {
+        for (t, to_dst) in rule_transitions(std_offset, dst_offset, start, end, y) {
+            if t <= ut && t >= latest {
+                latest = t;
+                is_dst = to_dst;
+            }
+        }
+    }
+    is_dst
+}
+
+impl PosixRule {
+    /// Seconds from the Unix epoch to the rule's local date and time in the given year, as if
+    /// local time were UT.
+    fn local_secs(&self, year: i64) -> i64 {
+        let jan1 = days_from_civil(year, 1, 1);
+        let day = match self.date {
+            RuleDate::Julian(n) => {
+                let n = i64::from(n);
+                jan1 + n - 1 + i64::from(is_leap(year) && n >= 60)
+            }
+            RuleDate::Zero(n) => jan1 + i64::from(n),
+            RuleDate::MonthWeekDay {
+                month,
+                week,
+                weekday: wday,
+            } => {
+                let first = days_from_civil(year, month, 1);
+                let last = first + i64::from(days_in_month(year, month)) - 1;
+                let mut day =
+                    first + i64::from((7 + wday - weekday(first)) % 7) + 7 * i64::from(week - 1);
+                while day > last {
+                    day -= 7;
+                }
+                day
+            }
+        };
+        day * SECS_PER_DAY + i64::from(self.time)
+    }
+}
+
+impl TimeZoneInfo {
+    /// Build a zone with no transitions, whose local time is governed entirely by the given TZ
+    /// string in its footer.
+    pub fn from_posix(tz: PosixTz) -> Self {
+        let mut result = Self {
+            version: if tz.uses_v3_extensions() { 3 } else { 2 },
+            ..Self::default()
+        };
+        let types = core::iter::once((tz.std_offset, false, tz.std_abbr.as_str())).chain(
+            tz.dst
+                .as_ref()
+                .map(|dst| (dst.offset, true, dst.abbr.as_str())),
+        );
+        for (ut_off_secs, is_dst, abbr) in types {
+            // An abbreviation too long to be indexed falls back to the first designation.
+            let desig_idx = result.find_or_add_designation(abbr).unwrap_or(0);
+            result.local_time_types.push(LocalTimeTypeRecord {
+                ut_off_secs,
+                is_dst,
+                desig_idx,
+            });
+            result.is_std.push(IsStd::Standard);
+            result.is_ut.push(IsUT::UT);
+        }
+        result.footer = Some(tz);
+        result
+    }
+
+    /// Whether the footer uses the extensions of version 3, so that the zone can't be written
+    /// as an earlier version without expanding it. See [`PosixTz::uses_v3_extensions`].
+    pub fn uses_v3_extensions(&self) -> bool {
+        self.footer
+            .as_ref()
+            .is_some_and(PosixTz::uses_v3_extensions)
+    }
+
+    /// Build a zone which is always at the given UT offset, with the given designation, and
+    /// has no transitions.
+    ///
+    /// The offset must be within ±167:59:59, and beyond ±24:59:59 makes the zone version 3. The
+    /// designation must be at least three ASCII letters, digits, "+" or "-"; those which aren't
+    /// alphabetic are quoted in the footer's TZ string as `<-05>` is. Otherwise, the error gives
+    /// the position of what's wrong in the TZ string that would have been written.
+    pub fn fixed(offset_secs: i32, designation: &str) -> Result<Self, PosixTzError> {
+        let valid = |b: u8| b.is_ascii_alphanumeric() || b == b'+' || b == b'-';
+        if designation.len() < 3 || !designation.bytes().all(valid) {
+            return Err(PosixTzError { position: 0 });
+        }
+        if offset_secs.unsigned_abs() > MAX_OFFSET_HOURS * 60 * 60 + 59 * 60 + 59 {
+            let quoted = !designation.bytes().all(|b| b.is_ascii_alphabetic());
+            return Err(PosixTzError {
+                position: designation.len() + if quoted { 2 } else { 0 },
+            });
+        }
+        Ok(Self::fixed_unchecked(offset_secs, designation))
+    }
+
+    fn fixed_unchecked(offset_secs: i32, designation: &str) -> Self {
+        Self::from_posix(PosixTz {
+            std_abbr: designation.to_owned(),
+            std_offset: offset_secs,
+            dst: None,
+        })
+    }
+
+    /// UTC, as in the `Etc/UTC` zone: a fallback which needs no zone database.
+    ///
+    /// This allocates each time; [`utc_static`](Self::utc_static) shares one instance.
+    pub fn utc() -> Self {
+        Self::fixed_unchecked(0, "UTC")
+    }
+
+    /// A shared instance of [`utc`](Self::utc), built the first time it's asked for.
+    #[cfg(feature = "std")]
+    pub fn utc_static() -> &'static Self {
+        static UTC: std::sync::OnceLock<TimeZoneInfo> = std::sync::OnceLock::new();
+        UTC.get_or_init(Self::utc)
+    }
+
+    /// The zone `Etc/GMT+N` or `Etc/GMT-N` for the given `N`, which like the zone names is
+    /// the number of hours *behind* UT: `etc_gmt(5)` is `Etc/GMT+5`, five hours behind, with
+    /// the designation "-05".
+    ///
+    /// Returns `None` outside the range of those zones, -14 to 12.
+    pub fn etc_gmt(hours_behind: i8) -> Option<Self> {
+        if !(-14..=12).contains(&hours_behind) {
+            return None;
+        }
+        let offset = -i32::from(hours_behind);
+        Some(match offset {
+            0 => Self::fixed_unchecked(0, "GMT"),
+            _ => Self::fixed_unchecked(offset * 3600, &format!("{offset:+03}")),
+        })
+    }
+}
+
+impl FromStr for PosixTz {
+    type Err = PosixTzError;
+
+    fn from_str(s: &str) -> Result<Self, PosixTzError> {
+        let mut p = Parser {
+            s: s.as_bytes(),
+            pos: 0,
+        };
+        let std_abbr = p.abbr()?;
+        let std_offset = p.offset()?;
+        let dst = if p.at_end() {
+            None
+        } else {
+            let abbr = p.abbr()?;
+            let offset = if p.at_end() || p.peek() == Some(b',') {
+                std_offset + 60 * 60
+            } else {
+                p.offset()?
+            };
+            let (start, end) = if p.eat(b',') {
+                let start = p.rule()?;
+                p.expect(b',')?;
+                (start, p.rule()?)
+            } else {
+                // The rules are implementation-defined when omitted; use the current US ones,
+                // as the C library does.
+                let rule = |month, week| PosixRule {
+                    date: RuleDate::MonthWeekDay {
+                        month,
+                        week,
+                        weekday: 0,
+                    },
+                    time: DEFAULT_RULE_TIME,
+                };
+                (rule(3, 2), rule(11, 1))
+            };
+            Some(PosixDst {
+                abbr,
+                offset,
+                start,
+                end,
+            })
+        };
+        if !p.at_end() {
+            return Err(p.error());
+        }
+        Ok(Self {
+            std_abbr,
+            std_offset,
+            dst,
+        })
+    }
+}
+
+impl FromStr for TimeZoneInfo {
+    type Err = PosixTzError;
+
+    /// Parse a TZ string into a zone with no transitions, as [`TimeZoneInfo::from_posix`]
+    /// builds.
+    fn from_str(s: &str) -> Result<Self, PosixTzError> {
+        s.parse().map(Self::from_posix)
+    }
+}
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self) -> PosixTzError {
+        PosixTzError { position: self.pos }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos == self.s.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        let found = self.peek() == Some(b);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), PosixTzError> {
+        if self.eat(b) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn take_while(&mut self, pred: impl Fn(u8) -> bool) -> &str {
+        let start = self.pos;
+        while self.peek().is_some_and(&pred) {
+            self.pos += 1;
+        }
+        // Only ASCII is ever accepted.
+        core::str::from_utf8(&self.s[start..self.pos]).unwrap_or_default()
+    }
+
+    /// An abbreviation: at least three letters, or at least three letters, digits and signs in
+    /// angle brackets.
+    fn abbr(&mut self) -> Result<String, PosixTzError> {
+        let start = self.pos;
+        let abbr = if self.eat(b'<') {
+            let abbr = self
+                .take_while(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-')
+                .to_owned();
+            self.expect(b'>')?;
+            abbr
+        } else {
+            self.take_while(|b| b.is_ascii_alphabetic()).to_owned()
+        };
+        if abbr.len() < 3 {
+            return Err(PosixTzError { position: start });
+        }
+        Ok(abbr)
+    }
+
+    /// A decimal number no larger than `max`.
+    fn number(&mut self, max: u32) -> Result<u32, PosixTzError> {
+        let start = self.pos;
+        let mut value = 0u32;
+        while let Some(b @ b'0'..=b'9') = self.peek() {
+            value = value * 10 + u32::from(b - b'0');
+            if value > max {
+                return Err(PosixTzError { position: start });
+            }
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.error());
+        }
+        Ok(value)
+    }
+
+    /// `[+-]hh[:mm[:ss]]`, in seconds.
+    fn hms(&mut self, max_hours: u32) -> Result<i32, PosixTzError> {
+        let negative = if self.eat(b'-') {
+            true
+        } else {
+            self.eat(b'+');
+            false
+        };
+        let mut secs = self.number(max_hours)? * 60 * 60;
+        if self.eat(b':') {
+            secs += self.number(59)? * 60;
+            if self.eat(b':') {
+                secs += self.number(59)?;
+            }
+        }
+        // The hour limits keep this well within range.
+        let secs = secs as i32;
+        Ok(if negative { -secs } else { secs })
+    }
+
+    /// An offset west of UT, returned as seconds east of UT.
+    fn offset(&mut self) -> Result<i32, PosixTzError> {
+        Ok(-self.hms(MAX_OFFSET_HOURS)?)
+    }
+
+    fn rule(&mut self) -> Result<PosixRule, PosixTzError> {
+        let start = self.pos;
+        let date = if self.eat(b'J') {
+            let n = self.number(365)?;
+            if n == 0 {
+                return Err(PosixTzError { position: start });
+            }
+            RuleDate::Julian(n as u16)
+        } else if self.eat(b'M') {
+            let month = self.number(12)?;
+            self.expect(b'.')?;
+            let week = self.number(5)?;
+            self.expect(b'.')?;
+            let weekday = self.number(6)?;
+            if month == 0 || week == 0 {
+                return Err(PosixTzError { position: start });
+            }
+            RuleDate::MonthWeekDay {
+                month: month as u8,
+                week: week as u8,
+                weekday: weekday as u8,
+            }
+        } else {
+            RuleDate::Zero(self.number(365)? as u16)
+        };
+        let time = if self.eat(b'/') {
+            self.hms(MAX_RULE_HOURS)?
+        } else {
+            DEFAULT_RULE_TIME
+        };
+        Ok(PosixRule { date, time })
+    }
+}
+
+fn write_abbr(f: &mut fmt::Formatter<'_>, abbr: &str) -> fmt::Result {
+    if abbr.bytes().all(|b| b.is_ascii_alphabetic()) {
+        f.write_str(abbr)
+    } else {
+        write!(f, "<{abbr}>")
+    }
+}
+
+fn write_hms(f: &mut fmt::Formatter<'_>, secs: i64) -> fmt::Result {
+    if secs < 0 {
+        f.write_str("-")?;
+    }
+    let secs = secs.unsigned_abs();
+    write!(f, "{}", secs / 3600)?;
+    let (mins, secs) = (secs / 60 % 60, secs % 60);
+    if mins != 0 || secs != 0 {
+        write!(f, ":{mins:02}")?;
+    }
+    if secs != 0 {
+        write!(f, ":{secs:02}")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for PosixTz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_abbr(f, &self.std_abbr)?;
+        write_hms(f, -i64::from(self.std_offset))?;
+        if let Some(dst) = &self.dst {
+            write_abbr(f, &dst.abbr)?;
+            if dst.offset != self.std_offset + 60 * 60 {
+                write_hms(f, -i64::from(dst.offset))?;
+            }
+            write!(f, ",{},{}", dst.start, dst.end)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PosixRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.date {
+            RuleDate::Julian(n) => write!(f, "J{n}")?,
+            RuleDate::Zero(n) => write!(f, "{n}")?,
+            RuleDate::MonthWeekDay {
+                month,
+                week,
+                weekday,
+            } => write!(f, "M{month}.{week}.{weekday}")?,
+        }
+        if self.time != DEFAULT_RULE_TIME {
+            f.write_str("/")?;
+            write_hms(f, i64::from(self.time))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParseError, ParseOptions};
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_parse_display() {
+        for s in [
+            "UTC0",
+            "<+0330>-3:30",
+            "EST5EDT,M3.2.0,M11.1.0",
+            "AEST-10AEDT,M10.1.0,M4.1.0/3",
+            "IST-2IDT,M3.4.4/26,M10.5.0",
+            "<-02>2<-01>,M3.5.0/-1,M10.5.0/0",
+            "XXX3:25:45YYY,J60/1:30,300/-167",
+        ] {
+            let tz: PosixTz = s.parse().unwrap();
+            assert_eq!(s, tz.to_string());
+        }
+
+        let tz: PosixTz = "CET-1CEST".parse().unwrap();
+        assert_eq!("CET-1CEST,M3.2.0,M11.1.0", tz.to_string());
+        assert_eq!(2 * 3600, tz.dst.unwrap().offset);
+
+        for (bad, position) in [
+            ("", 0),
+            ("EST", 3),
+            ("E5", 0),
+            ("<AB>5", 0),
+            ("EST168", 3),
+            ("EST5EDT,M13.1.0,M11.1.0", 9),
+            ("EST5EDT,M3.2.0", 14),
+            ("EST5EDT,J0,J365", 8),
+            ("EST5EDT,M3.2.0,M11.1.0/168", 23),
+            ("EST5 ", 4),
+        ] {
+            assert_eq!(
+                Err(PosixTzError { position }),
+                bad.parse::<PosixTz>(),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_v3_extensions() {
+        for (s, v3) in [
+            ("EST5EDT,M3.2.0,M11.1.0", false),
+            ("<-24>24:59:59", false),
+            ("<+25>-25", true),
+            ("XXX3YYY-26,M3.2.0,M11.1.0", true),
+            ("IST-2IDT,M3.4.4/24,M10.5.0", false),
+            ("IST-2IDT,M3.4.4/26,M10.5.0", true),
+            ("<-02>2<-01>,M3.5.0/-1,M10.5.0/0", true),
+        ] {
+            let tz: PosixTz = s.parse().unwrap();
+            assert_eq!(v3, tz.uses_v3_extensions(), "{s}");
+            assert_eq!(s, tz.to_string());
+            let tz = TimeZoneInfo::from_posix(tz);
+            assert_eq!(v3, tz.uses_v3_extensions(), "{s}");
+            assert_eq!(if v3 { 3 } else { 2 }, tz.version);
+        }
+        assert_eq!(-167 * 3600, "XXX167".parse::<PosixTz>().unwrap().std_offset);
+        assert!(!TimeZoneInfo::default().uses_v3_extensions());
+    }
+
+    #[test]
+    fn test_local_time_type_at() {
+        let us: PosixTz = "EST5EDT,M3.2.0,M11.1.0".parse().unwrap();
+        let abbr = |tz: &PosixTz, t| tz.local_time_type_at(t).desig.to_owned();
+        // 2024-03-10 07:00 UT and 2024-11-03 06:00 UT.
+        assert_eq!("EST", abbr(&us, 1710053999));
+        assert_eq!("EDT", abbr(&us, 1710054000));
+        assert_eq!("EDT", abbr(&us, 1730613599));
+        assert_eq!("EST", abbr(&us, 1730613600));
+        assert_eq!("EST", abbr(&us, i64::MIN));
+        assert_eq!("EST", abbr(&us, i64::MAX));
+
+        // Southern hemisphere: DST spans the new year.
+        let au: PosixTz = "AEST-10AEDT,M10.1.0,M4.1.0/3".parse().unwrap();
+        assert_eq!("AEDT", abbr(&au, 1704067200)); // 2024-01-01
+        assert_eq!("AEST", abbr(&au, 1719792000)); // 2024-07-01
+
+        // Permanent DST, expressed as a transition at the very end of each year.
+        let always: PosixTz = "EST5EDT,0/0,J365/25".parse().unwrap();
+        for t in [0, 1704085200, 1719792000, 1735707600] {
+            assert_eq!("EDT", abbr(&always, t));
+        }
+    }
+
+    #[test]
+    fn test_from_posix() {
+        let tz = TimeZoneInfo::from_posix("EST5EDT,M3.2.0,M11.1.0".parse().unwrap());
+        assert_eq!(2, tz.version);
+        assert!(tz.transition_times.is_empty());
+        assert_eq!("EDT", tz.local_time_type_at(1710054000).unwrap().desig);
+        let back = TimeZoneInfo::parse_slice(&tz.to_bytes(), &Default::default()).unwrap();
+        assert_eq!(tz.footer, back.footer);
+        assert_eq!(2, back.local_time_types.len());
+
+        let mut unterminated = tz.to_bytes();
+        unterminated.pop();
+        let strict = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        assert!(matches!(
+            TimeZoneInfo::parse_slice(&unterminated, &strict),
+            Err(ParseError::InvalidFooter { .. })
+        ));
+        let lenient = TimeZoneInfo::parse_slice(&unterminated, &Default::default()).unwrap();
+        assert_eq!(None, lenient.footer);
+
+        let tz = TimeZoneInfo::from_posix("<-02>2<-01>,M3.5.0/-1,M10.5.0/0".parse().unwrap());
+        assert_eq!(3, tz.version);
+
+        assert_eq!(Ok(tz), "<-02>2<-01>,M3.5.0/-1,M10.5.0/0".parse());
+        let bytes = back.to_bytes();
+        assert_eq!(back, TimeZoneInfo::try_from(&bytes[..]).unwrap());
+        assert_eq!(
+            Err(PosixTzError { position: 3 }),
+            "EST".parse::<TimeZoneInfo>()
+        );
+        assert!(matches!(
+            TimeZoneInfo::try_from(&unterminated[..4]),
+            Err(ParseError::Truncated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_fixed() {
+        let tz = TimeZoneInfo::fixed(5 * 3600 + 1800, "IST").unwrap();
+        assert!(tz.transition_times.is_empty());
+        let local = tz.local_time_type_at(0).unwrap();
+        assert_eq!(
+            ("IST", 19800, false),
+            (local.desig, local.ut_offset_secs, local.is_dst)
+        );
+        let back = TimeZoneInfo::parse_slice(&tz.to_bytes(), &ParseOptions::default()).unwrap();
+        assert_eq!("IST-5:30", back.footer.unwrap().to_string());
+        let max = 167 * 3600 + 59 * 60 + 59;
+        let tz = TimeZoneInfo::fixed(-max, "-167").unwrap();
+        assert_eq!("<-167>167:59:59", tz.footer.as_ref().unwrap().to_string());
+        assert_eq!(3, tz.version);
+        for (offset, desig, position) in [
+            (i32::MIN, "XXX", 3),
+            (max + 1, "XXX", 3),
+            (-max - 1, "+168", 6),
+            (0, "", 0),
+            (0, "XX", 0),
+            (0, "X,Y", 0),
+            (0, "X>Y", 0),
+        ] {
+            assert_eq!(
+                Err(PosixTzError { position }),
+                TimeZoneInfo::fixed(offset, desig).map(|_| ()),
+                "{desig}"
+            );
+        }
+
+        let utc = TimeZoneInfo::utc();
+        assert_eq!(Some("UTC"), utc.designation_at(i64::MAX));
+        assert_eq!(Some(0), utc.std_offset_at(i64::MIN));
+        assert_eq!("UTC0", utc.footer.as_ref().unwrap().to_string());
+        #[cfg(feature = "std")]
+        {
+            assert_eq!(utc.to_bytes(), TimeZoneInfo::utc_static().to_bytes());
+            assert!(core::ptr::eq(
+                TimeZoneInfo::utc_static(),
+                TimeZoneInfo::utc_static()
+            ));
+        }
+
+        let behind = TimeZoneInfo::etc_gmt(5).unwrap();
+        assert_eq!(Some("-05"), behind.designation_at(0));
+        assert_eq!("<-05>5", behind.footer.as_ref().unwrap().to_string());
+        let ahead = TimeZoneInfo::etc_gmt(-14).unwrap();
+        assert_eq!(
+            14 * 3600,
+            ahead.local_time_type_at(0).unwrap().ut_offset_secs
+        );
+        assert_eq!(Some("+14"), ahead.designation_at(0));
+        assert_eq!(
+            Some("GMT"),
+            TimeZoneInfo::etc_gmt(0).unwrap().designation_at(0)
+        );
+        assert!(TimeZoneInfo::etc_gmt(13).is_none());
+        assert!(TimeZoneInfo::etc_gmt(-15).is_none());
+    }
+}
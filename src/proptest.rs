@@ -0,0 +1,166 @@
+//! Generating zones for property tests with the `proptest` crate.
+
+use alloc::vec::Vec;
+
+use ::proptest::arbitrary::Arbitrary;
+use ::proptest::collection::{btree_set, vec};
+use ::proptest::option;
+use ::proptest::prelude::*;
+use ::proptest::sample::select;
+
+use crate::civil::SECS_PER_DAY;
+use crate::{IsStd, IsUT, LeapSecondRecord, LocalTimeTypeRecord, PosixTz, TimeZoneInfo};
+
+/// Designations to choose from, all of the form RFC 8536 recommends.
+const DESIGNATIONS: &[&str] = &["UTC", "LMT", "EST", "EDT", "CET", "CEST", "+0530", "-03"];
+
+/// TZ strings to choose from for footers, including one which needs version 3.
+const FOOTERS: &[&str] = &[
+    "UTC0",
+    "EST5EDT,M3.2.0,M11.1.0",
+    "<+1030>-10:30<+11>-11,M10.1.0,M4.1.0",
+    "<-03>3<-02>,M3.5.0/-2,M10.5.0/-1",
+];
+
+/// Transition times are drawn from well beyond the 32-bit range, but short of where adding a
+/// UT offset could overflow.
+const TIME_RANGE: core::ops::Range<i64> = -(1 << 40)..(1 << 40);
+
+impl Arbitrary for TimeZoneInfo {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Zones of version 2 to 4 which are internally consistent, so that
+    /// [`Zone::new`](crate::Zone::new) accepts them and they round-trip through
+    /// [`to_bytes`](TimeZoneInfo::to_bytes) and parsing unchanged.
+    ///
+    /// Each has one to eight local time types with offsets in the range RFC 8536 recommends,
+    /// up to 64 transitions, indicators or none, up to eight leap seconds, and possibly a
+    /// footer, which isn't necessarily consistent with the last transition.
+    fn arbitrary_with((): ()) -> Self::Strategy {
+        let types = vec(
+            (-89999..=93599i32, any::<bool>(), select(DESIGNATIONS)),
+            1..=8,
+        );
+        (types, 2..=4u8)
+            .prop_flat_map(|(types, version)| {
+                let count = types.len() as u8;
+                let transitions = btree_set(TIME_RANGE, 0..=64).prop_flat_map(move |times| {
+                    let len = times.len();
+                    (Just(times), vec(0..count, len))
+                });
+                let indicators = option::of(vec(
+                    select(
+                        &[
+                            (IsStd::Wall, IsUT::Local),
+                            (IsStd::Standard, IsUT::Local),
+                            (IsStd::Standard, IsUT::UT),
+                        ][..],
+                    ),
+                    types.len(),
+                ));
+                let leaps = vec(
+                    (28 * SECS_PER_DAY..400 * SECS_PER_DAY, any::<bool>()),
+                    0..=8,
+                );
+                (
+                    Just(types),
+                    Just(version),
+                    transitions,
+                    indicators,
+                    (0..1i64 << 31, leaps),
+                    option::of(select(FOOTERS)),
+                )
+            })
+            .prop_map(
+                |(types, version, (times, typ_idxs), indicators, (first, leaps), footer)| {
+                    let mut tz = TimeZoneInfo {
+                        version,
+                        transition_times: times.into_iter().collect(),
+                        transition_types: typ_idxs,
+                        ..TimeZoneInfo::default()
+                    };
+                    for (ut_off_secs, is_dst, desig) in types {
+                        let desig_idx = tz.find_or_add_designation(desig).unwrap();
+                        tz.local_time_types.push(LocalTimeTypeRecord {
+                            ut_off_secs,
+                            is_dst,
+                            desig_idx,
+                        });
+                    }
+                    if let Some(indicators) = indicators {
+                        (tz.is_std, tz.is_ut) = indicators.into_iter().unzip();
+                    }
+                    tz.leap_second_records = leap_seconds(first, &leaps);
+                    if let Some(footer) = footer {
+                        let footer: PosixTz = footer.parse().unwrap();
+                        if footer.uses_v3_extensions() {
+                            tz.version = tz.version.max(3);
+                        }
+                        tz.footer = Some(footer);
+                    }
+                    tz
+                },
+            )
+            .boxed()
+    }
+}
+
+/// Leap-second records, the first at `first` and each after it the given time later, with a
+/// positive leap second if the flag is set and a negative one if not.
+fn leap_seconds(first: i64, gaps: &[(i64, bool)]) -> Vec<LeapSecondRecord> {
+    let (mut at, mut correction) = (first, 0);
+    let mut records = Vec::new();
+    for &(gap, positive) in gaps {
+        correction += if positive { 1 } else { -1 };
+        records.push(LeapSecondRecord::new(at, correction));
+        at += gap;
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompactZone, ParseOptions, Zone};
+
+    proptest! {
+        #[test]
+        fn test_round_trip(tz: TimeZoneInfo) {
+            prop_assert!(Zone::new(tz.clone()).is_ok());
+            let parsed = TimeZoneInfo::parse_slice(&tz.to_bytes(), &ParseOptions::default());
+            prop_assert_eq!(tz, parsed.unwrap());
+        }
+
+        #[test]
+        fn test_lookup(tz: TimeZoneInfo, ut in TIME_RANGE) {
+            let local = tz.local_time_type_at(ut).unwrap();
+            // The footer takes over after the last transition; before it, the transitions say.
+            if tz.footer.is_none() || tz.transition_times.last().is_some_and(|&last| ut < last) {
+                let idx = tz.partition_transitions(|t| t <= ut);
+                let typ = if idx == 0 { 0 } else { tz.transition_types[idx - 1] };
+                let record = tz.local_time_types[usize::from(typ)];
+                prop_assert_eq!(record.ut_off_secs, local.ut_offset_secs);
+                prop_assert_eq!(record.is_dst, local.is_dst);
+            }
+        }
+
+        #[test]
+        fn test_compact_zone(tz: TimeZoneInfo, ut in TIME_RANGE) {
+            let zone = Zone::new(tz).unwrap();
+            let compact = CompactZone::new(&zone);
+            prop_assert_eq!(zone.local_time_type_at(ut), compact.local_time_type_at(ut));
+            for &at in zone.transition_times() {
+                prop_assert_eq!(zone.local_time_type_at(at), compact.local_time_type_at(at));
+            }
+        }
+
+        #[test]
+        fn test_offset_table(tz: TimeZoneInfo, ut in TIME_RANGE) {
+            let table = tz.offset_table(ut - SECS_PER_DAY * 400..ut + SECS_PER_DAY * 400);
+            let offset = tz.local_time_type_at(ut).map(|local| local.ut_offset_secs);
+            prop_assert_eq!(offset, tz.offset_at(ut));
+            prop_assert_eq!(offset, table.offset_at(ut));
+        }
+    }
+}
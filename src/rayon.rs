@@ -0,0 +1,69 @@
+//! Loading a whole database across threads with the `rayon` crate.
+
+use std::time::{Duration, Instant};
+
+use ::rayon::prelude::*;
+
+use crate::{ZoneDatabase, ZoneError};
+
+/// What [`ZoneDatabase::load_all_parallel`] did.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct LoadStats {
+    /// How many zones were loaded.
+    pub loaded: usize,
+
+    /// The zones which couldn't be loaded, by name, in order.
+    pub failed: Vec<(String, ZoneError)>,
+
+    /// How long it took, listing the zones included.
+    pub elapsed: Duration,
+}
+
+impl ZoneDatabase {
+    /// Load every zone in the database, as listed by [`zone_names`](Self::zone_names), parsing
+    /// them on rayon's thread pool.
+    ///
+    /// The zones go in the database's cache, so [`get`](Self::get) gives them without reading
+    /// them again. A zone which fails to load is reported and the rest still loaded.
+    pub fn load_all_parallel(&self) -> Result<LoadStats, ZoneError> {
+        let start = Instant::now();
+        let names = self.zone_names()?;
+        let total = names.len();
+        let mut failed: Vec<_> = names
+            .into_par_iter()
+            .filter_map(|name| match self.get(&name) {
+                Ok(_) => None,
+                Err(e) => Some((name, e)),
+            })
+            .collect();
+        failed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let loaded = total - failed.len();
+        Ok(LoadStats {
+            loaded,
+            failed,
+            elapsed: start.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_load_all_parallel() {
+        let tzdata = crate::android::tests::build(&[
+            ("Test/Sample", &sample().to_bytes()),
+            ("Test/Broken", b"TZif"),
+            ("Test/Other", &sample().to_bytes()),
+        ]);
+        let db = ZoneDatabase::from_android_bytes(tzdata).unwrap();
+        let stats = db.load_all_parallel().unwrap();
+        assert_eq!(2, stats.loaded);
+        assert_eq!(1, stats.failed.len());
+        assert_eq!("Test/Broken", stats.failed[0].0);
+        assert!(matches!(stats.failed[0].1, ZoneError::Parse(_)));
+    }
+}
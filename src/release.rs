@@ -0,0 +1,200 @@
+//! Comparing two zoneinfo trees, such as those built from successive tzdata releases.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path};
+
+use crate::verify::tree_files;
+use crate::{ParseOptions, TimeZoneInfo, ZoneDifference, ZoneSource};
+
+/// What a name in a zoneinfo tree refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeEntry {
+    Zone,
+
+    /// A link to the zone or link with the given name.
+    Link(String),
+}
+
+/// A name whose entry or data differs between two trees, from [`diff_trees`].
+#[derive(Debug)]
+pub struct NameChange {
+    pub name: String,
+
+    /// The entry in the old tree, if the name is in it.
+    pub old: Option<TreeEntry>,
+
+    /// The entry in the new tree, if the name is in it.
+    pub new: Option<TreeEntry>,
+
+    /// How the data the name gives differs, when the name is in both trees.
+    pub differences: Vec<ZoneDifference>,
+}
+
+/// Compare every TZif file in two zoneinfo directories, returning the names which were added,
+/// removed, turned from a zone into a link or back, relinked, or whose data changed, sorted by
+/// name.
+///
+/// A name is a link if it's a symlink to a file elsewhere in its tree, or if the tree's
+/// `tzdata.zi` lists it as one, which catches the hard links `zic` makes by default. Links are
+/// compared by the data they resolve to as well as by their targets. Files which aren't TZif
+/// data are ignored.
+pub fn diff_trees(old: impl AsRef<Path>, new: impl AsRef<Path>) -> io::Result<Vec<NameChange>> {
+    let mut old = read_tree(old.as_ref())?;
+    let new = read_tree(new.as_ref())?;
+
+    let mut changes = Vec::new();
+    for (name, (new_entry, new_info)) in new {
+        let change = match old.remove(&name) {
+            None => NameChange {
+                name,
+                old: None,
+                new: Some(new_entry),
+                differences: Vec::new(),
+            },
+            Some((old_entry, old_info)) => {
+                let differences = old_info.diff(&new_info);
+                if old_entry == new_entry && differences.is_empty() {
+                    continue;
+                }
+                NameChange {
+                    name,
+                    old: Some(old_entry),
+                    new: Some(new_entry),
+                    differences,
+                }
+            }
+        };
+        changes.push(change);
+    }
+    changes.extend(old.into_iter().map(|(name, (old_entry, _))| NameChange {
+        name,
+        old: Some(old_entry),
+        new: None,
+        differences: Vec::new(),
+    }));
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(changes)
+}
+
+/// Every TZif file in a tree, by name, with what it is and its data.
+fn read_tree(root: &Path) -> io::Result<BTreeMap<String, (TreeEntry, TimeZoneInfo)>> {
+    let root = fs::canonicalize(root)?;
+    let invalid = |name: &str, e: &dyn fmt::Display| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{name}: {e}"))
+    };
+
+    let links: BTreeMap<String, String> = match fs::read_to_string(root.join("tzdata.zi")) {
+        Ok(text) => {
+            let mut source = ZoneSource::new();
+            source.parse(&text).map_err(|e| invalid("tzdata.zi", &e))?;
+            source
+                .links()
+                .map(|(name, target)| (name.to_owned(), target.to_owned()))
+                .collect()
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => return Err(e),
+    };
+
+    let mut tree = BTreeMap::new();
+    for file in tree_files(&root)? {
+        let data = fs::read(&file.path)?;
+        if !data.starts_with(b"TZif") {
+            continue;
+        }
+        let info = TimeZoneInfo::parse_slice(&data, &ParseOptions::default())
+            .map_err(|e| invalid(&file.name, &e))?;
+        let symlink_target = if file.is_symlink {
+            fs::canonicalize(&file.path)?
+                .strip_prefix(&root)
+                .ok()
+                .and_then(tree_name)
+        } else {
+            None
+        };
+        let entry = match links.get(&file.name).cloned().or(symlink_target) {
+            Some(target) => TreeEntry::Link(target),
+            None => TreeEntry::Zone,
+        };
+        tree.insert(file.name, (entry, info));
+    }
+    Ok(tree)
+}
+
+/// The name of a path relative to the root of a tree, with `/` separators.
+fn tree_name(path: &Path) -> Option<String> {
+    let parts = path
+        .components()
+        .map(|part| match part {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("/"))
+}
+
+impl fmt::Display for TreeEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeEntry::Zone => f.write_str("zone"),
+            TreeEntry::Link(target) => write!(f, "link to {target}"),
+        }
+    }
+}
+
+impl fmt::Display for NameChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (None, Some(new)) => write!(f, "added {}: {new}", self.name),
+            (Some(old), None) => write!(f, "removed {}: was {old}", self.name),
+            (Some(old), Some(new)) if old != new => {
+                write!(f, "changed {}: {old} -> {new}", self.name)
+            }
+            _ => write!(f, "changed {}", self.name),
+        }?;
+        if !self.differences.is_empty() {
+            write!(f, ", {} differences", self.differences.len())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_diff_trees() {
+        let base = std::env::temp_dir().join(format!("tzif-release-{}", std::process::id()));
+        let (old, new) = (base.join("old"), base.join("new"));
+        for root in [&old, &new] {
+            fs::create_dir_all(root.join("America")).unwrap();
+            fs::write(root.join("America/Same"), sample().to_bytes()).unwrap();
+            fs::write(root.join("zone.tab"), "# nothing\n").unwrap();
+        }
+        let mut changed = sample();
+        changed.transition_times[1] = 250;
+        fs::write(old.join("America/Changed"), sample().to_bytes()).unwrap();
+        fs::write(new.join("America/Changed"), changed.to_bytes()).unwrap();
+        fs::write(old.join("Gone"), sample().to_bytes()).unwrap();
+        fs::write(new.join("Linked"), sample().to_bytes()).unwrap();
+        fs::write(new.join("tzdata.zi"), "Link America/Same Linked\n").unwrap();
+
+        let changes = diff_trees(&old, &new);
+        fs::remove_dir_all(&base).unwrap();
+        let changes = changes.unwrap();
+        let lines: Vec<_> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            vec![
+                "changed America/Changed, 2 differences",
+                "removed Gone: was zone",
+                "added Linked: link to America/Same",
+            ],
+            lines
+        );
+    }
+}
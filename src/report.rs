@@ -0,0 +1,105 @@
+//! A human-readable dump of everything in a zone, for debugging and snapshot tests.
+
+use core::fmt;
+use core::ops::Range;
+
+use crate::display::{DateTime, Ut};
+use crate::{IsStd, IsUT, TimeZoneInfo};
+
+/// A readable report of a zone, from [`TimeZoneInfo::report`].
+#[derive(Debug, Clone)]
+pub struct Report<'a> {
+    tzif: &'a TimeZoneInfo,
+    range: Range<i64>,
+}
+
+impl TimeZoneInfo {
+    /// A readable report of the zone: its version, local time types, changes of local time,
+    /// leap seconds and footer.
+    ///
+    /// Changes are listed the way `zdump -v` does, as the instants either side of each, for
+    /// those with UT times in `range`. After the last transition they come from the footer.
+    pub fn report(&self, range: Range<i64>) -> Report<'_> {
+        Report { tzif: self, range }
+    }
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tz = self.tzif;
+        writeln!(f, "version {}", tz.version)?;
+
+        writeln!(f, "local time types:")?;
+        for idx in 0..tz.local_time_types.len() {
+            write!(f, "  {idx}: {}", tz.local_time_type(idx))?;
+            match tz.is_std.get(idx) {
+                Some(IsStd::Standard) => f.write_str(" standard")?,
+                Some(IsStd::Wall) => f.write_str(" wall")?,
+                None => {}
+            }
+            match tz.is_ut.get(idx) {
+                Some(IsUT::UT) => f.write_str(" UT")?,
+                Some(IsUT::Local) => f.write_str(" local")?,
+                None => {}
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f, "changes:")?;
+        let (start, end) = (self.range.start, self.range.end);
+        if start < end {
+            let changes = tz.changes_between(start.saturating_sub(1), end - 1);
+            for (at, before, after) in changes {
+                for (t, local) in [(at - 1, before), (at, after)] {
+                    writeln!(
+                        f,
+                        "  {} = {} {} isdst={} gmtoff={}",
+                        Ut(t),
+                        DateTime(t.saturating_add(i64::from(local.ut_offset_secs))),
+                        local.desig,
+                        u8::from(local.is_dst),
+                        local.ut_offset_secs,
+                    )?;
+                }
+            }
+        }
+
+        writeln!(f, "leap seconds:")?;
+        for leap in tz.iter_leap_seconds() {
+            writeln!(f, "  {} correction {}", leap.occurrence, leap.correction)?;
+        }
+        if let Some(expires) = tz.leap_second_expiration() {
+            writeln!(f, "  {expires} expires")?;
+        }
+
+        match &tz.footer {
+            Some(footer) => writeln!(f, "footer: {footer}"),
+            None => writeln!(f, "footer: none"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::sample;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_report() {
+        let tz = sample();
+        assert_eq!(
+            "\
+version 2
+local time types:
+  0: PST (UTC-08:00) standard UT
+  1: PDT (UTC-07:00, DST) standard UT
+changes:
+  1970-01-01 00:03:19 UT = 1969-12-31 17:03:19 PDT isdst=1 gmtoff=-25200
+  1970-01-01 00:03:20 UT = 1969-12-31 16:03:20 PST isdst=0 gmtoff=-28800
+leap seconds:
+footer: none
+",
+            tz.report(101..201).to_string()
+        );
+    }
+}
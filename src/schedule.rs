@@ -0,0 +1,121 @@
+//! When daylight saving time starts and ends in a given year.
+
+use alloc::vec::Vec;
+
+use crate::civil::{civil_from_days, SECS_PER_DAY};
+use crate::posix::MAX_YEAR;
+use crate::{LocalTimeType, TimeZoneInfo};
+
+/// A year's daylight saving time, from [`TimeZoneInfo::dst_schedule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DstSchedule<'a> {
+    /// The UT time DST starts during the year, if it does.
+    pub start: Option<i64>,
+
+    /// The UT time DST ends during the year, if it does. This is before `start` in the
+    /// southern hemisphere, where the year begins and ends on DST.
+    pub end: Option<i64>,
+
+    /// How far DST moves clocks forward from standard time, in seconds.
+    pub save_secs: i32,
+
+    /// The local time type in effect outside of DST.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub std: LocalTimeType<'a>,
+
+    /// The local time type in effect during DST.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub dst: LocalTimeType<'a>,
+}
+
+impl TimeZoneInfo {
+    /// When DST starts and ends in the given year, from the recorded transitions or, after the
+    /// last of them, the footer's rule.
+    ///
+    /// A transition belongs to the year its local time falls in on the clock in effect before
+    /// it. If DST starts or ends more than once in the year, the first of each is given.
+    /// Returns `None` if DST neither starts nor ends during the year, even if it's in effect
+    /// throughout. The footer's rule is only followed to years within ±2^32, as elsewhere.
+    pub fn dst_schedule(&self, year: i64) -> Option<DstSchedule<'_>> {
+        let in_year = |ut: i64, before: &LocalTimeType<'_>| {
+            let local = ut.saturating_add(i64::from(before.ut_offset_secs));
+            civil_from_days(local.div_euclid(SECS_PER_DAY)).0 == year
+        };
+
+        // Each change into or out of DST during the year: its UT time and the types either side.
+        let mut changes: Vec<(i64, LocalTimeType<'_>, LocalTimeType<'_>)> = self
+            .iter_transitions()
+            .map(|tr| (tr.at_time.to_ut(), tr.prev, tr.local))
+            .filter(|(at, prev, local)| prev.is_dst != local.is_dst && in_year(*at, prev))
+            .collect();
+        if let Some(footer) = self
+            .footer
+            .as_ref()
+            .filter(|_| (-MAX_YEAR..=MAX_YEAR).contains(&year))
+        {
+            let last = self.transition_times.last().copied();
+            for y in year - 1..=year + 1 {
+                for (at, _) in footer.transitions(y).into_iter().flatten() {
+                    if last.is_some_and(|last| at <= last) {
+                        continue;
+                    }
+                    let prev = footer.local_time_type_at(at - 1);
+                    if in_year(at, &prev) {
+                        changes.push((at, prev, footer.local_time_type_at(at)));
+                    }
+                }
+            }
+            changes.sort_by_key(|&(at, _, _)| at);
+        }
+
+        let start = changes.iter().find(|(_, _, local)| local.is_dst);
+        let end = changes.iter().find(|(_, _, local)| !local.is_dst);
+        let (std, dst) = match (start, end) {
+            (Some(&(_, std, dst)), _) => (std, dst),
+            (None, Some(&(_, dst, std))) => (std, dst),
+            (None, None) => return None,
+        };
+        Some(DstSchedule {
+            start: start.map(|&(at, _, _)| at),
+            end: end.map(|&(at, _, _)| at),
+            save_secs: dst.ut_offset_secs - std.ut_offset_secs,
+            std,
+            dst,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::civil::days_from_civil;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_dst_schedule() {
+        let mut tz = sample();
+        let at = |y, m, d, h: i64| days_from_civil(y, m, d) * 86400 + h * 3600;
+        tz.transition_times = vec![at(2006, 4, 2, 10), at(2006, 10, 29, 9)];
+        tz.transition_types = vec![1, 0];
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+
+        let recorded = tz.dst_schedule(2006).unwrap();
+        assert_eq!(Some(at(2006, 4, 2, 10)), recorded.start);
+        assert_eq!(Some(at(2006, 10, 29, 9)), recorded.end);
+        assert_eq!(3600, recorded.save_secs);
+        assert_eq!(("PST", "PDT"), (recorded.std.desig, recorded.dst.desig));
+
+        let footer = tz.dst_schedule(2024).unwrap();
+        assert_eq!(Some(at(2024, 3, 10, 10)), footer.start);
+        assert_eq!(Some(at(2024, 11, 3, 9)), footer.end);
+        assert_eq!("PDT", footer.dst.desig);
+
+        assert!(tz.dst_schedule(2000).is_none());
+        // Years too far out for the footer's rule, and at the ends of the range.
+        for year in [1 << 40, -(1 << 40), i64::MAX, i64::MIN] {
+            assert!(tz.dst_schedule(year).is_none(), "{year}");
+        }
+        tz.footer = Some("PST8".parse().unwrap());
+        assert!(tz.dst_schedule(2024).is_none());
+    }
+}
@@ -0,0 +1,228 @@
+use std::io::{Read, Seek};
+
+use crate::parse::{read_header, Cursor, OffsetReader, Source};
+use crate::{Header, LocalTimeType, ParseError, ParseOptions, Time, TimeZoneInfo};
+
+/// Number of transitions read from the input at a time.
+const CHUNK: usize = 64;
+
+/// A transition read by [`TzifStream`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StreamTransition {
+    pub at_time: Time,
+
+    /// Index of the local time type which takes effect; see [`TzifStream::local_time_type`].
+    pub type_idx: u8,
+}
+
+/// Reads transitions lazily from TZif data, without loading all of them into memory.
+///
+/// Only the header, local time types, designations, leap seconds and indicators are held in
+/// memory; transition times are read from the input in small chunks as the iterator advances.
+pub struct TzifStream<R> {
+    reader: OffsetReader<R>,
+    header: Header,
+    version: u8,
+    v1: bool,
+    strict: bool,
+    /// Holds everything from the data block except the transitions.
+    types: TimeZoneInfo,
+    times_offset: u64,
+    idx: u32,
+    chunk_times: [i64; CHUNK],
+    chunk_types: [u8; CHUNK],
+    chunk_len: usize,
+    chunk_pos: usize,
+    prev: Option<i64>,
+    /// The local time type in effect before the next transition, and the standard time offset.
+    prev_type: usize,
+    std_offset: i32,
+}
+
+impl<R: Read + Seek> TzifStream<R> {
+    pub fn new(reader: R) -> Result<Self, ParseError> {
+        Self::with_options(reader, &ParseOptions::default())
+    }
+
+    /// Parse the header(s) and the non-transition tables, and position the stream at the first
+    /// transition of the newest data block in the input.
+    pub fn with_options(reader: R, opts: &ParseOptions) -> Result<Self, ParseError> {
+        let mut reader = OffsetReader {
+            inner: reader,
+            offset: 0,
+        };
+        let mut header = read_header(&mut reader)?;
+        let mut version = header.validate(0, opts)?;
+        let mut v1 = true;
+        if version >= 2 {
+            let hdr_offset = Header::SIZE as u64 + header.block_len(true);
+            reader.seek(hdr_offset)?;
+            header = read_header(&mut reader)?;
+            version = header.validate(hdr_offset, opts)?;
+            v1 = false;
+        }
+
+        let time_size = if v1 { 4 } else { 8 };
+        let times_offset = reader.offset;
+        let types_offset = times_offset + u64::from(header.timecnt) * (time_size + 1);
+        reader.seek(types_offset)?;
+        let rest = Header {
+            timecnt: 0,
+            ..header
+        };
+        let block = reader.read_block(rest.block_len(v1) as usize)?;
+        let types =
+            TimeZoneInfo::decode_block(&rest, &mut Cursor::new(&block, types_offset), v1, opts)?;
+
+        Ok(Self {
+            reader,
+            header,
+            version,
+            v1,
+            strict: opts.strict,
+            times_offset,
+            idx: 0,
+            chunk_times: [0; CHUNK],
+            chunk_types: [0; CHUNK],
+            chunk_len: 0,
+            chunk_pos: 0,
+            prev: None,
+            prev_type: 0,
+            std_offset: types.initial_std_offset(),
+            types,
+        })
+    }
+
+    /// The header of the data block being read.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Everything from the data block other than the transitions: local time types,
+    /// designations, leap-second records and indicators.
+    pub fn tables(&self) -> &TimeZoneInfo {
+        &self.types
+    }
+
+    /// Look up a local time type by the index given in a [`StreamTransition`].
+    pub fn local_time_type(&self, idx: u8) -> LocalTimeType<'_> {
+        self.types.local_time_type(idx as usize)
+    }
+
+    fn fill_chunk(&mut self) -> Result<(), ParseError> {
+        let time_size = if self.v1 { 4 } else { 8 };
+        let n = CHUNK.min((self.header.timecnt - self.idx) as usize);
+
+        let offset = self.times_offset + u64::from(self.idx) * time_size;
+        self.reader.seek(offset)?;
+        let mut buf = [0u8; CHUNK * 8];
+        self.reader.read_exact(&mut buf[..n * time_size as usize])?;
+        let mut cursor = Cursor::new(&buf, offset);
+        for i in 0..n {
+            let offset = cursor.offset();
+            let t = cursor.time(self.v1)?;
+            if self.strict && self.prev.is_some_and(|prev| prev >= t) {
+                return Err(ParseError::UnsortedTransitions { offset });
+            }
+            self.prev = Some(t);
+            self.chunk_times[i] = t;
+        }
+
+        let offset =
+            self.times_offset + u64::from(self.header.timecnt) * time_size + u64::from(self.idx);
+        self.reader.seek(offset)?;
+        let mut buf = [0u8; CHUNK];
+        self.reader.read_exact(&mut buf[..n])?;
+        for (i, &typ_idx) in buf[..n].iter().enumerate() {
+            if typ_idx as usize >= self.types.local_time_types.len() {
+                return Err(ParseError::TransitionTypeOutOfRange {
+                    offset: offset + i as u64,
+                    index: typ_idx,
+                });
+            }
+            self.chunk_types[i] = typ_idx;
+        }
+
+        self.chunk_len = n;
+        self.chunk_pos = 0;
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Iterator for TzifStream<R> {
+    type Item = Result<StreamTransition, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.idx >= self.header.timecnt {
+            return None;
+        }
+        if self.chunk_pos == self.chunk_len {
+            if let Err(e) = self.fill_chunk() {
+                // Don't try to keep going after an error.
+                self.idx = self.header.timecnt;
+                return Some(Err(e));
+            }
+        }
+
+        let ts = self.chunk_times[self.chunk_pos];
+        let type_idx = self.chunk_types[self.chunk_pos];
+        self.chunk_pos += 1;
+        self.idx += 1;
+        let at_time = self
+            .types
+            .adj_time(ts, type_idx as usize, self.prev_type, self.std_offset);
+        self.prev_type = type_idx as usize;
+        let typ = &self.types.local_time_types[self.prev_type];
+        if !typ.is_dst {
+            self.std_offset = typ.ut_off_secs;
+        }
+        Some(Ok(StreamTransition { at_time, type_idx }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.header.timecnt - self.idx) as usize;
+        (0, Some(remaining))
+    }
+}
+
+impl<R: Read> OffsetReader<R> {
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), ParseError> {
+        self.inner
+            .read_exact(buf)
+            .map_err(|e| ParseError::from_io(self.offset, e))?;
+        self.offset += buf.len() as u64;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_stream() {
+        let tz = sample();
+        let bytes = tz.to_bytes();
+        let mut stream = TzifStream::new(std::io::Cursor::new(&bytes)).unwrap();
+        assert_eq!(2, stream.version());
+        assert_eq!(3, stream.header().timecnt);
+
+        let mut seen = vec![];
+        while let Some(tr) = stream.next() {
+            let tr = tr.unwrap();
+            let local = stream.local_time_type(tr.type_idx);
+            seen.push((tr.at_time.to_ut(), local.desig.to_owned()));
+        }
+        let expected: Vec<_> = tz
+            .iter_transitions()
+            .map(|tr| (tr.at_time.to_ut(), tr.local.desig.to_owned()))
+            .collect();
+        assert_eq!(expected, seen);
+    }
+}
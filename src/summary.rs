@@ -0,0 +1,132 @@
+//! The few facts about a zone a zone picker shows.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::civil::{civil_from_days, SECS_PER_DAY};
+use crate::{LocalTimeType, TimeZoneInfo};
+
+/// A summary of a zone, from [`TimeZoneInfo::summary`] or [`TimeZoneInfo::summary_at`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ZoneSummary<'a> {
+    /// The least UT offset the zone has ever used or will use, in seconds.
+    pub min_offset_secs: i32,
+
+    /// The greatest UT offset the zone has ever used or will use, in seconds.
+    pub max_offset_secs: i32,
+
+    /// The distinct designations the zone uses, in order of first use.
+    pub designations: Vec<&'a str>,
+
+    /// Whether DST starts or ends during the year of the time the summary was made for.
+    pub observes_dst: bool,
+
+    /// The standard time UT offset in effect at the time the summary was made for, in seconds.
+    pub std_offset_secs: Option<i32>,
+}
+
+impl TimeZoneInfo {
+    /// Summarize the zone as of now. See [`summary_at`](Self::summary_at).
+    #[cfg(feature = "std")]
+    pub fn summary(&self) -> ZoneSummary<'_> {
+        let now = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        };
+        self.summary_at(now)
+    }
+
+    /// Summarize the zone as of the given UT time: the range of UT offsets and the designations
+    /// used by the local time types in effect at some time, which are the first type, those of
+    /// the transitions and those of the footer, and whether DST is observed and what standard
+    /// time is then.
+    ///
+    /// A zone with no local time types has offsets of 0 and no designations.
+    pub fn summary_at(&self, ut: i64) -> ZoneSummary<'_> {
+        let recorded = (!self.local_time_types.is_empty())
+            .then_some(0)
+            .into_iter()
+            .chain(self.transition_types.iter().map(|&typ| usize::from(typ)))
+            .map(|idx| self.local_time_type(idx));
+        let footer = self.footer.iter().flat_map(|footer| {
+            let std = LocalTimeType {
+                desig: &footer.std_abbr,
+                ut_offset_secs: footer.std_offset,
+                is_dst: false,
+            };
+            let dst = footer.dst.as_ref().map(|dst| LocalTimeType {
+                desig: &dst.abbr,
+                ut_offset_secs: dst.offset,
+                is_dst: true,
+            });
+            core::iter::once(std).chain(dst)
+        });
+
+        let mut summary = ZoneSummary {
+            min_offset_secs: 0,
+            max_offset_secs: 0,
+            designations: Vec::new(),
+            observes_dst: false,
+            std_offset_secs: self.std_offset_at(ut),
+        };
+        for (idx, local) in recorded.chain(footer).enumerate() {
+            if idx == 0 {
+                summary.min_offset_secs = local.ut_offset_secs;
+                summary.max_offset_secs = local.ut_offset_secs;
+            }
+            summary.min_offset_secs = summary.min_offset_secs.min(local.ut_offset_secs);
+            summary.max_offset_secs = summary.max_offset_secs.max(local.ut_offset_secs);
+            if !summary.designations.contains(&local.desig) {
+                summary.designations.push(local.desig);
+            }
+        }
+        let year = civil_from_days(ut.div_euclid(SECS_PER_DAY)).0;
+        summary.observes_dst = self.dst_schedule(year).is_some();
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_summary() {
+        let mut tz = sample();
+        let summary = tz.summary_at(1_720_000_000);
+        assert_eq!(
+            (-8 * 3600, -7 * 3600),
+            (summary.min_offset_secs, summary.max_offset_secs)
+        );
+        assert_eq!(vec!["PST", "PDT"], summary.designations);
+        assert!(!summary.observes_dst);
+        assert_eq!(Some(-8 * 3600), summary.std_offset_secs);
+
+        tz.footer = Some("<-0630>6:30<-0530>,M3.2.0,M11.1.0".parse().unwrap());
+        let summary = tz.summary_at(1_720_000_000);
+        assert_eq!(
+            (-8 * 3600, -5 * 3600 - 1800),
+            (summary.min_offset_secs, summary.max_offset_secs)
+        );
+        assert_eq!(vec!["PST", "PDT", "-0630", "-0530"], summary.designations);
+        assert!(summary.observes_dst);
+        assert_eq!(Some(-6 * 3600 - 1800), summary.std_offset_secs);
+        assert!(!tz.summary_at(-1_000_000_000).observes_dst);
+
+        let empty = TimeZoneInfo::default();
+        let summary = empty.summary();
+        assert_eq!(
+            (0, 0, None),
+            (
+                summary.min_offset_secs,
+                summary.max_offset_secs,
+                summary.std_offset_secs
+            )
+        );
+        assert!(summary.designations.is_empty());
+    }
+}
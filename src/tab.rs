@@ -0,0 +1,137 @@
+//! The `zone1970.tab` and `zone.tab` tables which accompany the zoneinfo database.
+//!
+//! Each non-comment line has tab-separated fields: country codes, coordinates, zone name, and
+//! an optional comment. `zone1970.tab` allows a comma-separated list of country codes, while
+//! `zone.tab` has exactly one.
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::ZoneTabError;
+
+/// One zone's line in a `zone1970.tab` or `zone.tab` table.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoneTabEntry {
+    /// ISO 3166 alpha-2 codes of the countries which use the zone.
+    pub countries: Vec<String>,
+
+    /// Latitude of the zone's principal location, in degrees north.
+    pub latitude: f64,
+
+    /// Longitude of the zone's principal location, in degrees east.
+    pub longitude: f64,
+
+    pub zone: String,
+
+    /// Comments distinguishing the zone from others in the same country, if any.
+    pub comment: Option<String>,
+}
+
+impl ZoneTabEntry {
+    /// Parse the full text of a `zone1970.tab` or `zone.tab` file.
+    pub fn parse_table(text: &str) -> Result<Vec<Self>, ZoneTabError> {
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(idx, line)| Self::parse_line(line).ok_or(ZoneTabError { line: idx + 1 }))
+            .collect()
+    }
+
+    fn parse_line(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let countries = fields.next()?;
+        let (latitude, longitude) = parse_coordinates(fields.next()?)?;
+        let zone = fields.next().filter(|zone| !zone.is_empty())?;
+        let comment = fields.next();
+        if fields.next().is_some() {
+            return None;
+        }
+        let countries = countries
+            .split(',')
+            .map(|code| {
+                (code.len() == 2 && code.bytes().all(|b| b.is_ascii_uppercase()))
+                    .then(|| code.to_owned())
+            })
+            .collect::<Option<_>>()?;
+        Some(Self {
+            countries,
+            latitude,
+            longitude,
+            zone: zone.to_owned(),
+            comment: comment.map(ToOwned::to_owned),
+        })
+    }
+}
+
+/// Parse ISO 6709 coordinates of the form `±DDMM±DDDMM` or `±DDMMSS±DDDMMSS`.
+fn parse_coordinates(s: &str) -> Option<(f64, f64)> {
+    let split = s.get(1..)?.find(['+', '-'])? + 1;
+    let (lat, lon) = s.split_at(split);
+    Some((parse_angle(lat, 2)?, parse_angle(lon, 3)?))
+}
+
+/// Parse a signed angle with the given number of degree digits, followed by minutes and
+/// optionally seconds.
+fn parse_angle(s: &str, degree_digits: usize) -> Option<f64> {
+    let (sign, digits) = match s.as_bytes().first()? {
+        b'+' => (1., &s[1..]),
+        b'-' => (-1., &s[1..]),
+        _ => return None,
+    };
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let has_seconds = match digits.len().checked_sub(degree_digits)? {
+        2 => false,
+        4 => true,
+        _ => return None,
+    };
+    let field = |range: core::ops::Range<usize>| digits[range].parse::<u32>().ok().map(f64::from);
+    let degrees = field(0..degree_digits)?;
+    let minutes = field(degree_digits..degree_digits + 2)?;
+    let seconds = if has_seconds {
+        field(degree_digits + 2..degree_digits + 4)?
+    } else {
+        0.
+    };
+    Some(sign * (degrees + minutes / 60. + seconds / 3600.))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table() {
+        let text = "# comment\n\
+                    AD\t+4230+00131\tEurope/Andorra\n\
+                    AE,OM,RE,SC,TF\t+2518+05518\tAsia/Dubai\tCrozet\n\
+                    AQ\t-720041+0023206\tAntarctica/Troll\tTroll\n";
+        let entries = ZoneTabEntry::parse_table(text).unwrap();
+        assert_eq!(3, entries.len());
+        assert_eq!("Europe/Andorra", entries[0].zone);
+        assert_eq!(None, entries[0].comment);
+        assert!((entries[0].latitude - 42.5).abs() < 1e-9);
+        assert!((entries[0].longitude - (1. + 31. / 60.)).abs() < 1e-9);
+        assert_eq!(5, entries[1].countries.len());
+        assert_eq!(Some("Crozet".to_owned()), entries[1].comment);
+        let troll_lat = -(72. + 41. / 3600.);
+        assert!((entries[2].latitude - troll_lat).abs() < 1e-9);
+
+        for bad in [
+            "AD\t4230+00131\tEurope/Andorra",
+            "AD\t+4230+0013\tEurope/Andorra",
+            "ad\t+4230+00131\tEurope/Andorra",
+            "AD\t+4230+00131",
+        ] {
+            let text = format!("# header\n{bad}\n");
+            assert_eq!(
+                Err(ZoneTabError { line: 2 }),
+                ZoneTabEntry::parse_table(&text),
+                "{bad}"
+            );
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Integration with the `time` crate.
+
+use ::time::{OffsetDateTime, UtcOffset};
+
+use crate::{LocalTimeType, TimeTransition, TimeZoneInfo};
+
+impl TimeZoneInfo {
+    /// The UT offset in effect at the given instant. Zones with no local time types are UTC.
+    pub fn utc_offset_at(&self, t: OffsetDateTime) -> UtcOffset {
+        self.local_time_type_at(t.unix_timestamp())
+            .map_or(UtcOffset::UTC, |local| local.utc_offset())
+    }
+
+    /// Convert the given instant to the local time in this zone.
+    pub fn to_local(&self, t: OffsetDateTime) -> OffsetDateTime {
+        t.to_offset(self.utc_offset_at(t))
+    }
+}
+
+impl LocalTimeType<'_> {
+    /// The UT offset as a `time::UtcOffset`.
+    ///
+    /// Offsets outside of the range `time` supports (±25:59:59) are clamped to UTC.
+    pub fn utc_offset(&self) -> UtcOffset {
+        UtcOffset::from_whole_seconds(self.ut_offset_secs).unwrap_or(UtcOffset::UTC)
+    }
+}
+
+impl TimeTransition<'_> {
+    /// The instant of the transition, expressed in the local time which takes effect.
+    ///
+    /// Returns `None` if the instant is outside the range `time` supports, which is the case
+    /// for the "big bang" transitions some zic versions emit.
+    pub fn to_offset_date_time(&self) -> Option<OffsetDateTime> {
+        let ut = self.at_time.to_ut();
+        OffsetDateTime::from_unix_timestamp(ut)
+            .ok()?
+            .checked_to_offset(self.local.utc_offset())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_time() {
+        let tz = sample();
+        let at = |t| OffsetDateTime::from_unix_timestamp(t).unwrap();
+        assert_eq!(
+            UtcOffset::from_hms(-8, 0, 0).unwrap(),
+            tz.utc_offset_at(at(0))
+        );
+        assert_eq!(
+            UtcOffset::from_hms(-7, 0, 0).unwrap(),
+            tz.utc_offset_at(at(150))
+        );
+        assert_eq!(at(150), tz.to_local(at(150)));
+
+        let transitions: Vec<_> = tz
+            .iter_transitions()
+            .map(|tr| tr.to_offset_date_time().unwrap())
+            .collect();
+        assert_eq!(at(100), transitions[0]);
+        assert_eq!(-7, transitions[0].offset().whole_hours());
+        assert_eq!(-8, transitions[1].offset().whole_hours());
+    }
+}
@@ -0,0 +1,138 @@
+//! Parsing TZif data from a `tokio` asynchronous reader.
+
+use alloc::vec::Vec;
+
+use ::tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::parse::MAX_FOOTER_LEN;
+use crate::{Header, ParseError, ParseOptions, TimeZoneInfo};
+
+impl TimeZoneInfo {
+    /// Parse TZif data from an asynchronous reader, such as the body of a network response,
+    /// without blocking.
+    ///
+    /// This reads as far as the header counts say the data runs and no further, then parses it
+    /// as [`parse_slice`](Self::parse_slice) does. Counts beyond the limits in `opts` stop it
+    /// early, so a hostile header can't make it wait for or buffer more than they allow.
+    pub async fn parse_async(reader: impl AsyncRead + Unpin) -> Result<Self, ParseError> {
+        Self::parse_async_with(reader, &ParseOptions::default()).await
+    }
+
+    pub async fn parse_async_with(
+        mut reader: impl AsyncRead + Unpin,
+        opts: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let mut data = Vec::new();
+        if let Some(hdr) = read_header(&mut reader, &mut data, opts).await? {
+            let v1_complete = read_len(&mut reader, &mut data, hdr.block_len(true)).await?;
+            if v1_complete && hdr.version() != Some(1) {
+                if let Some(hdr) = read_header(&mut reader, &mut data, opts).await? {
+                    if read_len(&mut reader, &mut data, hdr.block_len(false)).await? {
+                        read_footer(&mut reader, &mut data).await?;
+                    }
+                }
+            }
+        }
+        Self::parse_slice(&data, opts)
+    }
+}
+
+/// Read up to `len` more bytes into `data`, returning whether there were that many.
+async fn read_len(
+    reader: &mut (impl AsyncRead + Unpin),
+    data: &mut Vec<u8>,
+    len: u64,
+) -> Result<bool, ParseError> {
+    let offset = data.len() as u64;
+    let read = reader
+        .take(len)
+        .read_to_end(data)
+        .await
+        .map_err(|e| ParseError::from_io(offset, e))?;
+    Ok(read as u64 == len)
+}
+
+/// Read a header into `data`, giving it if it's all there and within the limits in `opts`.
+/// Otherwise, parsing what has been read reports the problem.
+async fn read_header(
+    reader: &mut (impl AsyncRead + Unpin),
+    data: &mut Vec<u8>,
+    opts: &ParseOptions,
+) -> Result<Option<Header>, ParseError> {
+    let offset = data.len();
+    if !read_len(reader, data, Header::SIZE as u64).await? {
+        return Ok(None);
+    }
+    let hdr = Header::from_array(data[offset..].try_into().unwrap());
+    Ok(hdr.validate(offset as u64, opts).is_ok().then_some(hdr))
+}
+
+/// Read the footer, a newline, TZ string and newline, into `data`, stopping at the end of the
+/// input or once it's longer than any footer accepted.
+async fn read_footer(
+    reader: &mut (impl AsyncRead + Unpin),
+    data: &mut Vec<u8>,
+) -> Result<(), ParseError> {
+    let mut newlines = 0;
+    for _ in 0..MAX_FOOTER_LEN + 2 {
+        let offset = data.len() as u64;
+        let b = match reader.read_u8().await {
+            Ok(b) => b,
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(ParseError::from_io(offset, e)),
+        };
+        data.push(b);
+        if b == b'\n' {
+            newlines += 1;
+            if newlines == 2 {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    fn block_on<T>(future: impl core::future::Future<Output = T>) -> T {
+        ::tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_parse_async() {
+        let mut tz = sample();
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        let mut data = tz.to_bytes();
+        assert_eq!(tz, block_on(TimeZoneInfo::parse_async(&data[..])).unwrap());
+
+        // Nothing after the footer is read.
+        data.extend_from_slice(b"trailing");
+        let mut reader = &data[..];
+        assert_eq!(
+            tz,
+            block_on(TimeZoneInfo::parse_async(&mut reader)).unwrap()
+        );
+        assert_eq!(b"trailing", reader);
+
+        let truncated = &data[..data.len() - 20];
+        assert_eq!(
+            TimeZoneInfo::parse_slice(truncated, &ParseOptions::default()).map_err(|e| e.offset()),
+            block_on(TimeZoneInfo::parse_async(truncated)).map_err(|e| e.offset())
+        );
+
+        let opts = ParseOptions {
+            max_timecnt: 2,
+            ..ParseOptions::default()
+        };
+        assert!(matches!(
+            block_on(TimeZoneInfo::parse_async_with(&data[..], &opts)),
+            Err(ParseError::LimitExceeded { offset: 32, .. })
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+//! Dropping a zone's history outside a range of times, like `zic -r`.
+
+use core::ops::{Bound, RangeBounds};
+
+use crate::TimeZoneInfo;
+
+impl TimeZoneInfo {
+    /// Drop the transitions outside the given range of UT times, keeping local time the same
+    /// for every instant in it.
+    ///
+    /// Transitions at or before the start of the range are dropped, and the local time type in
+    /// effect at the start becomes the first type, so it's used for all earlier times. After
+    /// the end, the transitions are dropped apart from the first, if the zone has a footer, so
+    /// that the footer still takes over after the range rather than within it. Local time
+    /// outside the range is unspecified afterwards. Leap-second records are kept as they are,
+    /// and so are the types and designations no longer used; [`normalize`](Self::normalize)
+    /// removes those.
+    pub fn truncate(&mut self, range: impl RangeBounds<i64>) {
+        if self.local_time_types.is_empty() {
+            return;
+        }
+
+        if let Some(hi) = match range.end_bound() {
+            Bound::Included(&hi) => Some(hi.saturating_add(1)),
+            Bound::Excluded(&hi) => Some(hi),
+            Bound::Unbounded => None,
+        } {
+            let mut end = self.partition_transitions(|t| t < hi);
+            if self.footer.is_some() && end < self.transition_times.len() {
+                end += 1;
+            }
+            self.transition_times.truncate(end);
+            self.transition_types.truncate(end);
+        }
+
+        if let Some(lo) = match range.start_bound() {
+            Bound::Included(&lo) => Some(lo),
+            Bound::Excluded(&lo) => Some(lo.saturating_add(1)),
+            Bound::Unbounded => None,
+        } {
+            let start = self.partition_transitions(|t| t <= lo);
+            let first = match start {
+                0 => 0,
+                _ => self.transition_types[start - 1],
+            };
+            self.transition_times.drain(..start);
+            self.transition_types.drain(..start);
+            self.swap_types(0, first);
+        }
+    }
+
+    /// Exchange the indices of two local time types.
+    fn swap_types(&mut self, a: u8, b: u8) {
+        if a == b {
+            return;
+        }
+        let (a_idx, b_idx) = (usize::from(a), usize::from(b));
+        self.local_time_types.swap(a_idx, b_idx);
+        if self.is_std.len() > a_idx.max(b_idx) {
+            self.is_std.swap(a_idx, b_idx);
+        }
+        if self.is_ut.len() > a_idx.max(b_idx) {
+            self.is_ut.swap(a_idx, b_idx);
+        }
+        for typ in &mut self.transition_types {
+            if *typ == a {
+                *typ = b;
+            } else if *typ == b {
+                *typ = a;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::sample;
+    use crate::TimeZoneInfo;
+
+    fn zone() -> TimeZoneInfo {
+        let mut tz = sample();
+        tz.transition_times = vec![100, 200, 300, 400];
+        tz.transition_types = vec![1, 0, 1, 0];
+        tz
+    }
+
+    fn desig_at(tz: &TimeZoneInfo, t: i64) -> &str {
+        tz.designation_at(t).unwrap()
+    }
+
+    #[test]
+    fn test_truncate() {
+        let tz = zone();
+        let mut truncated = zone();
+        truncated.truncate(150..350);
+        assert_eq!(vec![200, 300], truncated.transition_times);
+        // PDT, in effect at 150, is now the first type.
+        assert_eq!(vec![1, 0], truncated.transition_types);
+        assert_eq!("PDT", desig_at(&truncated, 0));
+        for t in 150..350 {
+            assert_eq!(desig_at(&tz, t), desig_at(&truncated, t));
+        }
+
+        // With a footer, the first transition after the range is kept for it to follow.
+        let mut tz = zone();
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        tz.truncate(..=250);
+        assert_eq!(vec![100, 200, 300], tz.transition_times);
+        assert_eq!("PST", desig_at(&tz, 299));
+        tz.truncate(300..);
+        assert!(tz.transition_times.is_empty());
+        assert_eq!("PDT", desig_at(&tz, 1710064800));
+    }
+}
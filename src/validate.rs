@@ -0,0 +1,329 @@
+//! Checking a zone against RFC 8536, reporting everything which doesn't conform.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::leap::leap_second_ok;
+use crate::parse::is_rfc_designation;
+use crate::{IsStd, IsUT, TimeZoneInfo};
+
+/// The earliest transition time RFC 8536 recommends, -2<sup>59</sup>.
+const EARLIEST_TIME: i64 = -(1 << 59);
+
+/// The range of UT offsets RFC 8536 recommends, just short of ±25 hours.
+const OFFSET_RANGE: core::ops::RangeInclusive<i32> = -89999..=93599;
+
+/// How serious a [`Finding`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// Breaks a rule RFC 8536 says MUST be followed; readers may reject the data.
+    Error,
+
+    /// Goes against a recommendation of RFC 8536; readers may misinterpret the data.
+    Warning,
+}
+
+/// Something in a zone which doesn't conform to RFC 8536, from [`TimeZoneInfo::validate`].
+/// Indices are of the transition, local time type or leap-second record at fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Finding {
+    /// The version isn't one from 1 to 4.
+    UnsupportedVersion(u8),
+
+    /// There are no local time types.
+    NoLocalTimeTypes,
+
+    /// There are more local time types than a transition type can refer to.
+    TooManyLocalTimeTypes,
+
+    /// An array didn't have the length the others call for.
+    LengthMismatch { field: &'static str },
+
+    /// A transition time wasn't later than the one before it.
+    UnsortedTransitions { index: usize },
+
+    /// A transition type referred to a local time type which doesn't exist.
+    TransitionTypeOutOfRange { index: usize },
+
+    /// A transition or leap second in a version 1 zone was outside the range of 32-bit times.
+    TimeOutOfV1Range { index: usize, leap_second: bool },
+
+    /// A transition time was before -2<sup>59</sup>.
+    EarlyTransition { index: usize },
+
+    /// A local time type's offset was -2<sup>31</sup>.
+    InvalidOffset { index: usize },
+
+    /// A local time type's offset was outside of -89999 to 93599 seconds.
+    OffsetOutOfRange { index: usize },
+
+    /// The designation table was empty or didn't end with a NUL.
+    UnterminatedDesignations,
+
+    /// A local time type's designation index was past the end of the designation table.
+    DesignationOutOfRange { index: usize },
+
+    /// A local time type's designation wasn't three to six ASCII alphanumerics, '-' or '+'.
+    NonConformingDesignation { index: usize },
+
+    /// A local time type was marked as both UT and wall clock time.
+    UniversalWall { index: usize },
+
+    /// A leap-second record broke the rules on how leap seconds follow one another.
+    InvalidLeapSecond { index: usize },
+
+    /// The zone has a footer, which its version is too low to have.
+    FooterNeedsVersion,
+
+    /// The footer's local time type at the last transition isn't the one the transition
+    /// changes to.
+    FooterMismatch,
+}
+
+impl Finding {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Finding::EarlyTransition { .. }
+            | Finding::OffsetOutOfRange { .. }
+            | Finding::NonConformingDesignation { .. }
+            | Finding::FooterMismatch => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// The section of RFC 8536 with the rule broken, as in "3.2".
+    pub fn section(&self) -> &'static str {
+        match self {
+            Finding::UnsupportedVersion(_)
+            | Finding::NoLocalTimeTypes
+            | Finding::TooManyLocalTimeTypes
+            | Finding::LengthMismatch { .. } => "3.1",
+            Finding::FooterNeedsVersion | Finding::FooterMismatch => "3.3",
+            _ => "3.2",
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Finding::UnsupportedVersion(version) => write!(f, "unsupported version {version}"),
+            Finding::NoLocalTimeTypes => f.write_str("no local time types"),
+            Finding::TooManyLocalTimeTypes => f.write_str("too many local time types"),
+            Finding::LengthMismatch { field } => write!(f, "{field} has the wrong length"),
+            Finding::UnsortedTransitions { index } => {
+                write!(f, "transition {index} not later than the one before")
+            }
+            Finding::TransitionTypeOutOfRange { index } => {
+                write!(f, "transition {index} has a type out of range")
+            }
+            Finding::TimeOutOfV1Range {
+                index,
+                leap_second: false,
+            } => write!(f, "transition {index} out of range for version 1"),
+            Finding::TimeOutOfV1Range {
+                index,
+                leap_second: true,
+            } => write!(f, "leap-second record {index} out of range for version 1"),
+            Finding::EarlyTransition { index } => {
+                write!(f, "transition {index} before -2^59")
+            }
+            Finding::InvalidOffset { index } => {
+                write!(f, "local time type {index} has an invalid offset")
+            }
+            Finding::OffsetOutOfRange { index } => {
+                write!(f, "local time type {index} has an offset out of range")
+            }
+            Finding::UnterminatedDesignations => f.write_str("designations not NUL-terminated"),
+            Finding::DesignationOutOfRange { index } => write!(
+                f,
+                "local time type {index} has a designation index out of range"
+            ),
+            Finding::NonConformingDesignation { index } => {
+                write!(
+                    f,
+                    "local time type {index} has a non-conforming designation"
+                )
+            }
+            Finding::UniversalWall { index } => {
+                write!(f, "local time type {index} is universal + wall")
+            }
+            Finding::InvalidLeapSecond { index } => {
+                write!(f, "invalid leap-second record {index}")
+            }
+            Finding::FooterNeedsVersion => f.write_str("version too low for the footer"),
+            Finding::FooterMismatch => f.write_str("footer inconsistent with the last transition"),
+        }?;
+        write!(f, " (RFC 8536 section {})", self.section())
+    }
+}
+
+impl TimeZoneInfo {
+    /// Check the zone against RFC 8536, returning everything found which doesn't conform, in
+    /// the order of the file: header, transitions, local time types, leap seconds, footer.
+    ///
+    /// Unlike parsing, which stops at the first problem, and [`Zone::new`](crate::Zone::new),
+    /// which checks only what the rest of this crate relies on, this also reports where the
+    /// zone goes against RFC 8536's recommendations, as [`Severity::Warning`]s.
+    pub fn validate(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        if !(1..=4).contains(&self.version) {
+            findings.push(Finding::UnsupportedVersion(self.version));
+        }
+        let count = self.local_time_types.len();
+        if count == 0 {
+            findings.push(Finding::NoLocalTimeTypes);
+        }
+        if count > 256 {
+            findings.push(Finding::TooManyLocalTimeTypes);
+        }
+        if self.transition_types.len() != self.transition_times.len() {
+            findings.push(Finding::LengthMismatch {
+                field: "transition_types",
+            });
+        }
+        if !self.is_std.is_empty() && self.is_std.len() != count {
+            findings.push(Finding::LengthMismatch { field: "is_std" });
+        }
+        if !self.is_ut.is_empty() && self.is_ut.len() != count {
+            findings.push(Finding::LengthMismatch { field: "is_ut" });
+        }
+
+        let v1 = self.version == 1;
+        for (index, &t) in self.transition_times.iter().enumerate() {
+            if index > 0 && self.transition_times[index - 1] >= t {
+                findings.push(Finding::UnsortedTransitions { index });
+            }
+            if v1 && i32::try_from(t).is_err() {
+                findings.push(Finding::TimeOutOfV1Range {
+                    index,
+                    leap_second: false,
+                });
+            }
+            if t < EARLIEST_TIME {
+                findings.push(Finding::EarlyTransition { index });
+            }
+        }
+        for (index, &typ) in self.transition_types.iter().enumerate() {
+            if usize::from(typ) >= count {
+                findings.push(Finding::TransitionTypeOutOfRange { index });
+            }
+        }
+
+        if self.time_zone_designations.last() != Some(&0) {
+            findings.push(Finding::UnterminatedDesignations);
+        }
+        for (index, typ) in self.local_time_types.iter().enumerate() {
+            if typ.ut_off_secs == i32::MIN {
+                findings.push(Finding::InvalidOffset { index });
+            } else if !OFFSET_RANGE.contains(&typ.ut_off_secs) {
+                findings.push(Finding::OffsetOutOfRange { index });
+            }
+            match self.designation_bytes(usize::from(typ.desig_idx)) {
+                None => findings.push(Finding::DesignationOutOfRange { index }),
+                Some(desig) if !is_rfc_designation(desig) => {
+                    findings.push(Finding::NonConformingDesignation { index });
+                }
+                Some(_) => {}
+            }
+        }
+        for (index, pair) in self.is_std.iter().zip(&self.is_ut).enumerate() {
+            if pair == (&IsStd::Wall, &IsUT::UT) {
+                findings.push(Finding::UniversalWall { index });
+            }
+        }
+
+        let v4 = self.version >= 4;
+        for index in 0..self.leap_second_records.len() {
+            if !leap_second_ok(&self.leap_second_records, index, v4) {
+                findings.push(Finding::InvalidLeapSecond { index });
+            }
+            if v1 && i32::try_from(self.leap_second_records[index].occurrence).is_err() {
+                findings.push(Finding::TimeOutOfV1Range {
+                    index,
+                    leap_second: true,
+                });
+            }
+        }
+
+        if let Some(footer) = &self.footer {
+            if self.version < 2 || (self.version < 3 && footer.uses_v3_extensions()) {
+                findings.push(Finding::FooterNeedsVersion);
+            }
+            let last = self
+                .transition_times
+                .last()
+                .zip(self.transition_types.last());
+            if let Some((&at, &typ)) = last.filter(|&(_, &typ)| usize::from(typ) < count) {
+                if footer.local_time_type_at(at) != self.local_time_type(usize::from(typ)) {
+                    findings.push(Finding::FooterMismatch);
+                }
+            }
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{leap_records, sample};
+    use crate::LeapSecondRecord;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Vec::<Finding>::new(), sample().validate());
+
+        let mut tz = sample();
+        tz.transition_times = vec![100, 100, -(1 << 60), 400];
+        tz.transition_types = vec![1, 0, 2, 1];
+        tz.local_time_types[0].ut_off_secs = -25 * 3600;
+        tz.time_zone_designations = b"PST\0PDT\0P\0".to_vec();
+        tz.local_time_types[1].desig_idx = 8;
+        tz.is_std[0] = IsStd::Wall;
+        tz.footer = Some("EST5".parse().unwrap());
+        let findings = tz.validate();
+        assert_eq!(
+            vec![
+                Finding::UnsortedTransitions { index: 1 },
+                Finding::UnsortedTransitions { index: 2 },
+                Finding::EarlyTransition { index: 2 },
+                Finding::TransitionTypeOutOfRange { index: 2 },
+                Finding::OffsetOutOfRange { index: 0 },
+                Finding::NonConformingDesignation { index: 1 },
+                Finding::UniversalWall { index: 0 },
+                Finding::FooterMismatch,
+            ],
+            findings
+        );
+        assert_eq!(Severity::Error, findings[0].severity());
+        assert_eq!(Severity::Warning, findings[2].severity());
+        assert_eq!(
+            "transition 1 not later than the one before (RFC 8536 section 3.2)",
+            findings[0].to_string()
+        );
+
+        let mut tz = sample();
+        tz.version = 1;
+        tz.transition_times[2] = 1 << 40;
+        tz.leap_second_records = leap_records(&[(78796800, 1), (78796801, 2)]);
+        tz.leap_second_records
+            .push(LeapSecondRecord::new(1 << 40, 3));
+        assert_eq!(
+            vec![
+                Finding::TimeOutOfV1Range {
+                    index: 2,
+                    leap_second: false
+                },
+                Finding::InvalidLeapSecond { index: 1 },
+                Finding::TimeOutOfV1Range {
+                    index: 2,
+                    leap_second: true
+                },
+            ],
+            tz.validate()
+        );
+    }
+}
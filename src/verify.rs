@@ -0,0 +1,135 @@
+//! Checking every file in a zoneinfo directory, as a sanity check after building tzdata.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{ParseError, ParseOptions, TimeZoneInfo};
+
+/// The result of checking one file with [`verify_tree`].
+#[derive(Debug)]
+pub struct FileReport {
+    /// The file's path relative to the root of the tree, with `/` separators.
+    pub name: String,
+    pub status: FileStatus,
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum FileStatus {
+    /// The file is valid TZif data of the given version.
+    Valid { version: u8 },
+
+    /// The file isn't TZif data at all, like `zone.tab` or `tzdata.zi`.
+    NotTzif,
+
+    /// The file starts like TZif data but doesn't conform to RFC 8536.
+    Invalid(ParseError),
+
+    /// The file couldn't be read.
+    Unreadable(io::Error),
+}
+
+impl FileStatus {
+    /// Whether this is a failure: a file which is invalid or unreadable.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, FileStatus::Invalid(_) | FileStatus::Unreadable(_))
+    }
+}
+
+/// Parse every file under the given zoneinfo directory with strict RFC 8536 conformance,
+/// returning a report for each sorted by name.
+///
+/// Files which don't begin with the TZif magic are reported as [`FileStatus::NotTzif`].
+/// Symlinks to files are followed, but not symlinks to directories, which may loop.
+pub fn verify_tree(root: impl AsRef<Path>) -> io::Result<Vec<FileReport>> {
+    let opts = ParseOptions {
+        strict: true,
+        ..ParseOptions::default()
+    };
+    let mut reports = Vec::new();
+    for file in tree_files(root.as_ref())? {
+        let status = match fs::read(&file.path) {
+            Err(e) => FileStatus::Unreadable(e),
+            Ok(data) if !data.starts_with(b"TZif") => FileStatus::NotTzif,
+            Ok(data) => match TimeZoneInfo::parse_slice(&data, &opts) {
+                Ok(info) => FileStatus::Valid {
+                    version: info.version,
+                },
+                Err(e) => FileStatus::Invalid(e),
+            },
+        };
+        reports.push(FileReport {
+            name: file.name,
+            status,
+        });
+    }
+    Ok(reports)
+}
+
+/// A file found by [`tree_files`].
+pub(crate) struct TreeFile {
+    /// The path relative to the root of the tree, with `/` separators.
+    pub name: String,
+    pub path: PathBuf,
+    pub is_symlink: bool,
+}
+
+/// Every file under the given directory, sorted by name. Symlinks to files are included, but
+/// not symlinks to directories, which may loop.
+pub(crate) fn tree_files(root: &Path) -> io::Result<Vec<TreeFile>> {
+    let mut files = Vec::new();
+    walk(root, "", &mut files)?;
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+fn walk(dir: &Path, prefix: &str, files: &mut Vec<TreeFile>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            walk(&path, &format!("{name}/"), files)?;
+            continue;
+        }
+        if file_type.is_symlink() && path.is_dir() {
+            continue;
+        }
+        files.push(TreeFile {
+            name,
+            path,
+            is_symlink: file_type.is_symlink(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_verify_tree() {
+        let root = std::env::temp_dir().join(format!("tzif-verify-{}", std::process::id()));
+        fs::create_dir_all(root.join("America")).unwrap();
+        let good = sample().to_bytes();
+        fs::write(root.join("America/Good"), &good).unwrap();
+        fs::write(root.join("Bad"), &good[..good.len() - 1]).unwrap();
+        fs::write(root.join("zone.tab"), "# nothing\n").unwrap();
+
+        let reports = verify_tree(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        let names: Vec<_> = reports.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(vec!["America/Good", "Bad", "zone.tab"], names);
+        assert!(matches!(
+            reports[0].status,
+            FileStatus::Valid { version: 2 }
+        ));
+        assert!(matches!(reports[1].status, FileStatus::Invalid(_)));
+        assert!(reports[1].status.is_failure());
+        assert!(matches!(reports[2].status, FileStatus::NotTzif));
+    }
+}
@@ -0,0 +1,241 @@
+//! Converting a zone between TZif versions.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{IsStd, IsUT, LocalTimeTypeRecord, TimeZoneInfo};
+
+/// Something [`TimeZoneInfo::to_version`] couldn't carry over to an older version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum VersionLoss {
+    /// Transitions before or after the 32-bit range of version 1 were dropped. The type in
+    /// effect at the start of the range became the first type, so only times before the range
+    /// are affected by the earlier ones going.
+    TransitionsDropped { before: usize, after: usize },
+
+    /// Leap seconds after the 32-bit range of version 1 were dropped.
+    LeapSecondsDropped { count: usize },
+
+    /// The footer was replaced by this many transitions it gives up to the end of the 32-bit
+    /// range, either because version 1 has no footer or because its TZ string needs version 3.
+    /// Local time after 2038-01-19 03:14:07 UT stays as it is then.
+    FooterExpanded { transitions: usize },
+
+    /// The version 4 record of when the leap-second table expires was dropped.
+    LeapExpirationDropped { expires: i64 },
+}
+
+impl TimeZoneInfo {
+    /// Convert the zone to the given version, from 1 to 4, returning what couldn't be kept.
+    ///
+    /// Going up, nothing changes but the version: [`to_bytes`](Self::to_bytes) writes a 64-bit
+    /// data block and a footer for version 2 and later, which is all the widening there is.
+    /// Going down to version 1 limits the data to what 32-bit times can hold, as described by
+    /// [`VersionLoss`]; going down to version 2 expands a footer which needs version 3, and
+    /// going down from 4 drops the leap-second table's expiration. Returns `None` for an
+    /// unknown version.
+    pub fn to_version(&self, version: u8) -> Option<(TimeZoneInfo, Vec<VersionLoss>)> {
+        if !(1..=4).contains(&version) {
+            return None;
+        }
+        let mut result = self.clone();
+        let mut losses = Vec::new();
+
+        if self.version >= 4 && version < 4 {
+            if let Some(expires) = self.leap_second_expiration() {
+                result.leap_second_records.pop();
+                losses.push(VersionLoss::LeapExpirationDropped { expires });
+            }
+        }
+
+        let expand = match &self.footer {
+            Some(footer) => version == 1 || (version == 2 && footer.uses_v3_extensions()),
+            None => false,
+        };
+        if expand {
+            let transitions = result.expand_footer(i64::from(i32::MAX));
+            losses.push(VersionLoss::FooterExpanded { transitions });
+        }
+
+        if version == 1 {
+            let fits = |t: &i64| i32::try_from(*t).is_ok();
+            let before = result
+                .transition_times
+                .iter()
+                .take_while(|t| !fits(t))
+                .count();
+            let after = result
+                .transition_times
+                .iter()
+                .rev()
+                .take_while(|t| !fits(t))
+                .count();
+            if before + after > 0 {
+                result.truncate(i64::from(i32::MIN) - 1..=i64::from(i32::MAX));
+                losses.push(VersionLoss::TransitionsDropped { before, after });
+            }
+
+            let count = result.leap_second_records.len();
+            result
+                .leap_second_records
+                .retain(|leap| fits(&leap.occurrence));
+            let count = count - result.leap_second_records.len();
+            if count > 0 {
+                losses.push(VersionLoss::LeapSecondsDropped { count });
+            }
+        }
+
+        result.version = version;
+        Some((result, losses))
+    }
+
+    /// Record the footer's transitions after the last recorded one, up to and including
+    /// `until`, then drop the footer. Returns how many transitions were added.
+    pub(crate) fn expand_footer(&mut self, until: i64) -> usize {
+        let from = self
+            .transition_times
+            .last()
+            .map_or(i64::from(i32::MIN), |&last| last);
+        let changes: Vec<(i64, i32, bool, String)> = self
+            .changes_between(from, until)
+            .into_iter()
+            .map(|(at, _, local)| {
+                let desig = local.desig.to_string();
+                (at, local.ut_offset_secs, local.is_dst, desig)
+            })
+            .collect();
+        // With no transitions, the footer's type from the start is the first one.
+        if self.transition_times.is_empty() {
+            if let Some(footer) = &self.footer {
+                let first = footer.local_time_type_at(from);
+                let desig = first.desig.to_string();
+                let typ = self.find_or_add_type(first.ut_offset_secs, first.is_dst, &desig);
+                self.transition_times.push(from);
+                self.transition_types.push(typ);
+            }
+        }
+        for (at, ut_off_secs, is_dst, desig) in &changes {
+            let typ = self.find_or_add_type(*ut_off_secs, *is_dst, desig);
+            self.transition_times.push(*at);
+            self.transition_types.push(typ);
+        }
+        self.footer = None;
+        changes.len()
+    }
+
+    /// Find a local time type with the given offset, DST flag and designation, or add one with
+    /// wall clock and local time indicators, returning its index.
+    fn find_or_add_type(&mut self, ut_off_secs: i32, is_dst: bool, desig: &str) -> u8 {
+        let existing = (0..self.local_time_types.len()).find(|&idx| {
+            let local = self.local_time_type(idx);
+            (local.ut_offset_secs, local.is_dst, local.desig) == (ut_off_secs, is_dst, desig)
+        });
+        if let Some(idx) = existing {
+            return idx as u8;
+        }
+        // A designation too long to be indexed falls back to the first designation.
+        let desig_idx = self.find_or_add_designation(desig).unwrap_or(0);
+        self.local_time_types.push(LocalTimeTypeRecord {
+            ut_off_secs,
+            is_dst,
+            desig_idx,
+        });
+        if !self.is_std.is_empty() {
+            self.is_std.push(IsStd::Wall);
+        }
+        if !self.is_ut.is_empty() {
+            self.is_ut.push(IsUT::Local);
+        }
+        (self.local_time_types.len() - 1) as u8
+    }
+}
+
+impl fmt::Display for VersionLoss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionLoss::TransitionsDropped { before, after } => write!(
+                f,
+                "dropped {before} transitions before and {after} after the 32-bit range"
+            ),
+            VersionLoss::LeapSecondsDropped { count } => {
+                write!(f, "dropped {count} leap seconds after the 32-bit range")
+            }
+            VersionLoss::FooterExpanded { transitions } => {
+                write!(f, "replaced the footer with {transitions} transitions")
+            }
+            VersionLoss::LeapExpirationDropped { expires } => {
+                write!(f, "dropped the leap-second table's expiration at {expires}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use crate::LeapSecondRecord;
+
+    #[test]
+    fn test_to_version() {
+        let mut tz = sample();
+        tz.transition_times = vec![-3_000_000_000, 100, 200];
+        tz.transition_types = vec![1, 0, 1];
+        tz.footer = Some("PST8PDT,M3.2.0,M11.1.0".parse().unwrap());
+        assert!(tz.to_version(5).is_none());
+
+        let (v3, losses) = tz.to_version(3).unwrap();
+        assert!(losses.is_empty());
+        assert_eq!((3, &tz.footer), (v3.version, &v3.footer));
+
+        let (v1, losses) = tz.to_version(1).unwrap();
+        assert_eq!(1, v1.version);
+        assert!(v1.footer.is_none());
+        let expanded = v1.transition_times.len() - 2;
+        assert_eq!(
+            vec![
+                VersionLoss::FooterExpanded {
+                    transitions: expanded
+                },
+                VersionLoss::TransitionsDropped {
+                    before: 1,
+                    after: 0
+                },
+            ],
+            losses
+        );
+        // Two changes a year from 1970 through 2037.
+        assert_eq!(2 * 68, expanded);
+        assert_eq!("PDT", v1.designation_at(i64::from(i32::MIN)).unwrap());
+        for t in [
+            150,
+            1710064799,
+            1710064800,
+            2_000_000_000,
+            i64::from(i32::MAX),
+        ] {
+            assert_eq!(tz.local_time_type_at(t), v1.local_time_type_at(t));
+        }
+
+        let mut tz = sample();
+        tz.version = 4;
+        tz.leap_second_records = vec![LeapSecondRecord::new(78796800, 1)];
+        tz.leap_second_records
+            .push(LeapSecondRecord::new(5_000_000_000, 2));
+        tz.leap_second_records
+            .push(LeapSecondRecord::new(6_000_000_000, 2));
+        let (v1, losses) = tz.to_version(1).unwrap();
+        assert_eq!(
+            vec![
+                VersionLoss::LeapExpirationDropped {
+                    expires: 6_000_000_000
+                },
+                VersionLoss::LeapSecondsDropped { count: 1 },
+            ],
+            losses
+        );
+        assert_eq!(1, v1.leap_second_records.len());
+    }
+}
@@ -0,0 +1,237 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::civil::{days_from_civil, SECS_PER_DAY};
+use crate::{IsStd, IsUT, TimeZoneInfo};
+
+/// How many transitions [`TimeZoneInfo::to_bytes_with`] writes, as `zic -b` chooses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Bloat {
+    /// The zone's transitions as they are.
+    #[default]
+    Unchanged,
+
+    /// As few as the footer allows, as `zic -b slim` writes: trailing transitions which the
+    /// footer gives anyway are left out, and a version 2+ file's version 1 data block has no
+    /// transitions or leap seconds.
+    Slim,
+
+    /// The footer's transitions as well, through the end of the given year, for readers which
+    /// ignore the footer. The footer is kept for times after that. `zic -b fat` writes them
+    /// through 2037.
+    Fat { until_year: i64 },
+}
+
+/// Options controlling how a zone is written as TZif data.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WriteOptions {
+    pub bloat: Bloat,
+}
+
+impl TimeZoneInfo {
+    /// Encode the zone as TZif data.
+    ///
+    /// For version 2+ zones, the version 1 data block holds only the transitions and leap
+    /// seconds which fit in 32 bits, and the footer is written after the second data block.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with(&WriteOptions::default())
+    }
+
+    /// Encode the zone as TZif data, as [`to_bytes`](Self::to_bytes) does but with the
+    /// transitions `opts` asks for. Reading it back gives the same local time at every instant
+    /// either way, for a reader which follows the footer.
+    pub fn to_bytes_with(&self, opts: &WriteOptions) -> Vec<u8> {
+        match opts.bloat {
+            Bloat::Unchanged => self.write(false),
+            Bloat::Slim => {
+                let mut slim = self.clone();
+                slim.drop_footer_transitions();
+                slim.write(slim.version >= 2)
+            }
+            Bloat::Fat { until_year } => {
+                let mut fat = self.clone();
+                if let Some(footer) = fat.footer.clone() {
+                    let until = days_from_civil(until_year.saturating_add(1), 1, 1)
+                        .saturating_mul(SECS_PER_DAY)
+                        .saturating_sub(1);
+                    fat.expand_footer(until);
+                    fat.footer = Some(footer);
+                }
+                fat.write(false)
+            }
+        }
+    }
+
+    /// Leave out the trailing transitions which the footer gives anyway, keeping the one it
+    /// takes over from.
+    fn drop_footer_transitions(&mut self) {
+        let Some(footer) = &self.footer else {
+            return;
+        };
+        let same = |idx: usize, t: i64| {
+            let (local, ours) = (footer.local_time_type_at(t), self.local_time_type(idx));
+            (local.ut_offset_secs, local.is_dst, local.desig)
+                == (ours.ut_offset_secs, ours.is_dst, ours.desig)
+        };
+        let times = &self.transition_times;
+        let types = &self.transition_types;
+        let mut keep = times.len();
+        // The footer takes over from transition `keep - 2` if it gives the same local time
+        // from there to transition `keep - 1`, where it must give that one's too.
+        while keep >= 2 {
+            let (prev, last) = (times[keep - 2], times[keep - 1]);
+            let reproduced = same(usize::from(types[keep - 2]), prev)
+                && same(usize::from(types[keep - 1]), last)
+                && footer
+                    .changes_around(prev)
+                    .1
+                    .is_none_or(|next| next >= last);
+            if !reproduced {
+                break;
+            }
+            keep -= 1;
+        }
+        self.transition_times.truncate(keep);
+        self.transition_types.truncate(keep);
+    }
+
+    /// Encode the zone, leaving the version 1 data block of a version 2+ zone without
+    /// transitions or leap seconds if `slim`.
+    fn write(&self, slim: bool) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_block(&mut out, true, slim);
+        if self.version >= 2 {
+            self.write_block(&mut out, false, false);
+            out.push(b'\n');
+            if let Some(footer) = &self.footer {
+                match &self.raw_footer {
+                    Some(raw) if raw.parse().ok().as_ref() == Some(footer) => {
+                        out.extend_from_slice(raw.as_bytes())
+                    }
+                    _ => out.extend_from_slice(footer.to_string().as_bytes()),
+                }
+            }
+            out.push(b'\n');
+        }
+        out
+    }
+
+    /// Encode a data block, with no transitions or leap seconds if `empty`.
+    fn write_block(&self, out: &mut Vec<u8>, v1: bool, empty: bool) {
+        let fits = |t: i64| !empty && (!v1 || i32::try_from(t).is_ok());
+        let transitions = || {
+            self.transition_times
+                .iter()
+                .zip(&self.transition_types)
+                .filter(|(&t, _)| fits(t))
+        };
+        let leaps = || {
+            self.leap_second_records
+                .iter()
+                .filter(|leap| fits(leap.occurrence))
+        };
+        let write_time = |out: &mut Vec<u8>, t: i64| {
+            if v1 {
+                out.extend_from_slice(&(t as i32).to_be_bytes());
+            } else {
+                out.extend_from_slice(&t.to_be_bytes());
+            }
+        };
+
+        out.extend_from_slice(b"TZif");
+        out.push(self.version_byte());
+        out.extend_from_slice(&[0; 15]);
+        for count in [
+            self.is_ut.len(),
+            self.is_std.len(),
+            leaps().count(),
+            transitions().count(),
+            self.local_time_types.len(),
+            self.time_zone_designations.len(),
+        ] {
+            out.extend_from_slice(&(count as u32).to_be_bytes());
+        }
+
+        for (&t, _) in transitions() {
+            write_time(out, t);
+        }
+        out.extend(transitions().map(|(_, &typ)| typ));
+        for typ in &self.local_time_types {
+            out.extend_from_slice(&typ.ut_off_secs.to_be_bytes());
+            out.extend_from_slice(&[typ.is_dst as u8, typ.desig_idx]);
+        }
+        out.extend_from_slice(&self.time_zone_designations);
+        for leap in leaps() {
+            write_time(out, leap.occurrence);
+            out.extend_from_slice(&leap.correction.to_be_bytes());
+        }
+        out.extend(self.is_std.iter().map(|&s| (s == IsStd::Standard) as u8));
+        out.extend(self.is_ut.iter().map(|&u| (u == IsUT::UT) as u8));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+    use crate::{Header, ParseOptions, ZoneSource};
+
+    #[test]
+    fn test_bloat() {
+        let mut source = ZoneSource::new();
+        source
+            .parse(
+                "\
+Rule  US  1918  2006  -  Apr  lastSun  2:00  1:00  D
+Rule  US  1918  2006  -  Oct  lastSun  2:00  0     S
+Rule  US  2007  max   -  Mar  Sun>=8   2:00  1:00  D
+Rule  US  2007  max   -  Nov  Sun>=1   2:00  0     S
+Zone  Test  -5:00  -   LMT  1900
+            -5:00  US  E%sT
+",
+            )
+            .unwrap();
+        let tz = source.compile("Test").unwrap();
+        let parse = |bytes: &[u8]| TimeZoneInfo::parse_slice(bytes, &ParseOptions::default());
+        assert_eq!(tz.to_bytes(), tz.to_bytes_with(&WriteOptions::default()));
+
+        // Through 2037, whose last transition is on 2037-11-01.
+        let fat = parse(&tz.to_bytes_with(&WriteOptions {
+            bloat: Bloat::Fat { until_year: 2037 },
+        }))
+        .unwrap();
+        assert_eq!(Some(&2140668000), fat.transition_times.last());
+        assert_eq!(tz.footer, fat.footer);
+        assert!(fat.transition_times.len() > tz.transition_times.len() + 50);
+        assert!(tz.diff(&fat).is_empty());
+
+        let slim_bytes = fat.to_bytes_with(&WriteOptions { bloat: Bloat::Slim });
+        let slim = parse(&slim_bytes).unwrap();
+        assert!(tz.diff(&slim).is_empty());
+        assert!(slim.transition_times.len() <= tz.transition_times.len());
+        let v1 = Header::from_array(slim_bytes[..Header::SIZE].try_into().unwrap());
+        assert_eq!((0, 0), (v1.timecnt, v1.leapcnt));
+        for ut in [
+            0,
+            1_000_000_000,
+            1_700_000_000,
+            2_100_000_000,
+            4_000_000_000,
+        ] {
+            assert_eq!(
+                fat.local_time_type_at(ut),
+                slim.local_time_type_at(ut),
+                "{ut}"
+            );
+        }
+
+        // Without a footer, there's nothing to add or take away.
+        let tz = sample();
+        for bloat in [Bloat::Slim, Bloat::Fat { until_year: 2037 }] {
+            let bytes = tz.to_bytes_with(&WriteOptions { bloat });
+            assert_eq!(tz, parse(&bytes).unwrap());
+        }
+    }
+}
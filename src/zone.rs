@@ -0,0 +1,388 @@
+//! A zone whose parts are kept consistent with one another.
+
+use core::ops::{Deref, RangeBounds};
+
+use crate::leap::check_leap_seconds;
+use crate::{
+    ConsistencyError, IsStd, IsUT, LeapSecondRecord, LocalTimeTypeRecord, PosixTz, TimeZoneInfo,
+};
+
+/// A [`TimeZoneInfo`] which is known to be internally consistent, and can only be changed in
+/// ways which keep it so.
+///
+/// The fields of `TimeZoneInfo` are public, which is convenient for building and inspecting
+/// zones but makes it easy to leave one with transition types which refer to no local time
+/// type, say, or indicator arrays of the wrong length. A `Zone` holds one privately, checks it
+/// when it's created, and offers read-only access to everything in it, through the accessors
+/// here or [`Deref`] to the `TimeZoneInfo` for its lookup methods. [`raw`](Self::raw) and
+/// [`into_raw`](Self::into_raw) give up the guarantee for anything these don't cover.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Zone {
+    info: TimeZoneInfo,
+}
+
+impl Zone {
+    /// Check that the zone is consistent, and wrap it.
+    ///
+    /// It must have a version from 1 to 4, one to 256 local time types, and a transition type
+    /// in range for each transition time, with the times in ascending order. Every local time
+    /// type's designation must be a UTF-8 string in the NUL-terminated designation table, and
+    /// its offset can't be -2<sup>31</sup>. The indicator arrays must be empty or have one
+    /// entry per type, with no type both UT and wall clock time. The leap-second records must
+    /// follow RFC 8536's rules, and a footer needs version 2, or 3 if it uses version 3
+    /// extensions.
+    pub fn new(info: TimeZoneInfo) -> Result<Self, ConsistencyError> {
+        check(&info)?;
+        Ok(Self { info })
+    }
+
+    /// A zone with no transitions, following the given TZ string at all times.
+    pub fn from_posix(footer: PosixTz) -> Self {
+        Self {
+            info: TimeZoneInfo::from_posix(footer),
+        }
+    }
+
+    /// The zone, for reading whatever the accessors here don't expose.
+    pub fn raw(&self) -> &TimeZoneInfo {
+        &self.info
+    }
+
+    /// Unwrap the zone, to change it in ways the methods here don't allow. Passing it to
+    /// [`new`](Self::new) afterwards checks it again.
+    pub fn into_raw(self) -> TimeZoneInfo {
+        self.info
+    }
+
+    pub fn version(&self) -> u8 {
+        self.info.version
+    }
+
+    pub fn transition_times(&self) -> &[i64] {
+        &self.info.transition_times
+    }
+
+    /// The index of the local time type each transition changes to.
+    pub fn transition_types(&self) -> &[u8] {
+        &self.info.transition_types
+    }
+
+    pub fn local_time_types(&self) -> &[LocalTimeTypeRecord] {
+        &self.info.local_time_types
+    }
+
+    /// The designation table: NUL-terminated designations, which local time types refer to by
+    /// the index of their first byte. [`designation`](TimeZoneInfo::designation) looks them up.
+    pub fn time_zone_designations(&self) -> &[u8] {
+        &self.info.time_zone_designations
+    }
+
+    pub fn leap_second_records(&self) -> &[LeapSecondRecord] {
+        &self.info.leap_second_records
+    }
+
+    /// The standard/wall indicator of each local time type, or nothing if the zone has none.
+    pub fn is_std(&self) -> &[IsStd] {
+        &self.info.is_std
+    }
+
+    /// The UT/local indicator of each local time type, or nothing if the zone has none.
+    pub fn is_ut(&self) -> &[IsUT] {
+        &self.info.is_ut
+    }
+
+    pub fn footer(&self) -> Option<&PosixTz> {
+        self.info.footer.as_ref()
+    }
+
+    /// Add a local time type, with the given designation added to the table if it isn't in it
+    /// already, returning the new type's index. If the zone has indicators, the type is given
+    /// wall clock and local time ones.
+    pub fn add_local_time_type(
+        &mut self,
+        ut_off_secs: i32,
+        is_dst: bool,
+        desig: &str,
+    ) -> Result<u8, ConsistencyError> {
+        if ut_off_secs == i32::MIN {
+            return Err(ConsistencyError::InvalidOffset {
+                index: self.info.local_time_types.len(),
+            });
+        }
+        let idx = u8::try_from(self.info.local_time_types.len())
+            .map_err(|_| ConsistencyError::TooManyLocalTimeTypes)?;
+        let desig_idx = self
+            .info
+            .find_or_add_designation(desig)
+            .ok_or(ConsistencyError::DesignationTableFull)?;
+        self.info.local_time_types.push(LocalTimeTypeRecord {
+            ut_off_secs,
+            is_dst,
+            desig_idx,
+        });
+        if !self.info.is_std.is_empty() {
+            self.info.is_std.push(IsStd::Wall);
+        }
+        if !self.info.is_ut.is_empty() {
+            self.info.is_ut.push(IsUT::Local);
+        }
+        Ok(idx)
+    }
+
+    /// Set a local time type's indicators, giving every other type wall clock and local time
+    /// ones if the zone had none.
+    pub fn set_indicators(
+        &mut self,
+        idx: u8,
+        is_std: IsStd,
+        is_ut: IsUT,
+    ) -> Result<(), ConsistencyError> {
+        let idx = usize::from(idx);
+        let count = self.info.local_time_types.len();
+        if idx >= count {
+            return Err(ConsistencyError::LocalTimeTypeOutOfRange { index: idx });
+        }
+        if (is_std, is_ut) == (IsStd::Wall, IsUT::UT) {
+            return Err(ConsistencyError::UniversalWall { index: idx });
+        }
+        self.info.is_std.resize(count, IsStd::Wall);
+        self.info.is_ut.resize(count, IsUT::Local);
+        self.info.is_std[idx] = is_std;
+        self.info.is_ut[idx] = is_ut;
+        Ok(())
+    }
+
+    /// Add a transition to the local time type with the given index, after all the others.
+    pub fn push_transition(&mut self, at: i64, typ: u8) -> Result<(), ConsistencyError> {
+        let index = self.info.transition_times.len();
+        if usize::from(typ) >= self.info.local_time_types.len() {
+            return Err(ConsistencyError::TransitionTypeOutOfRange { index });
+        }
+        if self
+            .info
+            .transition_times
+            .last()
+            .is_some_and(|&last| last >= at)
+        {
+            return Err(ConsistencyError::UnsortedTransitions { index });
+        }
+        self.info.transition_times.push(at);
+        self.info.transition_types.push(typ);
+        Ok(())
+    }
+
+    /// Add a leap-second record after all the others.
+    pub fn push_leap_second(&mut self, record: LeapSecondRecord) -> Result<(), ConsistencyError> {
+        self.info.leap_second_records.push(record);
+        let v4 = self.info.version >= 4;
+        if let Err(index) = check_leap_seconds(&self.info.leap_second_records, v4) {
+            self.info.leap_second_records.pop();
+            return Err(ConsistencyError::InvalidLeapSecond { index });
+        }
+        Ok(())
+    }
+
+    /// Set or remove the footer, raising the version to 2, or 3 if the TZ string uses version
+    /// 3 extensions, if it's too low to have it.
+    pub fn set_footer(&mut self, footer: Option<PosixTz>) {
+        if let Some(footer) = &footer {
+            let needed = if footer.uses_v3_extensions() { 3 } else { 2 };
+            self.info.version = self.info.version.max(needed);
+        }
+        self.info.footer = footer;
+        self.info.raw_footer = None;
+    }
+
+    /// See [`TimeZoneInfo::truncate`].
+    pub fn truncate(&mut self, range: impl RangeBounds<i64>) {
+        self.info.truncate(range);
+    }
+
+    /// See [`TimeZoneInfo::normalize`].
+    pub fn normalize(&mut self) {
+        self.info.normalize();
+    }
+}
+
+impl Deref for Zone {
+    type Target = TimeZoneInfo;
+
+    fn deref(&self) -> &TimeZoneInfo {
+        &self.info
+    }
+}
+
+impl AsRef<TimeZoneInfo> for Zone {
+    fn as_ref(&self) -> &TimeZoneInfo {
+        &self.info
+    }
+}
+
+impl TryFrom<TimeZoneInfo> for Zone {
+    type Error = ConsistencyError;
+
+    fn try_from(info: TimeZoneInfo) -> Result<Self, ConsistencyError> {
+        Zone::new(info)
+    }
+}
+
+impl From<Zone> for TimeZoneInfo {
+    fn from(zone: Zone) -> Self {
+        zone.info
+    }
+}
+
+/// Check everything [`Zone::new`] promises.
+fn check(info: &TimeZoneInfo) -> Result<(), ConsistencyError> {
+    if !(1..=4).contains(&info.version) {
+        return Err(ConsistencyError::UnsupportedVersion(info.version));
+    }
+
+    let count = info.local_time_types.len();
+    if count == 0 {
+        return Err(ConsistencyError::NoLocalTimeTypes);
+    }
+    if count > 256 {
+        return Err(ConsistencyError::TooManyLocalTimeTypes);
+    }
+    if info.transition_types.len() != info.transition_times.len() {
+        return Err(ConsistencyError::LengthMismatch {
+            field: "transition_types",
+        });
+    }
+    if !info.is_std.is_empty() && info.is_std.len() != count {
+        return Err(ConsistencyError::LengthMismatch { field: "is_std" });
+    }
+    if !info.is_ut.is_empty() && info.is_ut.len() != count {
+        return Err(ConsistencyError::LengthMismatch { field: "is_ut" });
+    }
+
+    if let Some(index) = info
+        .transition_times
+        .windows(2)
+        .position(|pair| pair[0] >= pair[1])
+    {
+        return Err(ConsistencyError::UnsortedTransitions { index: index + 1 });
+    }
+    if let Some(index) = info
+        .transition_types
+        .iter()
+        .position(|&typ| usize::from(typ) >= count)
+    {
+        return Err(ConsistencyError::TransitionTypeOutOfRange { index });
+    }
+
+    if info.time_zone_designations.last() != Some(&0) {
+        return Err(ConsistencyError::UnterminatedDesignations);
+    }
+    for (index, typ) in info.local_time_types.iter().enumerate() {
+        if typ.ut_off_secs == i32::MIN {
+            return Err(ConsistencyError::InvalidOffset { index });
+        }
+        let desig = info
+            .designation_bytes(usize::from(typ.desig_idx))
+            .ok_or(ConsistencyError::DesignationOutOfRange { index })?;
+        if core::str::from_utf8(desig).is_err() {
+            return Err(ConsistencyError::InvalidDesignation { index });
+        }
+    }
+    // Missing standard/wall indicators are wall clock ones.
+    if let Some(index) = info.is_ut.iter().enumerate().position(|(i, is_ut)| {
+        (info.is_std.get(i).unwrap_or(&IsStd::Wall), is_ut) == (&IsStd::Wall, &IsUT::UT)
+    }) {
+        return Err(ConsistencyError::UniversalWall { index });
+    }
+
+    check_leap_seconds(&info.leap_second_records, info.version >= 4)
+        .map_err(|index| ConsistencyError::InvalidLeapSecond { index })?;
+
+    if let Some(footer) = &info.footer {
+        if info.version < 2 || (info.version < 3 && footer.uses_v3_extensions()) {
+            return Err(ConsistencyError::FooterNeedsVersion);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn test_zone() {
+        let mut zone = Zone::new(sample()).unwrap();
+        assert_eq!(&[100, 200, 300], zone.transition_times());
+        assert_eq!(Some("PDT"), zone.designation_at(150));
+
+        let ast = zone.add_local_time_type(-4 * 3600, false, "AST").unwrap();
+        assert_eq!(2, ast);
+        assert_eq!(3, zone.is_std().len());
+        assert_eq!(Some("AST"), zone.designation(2));
+        assert_eq!(
+            Err(ConsistencyError::UnsortedTransitions { index: 3 }),
+            zone.push_transition(300, ast)
+        );
+        assert_eq!(
+            Err(ConsistencyError::TransitionTypeOutOfRange { index: 3 }),
+            zone.push_transition(400, 3)
+        );
+        zone.push_transition(400, ast).unwrap();
+        assert_eq!(Some("AST"), zone.designation_at(400));
+        assert_eq!(
+            Err(ConsistencyError::UniversalWall { index: 2 }),
+            zone.set_indicators(ast, IsStd::Wall, IsUT::UT)
+        );
+
+        zone.push_leap_second(LeapSecondRecord::new(78796800, 1))
+            .unwrap();
+        assert_eq!(
+            Err(ConsistencyError::InvalidLeapSecond { index: 1 }),
+            zone.push_leap_second(LeapSecondRecord::new(78796801, 2))
+        );
+        assert_eq!(1, zone.leap_second_records().len());
+
+        let mut v1 = zone.into_raw();
+        v1.version = 1;
+        let mut zone = Zone::try_from(v1).unwrap();
+        zone.set_footer(Some("<-03>3<-02>,M3.5.0/-2,M10.5.0/-1".parse().unwrap()));
+        assert_eq!(3, zone.version());
+
+        for s in [
+            "UTC0",
+            "PST8PDT,M3.2.0,M11.1.0",
+            "<-03>3<-02>,M3.5.0/-2,M10.5.0/-1",
+        ] {
+            let footer: PosixTz = s.parse().unwrap();
+            assert!(Zone::new(TimeZoneInfo::from_posix(footer)).is_ok(), "{s}");
+        }
+
+        let mut tz = sample();
+        tz.transition_types.push(0);
+        assert_eq!(
+            Err(ConsistencyError::LengthMismatch {
+                field: "transition_types"
+            }),
+            Zone::new(tz).map(|_| ())
+        );
+        let mut tz = sample();
+        tz.local_time_types[1].desig_idx = 8;
+        assert_eq!(
+            Err(ConsistencyError::DesignationOutOfRange { index: 1 }),
+            Zone::new(tz).map(|_| ())
+        );
+        let mut tz = sample();
+        tz.version = 1;
+        tz.footer = Some("PST8".parse().unwrap());
+        assert_eq!(
+            Err(ConsistencyError::FooterNeedsVersion),
+            Zone::new(tz).map(|_| ())
+        );
+        let mut tz = sample();
+        tz.is_std.clear();
+        assert_eq!(
+            Err(ConsistencyError::UniversalWall { index: 0 }),
+            Zone::new(tz).map(|_| ())
+        );
+    }
+}